@@ -514,9 +514,7 @@ impl Boss {
     }
 
     /// 生成 Boss 掉落物品
-    pub fn generate_loot(&self) -> BossLoot {
-        let mut rng = rand::rng();
-
+    pub fn generate_loot(&self, rng: &mut impl Rng) -> BossLoot {
         // 基础金币掉落
         let gold = match self.boss_type {
             BossType::GiantOgre => rng.random_range(100..200),
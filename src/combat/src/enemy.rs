@@ -199,10 +199,10 @@ impl Enemy {
         self.is_surprised = false; // 移动后不再处于惊讶状态
     }
 
-    /// 敌人死亡时掉落物品
-    pub fn drop_items(&self) -> Vec<DropItem> {
+    /// 敌人死亡时掉落物品，`luck` 为财富加成，按比例提高稀有与特殊掉落的概率
+    pub fn drop_items(&self, rng: &mut impl Rng, luck: f32) -> Vec<DropItem> {
         let mut drops = Vec::new();
-        let mut rng = rand::rng();
+        let chance = |base: f32| base * (1.0 + luck.max(0.0));
 
         // 基础掉落：金币
         let gold_amount = match self.kind {
@@ -232,7 +232,7 @@ impl Enemy {
             EnemyKind::Golem => 0.3,
         };
 
-        if rng.random::<f32>() < rare_drop_chance {
+        if rng.random::<f32>() < chance(rare_drop_chance) {
             drops.push(match rng.random_range(0..6) {
                 0 => DropItem::HealthPotion,
                 1 => DropItem::Weapon("Dagger".to_string()),
@@ -246,12 +246,12 @@ impl Enemy {
         // 特殊敌人掉落
         match self.kind {
             EnemyKind::Guard => {
-                if rng.random::<f32>() < 0.1 {
+                if rng.random::<f32>() < chance(0.1) {
                     drops.push(DropItem::Key);
                 }
             }
             EnemyKind::Warlock => {
-                if rng.random::<f32>() < 0.15 {
+                if rng.random::<f32>() < chance(0.15) {
                     drops.push(DropItem::Scroll("Magic Mapping".to_string()));
                 }
             }
@@ -344,6 +344,21 @@ impl Enemy {
         }
     }
 
+    /// 敌人等级（用于计算英雄击杀时获得的经验）
+    pub fn level(&self) -> u32 {
+        match self.kind {
+            EnemyKind::Rat => 1,
+            EnemyKind::Snake => 2,
+            EnemyKind::Gnoll => 3,
+            EnemyKind::Crab => 4,
+            EnemyKind::Bat => 5,
+            EnemyKind::Scorpion => 7,
+            EnemyKind::Guard => 8,
+            EnemyKind::Warlock => 10,
+            EnemyKind::Golem => 12,
+        }
+    }
+
     /// 计算攻击伤害（考虑惊讶状态和武器）
     pub fn calculate_attack(&self) -> u32 {
         let base_damage = self.attack_power();
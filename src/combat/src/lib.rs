@@ -152,9 +152,10 @@ impl Combat {
             raw_damage *= constants::CRIT_MULTIPLIER;
         }
 
-        // Apply ambush attack modifier (2x damage for unaware targets)
+        // Apply ambush attack modifier (2x damage for unaware targets, more with a dagger)
         if is_ambush {
-            raw_damage *= constants::SURPRISE_ATTACK_MODIFIER;
+            raw_damage *= constants::SURPRISE_ATTACK_MODIFIER
+                * attacker.weapon().map_or(1.0, |w| w.surprise_multiplier());
         }
 
         // Defense reduces damage by percentage (capped at DEFENSE_CAP)
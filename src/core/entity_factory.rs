@@ -2,7 +2,7 @@ use crate::ecs::{
     AI, Actor, Energy, Faction, Inventory, Position, Renderable, Stats,
     TerrainType as EcsTerrainType, Viewshed,
 };
-use dungeon::level::tiles::TerrainType as DungeonTerrainType;
+use dungeon::level::tiles::{DoorState, TerrainType as DungeonTerrainType};
 use hecs::{Entity, World};
 use hero::class::Class;
 use items::Item;
@@ -35,12 +35,10 @@ impl EntityFactory {
         let mut inventory_items = Vec::new();
 
         for item in starting_kit {
-            if let Ok(ecs_item) = crate::ecs::ECSItem::from_items_item(&item) {
-                inventory_items.push(crate::ecs::ItemSlot {
-                    item: Some(ecs_item),
-                    quantity: item.quantity,
-                });
-            }
+            inventory_items.push(crate::ecs::ItemSlot {
+                quantity: item.quantity,
+                item: Some(crate::ecs::ECSItem::new(item)),
+            });
         }
 
         world.spawn((
@@ -168,6 +166,7 @@ impl EntityFactory {
         let (symbol, is_passable, blocks_sight) = match terrain_type {
             DungeonTerrainType::Floor => ('.', true, false),
             DungeonTerrainType::Wall => ('#', false, true),
+            DungeonTerrainType::Door(DoorState::Hidden) => ('#', false, true), // 暗门在被发现前看起来是墙
             DungeonTerrainType::Door(_) => ('+', true, true), // 门是可通行的但阻挡视线
             DungeonTerrainType::Stair(dungeon::level::tiles::StairDirection::Down) => {
                 ('>', true, false)
@@ -183,7 +182,9 @@ impl EntityFactory {
             Renderable {
                 symbol,
                 fg_color: match terrain_type {
-                    DungeonTerrainType::Wall => crate::ecs::Color::Gray,
+                    DungeonTerrainType::Wall | DungeonTerrainType::Door(DoorState::Hidden) => {
+                        crate::ecs::Color::Gray
+                    }
                     _ => crate::ecs::Color::White,
                 },
                 bg_color: Some(crate::ecs::Color::Black),
@@ -193,6 +194,7 @@ impl EntityFactory {
                 terrain_type: match terrain_type {
                     DungeonTerrainType::Floor => EcsTerrainType::Floor,
                     DungeonTerrainType::Wall => EcsTerrainType::Wall,
+                    DungeonTerrainType::Door(DoorState::Hidden) => EcsTerrainType::Wall,
                     DungeonTerrainType::Door(_) => EcsTerrainType::Door,
                     DungeonTerrainType::Stair(dungeon::level::tiles::StairDirection::Down) => {
                         EcsTerrainType::StairsDown
@@ -11,6 +11,7 @@ use std::collections::HashSet;
 pub mod rooms;
 pub mod tiles;
 
+use crate::InteractionEvent;
 use crate::TrapEffect;
 use crate::boss_room::BossRoom;
 use crate::level::tiles::{DoorState, StairDirection, TerrainType, Tile, TileInfo};
@@ -18,10 +19,16 @@ use crate::trap::{Trap, TrapKind};
 use combat::boss::{Boss, BossType};
 use combat::enemy::{Enemy, EnemyKind};
 use items::{
-    Armor, Food, Herb, Item, ItemKind, MiscItem, Potion, Ring, Scroll, Seed, Stone, Throwable,
-    Wand, Weapon,
+    Armor, Food, Herb, Item, ItemKind, MiscItem, MiscKind, Potion, Ring, Scroll, Seed, Stone,
+    Throwable, Wand, Weapon,
 };
 
+/// 每层出现炼金室的概率
+const ALCHEMY_ROOM_CHANCE: f64 = 0.5;
+
+/// 每个房间出现一堆金币的概率
+const GOLD_PILE_CHANCE: f64 = 0.3;
+
 #[derive(Clone, Debug, Encode, Decode, Serialize, Deserialize)]
 pub struct Level {
     pub rooms: Vec<Room>,
@@ -95,11 +102,18 @@ impl Level {
             None
         };
 
+        // 非 Boss 层可能有一间放着炼金釜的特殊房间，先选好位置以免敌人和物品落在釜上
+        let alchemy_pot = if is_boss_level {
+            None
+        } else {
+            Self::choose_alchemy_pot(&mut rng, &rooms)
+        };
+
         // 放置敌人和物品（如果不是 Boss 层）
         let (enemies, items) = if is_boss_level {
             (Vec::new(), Vec::new()) // Boss 层只有 Boss，暂时不放置其他敌人
         } else {
-            Self::place_entities(&mut rng, &rooms)
+            Self::place_entities(&mut rng, &rooms, depth, alchemy_pot)
         };
 
         // 创建地牢实例
@@ -122,6 +136,10 @@ impl Level {
         // 应用生成的布局到瓦片
         level.apply_layout_to_tiles(&mut rng);
 
+        if let Some(tile) = alchemy_pot.and_then(|(x, y)| level.get_tile_mut(x, y)) {
+            tile.info = TileInfo::new(false, false, TerrainType::AlchemyPot);
+        }
+
         // 如果有 Boss 房间，生成竞技场
         if let Some(ref boss_room) = level.boss_room {
             level.generate_boss_arena(&boss_room.clone());
@@ -172,10 +190,15 @@ impl Level {
             }
         }
 
-        // 批量处理门
+        // 批量处理门（一成的门是暗门）
         for (x, y) in door_positions {
+            let state = if rng.random_bool(0.1) {
+                DoorState::Hidden
+            } else {
+                DoorState::Closed
+            };
             if let Some(tile) = self.get_tile_mut(x, y) {
-                tile.info.terrain_type = TerrainType::Door(DoorState::Closed);
+                tile.info.terrain_type = TerrainType::Door(state);
                 tile.info.passable = false;
                 tile.info.blocks_sight = true;
             }
@@ -220,6 +243,17 @@ impl Level {
         }
     }
 
+    /// 随机选择一个中间房间作为炼金室，返回其中央放置炼金釜的位置
+    ///
+    /// 第一个和最后一个房间放着楼梯，不会被选中。
+    fn choose_alchemy_pot(rng: &mut impl Rng, rooms: &[Room]) -> Option<(i32, i32)> {
+        if rooms.len() < 3 || !rng.random_bool(ALCHEMY_ROOM_CHANCE) {
+            return None;
+        }
+        let index = rng.random_range(1..rooms.len() - 1);
+        Some(rooms[index].center())
+    }
+
     /// 生成地牢布局 (房间和走廊)
     fn generate_dungeon_layout(
         rng: &mut impl Rng,
@@ -257,7 +291,12 @@ impl Level {
     }
 
     /// 放置敌人和物品
-    fn place_entities(rng: &mut impl Rng, rooms: &[Room]) -> (Vec<Enemy>, Vec<Item>) {
+    fn place_entities(
+        rng: &mut impl Rng,
+        rooms: &[Room],
+        depth: usize,
+        alchemy_pot: Option<(i32, i32)>,
+    ) -> (Vec<Enemy>, Vec<Item>) {
         let mut enemies = Vec::new();
         let mut items = Vec::new();
 
@@ -266,7 +305,7 @@ impl Level {
             // 放置1-3个敌人
             let enemy_count = rng.random_range(1..=3);
             for _ in 0..enemy_count {
-                let (x, y) = room.random_point(rng);
+                let (x, y) = room.random_point_except(rng, alchemy_pot);
 
                 // 根据深度决定敌人类型
                 let kind = match i {
@@ -281,11 +320,11 @@ impl Level {
 
             // 10%几率放置物品
             if rng.random_bool(0.1) {
-                let (x, y) = room.random_point(rng);
+                let (x, y) = room.random_point_except(rng, alchemy_pot);
 
                 // 创建随机物品
                 let item = match rng.random_range(0..12) {
-                    0 => Item::new(ItemKind::Weapon(Weapon::random_new())),
+                    0 => Item::new(ItemKind::Weapon(Weapon::random_new(depth))),
                     1 => Item::new(ItemKind::Armor(Armor::random_new())),
                     2 => Item::new(ItemKind::Potion(Potion::random_new())),
                     3 => Item::new(ItemKind::Scroll(Scroll::random_new())),
@@ -310,6 +349,16 @@ impl Level {
                 };
                 items.push(item);
             }
+
+            // 金币堆，越深处数量越多
+            if rng.random_bool(GOLD_PILE_CHANCE) {
+                let (x, y) = room.random_point_except(rng, alchemy_pot);
+                let amount = rng.random_range(10..=30) * (1 + depth as u32 / 5);
+                let mut gold: Item = MiscItem::new(MiscKind::Gold(amount)).into();
+                gold.x = x;
+                gold.y = y;
+                items.push(gold);
+            }
         }
 
         (enemies, items)
@@ -490,6 +539,18 @@ impl Level {
         }
     }
 
+    /// 揭示本层所有暗门，使其变为普通的关闭的门
+    pub fn reveal_secret_doors(&mut self) -> Vec<InteractionEvent> {
+        self.tiles
+            .iter_mut()
+            .filter(|tile| tile.info.terrain_type == TerrainType::Door(DoorState::Hidden))
+            .map(|tile| {
+                tile.info.terrain_type = TerrainType::Door(DoorState::Closed);
+                InteractionEvent::SecretRevealed(tile.x, tile.y)
+            })
+            .collect()
+    }
+
     /// 触发陷阱
     pub fn trigger_trap(&mut self, x: i32, y: i32) -> Option<TrapEffect> {
         if let Some(tile) = self.get_tile_mut(x, y) {
@@ -587,6 +648,20 @@ impl Room {
         (x, y)
     }
 
+    /// 获取房间内的随机点，避开给定的格子
+    pub fn random_point_except(
+        &self,
+        rng: &mut impl Rng,
+        excluded: Option<(i32, i32)>,
+    ) -> (i32, i32) {
+        loop {
+            let point = self.random_point(rng);
+            if Some(point) != excluded {
+                return point;
+            }
+        }
+    }
+
     /// 获取房间边缘的随机点
     pub fn random_point_on_edge(&self, rng: &mut impl Rng) -> (i32, i32) {
         match rng.random_range(0..4) {
@@ -91,7 +91,7 @@ impl Tile {
                     self.info.blocks_sight = false;
                     true
                 }
-                DoorState::Locked => false,
+                DoorState::Locked | DoorState::Hidden => false,
                 DoorState::Open => true,
             }
         } else {
@@ -186,6 +186,8 @@ pub enum TerrainType {
     Stair(StairDirection),
     /// 特殊地形(如祭坛等)
     Special,
+    /// 炼金釜(位于特殊房间中，可投入材料炼制药水与魔法石)
+    AlchemyPot,
 }
 
 /// 门的状态
@@ -197,6 +199,8 @@ pub enum DoorState {
     Open,
     /// 锁定状态
     Locked,
+    /// 暗门（被发现前看起来和墙壁一样）
+    Hidden,
 }
 
 /// 楼梯方向
@@ -54,7 +54,7 @@ impl ECSWorld {
         let dungeon = dungeon::Dungeon::generate(max_depth, seed)?;
         set_dungeon_instance(&mut self.world, dungeon);
         // 重新初始化 RNG 以确保游戏中的随机性一致
-        self.resources.rng = StdRng::seed_from_u64(seed);
+        self.resources.reseed_rng(seed);
         self.resources.game_state.depth = 1;
         Ok(())
     }
@@ -120,6 +120,13 @@ impl ECSWorld {
                     .push(format!("拾取了 {}", item_name));
             }
 
+            GameEvent::GoldCollected { amount, .. } => {
+                self.resources
+                    .game_state
+                    .message_log
+                    .push(format!("拾取了 {} 枚金币", amount));
+            }
+
             GameEvent::ItemUsed {
                 item_name, effect, ..
             } => {
@@ -129,6 +136,13 @@ impl ECSWorld {
                     .push(format!("使用了 {}，{}", item_name, effect));
             }
 
+            GameEvent::ItemThrown { item_name, .. } => {
+                self.resources
+                    .game_state
+                    .message_log
+                    .push(format!("投出了 {}", item_name));
+            }
+
             GameEvent::LevelChanged {
                 old_level,
                 new_level,
@@ -248,6 +262,13 @@ impl ECSWorld {
                     .push(format!("你吃了{}，恢复了{}点饱食度", food_name, satiety_restored));
             }
 
+            GameEvent::LevelUp { new_level, .. } => {
+                self.resources
+                    .game_state
+                    .message_log
+                    .push(format!("你升到了 {} 级！", new_level));
+            }
+
             // ===== 新增事件处理 =====
 
             // 职业技能事件
@@ -462,6 +483,11 @@ impl ECSWorld {
                 self.resources.achievements.on_item_pickup()
             }
 
+            GameEvent::GoldCollected { amount, .. } => {
+                // 跟踪收集的金币
+                self.resources.achievements.on_gold_collected(*amount)
+            }
+
             GameEvent::TurnEnded { turn } => {
                 // 跟踪存活的回合数
                 self.resources.achievements.on_turn_end(*turn)
@@ -615,6 +641,9 @@ pub struct Resources {
     /// 随机数生成器状态
     pub rng: StdRng,
 
+    /// 战利品随机数流（独立于 `rng`，掉落不会改变战斗与地牢的随机序列）
+    pub loot_rng: StdRng,
+
     /// 地牢状态标记实体（实际地牢存储为组件）
     pub dungeon: Option<hecs::Entity>,
 
@@ -626,6 +655,9 @@ pub struct Resources {
 
     /// 后续处理队列（死亡、战利品、经验）
     pub aftermath_queue: Vec<AftermathEvent>,
+
+    /// 本局的物品外观分配与鉴定知识
+    pub item_knowledge: game_items::ItemKnowledge,
 }
 
 impl Default for Resources {
@@ -636,10 +668,12 @@ impl Default for Resources {
             input_buffer: InputBuffer::default(),
             config: GameConfig::new(),
             rng: StdRng::seed_from_u64(12345), // 默认种子
+            loot_rng: Self::loot_stream(12345),
             dungeon: None,
             achievements: AchievementsManager::new(),
             combat_intents: Vec::new(),
             aftermath_queue: Vec::new(),
+            item_knowledge: game_items::ItemKnowledge::from_seed(12345),
         }
     }
 }
@@ -653,16 +687,24 @@ impl Resources {
             input_buffer: InputBuffer::default(),
             config: GameConfig::new(),
             rng: StdRng::seed_from_u64(seed),
+            loot_rng: Self::loot_stream(seed),
             dungeon: None,
             achievements: AchievementsManager::new(),
             combat_intents: Vec::new(),
             aftermath_queue: Vec::new(),
+            item_knowledge: game_items::ItemKnowledge::from_seed(seed),
         }
     }
 
     /// 重新设置 RNG 种子（用于存档/读档）
     pub fn reseed_rng(&mut self, seed: u64) {
         self.rng = StdRng::seed_from_u64(seed);
+        self.loot_rng = Self::loot_stream(seed);
+    }
+
+    /// 由游戏种子派生战利品随机数流
+    fn loot_stream(seed: u64) -> StdRng {
+        StdRng::seed_from_u64(seed ^ 0x6c6f_6f74)
     }
 }
 
@@ -715,11 +757,33 @@ pub enum GameStatus {
     Inventory {
         selected_item: usize,
     },
+    // 物品选择：为需要目标的物品（强化、鉴定、变形卷轴等）选择背包中的目标物品
+    ItemPicker {
+        source_slot: usize,
+        selected_item: usize,
+    },
+    // 拾取选择：脚下有多件物品时选择要拾起的一件（选中的是物品堆中的序号）
+    PickupChooser {
+        selected_item: usize,
+    },
     Options {
         selected_option: usize,
     },
     Help,
     CharacterInfo,
+    // 物品图鉴：本局药水、卷轴、戒指的外观与鉴定情况
+    ItemCatalog,
+    // 炼金釜：从背包的炼金材料中选择要投入的物品（选中的是材料列表中的序号，投入的是背包格子）
+    Alchemy {
+        selected_item: usize,
+        chosen: [Option<usize>; game_items::alchemy::MAX_INGREDIENTS],
+    },
+    // 瞄准模式：在地图上移动光标选择目标位置
+    Targeting {
+        purpose: TargetingPurpose,
+        cursor_x: i32,
+        cursor_y: i32,
+    },
     // 确认退出对话框
     ConfirmQuit {
         return_to: ReturnTo,
@@ -727,6 +791,19 @@ pub enum GameStatus {
     },
 }
 
+/// 瞄准用途（确认目标后生成对应的动作）
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TargetingPurpose {
+    /// 投掷背包中指定格子的物品
+    Throw(usize),
+    /// 挥动背包中指定格子的法杖
+    Zap(usize),
+    /// 释放需要目标的职业主动技能（技能索引）
+    Skill(usize),
+    /// 使用当前武器进行远程攻击
+    RangedAttack,
+}
+
 /// 退出对话框返回目的地
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum ReturnTo {
@@ -761,10 +838,29 @@ pub enum PlayerAction {
     Move(Direction),
     Attack(Position),
     UseItem(usize),
+    /// 对背包中的另一件物品使用物品（物品格子，目标格子）
+    UseItemOn(usize, usize),
+    /// 将背包格子中的物品投入炼金釜（同一格子可出现多次，每次投入一个）
+    Brew(Vec<usize>),
+    /// 把背包格子中的种子种在脚下
+    PlantSeed(usize),
+    /// 使用快捷栏第 N 格中的物品
+    UseQuickslot(usize),
+    /// 把背包格子中的物品放入快捷栏（已在快捷栏中则移出）
+    AssignQuickslot(usize),
+    /// 把背包格子中的一堆物品拆出一半放到新格子
+    SplitStack(usize),
+    /// 拾起脚下的物品（有多件时打开拾取选择）
+    PickUp,
+    /// 拾起脚下物品堆中的第 N 件物品
+    PickUpItem(usize),
     DropItem(usize),
     EquipItem(usize),
     UnequipItem(usize),
     ThrowItem(usize, Direction),
+    ThrowItemAt(usize, Position),
+    ZapWand(usize, Position),
+    UseSkill(usize, Position),
     Descend,
     Ascend,
     Wait,
@@ -775,12 +871,19 @@ pub enum PlayerAction {
     OpenOptions,
     OpenHelp,
     OpenCharacterInfo,
+    OpenCatalog,
     CloseMenu,
 
     // 菜单导航
     MenuNavigate(NavigateDirection),
     MenuSelect,
     MenuBack,
+
+    // 瞄准模式
+    BeginTargeting(TargetingPurpose),
+    MoveCursor(Direction),
+    CycleTarget,
+    ConfirmTarget,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -912,6 +1015,32 @@ impl Position {
         let dy = (self.y - other.y) as f32;
         (dx * dx + dy * dy).sqrt()
     }
+
+    /// Bresenham 直线：从当前位置到目标的路径（不含起点，含终点）
+    pub fn line_to(&self, target: &Position) -> Vec<Position> {
+        let dx = (target.x - self.x).abs();
+        let dy = (target.y - self.y).abs();
+        let sx = if self.x < target.x { 1 } else { -1 };
+        let sy = if self.y < target.y { 1 } else { -1 };
+        let mut err = dx - dy;
+        let (mut x, mut y) = (self.x, self.y);
+        let mut path = Vec::new();
+
+        while x != target.x || y != target.y {
+            let e2 = 2 * err;
+            if e2 > -dy {
+                err -= dy;
+                x += sx;
+            }
+            if e2 < dx {
+                err += dx;
+                y += sy;
+            }
+            path.push(Position::new(x, y, self.z));
+        }
+
+        path
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -931,9 +1060,84 @@ pub enum TerrainType {
     StairsDown,
     StairsUp,
     Water,
+    Grass,
     Trap,
     Barrel,
     Empty,
+    /// 被冰霜药水冻结的水面
+    Ice,
+    /// 炼金釜：走向它即可打开炼金界面
+    AlchemyPot,
+}
+
+/// 地图上的气体或火焰（由药水等产生），每回合扩散并逐渐消散
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Blob {
+    pub kind: BlobKind,
+    pub volume: u32, // 剩余浓度，归零时消失
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlobKind {
+    ToxicGas,
+    ParalyticGas,
+    Fire,
+}
+
+impl BlobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlobKind::ToxicGas => "毒气",
+            BlobKind::ParalyticGas => "麻痹气体",
+            BlobKind::Fire => "火焰",
+        }
+    }
+
+    /// 身处其中的生物每回合受到的状态效果与持续回合
+    pub fn status(&self) -> (EffectType, u32) {
+        match self {
+            BlobKind::ToxicGas => (EffectType::Poison, 3),
+            BlobKind::ParalyticGas => (EffectType::Paralysis, 2),
+            BlobKind::Fire => (EffectType::Burning, 3),
+        }
+    }
+
+    /// 渲染时的背景色
+    pub fn color(&self) -> Color {
+        match self {
+            BlobKind::ToxicGas => Color::Rgb(40, 90, 30),
+            BlobKind::ParalyticGas => Color::Rgb(100, 90, 20),
+            BlobKind::Fire => Color::Rgb(150, 50, 0),
+        }
+    }
+}
+
+/// 种下的植物：任何生物踩上去都会触发种子的效果，触发后枯萎
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Plant {
+    pub kind: game_items::seed::SeedKind,
+}
+
+impl Plant {
+    pub fn name(&self) -> String {
+        game_items::Seed::new(self.kind).name()
+    }
+
+    /// 渲染时的前景色（与种子颜色一致）
+    pub fn color(&self) -> Color {
+        use game_items::seed::SeedKind;
+
+        match self.kind {
+            SeedKind::Earthroot => Color::Green,
+            SeedKind::Fadeleaf => Color::Cyan,
+            SeedKind::Firebloom => Color::Red,
+            SeedKind::Icecap => Color::Blue,
+            SeedKind::Sorrowmoss => Color::Magenta,
+            SeedKind::Dreamfoil => Color::White,
+            SeedKind::Stormvine => Color::Yellow,
+            SeedKind::Rotberry => Color::Rgb(140, 20, 20),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -993,167 +1197,242 @@ pub struct Inventory {
     pub max_slots: usize,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct ItemSlot {
-    pub item: Option<ECSItem>,
-    pub quantity: u32,
-}
+impl Inventory {
+    /// 物品栏的分类标签页：按 `ItemCategory` 分组，组内按 `sort_value` 从高到低排列。
+    ///
+    /// 返回背包格子的下标，只包含背包中实际拥有的分类；物品在背包中的存放顺序不变。
+    /// 收纳在容器中的物品不出现在自己的分类里，而是紧跟在所在容器之后。
+    pub fn category_tabs(&self) -> Vec<(game_items::ItemCategory, Vec<usize>)> {
+        use game_items::ItemTrait;
 
-/// 增强的 ECS 物品组件（支持 items 模块的完整功能）
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct ECSItem {
-    pub name: String,
-    pub item_type: ItemType,
-    pub value: u32,
-    pub identified: bool,
+        let containers = self.slot_containers();
+        let mut entries: Vec<(game_items::ItemCategory, u32, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| containers[*index].is_none())
+            .filter_map(|(index, slot)| {
+                let item = slot.item.as_ref()?;
+                Some((item.category(), item.sort_value(), index))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+
+        let mut tabs: Vec<(game_items::ItemCategory, Vec<usize>)> = Vec::new();
+        for (category, _, index) in entries {
+            let contents = (0..self.items.len()).filter(|&slot| containers[slot] == Some(index));
+            match tabs.last_mut() {
+                Some((current, slots)) if *current == category => {
+                    slots.push(index);
+                    slots.extend(contents);
+                }
+                _ => tabs.push((category, std::iter::once(index).chain(contents).collect())),
+            }
+        }
+        tabs
+    }
+
+    /// 每个格子所在容器的格子下标，不在容器中时为 None
+    ///
+    /// 容器按背包中的顺序收纳能放入的物品，每个容器最多 `CONTAINER_CAPACITY` 格；
+    /// 其余物品（包括容器本身）占用背包格子。
+    pub fn slot_containers(&self) -> Vec<Option<usize>> {
+        let mut room: Vec<(usize, game_items::MiscKind, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| match &slot.item.as_ref()?.kind {
+                game_items::ItemKind::Misc(misc) if misc.kind.is_container() => {
+                    Some((index, misc.kind, game_items::misc::CONTAINER_CAPACITY))
+                }
+                _ => None,
+            })
+            .collect();
+
+        self.items
+            .iter()
+            .map(|slot| {
+                let item = slot.item.as_ref()?;
+                let (container, _, free) = room
+                    .iter_mut()
+                    .find(|(_, kind, free)| *free > 0 && kind.holds(&item.kind))?;
+                *free -= 1;
+                Some(*container)
+            })
+            .collect()
+    }
+
+    /// 占用的背包格子数（不含收纳在容器中的物品）
+    pub fn main_slots_used(&self) -> usize {
+        self.slot_containers().iter().filter(|container| container.is_none()).count()
+    }
+
+    /// 是否还能为该物品新开一个格子：背包有空位，或有能收纳它且未满的容器
+    pub fn has_room_for(&self, item: &game_items::Item) -> bool {
+        if self.main_slots_used() < self.max_slots {
+            return true;
+        }
+        let containers = self.slot_containers();
+        self.items.iter().enumerate().any(|(index, slot)| match slot.item.as_ref().map(|i| &i.kind) {
+            Some(game_items::ItemKind::Misc(misc)) if misc.kind.holds(&item.kind) => {
+                containers.iter().filter(|&&c| c == Some(index)).count() < game_items::misc::CONTAINER_CAPACITY
+            }
+            _ => false,
+        })
+    }
+
+    /// 放入物品：先并入 `stacking_id` 相同的已有堆叠（不超过 `max_stack`），
+    /// 剩余部分占用新的格子。
+    ///
+    /// 返回放不下的部分，背包有空间时返回 None。
+    pub fn add_item(&mut self, mut item: ECSItem) -> Option<ECSItem> {
+        use game_items::ItemTrait;
 
-    // ========== 扩展属性（支持 items 模块） ==========
-    pub quantity: u32,        // 堆叠数量（药水、卷轴、食物等）
-    pub level: i32,           // 升级等级（武器、护甲）
-    pub cursed: bool,         // 是否被诅咒
-    pub charges: Option<u32>, // 充能次数（法杖、魔法石）
+        let max_stack = item.max_stack().max(1);
+        if item.is_stackable() {
+            for slot in self.items.iter_mut() {
+                if item.quantity == 0 {
+                    return None;
+                }
+                let Some(existing) = slot.item.as_mut() else {
+                    continue;
+                };
+                if !existing.stacks_with(&item) || existing.quantity >= max_stack {
+                    continue;
+                }
+                let moved = item.quantity.min(max_stack - existing.quantity);
+                let total = existing.quantity + moved;
+                existing.set_quantity(total);
+                slot.quantity = total;
+                let left = item.quantity - moved;
+                item.set_quantity(left);
+            }
+        }
 
-    /// 详细数据（可选）：序列化的 items::Item
-    /// 用于存储完整的 items 模块对象，实现完全兼容
-    pub detailed_data: Option<Vec<u8>>,
+        while item.quantity > 0 && self.has_room_for(&item) {
+            let amount = if item.is_stackable() {
+                item.quantity.min(max_stack)
+            } else {
+                item.quantity
+            };
+            let mut part = item.clone();
+            part.set_quantity(amount);
+            self.items.push(ItemSlot {
+                item: Some(part),
+                quantity: amount,
+            });
+            let left = item.quantity - amount;
+            item.set_quantity(left);
+        }
+
+        (item.quantity > 0).then_some(item)
+    }
+
+    /// 合并所有可以堆叠在一起的格子（例如鉴定后外观相同的药水）
+    pub fn merge_stacks(&mut self) {
+        let slots = std::mem::take(&mut self.items);
+        for slot in slots {
+            match slot.item {
+                Some(mut item) => {
+                    item.set_quantity(slot.quantity);
+                    if let Some(rest) = self.add_item(item) {
+                        // 合并只会减少格子数，这里不会真正放不下
+                        let quantity = rest.quantity;
+                        self.items.push(ItemSlot {
+                            item: Some(rest),
+                            quantity,
+                        });
+                    }
+                }
+                None => self.items.push(slot),
+            }
+        }
+    }
+
+    /// 把一堆物品拆出一半（至少一个）放到紧随其后的新格子里
+    ///
+    /// 数量不足两个或背包已满时返回 false。
+    pub fn split_stack(&mut self, index: usize) -> bool {
+        let has_room = self
+            .items
+            .get(index)
+            .and_then(|slot| slot.item.as_ref())
+            .is_some_and(|item| self.has_room_for(item));
+        if !has_room {
+            return false;
+        }
+        let Some(slot) = self.items.get_mut(index) else {
+            return false;
+        };
+        let Some(item) = slot.item.as_mut() else {
+            return false;
+        };
+        if slot.quantity < 2 {
+            return false;
+        }
+
+        let split = slot.quantity / 2;
+        let remaining = slot.quantity - split;
+        item.set_quantity(remaining);
+        slot.quantity = remaining;
+
+        let mut part = item.clone();
+        part.set_quantity(split);
+        self.items.insert(
+            index + 1,
+            ItemSlot {
+                item: Some(part),
+                quantity: split,
+            },
+        );
+        true
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub enum ItemType {
-    Weapon { damage: u32 },
-    Armor { defense: u32 },
-    Consumable { effect: ConsumableEffect },
-    Throwable { damage: (u32, u32), range: u8 },
-    Key,
-    Quest,
+pub struct ItemSlot {
+    pub item: Option<ECSItem>,
+    pub quantity: u32,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub enum ConsumableEffect {
-    Healing {
-        amount: u32,
-    },
-    Damage {
-        amount: u32,
-    },
-    Buff {
-        stat: StatType,
-        value: i32,
-        duration: u32,
-    },
-    Teleport,
-    Identify,
+/// ECS 物品组件：直接持有 items 模块的物品
+///
+/// 名称、数量、鉴定、诅咒与充能等状态都以 `item` 为准，可通过 `Deref` 直接访问；
+/// 使用方式由 `items::Usable` 决定。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ECSItem {
+    pub item: game_items::Item,
 }
 
 impl ECSItem {
-    /// 创建基础物品（不带详细数据）
-    pub fn new_basic(name: String, item_type: ItemType, value: u32) -> Self {
-        Self {
-            name,
-            item_type,
-            value,
-            identified: false,
-            quantity: 1,
-            level: 0,
-            cursed: false,
-            charges: None,
-            detailed_data: None,
-        }
-    }
-
-    /// 从 items::Item 创建 ECSItem（包含完整数据）
-    pub fn from_items_item(item: &items::Item) -> Result<Self, Box<dyn std::error::Error>> {
-        // 序列化完整的 items::Item
-        let detailed_data = bincode::encode_to_vec(item, bincode::config::standard())?;
-
-        // 映射基础类型
-        let item_type = Self::map_item_kind_to_type(&item.kind);
-
-        Ok(Self {
-            name: item.name.clone(),
-            item_type,
-            value: item.value(),
-            identified: !item.needs_identify(),
-            quantity: item.quantity,
-            level: 0,      // items::Item 没有直接的 level 字段
-            cursed: false, // 需要根据具体物品类型判断
-            charges: None, // 需要根据具体物品类型提取
-            detailed_data: Some(detailed_data),
-        })
-    }
-
-    /// 将 items::ItemKind 映射到 ItemType
-    fn map_item_kind_to_type(kind: &items::ItemKind) -> ItemType {
-        match kind {
-            items::ItemKind::Weapon(w) => ItemType::Weapon {
-                damage: w.damage.0, // 使用 damage 元组的第一个值（最小伤害）
-            },
-            items::ItemKind::Armor(a) => ItemType::Armor {
-                defense: a.defense as u32,
-            },
-            items::ItemKind::Potion(_) => ItemType::Consumable {
-                effect: ConsumableEffect::Healing { amount: 10 }, // 简化处理
-            },
-            items::ItemKind::Food(_) => ItemType::Consumable {
-                effect: ConsumableEffect::Healing { amount: 5 },
-            },
-            items::ItemKind::Scroll(_) => ItemType::Consumable {
-                effect: ConsumableEffect::Identify,
-            },
-            items::ItemKind::Throwable(t) => ItemType::Throwable {
-                damage: t.damage,
-                range: t.range,
-            },
-            items::ItemKind::Herb(_) => ItemType::Consumable {
-                effect: ConsumableEffect::Healing { amount: 8 },
-            },
-            _ => ItemType::Quest, // 其他类型映射为任务物品
-        }
+    pub fn new(item: game_items::Item) -> Self {
+        Self { item }
     }
 
-    /// 转换回 items::Item（如果有详细数据）
-    pub fn to_items_item(&self) -> Result<items::Item, Box<dyn std::error::Error>> {
-        if let Some(ref data) = self.detailed_data {
-            let (item, _): (items::Item, _) =
-                bincode::decode_from_slice(data, bincode::config::standard())?;
-            Ok(item)
-        } else {
-            Err("No detailed data available".into())
-        }
+    /// 按本局的鉴定知识刷新物品外观与名称
+    pub fn apply_knowledge(&mut self, knowledge: &game_items::ItemKnowledge) {
+        knowledge.apply(&mut self.item);
     }
+}
 
-    /// 是否为可堆叠物品
-    pub fn is_stackable(&self) -> bool {
-        matches!(
-            self.item_type,
-            ItemType::Consumable { .. } | ItemType::Throwable { .. }
-        )
+impl From<game_items::Item> for ECSItem {
+    fn from(item: game_items::Item) -> Self {
+        Self::new(item)
     }
+}
 
-    /// 是否可用
-    pub fn is_usable(&self) -> bool {
-        matches!(
-            self.item_type,
-            ItemType::Consumable { .. } | ItemType::Throwable { .. }
-        )
-    }
+impl std::ops::Deref for ECSItem {
+    type Target = game_items::Item;
 
-    /// 是否可装备
-    pub fn is_equippable(&self) -> bool {
-        matches!(
-            self.item_type,
-            ItemType::Weapon { .. } | ItemType::Armor { .. }
-        )
+    fn deref(&self) -> &Self::Target {
+        &self.item
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub enum StatType {
-    Hp,
-    Attack,
-    Defense,
-    Accuracy,
-    Evasion,
+impl std::ops::DerefMut for ECSItem {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.item
+    }
 }
 
 /// FOV（视野）算法类型
@@ -1462,6 +1741,492 @@ impl PlayerProgress {
     }
 }
 
+/// 快捷栏的格子数（对应数字键 1-9）
+pub const QUICKSLOT_COUNT: usize = 9;
+
+/// 快捷栏中的一格：记住物品的种类而不是背包位置
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickslotEntry {
+    /// 物品种类标识，见 `Quickslot::key_of`
+    pub key: String,
+    /// 放入快捷栏时的名称（物品用完后仍用它显示）
+    pub name: String,
+}
+
+/// 玩家的快捷栏
+///
+/// 格子按物品种类查找背包中的物品，物品在背包中移动后仍然有效；
+/// 整堆用完后该格保留但被跳过，重新拾到同种物品时自动恢复。
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Quickslot {
+    pub slots: Vec<Option<QuickslotEntry>>,
+}
+
+impl Quickslot {
+    /// 物品在快捷栏中的标识：同种物品不论数量、鉴定与充能状态都共用一个标识。
+    /// 武器、护甲、戒指等装备不能放入快捷栏。
+    pub fn key_of(item: &game_items::Item) -> Option<String> {
+        let key = match &item.kind {
+            game_items::ItemKind::Potion(p) => format!("potion:{:?}:{}", p.kind, p.exotic),
+            game_items::ItemKind::Scroll(s) => format!("scroll:{:?}:{}", s.kind, s.exotic),
+            game_items::ItemKind::Wand(w) => format!("wand:{:?}", w.kind),
+            game_items::ItemKind::Stone(s) => format!("stone:{:?}", s.kind),
+            game_items::ItemKind::Seed(s) => format!("seed:{:?}", s.kind),
+            game_items::ItemKind::Herb(h) => format!("herb:{:?}", h.kind),
+            game_items::ItemKind::Food(f) => format!("food:{:?}", f.kind),
+            game_items::ItemKind::Throwable(t) => format!("throwable:{:?}", t.kind),
+            game_items::ItemKind::Signature(s) => format!("signature:{:?}", s.kind),
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    pub fn entry(&self, index: usize) -> Option<&QuickslotEntry> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// 快捷栏中是否已有该种物品
+    pub fn index_of(&self, key: &str) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(|entry| entry.key == key))
+    }
+
+    /// 放入第一个空格，返回放入的格子；快捷栏已满时返回 None
+    pub fn assign(&mut self, entry: QuickslotEntry) -> Option<usize> {
+        self.slots.resize(QUICKSLOT_COUNT, None);
+        let index = self.slots.iter().position(Option::is_none)?;
+        self.slots[index] = Some(entry);
+        Some(index)
+    }
+
+    pub fn clear(&mut self, index: usize) {
+        if let Some(slot) = self.slots.get_mut(index) {
+            *slot = None;
+        }
+    }
+
+    /// 快捷栏某一格对应的背包格子；该种物品已经用完时返回 None
+    pub fn resolve(&self, inventory: &Inventory, index: usize) -> Option<usize> {
+        let entry = self.entry(index)?;
+        inventory.items.iter().position(|slot| {
+            slot.item
+                .as_ref()
+                .and_then(|item| Self::key_of(item))
+                .is_some_and(|key| key == entry.key)
+        })
+    }
+}
+
+/// 主动技能冷却（按技能索引记录可再次使用的回合）
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SkillCooldowns {
+    pub ready_at: Vec<u32>,
+}
+
+impl SkillCooldowns {
+    pub fn is_ready(&self, skill: usize, turn: u32) -> bool {
+        self.ready_at.get(skill).is_none_or(|ready| turn >= *ready)
+    }
+
+    pub fn start(&mut self, skill: usize, turn: u32, cooldown: u32) {
+        if self.ready_at.len() <= skill {
+            self.ready_at.resize(skill + 1, 0);
+        }
+        self.ready_at[skill] = turn + cooldown;
+    }
+}
+
+/// 活体大地法杖召唤的守卫（再次施法会强化已有守卫）
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EarthGuardian {
+    pub level: u8,
+}
+
+/// 普通敌人的种类，死亡时据此掉落战利品（Boss 按 [`BossComponent`] 掉落）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LootTable {
+    pub kind: combat::enemy::EnemyKind,
+}
+
+/// 吸收伤害的护盾（破损的纹章提供），伤害先扣除护盾再扣除生命值
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Shielding {
+    pub amount: u32,
+}
+
+/// 装备组件：武器、护甲、两枚戒指与神器
+///
+/// 装备中的物品不再占用背包格子；战斗时读取武器附魔与护甲刻印，
+/// 属性加成由 [`Equipment::bonuses`] 汇总后叠加到 [`BaseStats`] 上。
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Equipment {
+    pub weapon: Option<game_items::Weapon>,
+    pub armor: Option<game_items::Armor>,
+    pub rings: [Option<game_items::Ring>; 2],
+    pub artifact: Option<game_items::Item>,
+}
+
+/// 装备槽位（按 `UnequipItem` 的索引顺序排列）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Ring(usize),
+    Artifact,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 5] = [
+        EquipSlot::Weapon,
+        EquipSlot::Armor,
+        EquipSlot::Ring(0),
+        EquipSlot::Ring(1),
+        EquipSlot::Artifact,
+    ];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "weapon",
+            EquipSlot::Armor => "armor",
+            EquipSlot::Ring(_) => "ring",
+            EquipSlot::Artifact => "artifact",
+        }
+    }
+}
+
+/// 装备提供的属性加成
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EquipmentBonus {
+    pub attack: i32,
+    pub defense: i32,
+    pub accuracy: i32,
+    pub evasion: i32,
+    /// 额外暴击率
+    pub crit: f32,
+    /// 移动耗能减免比例
+    pub haste: f32,
+    /// 掉落与金币加成比例
+    pub wealth: f32,
+    /// 攻击耗能增加比例（沉重武器与力量不足）
+    pub attack_cost: f32,
+    /// 力量不足导致的移动耗能增加比例
+    pub move_cost: f32,
+}
+
+impl Equipment {
+    /// 戒指效果的基础值，经 `Ring::effect_value` 按等级放大
+    const RING_ACCURACY_BASE: f32 = 5.0;
+    const RING_EVASION_BASE: f32 = 5.0;
+    const RING_CRIT_BASE: f32 = 0.05;
+    const RING_HASTE_BASE: f32 = 0.1;
+    const RING_WEALTH_BASE: f32 = 0.1;
+
+    /// 汇总所有装备的属性加成
+    pub fn bonuses(&self) -> EquipmentBonus {
+        let mut bonus = EquipmentBonus::default();
+
+        if let Some(weapon) = &self.weapon {
+            bonus.attack += ((weapon.damage.0 + weapon.damage.1) / 2 + weapon.damage_bonus()) as i32;
+            bonus.accuracy += weapon.accuracy_bonus();
+            bonus.attack_cost += weapon.kind.swing_cost();
+        }
+        if let Some(armor) = &self.armor {
+            bonus.defense += armor.defense() as i32;
+            bonus.evasion -= armor.evasion_penalty() as i32;
+        }
+        for ring in self.rings.iter().flatten() {
+            match ring.kind {
+                game_items::ring::RingKind::Accuracy => {
+                    bonus.accuracy += ring.effect_value(Self::RING_ACCURACY_BASE).round() as i32;
+                }
+                game_items::ring::RingKind::Evasion => {
+                    bonus.evasion += ring.effect_value(Self::RING_EVASION_BASE).round() as i32;
+                }
+                game_items::ring::RingKind::Sharpshooting | game_items::ring::RingKind::Furor => {
+                    bonus.crit += ring.effect_value(Self::RING_CRIT_BASE);
+                }
+                game_items::ring::RingKind::Haste => {
+                    bonus.haste += ring.effect_value(Self::RING_HASTE_BASE);
+                }
+                game_items::ring::RingKind::Wealth => {
+                    bonus.wealth += ring.effect_value(Self::RING_WEALTH_BASE);
+                }
+                _ => {}
+            }
+        }
+
+        bonus
+    }
+
+    /// 每点力量不足降低的命中与闪避
+    const STR_ACCURACY_PENALTY: i32 = 10;
+    const STR_EVASION_PENALTY: i32 = 5;
+    /// 每点力量不足增加的耗能比例
+    const STR_ENERGY_PENALTY: f32 = 0.2;
+
+    /// 计入力量需求后的装备加成
+    ///
+    /// 武器每缺一点力量降低命中并拖慢攻击，护甲每缺一点力量降低闪避并拖慢移动；
+    /// 武器的富余力量每点增加一点攻击。
+    pub fn bonuses_for_strength(&self, strength: u8) -> EquipmentBonus {
+        let mut bonus = self.bonuses();
+
+        if let Some(weapon) = &self.weapon {
+            let diff = strength as i32 - weapon.str_requirement as i32;
+            if diff < 0 {
+                bonus.accuracy -= Self::STR_ACCURACY_PENALTY * -diff;
+                bonus.attack_cost += Self::STR_ENERGY_PENALTY * -diff as f32;
+            } else {
+                bonus.attack += diff;
+            }
+        }
+        if let Some(armor) = &self.armor {
+            let deficit = armor.str_requirement as i32 - strength as i32;
+            if deficit > 0 {
+                bonus.evasion -= Self::STR_EVASION_PENALTY * deficit;
+                bonus.move_cost += Self::STR_ENERGY_PENALTY * deficit as f32;
+            }
+        }
+
+        bonus
+    }
+
+    /// 实体当前装备的总加成，拥有 `PlayerProgress` 的实体计入力量需求
+    pub fn bonuses_of(world: &World, entity: Entity) -> EquipmentBonus {
+        let Ok(equipment) = world.get::<&Equipment>(entity) else {
+            return EquipmentBonus::default();
+        };
+        match world.get::<&PlayerProgress>(entity) {
+            Ok(progress) => equipment.bonuses_for_strength(progress.strength),
+            Err(_) => equipment.bonuses(),
+        }
+    }
+
+    /// 力量不足以驾驭的装备槽位
+    pub fn strength_deficits(&self, strength: u8) -> Vec<EquipSlot> {
+        let mut slots = Vec::new();
+        if self.weapon.as_ref().is_some_and(|w| w.str_requirement > strength) {
+            slots.push(EquipSlot::Weapon);
+        }
+        if self.armor.as_ref().is_some_and(|a| a.str_requirement > strength) {
+            slots.push(EquipSlot::Armor);
+        }
+        slots
+    }
+
+    /// 物品将要占用的槽位
+    ///
+    /// 戒指优先放入空槽，其次替换未被诅咒的戒指，两枚都被诅咒时指向第一枚。
+    pub fn slot_for(&self, item: &game_items::Item) -> Option<EquipSlot> {
+        match &item.kind {
+            game_items::ItemKind::Weapon(_) => Some(EquipSlot::Weapon),
+            game_items::ItemKind::Armor(_) => Some(EquipSlot::Armor),
+            game_items::ItemKind::Ring(_) => {
+                let index = self
+                    .rings
+                    .iter()
+                    .position(Option::is_none)
+                    .or_else(|| self.rings.iter().position(|r| r.as_ref().is_some_and(|r| !r.cursed)))
+                    .unwrap_or(0);
+                Some(EquipSlot::Ring(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// 槽位中的物品是否被诅咒（被诅咒的装备无法卸下）
+    pub fn is_cursed(&self, slot: EquipSlot) -> bool {
+        match slot {
+            EquipSlot::Weapon => self.weapon.as_ref().is_some_and(|w| w.cursed),
+            EquipSlot::Armor => self.armor.as_ref().is_some_and(|a| a.cursed),
+            EquipSlot::Ring(index) => self.rings.get(index).is_some_and(|r| r.as_ref().is_some_and(|r| r.cursed)),
+            EquipSlot::Artifact => self.artifact.as_ref().is_some_and(|a| a.is_cursed()),
+        }
+    }
+
+    /// 穿戴后揭示槽位中物品的诅咒，返回是否被诅咒
+    pub fn reveal_curse(&mut self, slot: EquipSlot) -> bool {
+        if let (EquipSlot::Armor, Some(armor)) = (slot, self.armor.as_mut()) {
+            armor.identify_curse();
+        }
+        self.is_cursed(slot)
+    }
+
+    /// 解除所有装备上的诅咒，返回被净化的物品名称
+    pub fn remove_curses(&mut self) -> Vec<String> {
+        let cursed: Vec<EquipSlot> = EquipSlot::ALL.into_iter().filter(|slot| self.is_cursed(*slot)).collect();
+        let mut names = Vec::new();
+        for slot in cursed {
+            match slot {
+                EquipSlot::Weapon => self.weapon.iter_mut().for_each(|w| w.remove_curse()),
+                EquipSlot::Armor => self.armor.iter_mut().for_each(|a| a.remove_curse()),
+                EquipSlot::Ring(index) => self.rings[index].iter_mut().for_each(|r| r.remove_curse()),
+                EquipSlot::Artifact => {
+                    self.artifact.iter_mut().for_each(|a| {
+                        a.remove_curse();
+                    });
+                }
+            }
+            names.extend(self.slot_name(slot));
+        }
+        names
+    }
+
+    /// 穿戴物品，返回占用的槽位和被替换下来的物品；无法装备时原样退回
+    ///
+    /// 两个戒指槽都有戒指时替换第一枚未被诅咒的戒指。
+    pub fn equip(
+        &mut self,
+        item: game_items::Item,
+    ) -> Result<(EquipSlot, Option<game_items::Item>), game_items::Item> {
+        let Some(slot) = self.slot_for(&item) else {
+            return Err(item);
+        };
+
+        let previous = self.unequip(slot);
+        match item.kind {
+            game_items::ItemKind::Weapon(weapon) => self.weapon = Some(weapon),
+            game_items::ItemKind::Armor(armor) => self.armor = Some(armor),
+            game_items::ItemKind::Ring(ring) => {
+                if let EquipSlot::Ring(index) = slot {
+                    self.rings[index] = Some(ring);
+                }
+            }
+            _ => unreachable!("槽位已按物品种类确定"),
+        }
+
+        Ok((slot, previous))
+    }
+
+    /// 卸下槽位中的物品
+    pub fn unequip(&mut self, slot: EquipSlot) -> Option<game_items::Item> {
+        match slot {
+            EquipSlot::Weapon => self
+                .weapon
+                .take()
+                .map(|weapon| game_items::Item::new(game_items::ItemKind::Weapon(weapon))),
+            EquipSlot::Armor => self
+                .armor
+                .take()
+                .map(|armor| game_items::Item::new(game_items::ItemKind::Armor(armor))),
+            EquipSlot::Ring(index) => self
+                .rings
+                .get_mut(index)
+                .and_then(Option::take)
+                .map(|ring| game_items::Item::new(game_items::ItemKind::Ring(ring))),
+            EquipSlot::Artifact => self.artifact.take(),
+        }
+    }
+
+    /// 槽位中物品的显示名称
+    ///
+    /// 穿戴中的诅咒物品已被揭示，名称后附加诅咒标记。
+    pub fn slot_name(&self, slot: EquipSlot) -> Option<String> {
+        let name = match slot {
+            EquipSlot::Weapon => self.weapon.as_ref().map(|w| w.name.clone()),
+            EquipSlot::Armor => self.armor.as_ref().map(|a| a.name()),
+            EquipSlot::Ring(index) => self.rings.get(index)?.as_ref().map(|r| r.name()),
+            EquipSlot::Artifact => self.artifact.as_ref().map(|a| a.name()),
+        }?;
+        // 护甲名称在诅咒揭示后自带标记
+        if self.is_cursed(slot) && slot != EquipSlot::Armor {
+            Some(format!("{} (诅咒)", name))
+        } else {
+            Some(name)
+        }
+    }
+}
+
+/// 未计入装备加成的基础属性，`Stats` 中的对应数值由它与装备加成推导
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BaseStats {
+    pub attack: u32,
+    pub defense: u32,
+    pub accuracy: u32,
+    pub evasion: u32,
+}
+
+impl BaseStats {
+    pub fn from_stats(stats: &Stats) -> Self {
+        Self {
+            attack: stats.attack,
+            defense: stats.defense,
+            accuracy: stats.accuracy,
+            evasion: stats.evasion,
+        }
+    }
+}
+
+impl Stats {
+    /// 根据基础属性与装备加成重新计算派生属性
+    pub fn apply_equipment(&mut self, base: &BaseStats, bonus: &EquipmentBonus) {
+        let add = |value: u32, delta: i32| (value as i32 + delta).max(0) as u32;
+        self.attack = add(base.attack, bonus.attack);
+        self.defense = add(base.defense, bonus.defense);
+        self.accuracy = add(base.accuracy, bonus.accuracy);
+        self.evasion = add(base.evasion, bonus.evasion);
+    }
+}
+
+/// 角色提升一级：按职业成长提高生命上限、攻击与防御并回满生命，返回新等级
+///
+/// 同时更新 `BaseStats`，使之后的装备重算不会丢失成长。
+pub fn level_up(world: &mut World, entity: Entity) -> Option<u32> {
+    let (level, attack, defense) = {
+        let mut stats = world.get::<&mut Stats>(entity).ok()?;
+        let (hp, attack, defense) = match &stats.class {
+            Some(class) => (class.hp_per_level(), class.attack_per_level(), class.defense_per_level()),
+            None => (4, 1, 1),
+        };
+        stats.level += 1;
+        stats.max_hp += hp;
+        stats.hp = stats.max_hp;
+        stats.attack += attack;
+        stats.defense += defense;
+        (stats.level, attack, defense)
+    };
+    if let Ok(mut base) = world.get::<&mut BaseStats>(entity) {
+        base.attack += attack;
+        base.defense += defense;
+    }
+    Some(level)
+}
+
+/// 增加经验，攒满时连续升级，返回途中升到的各个等级
+pub fn gain_experience(world: &mut World, entity: Entity, amount: u32) -> Vec<u32> {
+    let mut levels = Vec::new();
+    match world.get::<&mut Stats>(entity) {
+        Ok(mut stats) => stats.experience += amount,
+        Err(_) => return levels,
+    }
+    loop {
+        {
+            let Ok(mut stats) = world.get::<&mut Stats>(entity) else {
+                break;
+            };
+            let needed = Hero::max_exp(stats.level);
+            if stats.experience < needed {
+                break;
+            }
+            stats.experience -= needed;
+        }
+        levels.extend(level_up(world, entity));
+    }
+    levels
+}
+
+/// 击杀获得的经验：敌人比英雄低 2 级以内给予全部经验，
+/// 之后每低一级少给五分之一，低 7 级及以上不再给予经验
+pub fn kill_experience(base: u32, hero_level: u32, enemy_level: u32) -> u32 {
+    let gap = hero_level.saturating_sub(enemy_level + 2);
+    base * 5u32.saturating_sub(gap) / 5
+}
+
 // ========== Boss 相关组件 ==========
 
 /// Boss 标记组件
@@ -1612,16 +2377,68 @@ impl ECSWorld {
         let hero_class = hero.class.clone();
         let hero_skill_state = hero.class_skills.clone();
 
-        // Extract player energy and hunger state
+        // Extract player energy, hunger state, quickslots and inventory items
         let mut player_energy = 100u32;
         let mut player_hunger_last_turn = 0u32;
+        let mut quickslots = Vec::new();
+        let mut inventory_items = Vec::new();
+        let mut equipment = save::EquipmentData::default();
+        let mut base_stats = None;
+        let mut shield = 0;
+        let mut skill_cooldowns = Vec::new();
         if let Some((entity, _player_marker)) = self.world.query::<&Player>().iter().next() {
+            // 装备中的物品不在背包里，需要单独保存
+            if let Ok(worn) = self.world.get::<&Equipment>(entity) {
+                equipment = save::EquipmentData {
+                    weapon: worn.weapon.clone(),
+                    armor: worn.armor.clone(),
+                    rings: worn.rings.clone(),
+                    artifact: worn.artifact.clone(),
+                };
+            }
+            if let Ok(base) = self.world.get::<&BaseStats>(entity) {
+                base_stats = Some(save::BaseStatsData {
+                    attack: base.attack,
+                    defense: base.defense,
+                    accuracy: base.accuracy,
+                    evasion: base.evasion,
+                });
+            }
+            if let Ok(shielding) = self.world.get::<&Shielding>(entity) {
+                shield = shielding.amount;
+            }
+            if let Ok(cooldowns) = self.world.get::<&SkillCooldowns>(entity) {
+                skill_cooldowns = cooldowns.ready_at.clone();
+            }
+            if let Ok(inventory) = self.world.get::<&Inventory>(entity) {
+                inventory_items = inventory
+                    .items
+                    .iter()
+                    .filter_map(|slot| {
+                        let mut item = slot.item.as_ref()?.item.clone();
+                        item.quantity = slot.quantity.max(1);
+                        Some(item)
+                    })
+                    .collect();
+            }
             if let Ok(energy) = self.world.get::<&Energy>(entity) {
                 player_energy = energy.current;
             }
             if let Ok(hunger) = self.world.get::<&Hunger>(entity) {
                 player_hunger_last_turn = hunger.last_hunger_turn;
             }
+            if let Ok(quickslot) = self.world.get::<&Quickslot>(entity) {
+                quickslots = quickslot
+                    .slots
+                    .iter()
+                    .map(|slot| {
+                        slot.as_ref().map(|entry| save::QuickslotData {
+                            key: entry.key.clone(),
+                            name: entry.name.clone(),
+                        })
+                    })
+                    .collect();
+            }
         }
 
         // Extract turn system state
@@ -1706,6 +2523,13 @@ impl ECSWorld {
             player_energy,
             player_hunger_last_turn,
             entities,
+            item_knowledge: self.resources.item_knowledge.clone(),
+            quickslots,
+            inventory: inventory_items,
+            equipment,
+            base_stats,
+            shield,
+            skill_cooldowns,
         };
 
         Ok(save_data)
@@ -1721,8 +2545,9 @@ impl ECSWorld {
         self.clear();
 
         // Set up resources from save data
-        self.resources.rng = StdRng::seed_from_u64(save_data.game_seed);
+        self.resources.reseed_rng(save_data.game_seed);
         self.resources.game_state.depth = save_data.metadata.dungeon_depth;
+        self.resources.item_knowledge = save_data.item_knowledge.clone();
 
         // Restore clock state
         self.resources.clock.turn_count = save_data.clock_state.turn_count;
@@ -1736,8 +2561,32 @@ impl ECSWorld {
         hero.class = save_data.metadata.hero_class.clone();
         hero.class_skills = save_data.hero_skill_state.clone();
 
-        let stats: Stats = (&hero).into();
-        let inventory: Inventory = (&hero.bag).into();
+        let mut stats: Stats = (&hero).into();
+
+        // v6 起恢复装备与基础属性，派生属性按它们重新计算
+        let equipment = Equipment {
+            weapon: save_data.equipment.weapon,
+            armor: save_data.equipment.armor,
+            rings: save_data.equipment.rings,
+            artifact: save_data.equipment.artifact,
+        };
+        let base = match save_data.base_stats {
+            Some(base) => BaseStats {
+                attack: base.attack,
+                defense: base.defense,
+                accuracy: base.accuracy,
+                evasion: base.evasion,
+            },
+            None => BaseStats::from_stats(&stats),
+        };
+        stats.apply_equipment(&base, &equipment.bonuses_for_strength(hero.strength));
+
+        // v5 起存档直接保存背包中的物品；旧存档从 bag 重建
+        let inventory: Inventory = if save_data.inventory.is_empty() {
+            (&hero.bag).into()
+        } else {
+            save_data.inventory.clone().into()
+        };
 
         // ========== 新增：创建新组件 ==========
         let mut hunger: Hunger = (&hero).into();
@@ -1745,8 +2594,21 @@ impl ECSWorld {
         let wealth: Wealth = (&hero).into();
         let progress: PlayerProgress = (&hero).into();
 
+        let quickslot = Quickslot {
+            slots: save_data
+                .quickslots
+                .iter()
+                .map(|slot| {
+                    slot.as_ref().map(|data| QuickslotEntry {
+                        key: data.key.clone(),
+                        name: data.name.clone(),
+                    })
+                })
+                .collect(),
+        };
+
         // Spawn player entity with converted components（包含新组件）
-        self.world.spawn((
+        let player = self.world.spawn((
             Position::new(hero.x, hero.y, save_data.metadata.dungeon_depth as i32),
             Actor {
                 name: hero.name.clone(),
@@ -1775,9 +2637,30 @@ impl ECSWorld {
                 max: 100,
                 regeneration_rate: 1,
             },
-            Player, // Player marker component
+            quickslot, // 快捷栏
+            Player,    // Player marker component
         ));
 
+        // 装备、护盾与技能冷却
+        let _ = self.world.insert(
+            player,
+            (
+                equipment,
+                base,
+                SkillCooldowns {
+                    ready_at: save_data.skill_cooldowns,
+                },
+            ),
+        );
+        if save_data.shield > 0 {
+            let _ = self.world.insert_one(
+                player,
+                Shielding {
+                    amount: save_data.shield,
+                },
+            );
+        }
+
         // Restore non-player entities (enemies, NPCs, etc.)
         // Note: Full entity restoration would require more complex logic
         // For now, we'll skip this and let the game regenerate enemies
@@ -1833,52 +2716,13 @@ pub fn set_dungeon_instance(world: &mut World, dungeon: dungeon::Dungeon) {
 
 impl From<&Inventory> for Bag {
     fn from(inventory: &Inventory) -> Self {
-        use game_items::ItemTrait;
-
         let mut bag = Bag::new();
 
         for slot in &inventory.items {
             if let Some(item) = &slot.item {
-                if let Ok(mut source_item) = item.to_items_item() {
-                    let quantity = slot.quantity.max(1);
-
-                    if source_item.is_stackable() {
-                        source_item.quantity = 1;
-                    }
-
-                    for _ in 0..quantity {
-                        let _ = bag.add_item(source_item.clone());
-                    }
-
-                    continue;
-                }
-
-                let fallback_kind = match &item.item_type {
-                    ItemType::Weapon { .. } => game_items::ItemKind::Weapon(
-                        game_items::Weapon::new(1, game_items::weapon::WeaponKind::Dagger),
-                    ),
-                    ItemType::Armor { .. } => {
-                        game_items::ItemKind::Armor(game_items::Armor::new(1))
-                    }
-                    ItemType::Consumable { .. } => game_items::ItemKind::Potion(
-                        game_items::Potion::new_alchemy(game_items::potion::PotionKind::Healing),
-                    ),
-                    ItemType::Throwable { .. } => game_items::ItemKind::Throwable(
-                        game_items::Throwable::new(game_items::ThrowableKind::Dart),
-                    ),
-                    ItemType::Key => game_items::ItemKind::Misc(game_items::MiscItem::new(
-                        game_items::misc::MiscKind::Torch,
-                    )),
-                    ItemType::Quest => game_items::ItemKind::Misc(game_items::MiscItem::new(
-                        game_items::misc::MiscKind::Gold(10),
-                    )),
-                };
-
-                let fallback_item = game_items::Item::new(fallback_kind);
-                let iterations = slot.quantity.max(1);
-                for _ in 0..iterations {
-                    let _ = bag.add_item(fallback_item.clone());
-                }
+                let mut item = item.item.clone();
+                item.quantity = slot.quantity.max(1);
+                let _ = bag.add_item(item);
             }
         }
 
@@ -1904,26 +2748,43 @@ where
     }
 }
 
+/// 从存档恢复的物品栏至少拥有的格子数
+const BAG_DEFAULT_CAPACITY: usize = 64;
+
+impl From<Vec<game_items::Item>> for Inventory {
+    fn from(items: Vec<game_items::Item>) -> Self {
+        let item_count = items.len();
+        let items = items
+            .into_iter()
+            .map(|item| ItemSlot {
+                quantity: item.quantity.max(1),
+                item: Some(ECSItem::new(item)),
+            })
+            .collect();
+
+        Inventory {
+            items,
+            max_slots: BAG_DEFAULT_CAPACITY.max(item_count + 8),
+        }
+    }
+}
+
 impl From<&Bag> for Inventory {
     fn from(bag: &Bag) -> Self {
         let mut items: Vec<ItemSlot> = Vec::new();
 
-        const BAG_DEFAULT_CAPACITY: usize = 64;
-
         fn push_from_collection(
             collection: Vec<(game_items::Item, u32)>,
             slots: &mut Vec<ItemSlot>,
         ) {
             for (item, count) in collection {
-                if let Ok(mut ecs_item) = ECSItem::from_items_item(&item) {
-                    let quantity = count.max(1);
-                    ecs_item.quantity = quantity;
-                    ecs_item.identified = !item.needs_identify();
-                    slots.push(ItemSlot {
-                        item: Some(ecs_item),
-                        quantity,
-                    });
-                }
+                let quantity = count.max(1);
+                let mut ecs_item = ECSItem::new(item);
+                ecs_item.quantity = quantity;
+                slots.push(ItemSlot {
+                    item: Some(ecs_item),
+                    quantity,
+                });
             }
         }
 
@@ -2048,6 +2909,41 @@ mod tests {
     use super::*;
     use crate::event_bus::GameEvent;
 
+    #[test]
+    fn test_line_to_follows_bresenham() {
+        let from = Position::new(0, 0, 1);
+        let path = from.line_to(&Position::new(4, 2, 1));
+
+        let coords: Vec<_> = path.iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(coords, vec![(1, 0), (2, 1), (3, 1), (4, 2)]);
+        assert!(path.iter().all(|p| p.z == 1));
+        assert!(from.line_to(&from).is_empty());
+    }
+
+    #[test]
+    fn test_strength_requirements_penalize_and_reward() {
+        let equipment = Equipment {
+            weapon: Some(game_items::Weapon::new(2, game_items::weapon::WeaponKind::Sword)),
+            armor: Some(game_items::Armor::new(3)),
+            ..Default::default()
+        };
+        let base = equipment.bonuses();
+
+        // 长剑需要 13 力量，锁甲需要 14 力量
+        let weak = equipment.bonuses_for_strength(11);
+        assert_eq!(weak.accuracy, base.accuracy - 20);
+        assert_eq!(weak.evasion, base.evasion - 15);
+        assert!((weak.attack_cost - 0.4).abs() < f32::EPSILON);
+        assert!((weak.move_cost - 0.6).abs() < f32::EPSILON);
+        assert_eq!(equipment.strength_deficits(11), vec![EquipSlot::Weapon, EquipSlot::Armor]);
+
+        let strong = equipment.bonuses_for_strength(16);
+        assert_eq!(strong.attack, base.attack + 3);
+        assert_eq!(strong.accuracy, base.accuracy);
+        assert_eq!(strong.move_cost, 0.0);
+        assert!(equipment.strength_deficits(16).is_empty());
+    }
+
     #[test]
     fn test_event_bus_integration() {
         let mut world = ECSWorld::new();
@@ -2221,6 +3117,157 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_save_roundtrip_keeps_inventory_items_intact() {
+        let mut ecs_world = ECSWorld::new();
+        set_dungeon_instance(
+            &mut ecs_world.world,
+            dungeon::Dungeon::generate(1, 4242).expect("generate dungeon"),
+        );
+
+        let mut weapon = game_items::Weapon::new(2, game_items::weapon::WeaponKind::Sword);
+        weapon.upgrade();
+        weapon.cursed = true;
+        let mut wand = game_items::Wand::new_cursed(game_items::wand::WandKind::Frost, 1);
+        wand.use_wand();
+        let mut potions = game_items::Item::new(game_items::ItemKind::Potion(
+            game_items::Potion::new_alchemy(game_items::potion::PotionKind::Healing),
+        ));
+        potions.quantity = 3;
+        let items = vec![
+            game_items::Item::new(game_items::ItemKind::Wand(wand)),
+            game_items::Item::new(game_items::ItemKind::Weapon(weapon)),
+            potions,
+        ];
+
+        ecs_world.world.spawn((
+            Player,
+            Position::new(3, 3, 1),
+            Stats {
+                hp: 20,
+                max_hp: 20,
+                attack: 5,
+                defense: 1,
+                accuracy: 80,
+                evasion: 10,
+                level: 1,
+                experience: 0,
+                class: Some(Class::Warrior),
+            },
+            Inventory::from(items.clone()),
+        ));
+
+        let save_data = ecs_world
+            .to_save_data(&crate::turn_system::TurnSystem::new())
+            .expect("build save data");
+        let mut restored = ECSWorld::new();
+        restored.from_save_data(save_data).expect("restore save data");
+
+        let inventory = restored
+            .world
+            .query::<(&Player, &Inventory)>()
+            .iter()
+            .next()
+            .map(|(_, (_, inventory))| inventory.clone())
+            .expect("player inventory");
+        let restored_items: Vec<_> = inventory
+            .items
+            .iter()
+            .filter_map(|slot| slot.item.as_ref().map(|item| item.item.clone()))
+            .collect();
+        assert_eq!(restored_items, items);
+        assert_eq!(inventory.items[2].quantity, 3);
+    }
+
+    /// 生成带有地牢与玩家的世界，存档后载入到新的世界中
+    fn save_and_restore(components: impl hecs::DynamicBundle) -> (ECSWorld, Entity) {
+        let mut ecs_world = ECSWorld::new();
+        set_dungeon_instance(
+            &mut ecs_world.world,
+            dungeon::Dungeon::generate(1, 4242).expect("generate dungeon"),
+        );
+        let player = ecs_world.world.spawn(components);
+        let _ = ecs_world.world.insert(
+            player,
+            (
+                Player,
+                Position::new(3, 3, 1),
+                Stats {
+                    hp: 20,
+                    max_hp: 20,
+                    attack: 5,
+                    defense: 1,
+                    accuracy: 80,
+                    evasion: 10,
+                    level: 1,
+                    experience: 0,
+                    class: Some(Class::Warrior),
+                },
+            ),
+        );
+        crate::systems::InventorySystem::refresh_derived_stats(&mut ecs_world.world, player);
+
+        let save_data = ecs_world
+            .to_save_data(&crate::turn_system::TurnSystem::new())
+            .expect("build save data");
+        let mut restored = ECSWorld::new();
+        restored.from_save_data(save_data).expect("restore save data");
+        let player = restored
+            .world
+            .query::<&Player>()
+            .iter()
+            .next()
+            .map(|(entity, _)| entity)
+            .expect("player entity");
+        (restored, player)
+    }
+
+    #[test]
+    fn test_save_roundtrip_keeps_equipment() {
+        let weapon = game_items::Weapon::new(2, game_items::weapon::WeaponKind::Sword);
+        let armor = game_items::Armor::new(2);
+        let equipment = Equipment {
+            weapon: Some(weapon.clone()),
+            armor: Some(armor.clone()),
+            ..Default::default()
+        };
+        let (restored, player) = save_and_restore((equipment,));
+
+        let worn = restored.world.get::<&Equipment>(player).expect("equipment");
+        assert_eq!(worn.weapon, Some(weapon));
+        assert_eq!(worn.armor, Some(armor));
+
+        // 基础属性不含装备加成，派生属性仍然叠加装备
+        let base = restored.world.get::<&BaseStats>(player).expect("base stats");
+        assert_eq!(base.attack, 5);
+        assert_eq!(base.defense, 1);
+        let bonus = Equipment::bonuses_of(&restored.world, player);
+        assert!(bonus.attack > 0 && bonus.defense > 0);
+        let stats = restored.world.get::<&Stats>(player).expect("stats");
+        assert_eq!(stats.attack as i32, 5 + bonus.attack);
+        assert_eq!(stats.defense as i32, 1 + bonus.defense);
+    }
+
+    #[test]
+    fn test_save_roundtrip_keeps_shield_cooldowns_and_signature() {
+        let seal = hero::class::Class::Warrior.signature_item();
+        let equipment = Equipment {
+            artifact: Some(seal.clone()),
+            ..Default::default()
+        };
+        let cooldowns = SkillCooldowns {
+            ready_at: vec![0, 57],
+        };
+        let (restored, player) =
+            save_and_restore((equipment, Shielding { amount: 6 }, cooldowns));
+
+        let worn = restored.world.get::<&Equipment>(player).expect("equipment");
+        assert_eq!(worn.artifact, Some(seal));
+        assert_eq!(restored.world.get::<&Shielding>(player).expect("shield").amount, 6);
+        let cooldowns = restored.world.get::<&SkillCooldowns>(player).expect("cooldowns");
+        assert_eq!(cooldowns.ready_at, vec![0, 57]);
+    }
+
     #[test]
     fn test_herb_and_throwable_roundtrip_conversion() {
         let mut bag = Bag::new();
@@ -395,6 +395,8 @@ pub enum GameEvent {
     // ===== 物品事件 =====
     /// 拾取物品
     ItemPickedUp { entity: u32, item_name: String },
+    /// 拾取金币（直接计入财富，不占背包格子）
+    GoldCollected { entity: u32, amount: u32 },
     /// 丢弃物品
     ItemDropped { entity: u32, item_name: String },
     /// 使用物品
@@ -403,6 +405,21 @@ pub enum GameEvent {
         item_name: String,
         effect: String,
     },
+    /// 在炼金釜中炼制出物品
+    ItemCrafted {
+        entity: u32,
+        item_name: String,
+        ingredients: Vec<String>,
+    },
+    /// 投掷物品（沿弹道飞行后的落点）
+    ItemThrown {
+        entity: u32,
+        item_name: String,
+        from_x: i32,
+        from_y: i32,
+        to_x: i32,
+        to_y: i32,
+    },
     /// 装备物品
     ItemEquipped {
         entity: u32,
@@ -435,6 +452,8 @@ pub enum GameEvent {
         food_name: String,
         satiety_restored: u8,
     },
+    /// 角色升级
+    LevelUp { entity: u32, new_level: u32 },
 
     // ===== 游戏状态事件 =====
     /// 回合结束（AI 结算完成并且状态重新切换到玩家）
@@ -1043,8 +1062,11 @@ impl GameEvent {
             GameEvent::AIDecisionMade { .. } => "AIDecisionMade",
             GameEvent::AITargetChanged { .. } => "AITargetChanged",
             GameEvent::ItemPickedUp { .. } => "ItemPickedUp",
+            GameEvent::GoldCollected { .. } => "GoldCollected",
             GameEvent::ItemDropped { .. } => "ItemDropped",
             GameEvent::ItemUsed { .. } => "ItemUsed",
+            GameEvent::ItemCrafted { .. } => "ItemCrafted",
+            GameEvent::ItemThrown { .. } => "ItemThrown",
             GameEvent::ItemEquipped { .. } => "ItemEquipped",
             GameEvent::ItemUnequipped { .. } => "ItemUnequipped",
             GameEvent::TurnEnded { .. } => "TurnEnded",
@@ -1065,6 +1087,7 @@ impl GameEvent {
             GameEvent::PlayerStarving { .. } => "PlayerStarving",
             GameEvent::StarvationDamage { .. } => "StarvationDamage",
             GameEvent::FoodEaten { .. } => "FoodEaten",
+            GameEvent::LevelUp { .. } => "LevelUp",
             GameEvent::BossEncountered { .. } => "BossEncountered",
             GameEvent::BossRoomEntered { .. } => "BossRoomEntered",
             GameEvent::BossPhaseChanged { .. } => "BossPhaseChanged",
@@ -1148,8 +1171,11 @@ impl GameEvent {
 
             // Item events
             GameEvent::ItemPickedUp { .. }
+            | GameEvent::GoldCollected { .. }
             | GameEvent::ItemDropped { .. }
             | GameEvent::ItemUsed { .. }
+            | GameEvent::ItemCrafted { .. }
+            | GameEvent::ItemThrown { .. }
             | GameEvent::ItemEquipped { .. }
             | GameEvent::ItemUnequipped { .. } => EventCategory::Items,
 
@@ -1215,7 +1241,8 @@ impl GameEvent {
             | GameEvent::PlayerHungry { .. }
             | GameEvent::PlayerStarving { .. }
             | GameEvent::StarvationDamage { .. }
-            | GameEvent::FoodEaten { .. } => EventCategory::Status,
+            | GameEvent::FoodEaten { .. }
+            | GameEvent::LevelUp { .. } => EventCategory::Status,
         }
     }
 }
@@ -1563,6 +1590,13 @@ impl EventHandler for LoggingHandler {
             } => {
                 format!("使用了 {}，{}", item_name, effect)
             }
+            GameEvent::ItemCrafted {
+                item_name,
+                ingredients,
+                ..
+            } => {
+                format!("用 {} 炼制出了 {}", ingredients.join("、"), item_name)
+            }
             GameEvent::LevelChanged {
                 old_level,
                 new_level,
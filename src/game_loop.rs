@@ -86,6 +86,7 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
             Box::new(FOVSystem),
             Box::new(CombatSystem),
             Box::new(EffectSystem::new()),
+            Box::new(AftermathSystem),
             Box::new(InventorySystem),
             Box::new(HungerSystem),
             Box::new(DungeonSystem),
@@ -93,6 +94,8 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
         
         let post_turn_upkeep_systems: Vec<Box<dyn System>> = vec![
             Box::new(EnergySystem),
+            Box::new(WandRechargeSystem),
+            Box::new(BlobSystem),
         ];
         
         let render_systems: Vec<Box<dyn System>> = vec![
@@ -251,19 +254,9 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                 bg_color: Some(Color::Black),
                 order: 1,
             },
-            ECSItem {
-                name: "Health Potion".to_string(),
-                item_type: ItemType::Consumable {
-                    effect: ConsumableEffect::Healing { amount: 20 },
-                },
-                value: 10,
-                identified: true,
-                quantity: 1,
-                level: 0,
-                cursed: false,
-                charges: None,
-                detailed_data: None,
-            },
+            ECSItem::new(items::Item::new(items::ItemKind::Potion(items::Potion::new_alchemy(
+                items::potion::PotionKind::Healing,
+            )))),
             Tile {
                 terrain_type: TerrainType::Empty,
                 is_passable: true,
@@ -395,10 +388,18 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                             | PlayerAction::OpenOptions
                             | PlayerAction::OpenHelp
                             | PlayerAction::OpenCharacterInfo
+                            | PlayerAction::OpenCatalog
                             | PlayerAction::CloseMenu
                             | PlayerAction::MenuNavigate(_)
                             | PlayerAction::MenuSelect
                             | PlayerAction::MenuBack
+                            | PlayerAction::BeginTargeting(_)
+                            | PlayerAction::MoveCursor(_)
+                            | PlayerAction::CycleTarget
+                            | PlayerAction::ConfirmTarget
+                            | PlayerAction::UseQuickslot(_)
+                            | PlayerAction::AssignQuickslot(_)
+                            | PlayerAction::SplitStack(_)
                             | PlayerAction::Quit => {
                                 self.ecs_world
                                     .resources
@@ -407,6 +408,14 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                                     .push(action);
                             }
                             _ => {
+                                // 在物品栏中执行物品操作后返回游戏，由物品系统在下一帧结算
+                                if matches!(
+                                    self.ecs_world.resources.game_state.game_state,
+                                    GameStatus::Inventory { .. } | GameStatus::Alchemy { .. }
+                                ) {
+                                    self.ecs_world.resources.game_state.game_state =
+                                        GameStatus::Running;
+                                }
                                 self.ecs_world
                                     .resources
                                     .input_buffer
@@ -493,10 +502,15 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                 | GameStatus::Paused { .. }
                 | GameStatus::Options { .. }
                 | GameStatus::Inventory { .. }
+                | GameStatus::ItemPicker { .. }
+                | GameStatus::PickupChooser { .. }
                 | GameStatus::Help
                 | GameStatus::CharacterInfo
+                | GameStatus::ItemCatalog
+                | GameStatus::Alchemy { .. }
                 | GameStatus::ConfirmQuit { .. }
                 | GameStatus::ClassSelection { .. }
+                | GameStatus::Targeting { .. }
         );
 
         // 第1阶段：PreInput - 时间系统（总是运行）
@@ -580,6 +594,32 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                         }
                     }
                 }
+                "AftermathSystem" => {
+                    match AftermathSystem::run_with_events(&mut self.ecs_world) {
+                        SystemResult::Continue => continue,
+                        SystemResult::Stop => {
+                            self.is_running = false;
+                            return Ok(());
+                        }
+                        SystemResult::Error(msg) => {
+                            eprintln!("System error: {}", msg);
+                            return Err(anyhow::anyhow!(msg));
+                        }
+                    }
+                }
+                "InventorySystem" => {
+                    match InventorySystem::run_with_events(&mut self.ecs_world) {
+                        SystemResult::Continue => continue,
+                        SystemResult::Stop => {
+                            self.is_running = false;
+                            return Ok(());
+                        }
+                        SystemResult::Error(msg) => {
+                            eprintln!("System error: {}", msg);
+                            return Err(anyhow::anyhow!(msg));
+                        }
+                    }
+                }
                 "HungerSystem" => {
                     match HungerSystem::run_with_events(&mut self.ecs_world) {
                         SystemResult::Continue => continue,
@@ -606,6 +646,19 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                         }
                     }
                 }
+                "BlobSystem" => {
+                    match BlobSystem::run_with_events(&mut self.ecs_world) {
+                        SystemResult::Continue => continue,
+                        SystemResult::Stop => {
+                            self.is_running = false;
+                            return Ok(());
+                        }
+                        SystemResult::Error(msg) => {
+                            eprintln!("System error: {}", msg);
+                            return Err(anyhow::anyhow!(msg));
+                        }
+                    }
+                }
                 "AISystem" => {
                     match AISystem::run_with_events(&mut self.ecs_world) {
                         SystemResult::Continue => continue,
@@ -698,8 +751,12 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                 | GameStatus::Paused { .. }
                 | GameStatus::Options { .. }
                 | GameStatus::Inventory { .. }
+                | GameStatus::ItemPicker { .. }
+                | GameStatus::PickupChooser { .. }
                 | GameStatus::Help
                 | GameStatus::CharacterInfo
+                | GameStatus::ItemCatalog
+                | GameStatus::Alchemy { .. }
                 | GameStatus::ConfirmQuit { .. }
         );
         let is_menu = matches!(
@@ -708,8 +765,12 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
                 | GameStatus::Paused { .. }
                 | GameStatus::Options { .. }
                 | GameStatus::Inventory { .. }
+                | GameStatus::ItemPicker { .. }
+                | GameStatus::PickupChooser { .. }
                 | GameStatus::Help
                 | GameStatus::CharacterInfo
+                | GameStatus::ItemCatalog
+                | GameStatus::Alchemy { .. }
                 | GameStatus::ConfirmQuit { .. }
         );
 
@@ -777,6 +838,9 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
         // 重新生成地牢
         self.ecs_world.generate_and_set_dungeon(5, 42)?;
 
+        // 每局重新分配药水颜色、卷轴符文与戒指宝石
+        self.ecs_world.resources.item_knowledge = items::ItemKnowledge::from_seed(rand::random());
+
         // 获取起始位置
         let (start_x, start_y, _start_z) =
             if let Some(dungeon) = crate::ecs::get_dungeon_clone(&self.ecs_world.world) {
@@ -794,6 +858,7 @@ impl<R: Renderer, I: InputSource<Event = crate::input::InputEvent>, C: Clock> Ga
             start_y,
             class,
         );
+        InventorySystem::sync_item_knowledge(&mut self.ecs_world);
 
         // 生成一些敌人
         factory.create_monster(&mut self.ecs_world.world, start_x + 5, start_y, "goblin");
@@ -875,6 +940,126 @@ mod tests {
         assert_eq!(action_systems[1], "FOVSystem");
         assert_eq!(action_systems[2], "CombatSystem");
         assert_eq!(action_systems[3], "EffectSystem");
+        assert_eq!(action_systems[4], "AftermathSystem");
+    }
+
+    #[test]
+    fn test_kills_are_resolved_within_the_turn() {
+        let renderer = MockRenderer;
+        let input = MockInputSource::new();
+        let clock = MockClock;
+
+        let mut game_loop = GameLoop::new(renderer, input, clock);
+        game_loop.initialize().unwrap();
+        game_loop.ecs_world.resources.game_state.game_state = GameStatus::Running;
+
+        // 只剩 1 点生命的敌人在本回合末被烧死
+        let mut effects = StatusEffects::new();
+        effects.add_effect(combat::effect::Effect::new(EffectType::Burning, 3));
+        let enemy = game_loop.ecs_world.world.spawn((
+            Position::new(11, 10, 0),
+            Actor {
+                name: "Rat".to_string(),
+                faction: Faction::Enemy,
+            },
+            Stats {
+                hp: 1,
+                max_hp: 8,
+                attack: 2,
+                defense: 0,
+                accuracy: 50,
+                evasion: 5,
+                level: 1,
+                experience: 10,
+                class: None,
+            },
+            effects,
+        ));
+        game_loop.ecs_world.resources.clock.turn_count = 1;
+
+        game_loop.update_turn().unwrap();
+
+        let player = crate::systems::find_player_entity(&game_loop.ecs_world.world).unwrap();
+        assert!(game_loop.ecs_world.world.get::<&Stats>(enemy).is_err());
+        assert_eq!(game_loop.ecs_world.world.get::<&Stats>(player).unwrap().level, 2);
+    }
+
+    /// 已初始化并处于游戏中的循环，玩家位于 (10, 10)
+    fn running_game_loop() -> GameLoop<MockRenderer, MockInputSource, MockClock> {
+        let mut game_loop = GameLoop::new(MockRenderer, MockInputSource::new(), MockClock);
+        game_loop.initialize().unwrap();
+        game_loop.ecs_world.resources.game_state.game_state = GameStatus::Running;
+        game_loop.ecs_world.resources.clock.turn_count = 1;
+        game_loop
+    }
+
+    /// 只剩 1 点生命、身上着火的老鼠，会在本回合末死去并按掉落表掉落战利品
+    fn spawn_burning_rat(ecs_world: &mut ECSWorld, pos: Position) -> hecs::Entity {
+        let mut effects = StatusEffects::new();
+        effects.add_effect(combat::effect::Effect::new(EffectType::Burning, 3));
+        ecs_world.world.spawn((
+            pos,
+            Actor {
+                name: "Rat".to_string(),
+                faction: Faction::Enemy,
+            },
+            Stats {
+                hp: 1,
+                max_hp: 8,
+                attack: 2,
+                defense: 0,
+                accuracy: 50,
+                evasion: 5,
+                level: 1,
+                experience: 10,
+                class: None,
+            },
+            effects,
+            LootTable {
+                kind: combat::enemy::EnemyKind::Rat,
+            },
+        ))
+    }
+
+    #[test]
+    fn test_enemy_loot_lands_where_it_died_during_play() {
+        let mut game_loop = running_game_loop();
+        let death_pos = Position::new(11, 10, 0);
+        let rat = spawn_burning_rat(&mut game_loop.ecs_world, death_pos.clone());
+
+        game_loop.update_turn().unwrap();
+
+        assert!(game_loop.ecs_world.world.get::<&Stats>(rat).is_err());
+        assert!(!InventorySystem::ground_items_at(&game_loop.ecs_world.world, &death_pos).is_empty());
+    }
+
+    #[test]
+    fn test_gold_dropped_during_play_can_be_collected() {
+        let mut game_loop = running_game_loop();
+        let death_pos = Position::new(11, 10, 0);
+        spawn_burning_rat(&mut game_loop.ecs_world, death_pos.clone());
+        game_loop.update_turn().unwrap();
+        let dropped_gold = InventorySystem::ground_items_at(&game_loop.ecs_world.world, &death_pos)
+            .into_iter()
+            .any(|entity| {
+                game_loop.ecs_world.world.get::<&ECSItem>(entity).is_ok_and(|item| {
+                    matches!(item.kind, items::ItemKind::Misc(ref misc) if matches!(misc.kind, items::MiscKind::Gold(_)))
+                })
+            });
+        assert!(dropped_gold);
+
+        // 下一回合走上金币堆，金币直接计入财富
+        game_loop
+            .ecs_world
+            .resources
+            .input_buffer
+            .pending_actions
+            .push(PlayerAction::Move(Direction::East));
+        game_loop.update_turn().unwrap();
+
+        let player = crate::systems::find_player_entity(&game_loop.ecs_world.world).unwrap();
+        assert!(game_loop.ecs_world.world.get::<&Wealth>(player).unwrap().gold > 0);
+        assert!(InventorySystem::ground_items_at(&game_loop.ecs_world.world, &death_pos).is_empty());
     }
 
     #[test]
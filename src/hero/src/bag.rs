@@ -10,7 +10,7 @@ use items::{
     food::Food,
     herb::{Herb, HerbKind},
     misc::{MiscItem, MiscKind},
-    potion::{Potion, PotionKind},
+    potion::Potion,
     ring::Ring,
     scroll::{Scroll, ScrollKind},
     seed::{Seed, SeedKind},
@@ -293,6 +293,8 @@ impl Bag {
                     }
                 }
             },
+            // 旧版背包没有专属物品的位置，它们只随存档中的物品栏保存
+            ItemKind::Signature(_) => Err(InventoryError::UnknownItemType),
         }
         .map_err(Into::into)
     }
@@ -303,15 +305,8 @@ impl Bag {
         herb_kind: HerbKind,
         seed_kind: SeedKind,
     ) -> Result<Item, BagError> {
-        let recipe = match (herb_kind, seed_kind) {
-            (HerbKind::Sungrass, SeedKind::Earthroot) => PotionKind::Healing,
-            (HerbKind::Moonleaf, SeedKind::Fadeleaf) => PotionKind::Invisibility,
-            (HerbKind::Nightshade, SeedKind::Sorrowmoss) => PotionKind::ToxicGas,
-            (HerbKind::SpiritMoss, SeedKind::Dreamfoil) => PotionKind::Purity,
-            (HerbKind::Dragonthorn, SeedKind::Stormvine) => PotionKind::Strength,
-            (HerbKind::Glowcap, SeedKind::Icecap) => PotionKind::MindVision,
-            _ => return Err(BagError::CombinationFailed),
-        };
+        let recipe = items::alchemy::herb_recipe(herb_kind, seed_kind)
+            .ok_or(BagError::CombinationFailed)?;
 
         let herb_index = self
             .herbs
@@ -681,6 +676,7 @@ impl Bag {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use items::potion::PotionKind;
 
     #[test]
     fn combine_reagents_creates_identified_potion() {
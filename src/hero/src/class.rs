@@ -217,25 +217,39 @@ impl Class {
                 Item::new(ItemKind::Weapon(Weapon::new(1, WeaponKind::Sword))),
                 Item::new(ItemKind::Armor(Armor::new(2))),
                 Item::new(ItemKind::Potion(PotionKind::Healing.into())), // 治疗药水
+                warrior::signature_item(),
             ],
             Class::Mage => vec![
                 Item::new(ItemKind::Weapon(Weapon::new(1, WeaponKind::Sword))),
                 Item::new(ItemKind::Armor(Armor::new(1))),
                 Item::new(ItemKind::Scroll(ScrollKind::Upgrade.into())), // 魔法卷轴
+                mage::signature_item(),
             ],
             Class::Rogue => vec![
                 Item::new(ItemKind::Weapon(Weapon::new(1, WeaponKind::Dagger))),
                 Item::new(ItemKind::Armor(Armor::new(1))),
                 Item::new(ItemKind::Potion(PotionKind::Invisibility.into())), // 隐身药水
+                rogue::signature_item(),
             ],
             Class::Huntress => vec![
                 Item::new(ItemKind::Weapon(Weapon::new(1, WeaponKind::Sword))),
                 Item::new(ItemKind::Armor(Armor::new(1))),
                 Item::new(ItemKind::Seed(Seed::random_new())), // 自然种子
+                huntress::signature_item(),
             ],
         }
     }
 
+    /// 职业专属物品
+    pub fn signature_item(&self) -> Item {
+        match self {
+            Class::Warrior => warrior::signature_item(),
+            Class::Mage => mage::signature_item(),
+            Class::Rogue => rogue::signature_item(),
+            Class::Huntress => huntress::signature_item(),
+        }
+    }
+
     // === 职业特性 ===
 
     /// 获取职业描述（SPD特色）
@@ -1 +1,22 @@
+// src/hero/src/class/huntress.rs
+use items::{
+    Item, Signature, SignatureKind,
+    throwable::{Throwable, ThrowableKind},
+};
 
+/// 灵能弓的射程
+pub const SPIRIT_BOW_RANGE: u8 = 6;
+
+/// 女猎手的专属物品：灵能弓
+pub fn signature_item() -> Item {
+    Signature::new(SignatureKind::SpiritBow).into()
+}
+
+/// 灵能弓射出的一支灵能箭，伤害随等级提升
+pub fn spirit_arrow(level: u32) -> Throwable {
+    let mut arrow = Throwable::new(ThrowableKind::Dart);
+    arrow.damage = (2 + level, 6 + 2 * level);
+    arrow.range = SPIRIT_BOW_RANGE;
+    arrow.quantity = 1;
+    arrow
+}
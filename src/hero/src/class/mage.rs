@@ -1 +1,10 @@
+// src/hero/src/class/mage.rs
+use items::{Item, Signature, SignatureKind};
 
+/// 法师之杖吸收法杖时额外提供的充能上限
+pub const STAFF_BONUS_CHARGES: u8 = 1;
+
+/// 法师的专属物品：法师之杖
+pub fn signature_item() -> Item {
+    Signature::new(SignatureKind::MagesStaff).into()
+}
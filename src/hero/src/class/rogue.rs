@@ -1 +1,12 @@
+// src/hero/src/class/rogue.rs
+use items::{Item, Signature, SignatureKind};
 
+/// 盗贼的专属物品：暗影斗篷
+pub fn signature_item() -> Item {
+    Signature::new(SignatureKind::CloakOfShadows).into()
+}
+
+/// 一次斗篷隐身的持续回合数，随等级增长
+pub fn cloak_duration(level: u32) -> u32 {
+    4 + level / 2
+}
@@ -1 +1,12 @@
+// src/hero/src/class/warrior.rs
+use items::{Item, Signature, SignatureKind};
 
+/// 战士的专属物品：破损的纹章
+pub fn signature_item() -> Item {
+    Signature::new(SignatureKind::BrokenSeal).into()
+}
+
+/// 纹章提供的护盾值，随等级增长
+pub fn seal_shield(level: u32) -> u32 {
+    4 + 2 * level
+}
@@ -240,10 +240,15 @@ impl Hero {
         }
     }
 
+    /// 从 `level` 级升到下一级所需的经验
+    pub fn max_exp(level: u32) -> u32 {
+        5 + level * 5
+    }
+
     pub fn gain_exp(&mut self, exp: u32) {
         self.experience += exp;
-        while self.experience >= self.level * 100 {
-            self.experience -= self.level * 100;
+        while self.experience >= Self::max_exp(self.level) {
+            self.experience -= Self::max_exp(self.level);
             self.level_up();
         }
     }
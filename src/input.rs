@@ -214,12 +214,45 @@ pub fn key_event_to_player_action(
 ) -> Option<PlayerAction> {
     // 根据游戏状态决定如何解释按键
     match game_state {
+        crate::ecs::GameStatus::Targeting { .. } => match_key_for_targeting_context(key),
+        crate::ecs::GameStatus::Inventory { selected_item } => {
+            // 物品栏中可以直接对选中物品进入瞄准模式
+            match key.code {
+                CrosstermKeyCode::Char('t') => Some(PlayerAction::BeginTargeting(
+                    TargetingPurpose::Throw(*selected_item),
+                )),
+                CrosstermKeyCode::Char('z') => Some(PlayerAction::BeginTargeting(
+                    TargetingPurpose::Zap(*selected_item),
+                )),
+                CrosstermKeyCode::Char('e') => Some(PlayerAction::EquipItem(*selected_item)),
+                CrosstermKeyCode::Char('p') => Some(PlayerAction::PlantSeed(*selected_item)),
+                CrosstermKeyCode::Char('f') => Some(PlayerAction::AssignQuickslot(*selected_item)),
+                CrosstermKeyCode::Char('v') => Some(PlayerAction::SplitStack(*selected_item)),
+                CrosstermKeyCode::Delete => Some(PlayerAction::DropItem(*selected_item)),
+                // 数字键 1-5 卸下对应装备槽（武器、护甲、戒指×2、神器）
+                CrosstermKeyCode::Char(c @ '1'..='5') => {
+                    Some(PlayerAction::UnequipItem(c as usize - '1' as usize))
+                }
+                _ => match_key_for_menu_context(key),
+            }
+        }
+        crate::ecs::GameStatus::Alchemy { chosen, .. } => {
+            // 炼金界面中 Enter 投入选中的材料，b 开始炼制
+            match key.code {
+                CrosstermKeyCode::Char('b') => {
+                    Some(PlayerAction::Brew(chosen.iter().flatten().copied().collect()))
+                }
+                _ => match_key_for_menu_context(key),
+            }
+        }
         crate::ecs::GameStatus::MainMenu { .. }
+        | crate::ecs::GameStatus::ItemPicker { .. }
+        | crate::ecs::GameStatus::PickupChooser { .. }
         | crate::ecs::GameStatus::Paused { .. }
         | crate::ecs::GameStatus::Options { .. }
-        | crate::ecs::GameStatus::Inventory { .. }
         | crate::ecs::GameStatus::Help
         | crate::ecs::GameStatus::CharacterInfo
+        | crate::ecs::GameStatus::ItemCatalog
         | crate::ecs::GameStatus::ClassSelection { .. }
         | crate::ecs::GameStatus::ConfirmQuit { .. } => {
             // 在菜单状态下，按键被解释为菜单导航
@@ -260,12 +293,43 @@ fn match_key_for_menu_context(key: CrosstermKeyEvent) -> Option<PlayerAction> {
         CrosstermKeyCode::Char('o') => Some(PlayerAction::OpenOptions),
         CrosstermKeyCode::Char('?') => Some(PlayerAction::OpenHelp),
         CrosstermKeyCode::Char('c') => Some(PlayerAction::OpenCharacterInfo),
+        CrosstermKeyCode::Char('x') => Some(PlayerAction::OpenCatalog),
         CrosstermKeyCode::Char('q') => Some(PlayerAction::Quit),
 
         _ => None,
     }
 }
 
+/// 处理瞄准模式中的按键
+fn match_key_for_targeting_context(key: CrosstermKeyEvent) -> Option<PlayerAction> {
+    match key.code {
+        // 光标移动（支持方向键、vi-keys、WASD）
+        CrosstermKeyCode::Up | CrosstermKeyCode::Char('k') | CrosstermKeyCode::Char('w') => {
+            Some(PlayerAction::MoveCursor(Direction::North))
+        }
+        CrosstermKeyCode::Down | CrosstermKeyCode::Char('j') | CrosstermKeyCode::Char('s') => {
+            Some(PlayerAction::MoveCursor(Direction::South))
+        }
+        CrosstermKeyCode::Left | CrosstermKeyCode::Char('h') | CrosstermKeyCode::Char('a') => {
+            Some(PlayerAction::MoveCursor(Direction::West))
+        }
+        CrosstermKeyCode::Right | CrosstermKeyCode::Char('l') | CrosstermKeyCode::Char('d') => {
+            Some(PlayerAction::MoveCursor(Direction::East))
+        }
+        CrosstermKeyCode::Char('y') => Some(PlayerAction::MoveCursor(Direction::NorthWest)),
+        CrosstermKeyCode::Char('u') => Some(PlayerAction::MoveCursor(Direction::NorthEast)),
+        CrosstermKeyCode::Char('b') => Some(PlayerAction::MoveCursor(Direction::SouthWest)),
+        CrosstermKeyCode::Char('n') => Some(PlayerAction::MoveCursor(Direction::SouthEast)),
+
+        // 切换目标、确认与取消
+        CrosstermKeyCode::Tab => Some(PlayerAction::CycleTarget),
+        CrosstermKeyCode::Enter => Some(PlayerAction::ConfirmTarget),
+        CrosstermKeyCode::Esc | CrosstermKeyCode::Backspace => Some(PlayerAction::CloseMenu),
+
+        _ => None,
+    }
+}
+
 /// 处理游戏上下文中的按键
 fn match_key_for_game_context(key: CrosstermKeyEvent) -> Option<PlayerAction> {
     match (key.code, key.modifiers) {
@@ -320,19 +384,24 @@ fn match_key_for_game_context(key: CrosstermKeyEvent) -> Option<PlayerAction> {
             Some(PlayerAction::Attack(Position { x: 1, y: 1, z: 0 }))
         }
 
+        // 瞄准：远程攻击与职业技能
+        (CrosstermKeyCode::Char('f'), _) => {
+            Some(PlayerAction::BeginTargeting(TargetingPurpose::RangedAttack))
+        }
+        (CrosstermKeyCode::Char('z'), _) => {
+            Some(PlayerAction::BeginTargeting(TargetingPurpose::Skill(0)))
+        }
+
         // Game control
         (CrosstermKeyCode::Char('q'), _) => Some(PlayerAction::Quit),
 
-        // Number keys for items/spells (for later implementation)
-        (CrosstermKeyCode::Char('1'), _) => Some(PlayerAction::UseItem(0)),
-        (CrosstermKeyCode::Char('2'), _) => Some(PlayerAction::UseItem(1)),
-        (CrosstermKeyCode::Char('3'), _) => Some(PlayerAction::UseItem(2)),
-        (CrosstermKeyCode::Char('4'), _) => Some(PlayerAction::UseItem(3)),
-        (CrosstermKeyCode::Char('5'), _) => Some(PlayerAction::UseItem(4)),
-        (CrosstermKeyCode::Char('6'), _) => Some(PlayerAction::UseItem(5)),
-        (CrosstermKeyCode::Char('7'), _) => Some(PlayerAction::UseItem(6)),
-        (CrosstermKeyCode::Char('8'), _) => Some(PlayerAction::UseItem(7)),
-        (CrosstermKeyCode::Char('9'), _) => Some(PlayerAction::UseItem(8)),
+        // 数字键使用快捷栏中的物品
+        (CrosstermKeyCode::Char(c @ '1'..='9'), _) => {
+            Some(PlayerAction::UseQuickslot(c as usize - '1' as usize))
+        }
+
+        // 拾起脚下的物品
+        (CrosstermKeyCode::Char('g'), _) => Some(PlayerAction::PickUp),
 
         // Drop item - 现在使用 Delete 键而不是 'd' 键
         (CrosstermKeyCode::Delete, _) => Some(PlayerAction::DropItem(0)), // Default to first item
@@ -342,6 +411,7 @@ fn match_key_for_game_context(key: CrosstermKeyEvent) -> Option<PlayerAction> {
         (CrosstermKeyCode::Char('o'), _) => Some(PlayerAction::OpenOptions),
         (CrosstermKeyCode::Char('?'), _) => Some(PlayerAction::OpenHelp),
         (CrosstermKeyCode::Char('c'), _) => Some(PlayerAction::OpenCharacterInfo),
+        (CrosstermKeyCode::Char('x'), _) => Some(PlayerAction::OpenCatalog),
         (CrosstermKeyCode::Esc, _) => Some(PlayerAction::CloseMenu), // 暂停游戏
 
         _ => None,
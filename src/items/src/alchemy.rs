@@ -0,0 +1,243 @@
+//src/items/src/alchemy.rs
+//! 炼金系统模块
+//!
+//! 所有配方都以数据表的形式定义：
+//! - 药草 + 种子 → 药水（`HERB_SEED_RECIPES`，与 `Bag::combine_reagents` 共用）
+//! - 三颗同种种子 → 种子对应的药水（`Seed::to_potion`）
+//! - 药水 + 催化种子 → 异变药水（`EXOTIC_CATALYSTS`）
+//! - 卷轴 → 两颗对应的魔法石（`StoneKind::from_scroll`）
+//!
+//! 炼金釜的界面和背包操作由游戏主体处理，这里只负责配方匹配与产物生成。
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::herb::HerbKind;
+use crate::potion::PotionKind;
+use crate::scroll::ScrollKind;
+use crate::seed::SeedKind;
+use crate::stone::StoneKind;
+use crate::{Herb, Item, ItemKind, Potion, Seed, Stone};
+
+/// 一次炼金最多投入的材料数量
+pub const MAX_INGREDIENTS: usize = 3;
+
+/// 卷轴化为魔法石时得到的数量
+pub const STONES_PER_SCROLL: u32 = 2;
+
+/// 药草与种子的固定配方
+pub const HERB_SEED_RECIPES: [(HerbKind, SeedKind, PotionKind); 6] = [
+    (HerbKind::Sungrass, SeedKind::Earthroot, PotionKind::Healing),
+    (HerbKind::Moonleaf, SeedKind::Fadeleaf, PotionKind::Invisibility),
+    (HerbKind::Nightshade, SeedKind::Sorrowmoss, PotionKind::ToxicGas),
+    (HerbKind::SpiritMoss, SeedKind::Dreamfoil, PotionKind::Purity),
+    (HerbKind::Dragonthorn, SeedKind::Stormvine, PotionKind::Strength),
+    (HerbKind::Glowcap, SeedKind::Icecap, PotionKind::MindVision),
+];
+
+/// 药水与催化种子：两者合炼得到对应的异变药水（力量药水没有异变形态）
+pub const EXOTIC_CATALYSTS: [(PotionKind, SeedKind); 11] = [
+    (PotionKind::Healing, SeedKind::Earthroot),
+    (PotionKind::Experience, SeedKind::Dreamfoil),
+    (PotionKind::ToxicGas, SeedKind::Sorrowmoss),
+    (PotionKind::ParalyticGas, SeedKind::Stormvine),
+    (PotionKind::LiquidFlame, SeedKind::Firebloom),
+    (PotionKind::Levitation, SeedKind::Stormvine),
+    (PotionKind::Invisibility, SeedKind::Fadeleaf),
+    (PotionKind::Purity, SeedKind::Dreamfoil),
+    (PotionKind::Frost, SeedKind::Icecap),
+    (PotionKind::MindVision, SeedKind::Dreamfoil),
+    (PotionKind::Haste, SeedKind::Fadeleaf),
+];
+
+/// 可以投入炼金釜的材料
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ingredient {
+    Herb(HerbKind),
+    Seed(SeedKind),
+    Potion(PotionKind),
+    Scroll(ScrollKind),
+}
+
+impl Ingredient {
+    /// 物品对应的炼金材料（异变药水不能再次炼制）
+    pub fn from_item(item: &Item) -> Option<Self> {
+        match &item.kind {
+            ItemKind::Herb(h) => Some(Ingredient::Herb(h.kind)),
+            ItemKind::Seed(s) => Some(Ingredient::Seed(s.kind)),
+            ItemKind::Potion(p) if !p.exotic => Some(Ingredient::Potion(p.kind)),
+            ItemKind::Scroll(s) => Some(Ingredient::Scroll(s.kind)),
+            _ => None,
+        }
+    }
+
+    /// 材料的真实名称
+    pub fn name(&self) -> String {
+        match self {
+            Ingredient::Herb(kind) => Herb::new(*kind).name(),
+            Ingredient::Seed(kind) => Seed::new(*kind).name(),
+            Ingredient::Potion(kind) => kind.name(),
+            Ingredient::Scroll(kind) => {
+                let mut scroll = crate::Scroll::new(*kind);
+                scroll.identify();
+                scroll.name()
+            }
+        }
+    }
+}
+
+/// 炼金产物
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlchemyProduct {
+    Potion(PotionKind),
+    ExoticPotion(PotionKind),
+    Stones(StoneKind, u32),
+}
+
+impl AlchemyProduct {
+    /// 生成产物物品（炼金产物总是已鉴定的）
+    pub fn create(&self) -> Item {
+        match self {
+            AlchemyProduct::Potion(kind) => Item::new(ItemKind::Potion(Potion::new_alchemy(*kind))),
+            AlchemyProduct::ExoticPotion(kind) => {
+                Item::new(ItemKind::Potion(Potion::new_exotic(*kind)))
+            }
+            AlchemyProduct::Stones(kind, count) => {
+                let mut item = Item::new(ItemKind::Stone(Stone::new(*kind)));
+                item.quantity = *count;
+                item
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            AlchemyProduct::Potion(kind) => kind.name(),
+            AlchemyProduct::ExoticPotion(kind) => Potion::new_exotic(*kind).name(),
+            AlchemyProduct::Stones(kind, count) => format!("{} ×{}", Stone::new(*kind).name(), count),
+        }
+    }
+}
+
+/// 一条炼金配方：材料的顺序不影响匹配
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recipe {
+    pub inputs: Vec<Ingredient>,
+    pub product: AlchemyProduct,
+}
+
+impl Recipe {
+    fn new(inputs: Vec<Ingredient>, product: AlchemyProduct) -> Self {
+        Self { inputs, product }
+    }
+
+    /// 配方的唯一标识（用于记录本局已发现的配方）
+    pub fn id(&self) -> String {
+        let inputs: Vec<String> = self.inputs.iter().map(|i| format!("{:?}", i)).collect();
+        format!("{}=>{:?}", inputs.join("+"), self.product)
+    }
+
+    /// 投入的材料是否恰好满足配方
+    pub fn matches(&self, ingredients: &[Ingredient]) -> bool {
+        if ingredients.len() != self.inputs.len() {
+            return false;
+        }
+        let mut remaining = self.inputs.clone();
+        ingredients.iter().all(|ingredient| {
+            match remaining.iter().position(|needed| needed == ingredient) {
+                Some(index) => {
+                    remaining.swap_remove(index);
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// 配方描述，如“日光草 + 地根草 → 治疗药水”
+    pub fn describe(&self) -> String {
+        let inputs: Vec<String> = self.inputs.iter().map(Ingredient::name).collect();
+        format!("{} → {}", inputs.join(" + "), self.product.name())
+    }
+}
+
+/// 药草与种子合炼得到的药水
+pub fn herb_recipe(herb: HerbKind, seed: SeedKind) -> Option<PotionKind> {
+    HERB_SEED_RECIPES
+        .iter()
+        .find(|(h, s, _)| *h == herb && *s == seed)
+        .map(|(_, _, potion)| *potion)
+}
+
+/// 全部炼金配方
+pub fn recipes() -> Vec<Recipe> {
+    let herb_seed = HERB_SEED_RECIPES.iter().map(|(herb, seed, potion)| {
+        Recipe::new(
+            vec![Ingredient::Herb(*herb), Ingredient::Seed(*seed)],
+            AlchemyProduct::Potion(*potion),
+        )
+    });
+    let seeds = SeedKind::iter().filter_map(|seed| {
+        let potion = Seed::new(seed).to_potion()?;
+        Some(Recipe::new(
+            vec![Ingredient::Seed(seed); MAX_INGREDIENTS],
+            AlchemyProduct::Potion(potion),
+        ))
+    });
+    let exotic = EXOTIC_CATALYSTS.iter().map(|(potion, seed)| {
+        Recipe::new(
+            vec![Ingredient::Potion(*potion), Ingredient::Seed(*seed)],
+            AlchemyProduct::ExoticPotion(*potion),
+        )
+    });
+    let stones = ScrollKind::iter().map(|scroll| {
+        Recipe::new(
+            vec![Ingredient::Scroll(scroll)],
+            AlchemyProduct::Stones(StoneKind::from_scroll(scroll), STONES_PER_SCROLL),
+        )
+    });
+
+    herb_seed.chain(seeds).chain(exotic).chain(stones).collect()
+}
+
+/// 查找与投入材料匹配的配方
+pub fn find_recipe(ingredients: &[Ingredient]) -> Option<Recipe> {
+    recipes().into_iter().find(|recipe| recipe.matches(ingredients))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipes_ignore_ingredient_order() {
+        let recipe = find_recipe(&[
+            Ingredient::Seed(SeedKind::Earthroot),
+            Ingredient::Herb(HerbKind::Sungrass),
+        ])
+        .expect("herb and seed should brew");
+        assert_eq!(recipe.product, AlchemyProduct::Potion(PotionKind::Healing));
+
+        let exotic = find_recipe(&[
+            Ingredient::Seed(SeedKind::Icecap),
+            Ingredient::Potion(PotionKind::Frost),
+        ])
+        .expect("potion and catalyst should brew");
+        assert_eq!(exotic.product, AlchemyProduct::ExoticPotion(PotionKind::Frost));
+        assert!(exotic.product.create().name.starts_with("异变"));
+    }
+
+    #[test]
+    fn test_scrolls_become_matching_stones() {
+        for scroll in ScrollKind::iter() {
+            let recipe = find_recipe(&[Ingredient::Scroll(scroll)]).expect("every scroll has a recipe");
+            let item = recipe.product.create();
+            assert_eq!(item.quantity, STONES_PER_SCROLL);
+            assert!(matches!(item.kind, ItemKind::Stone(ref s) if s.kind == StoneKind::from_scroll(scroll)));
+        }
+
+        // 腐浆果不能用于炼金，两颗种子也凑不成配方
+        assert!(find_recipe(&[Ingredient::Seed(SeedKind::Rotberry); 3]).is_none());
+        assert!(find_recipe(&[Ingredient::Seed(SeedKind::Icecap); 2]).is_none());
+    }
+}
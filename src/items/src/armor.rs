@@ -42,6 +42,30 @@ pub enum ArmorGlyph {
     Thorns,      // 荆棘 - 反弹部分近战伤害
 }
 
+impl ArmorGlyph {
+    /// 随机选取一种刻印
+    pub fn random() -> Self {
+        Self::random_with(&mut rand::rng())
+    }
+
+    /// 使用给定的随机数源选取刻印
+    pub fn random_with(rng: &mut impl Rng) -> Self {
+        let glyphs = [
+            ArmorGlyph::Affection,
+            ArmorGlyph::AntiEntropy,
+            ArmorGlyph::Brimstone,
+            ArmorGlyph::Camouflage,
+            ArmorGlyph::Flow,
+            ArmorGlyph::Obfuscation,
+            ArmorGlyph::Potential,
+            ArmorGlyph::Repulsion,
+            ArmorGlyph::Stone,
+            ArmorGlyph::Thorns,
+        ];
+        glyphs[rng.random_range(0..glyphs.len())].clone()
+    }
+}
+
 impl Armor {
     /// 创建新护甲
     pub fn new(tier: u32) -> Self {
@@ -75,20 +99,7 @@ impl Armor {
 
         // 15%概率有刻印（原版概率）
         if rng.random_bool(0.15) {
-            let glyphs = [
-                ArmorGlyph::Affection,
-                ArmorGlyph::AntiEntropy,
-                ArmorGlyph::Brimstone,
-                ArmorGlyph::Camouflage,
-                ArmorGlyph::Flow,
-                ArmorGlyph::Obfuscation,
-                ArmorGlyph::Potential,
-                ArmorGlyph::Repulsion,
-                ArmorGlyph::Stone,
-                ArmorGlyph::Thorns,
-            ];
-            let glyph = glyphs[rng.random_range(0..glyphs.len())].clone();
-            armor.inscribe(glyph);
+            armor.inscribe(ArmorGlyph::random());
         }
 
         // 15%概率被诅咒（原版概率）
@@ -99,6 +110,21 @@ impl Armor {
         armor
     }
 
+    /// 按深度随机生成护甲，品阶分布与同深度的武器相同
+    pub fn random_for_depth(depth: usize, rng: &mut impl Rng) -> Self {
+        let tier = crate::weapon::Tier::random_for_depth(depth, rng).to_u32();
+        let mut armor = Armor::new(tier);
+
+        if rng.random_bool(0.15) {
+            armor.inscribe(ArmorGlyph::random_with(rng));
+        }
+        if rng.random_bool(0.15) {
+            armor.curse();
+        }
+
+        armor
+    }
+
     /// 获取基础价值（根据品阶）
     fn base_value(tier: u32) -> u32 {
         match tier {
@@ -306,28 +332,69 @@ impl Armor {
         self.cursed_known = true;
     }
 
-    /// 触发刻印效果（简化版）
-    pub fn trigger_glyph(&self) -> Option<GlyphEffect> {
-        if !self.identified {
-            return None;
-        }
+    /// 受到攻击时触发刻印效果
+    ///
+    /// 刻印在未鉴定时同样生效；`damage` 为本次受到的伤害。
+    /// 流动、混淆、潜能为被动刻印，不在受击时触发。
+    pub fn trigger_glyph<R: Rng>(&self, damage: u32, rng: &mut R) -> Option<GlyphEffect> {
+        let level = self.upgrade_level as u32;
 
         self.glyph.as_ref().map(|glyph| match glyph {
-            ArmorGlyph::Thorns => GlyphEffect::ReflectDamage(1 + self.upgrade_level as u32 / 3),
-            ArmorGlyph::Repulsion => GlyphEffect::Knockback(2),
-            ArmorGlyph::Affection => GlyphEffect::Charm(10 + self.upgrade_level as u32 * 5),
-            _ => GlyphEffect::None,
+            ArmorGlyph::Thorns => GlyphEffect::ReflectDamage((damage / 3).max(1) + level / 3),
+            ArmorGlyph::Repulsion => {
+                // 基础20%，每级+5%
+                if rng.random_range(0..100) < 20 + level * 5 {
+                    GlyphEffect::Knockback(2)
+                } else {
+                    GlyphEffect::None
+                }
+            }
+            ArmorGlyph::Affection => {
+                if rng.random_range(0..100) < 10 + level * 5 {
+                    GlyphEffect::Charm(2 + level / 2)
+                } else {
+                    GlyphEffect::None
+                }
+            }
+            ArmorGlyph::AntiEntropy | ArmorGlyph::Brimstone | ArmorGlyph::Stone => {
+                GlyphEffect::Immunity(self.glyph_immunities())
+            }
+            ArmorGlyph::Camouflage => GlyphEffect::Invisibility(3 + level / 2),
+            ArmorGlyph::Flow | ArmorGlyph::Obfuscation | ArmorGlyph::Potential => {
+                GlyphEffect::None
+            }
         })
     }
+
+    /// 刻印提供的状态免疫
+    pub fn glyph_immunities(&self) -> &'static [GlyphImmunity] {
+        match self.glyph {
+            Some(ArmorGlyph::AntiEntropy) => &[GlyphImmunity::Burning, GlyphImmunity::Frost],
+            Some(ArmorGlyph::Brimstone) => &[GlyphImmunity::Burning],
+            Some(ArmorGlyph::Stone) => &[GlyphImmunity::Paralysis, GlyphImmunity::Poison],
+            _ => &[],
+        }
+    }
 }
 
 /// 刻印触发效果
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum GlyphEffect {
     None,
-    ReflectDamage(u32), // 反弹伤害值
-    Knockback(u32),     // 击退距离
-    Charm(u32),         // 魅惑概率(百分比)
+    ReflectDamage(u32),                  // 反弹伤害值
+    Knockback(u32),                      // 击退距离
+    Charm(u32),                          // 魅惑回合数
+    Immunity(&'static [GlyphImmunity]), // 免疫并清除的状态
+    Invisibility(u32),                   // 隐身回合数
+}
+
+/// 刻印可免疫的状态类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphImmunity {
+    Burning,   // 燃烧
+    Frost,     // 冰冻
+    Paralysis, // 瘫痪
+    Poison,    // 中毒
 }
 
 impl fmt::Display for ArmorGlyph {
@@ -0,0 +1,231 @@
+//src/items/src/knowledge.rs
+//! 物品外观与鉴定知识
+//!
+//! 每局游戏开始时按种子为药水分配颜色、为卷轴分配符文、为戒指分配宝石；
+//! 鉴定过一件物品后，同种类的物品在本局中都会显示真实名称。
+//! 炼金配方同样需要在本局中亲手炼制过一次才会被记住。
+
+use bincode::{Decode, Encode};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::alchemy::Recipe;
+use crate::potion::{PotionColor, PotionKind};
+use crate::ring::{RingGem, RingKind};
+use crate::scroll::{ScrollKind, ScrollRune};
+use crate::{Item, ItemCategory, ItemKind, Ring, Scroll};
+
+/// 本局的物品外观分配与已知种类
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+pub struct ItemKnowledge {
+    potion_colors: Vec<(PotionKind, PotionColor)>,
+    scroll_runes: Vec<(ScrollKind, ScrollRune)>,
+    ring_gems: Vec<(RingKind, RingGem)>,
+    known_potions: Vec<PotionKind>,
+    known_scrolls: Vec<ScrollKind>,
+    known_rings: Vec<RingKind>,
+    /// 本局已通过实验发现的炼金配方（`Recipe::id`）
+    #[serde(default)]
+    known_recipes: Vec<String>,
+}
+
+/// 图鉴中的一个条目
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    pub category: ItemCategory,
+    /// 本局的外观名称（如“红色药水”）
+    pub appearance: String,
+    /// 真实名称，未鉴定时为 None
+    pub name: Option<String>,
+}
+
+impl ItemKnowledge {
+    /// 按种子打乱外观分配（同一种子总是得到相同的分配，各种类互不重复）
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut colors: Vec<PotionColor> = PotionColor::iter().collect();
+        colors.shuffle(&mut rng);
+        let mut runes: Vec<ScrollRune> = ScrollRune::iter().collect();
+        runes.shuffle(&mut rng);
+        let mut gems: Vec<RingGem> = RingGem::iter().collect();
+        gems.shuffle(&mut rng);
+
+        Self {
+            potion_colors: PotionKind::iter().zip(colors).collect(),
+            scroll_runes: ScrollKind::iter().zip(runes).collect(),
+            ring_gems: RingKind::iter().zip(gems).collect(),
+            known_potions: Vec::new(),
+            known_scrolls: Vec::new(),
+            known_rings: Vec::new(),
+            known_recipes: Vec::new(),
+        }
+    }
+
+    pub fn potion_color(&self, kind: PotionKind) -> PotionColor {
+        self.potion_colors
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, color)| *color)
+            .unwrap_or_else(|| kind.standard_color())
+    }
+
+    pub fn scroll_rune(&self, kind: ScrollKind) -> ScrollRune {
+        self.scroll_runes
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, rune)| *rune)
+            .unwrap_or_else(|| kind.standard_rune())
+    }
+
+    pub fn ring_gem(&self, kind: RingKind) -> RingGem {
+        self.ring_gems
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, gem)| *gem)
+            .unwrap_or_else(|| kind.standard_gem())
+    }
+
+    /// 该物品的种类是否已被鉴定（没有外观之分的物品总是已知）
+    pub fn is_known(&self, kind: &ItemKind) -> bool {
+        match kind {
+            ItemKind::Potion(p) => self.known_potions.contains(&p.kind),
+            ItemKind::Scroll(s) => self.known_scrolls.contains(&s.kind),
+            ItemKind::Ring(r) => self.known_rings.contains(&r.kind),
+            _ => true,
+        }
+    }
+
+    /// 记住该物品的种类，首次得知时返回 true
+    pub fn learn(&mut self, kind: &ItemKind) -> bool {
+        if self.is_known(kind) {
+            return false;
+        }
+        match kind {
+            ItemKind::Potion(p) => self.known_potions.push(p.kind),
+            ItemKind::Scroll(s) => self.known_scrolls.push(s.kind),
+            ItemKind::Ring(r) => self.known_rings.push(r.kind),
+            _ => return false,
+        }
+        true
+    }
+
+    /// 是否已经发现该炼金配方
+    pub fn knows_recipe(&self, recipe: &Recipe) -> bool {
+        self.known_recipes.contains(&recipe.id())
+    }
+
+    /// 记住炼金配方，首次发现时返回 true
+    pub fn learn_recipe(&mut self, recipe: &Recipe) -> bool {
+        if self.knows_recipe(recipe) {
+            return false;
+        }
+        self.known_recipes.push(recipe.id());
+        true
+    }
+
+    /// 为物品套用本局外观，已知种类的物品同时被鉴定，并刷新显示名称
+    pub fn apply(&self, item: &mut Item) {
+        let known = self.is_known(&item.kind);
+        match &mut item.kind {
+            ItemKind::Potion(p) => {
+                p.color = self.potion_color(p.kind);
+                if known {
+                    p.identify();
+                }
+            }
+            ItemKind::Scroll(s) => {
+                s.rune = self.scroll_rune(s.kind);
+                if known {
+                    s.identify();
+                }
+            }
+            ItemKind::Ring(r) => {
+                r.gem = self.ring_gem(r.kind);
+                if known {
+                    r.identify();
+                }
+            }
+            _ => return,
+        }
+        item.name = item.name();
+    }
+
+    /// 药水、卷轴、戒指各种类的图鉴条目
+    pub fn catalog(&self) -> Vec<CatalogEntry> {
+        let potions = PotionKind::iter().map(|kind| CatalogEntry {
+            category: ItemCategory::Potion,
+            appearance: format!("{}药水", self.potion_color(kind).name()),
+            name: self.known_potions.contains(&kind).then(|| kind.name()),
+        });
+        let scrolls = ScrollKind::iter().map(|kind| {
+            let mut scroll = Scroll::new(kind);
+            scroll.rune = self.scroll_rune(kind);
+            let appearance = scroll.name();
+            scroll.identify();
+            CatalogEntry {
+                category: ItemCategory::Scroll,
+                appearance,
+                name: self.known_scrolls.contains(&kind).then(|| scroll.name()),
+            }
+        });
+        let rings = RingKind::iter().map(|kind| CatalogEntry {
+            category: ItemCategory::Ring,
+            appearance: format!("{}戒指", self.ring_gem(kind).name()),
+            name: self
+                .known_rings
+                .contains(&kind)
+                .then(|| Ring::new(kind, 0).base_name().to_string()),
+        });
+        potions.chain(scrolls).chain(rings).collect()
+    }
+}
+
+impl Default for ItemKnowledge {
+    fn default() -> Self {
+        Self::from_seed(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Potion;
+
+    #[test]
+    fn test_same_seed_gives_same_unique_appearances() {
+        let first = ItemKnowledge::from_seed(7);
+        assert_eq!(first, ItemKnowledge::from_seed(7));
+
+        let mut colors: Vec<_> = PotionKind::iter().map(|k| first.potion_color(k)).collect();
+        colors.sort_by_key(|c| c.name());
+        colors.dedup();
+        assert_eq!(colors.len(), PotionKind::iter().count());
+
+        let differs = (0..20).any(|seed| {
+            let other = ItemKnowledge::from_seed(seed);
+            PotionKind::iter().any(|k| other.potion_color(k) != first.potion_color(k))
+        });
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_learning_a_kind_identifies_later_items() {
+        let mut knowledge = ItemKnowledge::from_seed(3);
+        let mut potion = Item::from(Potion::from(PotionKind::Haste));
+        knowledge.apply(&mut potion);
+        let color = knowledge.potion_color(PotionKind::Haste);
+        assert_eq!(potion.name, format!("{}药水", color.name()));
+
+        assert!(knowledge.learn(&potion.kind));
+        assert!(!knowledge.learn(&potion.kind));
+        knowledge.apply(&mut potion);
+        assert_eq!(potion.name, PotionKind::Haste.name());
+
+        let known = knowledge.catalog().iter().filter(|entry| entry.name.is_some()).count();
+        assert_eq!(known, 1);
+    }
+}
@@ -10,26 +10,33 @@ use serde::{Deserialize, Serialize};
 pub use crate::armor::Armor;
 pub use crate::food::Food;
 pub use crate::herb::{Herb, HerbKind};
+pub use crate::knowledge::ItemKnowledge;
 pub use crate::misc::{MiscItem, MiscKind};
 pub use crate::potion::Potion;
 pub use crate::ring::Ring;
 pub use crate::scroll::Scroll;
 pub use crate::seed::Seed;
+pub use crate::signature::{Signature, SignatureKind};
 pub use crate::stone::Stone;
 pub use crate::throwable::{Throwable, ThrowableKind};
+pub use crate::usage::{ItemUsage, Usable};
 pub use crate::wand::Wand;
 pub use crate::weapon::Weapon;
 
+pub mod alchemy;
 pub mod armor;
 pub mod food;
 pub mod herb;
+pub mod knowledge;
 pub mod misc;
 pub mod potion;
 pub mod ring;
 pub mod scroll;
 pub mod seed;
+pub mod signature;
 pub mod stone;
 pub mod throwable;
+pub mod usage;
 pub mod wand;
 pub mod weapon;
 
@@ -59,6 +66,7 @@ pub enum ItemKind {
     Misc(MiscItem),       // 杂项（钥匙等）
     Throwable(Throwable), // 投掷武器
     Herb(Herb),           // 药草
+    Signature(Signature), // 职业专属物品
 }
 
 impl Item {
@@ -76,6 +84,7 @@ impl Item {
             ItemKind::Throwable(t) => t.name(),
             ItemKind::Herb(h) => h.name(),
             ItemKind::Misc(m) => m.name().clone(),
+            ItemKind::Signature(s) => s.name(),
         };
 
         Self {
@@ -102,6 +111,7 @@ impl Item {
             ItemKind::Throwable(t) => t.name(),
             ItemKind::Herb(h) => h.name(),
             ItemKind::Misc(m) => m.name().clone(),
+            ItemKind::Signature(s) => s.name(),
         }
     }
 
@@ -132,6 +142,168 @@ impl Item {
         }
     }
 
+    /// 设置堆叠数量，同时同步投掷武器、药草等自带的数量字段
+    pub fn set_quantity(&mut self, quantity: u32) {
+        self.quantity = quantity;
+        match &mut self.kind {
+            ItemKind::Throwable(t) => t.quantity = quantity,
+            ItemKind::Herb(h) => h.quantity = quantity,
+            ItemKind::Misc(m) => m.quantity = quantity,
+            _ => {}
+        }
+    }
+
+    /// 能否与另一件物品合并为一堆：同类、同名且 `stacking_id` 相同的可堆叠物品
+    pub fn stacks_with(&self, other: &Item) -> bool {
+        self.is_stackable()
+            && self.name == other.name
+            && std::mem::discriminant(&self.kind) == std::mem::discriminant(&other.kind)
+            && self.stacking_id() == other.stacking_id()
+    }
+
+    /// 物品是否已被鉴定（不需要鉴定的物品视为已鉴定）
+    pub fn is_identified(&self) -> bool {
+        !self.needs_identify()
+    }
+
+    /// 法杖的剩余充能
+    pub fn charges(&self) -> Option<u8> {
+        self.as_wand().map(|w| w.charges)
+    }
+
+    /// 物品是否被诅咒
+    pub fn is_cursed(&self) -> bool {
+        match &self.kind {
+            ItemKind::Weapon(w) => w.cursed,
+            ItemKind::Armor(a) => a.cursed,
+            ItemKind::Ring(r) => r.cursed,
+            ItemKind::Wand(w) => w.cursed,
+            _ => false,
+        }
+    }
+
+    /// 解除物品上的诅咒，返回物品原先是否被诅咒
+    pub fn remove_curse(&mut self) -> bool {
+        let cursed = self.is_cursed();
+        match &mut self.kind {
+            ItemKind::Weapon(w) => w.remove_curse(),
+            ItemKind::Armor(a) => a.remove_curse(),
+            ItemKind::Ring(r) => r.remove_curse(),
+            ItemKind::Wand(w) => w.remove_curse(),
+            _ => {}
+        }
+        cursed
+    }
+
+    /// 鉴定物品
+    pub fn identify(&mut self) {
+        match &mut self.kind {
+            ItemKind::Potion(p) => p.identify(),
+            ItemKind::Scroll(s) => s.identify(),
+            ItemKind::Ring(r) => r.identify(),
+            ItemKind::Wand(w) => w.identify(),
+            ItemKind::Weapon(w) => w.identify(),
+            ItemKind::Armor(a) => a.identify(),
+            ItemKind::Throwable(t) => t.identified = true,
+            ItemKind::Herb(h) => h.identified = true,
+            _ => {}
+        }
+        self.name = self.name();
+    }
+
+    /// 强化物品（同时解除诅咒），不可强化的物品返回 false
+    pub fn upgrade(&mut self) -> bool {
+        match &mut self.kind {
+            ItemKind::Weapon(w) => {
+                w.remove_curse();
+                w.upgrade();
+            }
+            ItemKind::Armor(a) => a.upgrade(),
+            ItemKind::Ring(r) => r.upgrade(),
+            ItemKind::Wand(w) => w.upgrade(),
+            _ => return false,
+        }
+        self.name = self.name();
+        true
+    }
+
+    /// 为武器附魔或为护甲刻印（替换原有的附魔与刻印），其他物品返回 false
+    pub fn enchant(&mut self) -> bool {
+        match &mut self.kind {
+            ItemKind::Weapon(w) => {
+                w.remove_curse();
+                w.enchanted = None;
+                w.add_random_enhancement();
+            }
+            ItemKind::Armor(a) => {
+                a.remove_curse();
+                a.inscribe(armor::ArmorGlyph::random());
+            }
+            _ => return false,
+        }
+        self.name = self.name();
+        true
+    }
+
+    /// 将物品变形为同类别的另一种物品，保留强化等级；无法变形时返回 None
+    ///
+    /// 武器只会变成同品阶目录中的其他武器，并保留强化、附魔与诅咒。
+    pub fn transmute(&self, rng: &mut impl rand::Rng) -> Option<Item> {
+        use rand::seq::IndexedRandom;
+        use strum::IntoEnumIterator;
+
+        let kind = match &self.kind {
+            ItemKind::Weapon(w) => {
+                let others: Vec<_> = w.tier.catalog().iter().filter(|spec| spec.kind != w.kind).collect();
+                let mut weapon = Weapon::from_spec(w.tier, others.choose(rng)?);
+                for _ in 0..w.upgrade_level {
+                    weapon.upgrade();
+                }
+                weapon.enchanted = w.enchanted.clone();
+                weapon.modifier = w.modifier.clone();
+                weapon.cursed = w.cursed;
+                weapon.identified = w.identified;
+                ItemKind::Weapon(weapon)
+            }
+            ItemKind::Ring(r) => {
+                let others: Vec<_> = ring::RingKind::iter().filter(|k| *k != r.kind).collect();
+                let mut ring = Ring::new(*others.choose(rng)?, r.level);
+                ring.cursed = r.cursed;
+                ring.identified = r.identified;
+                ItemKind::Ring(ring)
+            }
+            ItemKind::Wand(w) => {
+                let others: Vec<_> = wand::WandKind::iter().filter(|k| *k != w.kind).collect();
+                let mut wand = Wand::new(*others.choose(rng)?, w.level);
+                wand.charges = w.charges.min(wand.max_charges);
+                wand.cursed = w.cursed;
+                wand.identified = w.identified;
+                ItemKind::Wand(wand)
+            }
+            ItemKind::Potion(p) => {
+                let others: Vec<_> = potion::PotionKind::iter().filter(|k| *k != p.kind).collect();
+                ItemKind::Potion(Potion::new_alchemy(*others.choose(rng)?))
+            }
+            ItemKind::Scroll(s) => {
+                let others: Vec<_> = scroll::ScrollKind::iter().filter(|k| *k != s.kind).collect();
+                let mut scroll = Scroll::new(*others.choose(rng)?);
+                scroll.exotic = s.exotic;
+                ItemKind::Scroll(scroll)
+            }
+            ItemKind::Seed(s) => ItemKind::Seed(
+                std::iter::repeat_with(|| Seed::random_with(rng)).find(|seed| seed.kind != s.kind)?,
+            ),
+            ItemKind::Stone(s) => ItemKind::Stone(
+                std::iter::repeat_with(|| Stone::random_with(rng)).find(|stone| stone.kind != s.kind)?,
+            ),
+            _ => return None,
+        };
+
+        let mut item = Item::new(kind);
+        item.quantity = self.quantity;
+        Some(item)
+    }
+
     /// 获取物品价值（用于商店系统）
     pub fn value(&self) -> u32 {
         match &self.kind {
@@ -147,6 +319,8 @@ impl Item {
             ItemKind::Throwable(t) => t.value(),
             ItemKind::Herb(h) => h.value(),
             ItemKind::Misc(m) => m.value(),
+            // 专属物品无法出售
+            ItemKind::Signature(_) => 0,
         }
     }
 
@@ -334,6 +508,25 @@ pub enum ItemCategory {
     Misc,      // 杂项
 }
 
+impl ItemCategory {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ItemCategory::Weapon => "武器",
+            ItemCategory::Armor => "护甲",
+            ItemCategory::Potion => "药水",
+            ItemCategory::Scroll => "卷轴",
+            ItemCategory::Wand => "法杖",
+            ItemCategory::Ring => "戒指",
+            ItemCategory::Seed => "种子",
+            ItemCategory::Stone => "魔法石",
+            ItemCategory::Throwable => "投掷",
+            ItemCategory::Herb => "药草",
+            ItemCategory::Food => "食物",
+            ItemCategory::Misc => "杂项",
+        }
+    }
+}
+
 impl ItemTrait for Item {
     fn is_stackable(&self) -> bool {
         match &self.kind {
@@ -349,6 +542,7 @@ impl ItemTrait for Item {
             ItemKind::Throwable(t) => t.is_stackable(),
             ItemKind::Herb(h) => h.is_stackable(),
             ItemKind::Misc(m) => m.is_stackable(),
+            ItemKind::Signature(s) => s.is_stackable(),
         }
     }
 
@@ -366,6 +560,7 @@ impl ItemTrait for Item {
             ItemKind::Throwable(t) => t.max_stack(),
             ItemKind::Herb(h) => h.max_stack(),
             ItemKind::Misc(m) => m.max_stack(),
+            ItemKind::Signature(s) => s.max_stack(),
         }
     }
 
@@ -383,6 +578,7 @@ impl ItemTrait for Item {
             ItemKind::Throwable(t) => t.stacking_id(),
             ItemKind::Herb(h) => h.stacking_id(),
             ItemKind::Misc(m) => m.stacking_id(),
+            ItemKind::Signature(s) => s.stacking_id(),
         }
     }
 
@@ -400,6 +596,7 @@ impl ItemTrait for Item {
             ItemKind::Throwable(t) => t.name(),
             ItemKind::Herb(h) => h.name(),
             ItemKind::Misc(m) => m.name(),
+            ItemKind::Signature(s) => s.display_name(),
         }
     }
 
@@ -417,6 +614,7 @@ impl ItemTrait for Item {
             ItemKind::Herb(_) => ItemCategory::Herb,
             ItemKind::Food(_) => ItemCategory::Food,
             ItemKind::Misc(_) => ItemCategory::Misc,
+            ItemKind::Signature(s) => s.category(),
         }
     }
 
@@ -434,6 +632,7 @@ impl ItemTrait for Item {
             ItemKind::Throwable(t) => t.rarity(),
             ItemKind::Herb(h) => h.rarity(),
             ItemKind::Misc(m) => m.rarity(),
+            ItemKind::Signature(s) => s.rarity(),
         }
     }
 
@@ -451,6 +650,7 @@ impl ItemTrait for Item {
             ItemKind::Herb(h) => h.sort_value(),
             ItemKind::Food(f) => f.sort_value(),
             ItemKind::Misc(m) => m.sort_value(),
+            ItemKind::Signature(s) => s.sort_value(),
         }
     }
 }
@@ -26,6 +26,45 @@ pub enum MiscKind {
     Torch,
     /// 其他未分类物品
     Other,
+    /// 种子袋 - 收纳种子与药草
+    SeedPouch,
+    /// 卷轴筒 - 收纳卷轴
+    ScrollHolder,
+    /// 药剂带 - 收纳药水
+    PotionBandolier,
+    /// 法杖套 - 收纳法杖
+    WandHolster,
+}
+
+/// 每个容器可以收纳的格子数（与破碎的像素地牢一致）
+pub const CONTAINER_CAPACITY: usize = 19;
+
+impl MiscKind {
+    /// 所有容器类型
+    pub const CONTAINERS: [MiscKind; 4] = [
+        MiscKind::SeedPouch,
+        MiscKind::ScrollHolder,
+        MiscKind::PotionBandolier,
+        MiscKind::WandHolster,
+    ];
+
+    /// 是否是容器
+    ///
+    /// 容器中的物品不占用背包格子，也不会被火焰烧毁或被冰冻冻裂。
+    pub fn is_container(&self) -> bool {
+        Self::CONTAINERS.contains(self)
+    }
+
+    /// 容器能否收纳该种类的物品（非容器一律返回 false）
+    pub fn holds(&self, kind: &ItemKind) -> bool {
+        matches!(
+            (self, kind),
+            (MiscKind::SeedPouch, ItemKind::Seed(_) | ItemKind::Herb(_))
+                | (MiscKind::ScrollHolder, ItemKind::Scroll(_))
+                | (MiscKind::PotionBandolier, ItemKind::Potion(_))
+                | (MiscKind::WandHolster, ItemKind::Wand(_))
+        )
+    }
 }
 
 /// 杂项物品结构体
@@ -49,6 +88,9 @@ impl MiscItem {
             MiscKind::Honeypot => 60,
             MiscKind::Torch => 50,
             MiscKind::Other => 10,
+            MiscKind::SeedPouch => 80,
+            MiscKind::ScrollHolder | MiscKind::PotionBandolier => 100,
+            MiscKind::WandHolster => 120,
         };
 
         MiscItem {
@@ -70,7 +112,12 @@ impl MiscItem {
             MiscKind::Torch,
             MiscKind::Other,
         ];
-        let kind = kinds[rng.random_range(0..kinds.len())];
+        // 容器比较稀有
+        let kind = if rng.random_bool(0.1) {
+            MiscKind::CONTAINERS[rng.random_range(0..MiscKind::CONTAINERS.len())]
+        } else {
+            kinds[rng.random_range(0..kinds.len())]
+        };
 
         let mut item = MiscItem::new(kind);
 
@@ -115,6 +162,10 @@ impl MiscItem {
             MiscKind::Torch => "火把",
             MiscKind::Honeypot => "蜂巢罐",
             MiscKind::Other => "神秘碎片",
+            MiscKind::SeedPouch => "种子袋",
+            MiscKind::ScrollHolder => "卷轴筒",
+            MiscKind::PotionBandolier => "药剂带",
+            MiscKind::WandHolster => "法杖套",
         }
     }
 
@@ -183,6 +234,11 @@ impl ItemTrait for MiscItem {
             MiscKind::Key => 20,           // 钥匙20把
             MiscKind::Torch => 15,         // 火把15支
             MiscKind::Other => 30,         // 神秘碎片30个
+            // 容器不能堆叠
+            MiscKind::SeedPouch
+            | MiscKind::ScrollHolder
+            | MiscKind::PotionBandolier
+            | MiscKind::WandHolster => 1,
         }
     }
 
@@ -191,6 +247,7 @@ impl ItemTrait for MiscItem {
             MiscKind::Gold(_) => "金币".to_string(),
             MiscKind::Key => "钥匙".to_string(),
             MiscKind::Torch => "火把".to_string(),
+            kind if kind.is_container() => self.base_name().to_string(),
             // ... other misc items
             _ => "杂项".to_string(),
         }
@@ -205,6 +262,7 @@ impl ItemTrait for MiscItem {
             MiscKind::Gold(_) => 100,
             MiscKind::Key => 90,
             MiscKind::Torch => 80,
+            kind if kind.is_container() => 70,
             // ... other misc items
             _ => 0,
         }
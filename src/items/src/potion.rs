@@ -23,24 +23,31 @@ pub struct Potion {
     pub kind: PotionKind,
     pub identified: bool,   // 是否已鉴定
     pub color: PotionColor, // 药水颜色（即使已鉴定也保留）
+    /// 是否是异变药水（只能通过炼金获得，效果更强）
+    #[serde(default)]
+    pub exotic: bool,
 }
 
 impl Potion {
     /// 创建一个新的未鉴定的随机药水
     pub fn random_new() -> Self {
-        let mut rng = rand::rng();
+        Self::random_with(&mut rand::rng())
+    }
 
-        // 随机分配药水类型和颜色（确保不重复）
+    /// 使用给定的随机数源生成随机药水
+    pub fn random_with(rng: &mut impl rand::Rng) -> Self {
+        // 随机选择药水类型；本局的颜色由 ItemKnowledge 在拾取时分配
         let kind = *PotionKind::iter()
             .collect::<Vec<_>>()
-            .choose(&mut rng)
+            .choose(rng)
             .unwrap();
-        let color = PotionColor::assign_random_color(&kind);
+        let color = kind.standard_color();
 
         Potion {
             kind,
             identified: false,
             color,
+            exotic: false,
         }
     }
 
@@ -53,6 +60,15 @@ impl Potion {
             kind,
             identified: true, // 炼金产物总是已鉴定
             color,
+            exotic: false,
+        }
+    }
+
+    /// 创建一个异变药水（药水与种子合炼的产物）
+    pub fn new_exotic(kind: PotionKind) -> Self {
+        Potion {
+            exotic: true,
+            ..Self::new_alchemy(kind)
         }
     }
 
@@ -75,6 +91,7 @@ impl Potion {
         };
 
         // 状态修正
+        let base_value = if self.exotic { base_value * 2 } else { base_value };
 
         if !self.identified {
             (base_value as f32 * 0.5) as u32 // 未鉴定药水价值减半
@@ -105,11 +122,12 @@ impl Potion {
 
     /// 获取药水的名称
     pub fn name(&self) -> String {
-        if self.identified {
-            self.kind.name()
-        } else {
+        match (self.identified, self.exotic) {
+            (true, false) => self.kind.name(),
+            (true, true) => format!("异变{}", self.kind.name()),
             // 未鉴定时使用颜色名称
-            format!("{}药水", self.color.name())
+            (false, false) => format!("{}药水", self.color.name()),
+            (false, true) => format!("{}异变药水", self.color.name()),
         }
     }
 
@@ -118,13 +136,9 @@ impl Potion {
         self.kind.effect()
     }
 
-    /// 鉴定药水
+    /// 鉴定药水（颜色是本局的外观，鉴定后保留）
     pub fn identify(&mut self) {
-        if !self.identified {
-            self.identified = true;
-            // 鉴定后显示标准颜色
-            self.color = self.kind.standard_color();
-        }
+        self.identified = true;
     }
 }
 
@@ -235,21 +249,6 @@ pub enum PotionColor {
 }
 
 impl PotionColor {
-    /// 为药水类型分配随机颜色（确保不重复）
-    pub fn assign_random_color(kind: &PotionKind) -> Self {
-        // 使用药水类型的哈希值作为随机种子，确保相同类型在不同游戏中也不同
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        kind.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        let colors = PotionColor::iter().collect::<Vec<_>>();
-        let idx = (hash as usize) % colors.len();
-        colors[idx]
-    }
-
     /// 转换为tui颜色
     pub fn to_tui_color(&self) -> Color {
         match self {
@@ -305,6 +304,7 @@ impl Default for Potion {
             kind: PotionKind::Healing, // 默认类型：治疗药水（最基础类型）
             identified: false,         // 默认未鉴定
             color: PotionColor::Red,   // 治疗药水的标准颜色
+            exotic: false,
         }
     }
 }
@@ -316,6 +316,7 @@ impl From<PotionKind> for Potion {
             kind,
             identified: false, // Default to unidentified
             color,
+            exotic: false,
         }
     }
 }
@@ -326,12 +327,12 @@ impl ItemTrait for Potion {
         let mut hasher = SeaHasher::new();
 
         if !self.identified {
-            let key = (&self.color, false);
+            let key = (&self.color, self.exotic, false);
             let bytes = encode_to_vec(key, BINCODE_CONFIG).unwrap();
             hasher.write(&bytes);
             hasher.finish()
         } else {
-            let key = (&self.kind, true);
+            let key = (&self.kind, self.exotic, true);
             let bytes = encode_to_vec(key, BINCODE_CONFIG).unwrap();
             hasher.write(&bytes);
             hasher.finish()
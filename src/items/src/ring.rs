@@ -2,6 +2,7 @@
 use bincode::serde::encode_to_vec;
 use bincode::{Decode, Encode};
 use seahash::SeaHasher;
+use strum_macros::EnumIter;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::hash::Hasher;
@@ -20,6 +21,9 @@ pub struct Ring {
     pub cursed: bool, // 是否被诅咒
     pub identified: bool,
     pub base_value: u32,
+    /// 未鉴定时显示的宝石（每局随机分配，见 `ItemKnowledge`）
+    #[serde(default)]
+    pub gem: RingGem,
 }
 
 impl Ring {
@@ -31,6 +35,7 @@ impl Ring {
             cursed: false,
             identified: false,
             base_value: Self::base_value_for_kind(&kind), // 根据戒指类型设置基础价值
+            gem: kind.standard_gem(),
         }
     }
 
@@ -42,6 +47,7 @@ impl Ring {
             cursed: true,
             identified: false,
             base_value: Self::base_value_for_kind(&kind), // 同样设置基础价值
+            gem: kind.standard_gem(),
         }
     }
 
@@ -63,9 +69,11 @@ impl Ring {
 
     /// 随机生成新戒指（5%概率为诅咒戒指）
     pub fn random_new() -> Self {
-        use rand::Rng;
-        let mut rng = rand::rng();
+        Self::random_with(&mut rand::rng())
+    }
 
+    /// 使用给定的随机数源生成随机戒指
+    pub fn random_with(rng: &mut impl rand::Rng) -> Self {
         let kinds = [
             RingKind::Accuracy,
             RingKind::Elements,
@@ -112,12 +120,9 @@ impl Ring {
 
     /// 获取显示名称（包含等级信息）
     pub fn name(&self) -> String {
+        // 诅咒只有在戴上后才会暴露
         if !self.identified {
-            return if self.cursed {
-                "未鉴定的诅咒戒指".to_string()
-            } else {
-                "未鉴定的戒指".to_string()
-            };
+            return format!("{}戒指", self.gem.name());
         }
 
         let base = self.base_name();
@@ -144,28 +149,45 @@ impl Ring {
         }
     }
 
-    /// 计算戒指提供的实际增益效果
+    /// 实际生效的等级：诅咒戒指为负等级（-1 起）
+    pub fn effective_level(&self) -> i32 {
+        if self.cursed {
+            -(self.level + 1)
+        } else {
+            self.level
+        }
+    }
+
+    /// 计算戒指提供的实际增益效果（诅咒戒指按负等级给出负面效果）
     pub fn effect_value(&self, base_value: f32) -> f32 {
+        let level = self.effective_level().abs() as f32;
         let multiplier = match self.kind {
-            RingKind::Accuracy | RingKind::Evasion | RingKind::Sharpshooting => {
-                1.0 + 0.2 * self.level as f32
-            }
-            RingKind::Elements | RingKind::Energy | RingKind::Might => {
-                1.0 + 0.15 * (self.level as f32).sqrt()
-            }
-            RingKind::Force => 1.0 + 0.1 * self.level as f32,
-            RingKind::Furor => 1.0 + 0.25 * (self.level as f32).log2(),
-            RingKind::Haste => 1.0 + 0.05 * self.level as f32,
-            RingKind::Wealth => 1.0 + 0.3 * (self.level as f32).powf(0.5),
+            RingKind::Accuracy | RingKind::Evasion | RingKind::Sharpshooting => 1.0 + 0.2 * level,
+            RingKind::Elements | RingKind::Energy | RingKind::Might => 1.0 + 0.15 * level.sqrt(),
+            RingKind::Force => 1.0 + 0.1 * level,
+            RingKind::Furor => 1.0 + 0.25 * level.max(1.0).log2(),
+            RingKind::Haste => 1.0 + 0.05 * level,
+            RingKind::Wealth => 1.0 + 0.3 * level.powf(0.5),
         };
 
         if self.cursed {
-            base_value * multiplier * 0.5
+            -base_value * multiplier
         } else {
             base_value * multiplier
         }
     }
 
+    /// 解除诅咒
+    pub fn remove_curse(&mut self) {
+        self.cursed = false;
+    }
+
+    /// 强化戒指（等级+1，同时解除诅咒）
+    pub fn upgrade(&mut self) {
+        self.level = (self.level + 1).min(10);
+        self.cursed = false;
+    }
+
     /// 更精确的防御加成计算（基于游戏平衡性）
     pub fn defense_bonus(&self) -> f32 {
         if !self.identified || self.cursed {
@@ -211,7 +233,7 @@ impl Ring {
 }
 
 /// 戒指类型枚举
-#[derive(Copy, Eq, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default)]
+#[derive(Copy, Eq, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default, EnumIter)]
 pub enum RingKind {
     #[default]
     Accuracy, // 提升命中率
@@ -253,6 +275,7 @@ impl Default for Ring {
             cursed: false,            // 默认未诅咒
             identified: false,        // 默认未鉴定
             base_value: 1000,         // 精准之戒的基础价值
+            gem: RingKind::Accuracy.standard_gem(),
         }
     }
 }
@@ -297,6 +320,9 @@ impl fmt::Display for Ring {
 impl Ring {
     /// 获取戒指效果的详细描述
     fn effect_description(&self) -> String {
+        if self.cursed {
+            return format!("受到诅咒，{}的效果变为负面", self.kind);
+        }
         match self.kind {
             RingKind::Accuracy => {
                 format!("提升命中率 {:.0}%", (self.effect_value(1.0) - 1.0) * 100.0)
@@ -329,6 +355,63 @@ impl Ring {
     }
 }
 
+impl RingKind {
+    /// 戒指的标准宝石（未经 `ItemKnowledge` 分配时使用）
+    pub fn standard_gem(&self) -> RingGem {
+        match self {
+            RingKind::Accuracy => RingGem::Garnet,
+            RingKind::Elements => RingGem::Ruby,
+            RingKind::Energy => RingGem::Topaz,
+            RingKind::Evasion => RingGem::Emerald,
+            RingKind::Force => RingGem::Onyx,
+            RingKind::Furor => RingGem::Amethyst,
+            RingKind::Haste => RingGem::Sapphire,
+            RingKind::Might => RingGem::Quartz,
+            RingKind::Sharpshooting => RingGem::Diamond,
+            RingKind::Wealth => RingGem::Opal,
+        }
+    }
+}
+
+/// 戒指宝石（12种，未鉴定戒指以宝石区分）
+#[derive(
+    Eq, Hash, PartialEq, Debug, Copy, Clone, Encode, Decode, Serialize, Deserialize, EnumIter, Default,
+)]
+pub enum RingGem {
+    #[default]
+    Garnet,
+    Ruby,
+    Topaz,
+    Emerald,
+    Onyx,
+    Amethyst,
+    Sapphire,
+    Quartz,
+    Diamond,
+    Opal,
+    Jade,
+    Tourmaline,
+}
+
+impl RingGem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RingGem::Garnet => "石榴石",
+            RingGem::Ruby => "红宝石",
+            RingGem::Topaz => "黄玉",
+            RingGem::Emerald => "祖母绿",
+            RingGem::Onyx => "缟玛瑙",
+            RingGem::Amethyst => "紫水晶",
+            RingGem::Sapphire => "蓝宝石",
+            RingGem::Quartz => "石英",
+            RingGem::Diamond => "钻石",
+            RingGem::Opal => "蛋白石",
+            RingGem::Jade => "翡翠",
+            RingGem::Tourmaline => "碧玺",
+        }
+    }
+}
+
 impl fmt::Display for RingKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let name = match self {
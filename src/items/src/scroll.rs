@@ -28,6 +28,9 @@ pub struct Scroll {
     pub identified: bool,
     /// 是否是异变卷轴（SPD中的"异变卷轴"变种）
     pub exotic: bool,
+    /// 未鉴定时显示的符文（每局随机分配，见 `ItemKnowledge`）
+    #[serde(default)]
+    pub rune: ScrollRune,
 }
 
 impl Scroll {
@@ -37,6 +40,7 @@ impl Scroll {
             kind,
             identified: false,
             exotic: false,
+            rune: kind.standard_rune(),
         }
     }
 
@@ -46,14 +50,17 @@ impl Scroll {
             kind,
             identified: false,
             exotic: true,
+            rune: kind.standard_rune(),
         }
     }
 
     /// 随机生成新卷轴（10%概率为异变卷轴）
     pub fn random_new() -> Self {
-        use rand::Rng;
-        let mut rng = rand::rng();
+        Self::random_with(&mut rand::rng())
+    }
 
+    /// 使用给定的随机数源生成随机卷轴
+    pub fn random_with(rng: &mut impl rand::Rng) -> Self {
         let kinds = ScrollKind::iter().collect::<Vec<_>>();
         let kind = kinds[rng.random_range(0..kinds.len())];
 
@@ -99,9 +106,9 @@ impl Scroll {
     pub fn name(&self) -> String {
         if !self.identified {
             return if self.exotic {
-                "未鉴定的异变卷轴".to_string()
+                format!("「{}」异变卷轴", self.rune.name())
             } else {
-                "未鉴定的卷轴".to_string()
+                format!("「{}」卷轴", self.rune.name())
             };
         }
 
@@ -190,12 +197,81 @@ pub enum ScrollKind {
     Transmutation, // 变形卷轴 - 改变物品
 }
 
+impl ScrollKind {
+    /// 卷轴的标准符文（未经 `ItemKnowledge` 分配时使用）
+    pub fn standard_rune(&self) -> ScrollRune {
+        match self {
+            ScrollKind::Upgrade => ScrollRune::Kaunan,
+            ScrollKind::RemoveCurse => ScrollRune::Sowilo,
+            ScrollKind::Identify => ScrollRune::Laguz,
+            ScrollKind::MagicMapping => ScrollRune::Yngvi,
+            ScrollKind::MirrorImage => ScrollRune::Gyfu,
+            ScrollKind::Teleportation => ScrollRune::Raido,
+            ScrollKind::Lullaby => ScrollRune::Isaz,
+            ScrollKind::Rage => ScrollRune::Mannaz,
+            ScrollKind::Recharging => ScrollRune::Naudiz,
+            ScrollKind::Transmutation => ScrollRune::Berkanan,
+        }
+    }
+}
+
+/// 卷轴符文（12种，未鉴定卷轴以符文区分）
+#[derive(
+    Eq,
+    Hash,
+    PartialEq,
+    Debug,
+    Copy,
+    Clone,
+    Encode,
+    Decode,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    Default,
+)]
+pub enum ScrollRune {
+    #[default]
+    Kaunan,
+    Sowilo,
+    Laguz,
+    Yngvi,
+    Gyfu,
+    Raido,
+    Isaz,
+    Mannaz,
+    Naudiz,
+    Berkanan,
+    Odal,
+    Tiwaz,
+}
+
+impl ScrollRune {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScrollRune::Kaunan => "KAUNAN",
+            ScrollRune::Sowilo => "SOWILO",
+            ScrollRune::Laguz => "LAGUZ",
+            ScrollRune::Yngvi => "YNGVI",
+            ScrollRune::Gyfu => "GYFU",
+            ScrollRune::Raido => "RAIDO",
+            ScrollRune::Isaz => "ISAZ",
+            ScrollRune::Mannaz => "MANNAZ",
+            ScrollRune::Naudiz => "NAUDIZ",
+            ScrollRune::Berkanan => "BERKANAN",
+            ScrollRune::Odal => "ODAL",
+            ScrollRune::Tiwaz => "TIWAZ",
+        }
+    }
+}
+
 impl Default for Scroll {
     fn default() -> Self {
         Scroll {
             kind: ScrollKind::Identify, // 默认选择鉴定卷轴（基础类型）
             identified: false,          // 默认未鉴定
             exotic: false,              // 默认非异变卷轴
+            rune: ScrollKind::Identify.standard_rune(),
         }
     }
 }
@@ -214,6 +290,7 @@ impl ItemTrait for Scroll {
             self.kind,
             self.identified,
             self.exotic, // 包含异变状态
+            self.rune,
         );
 
         let bytes = encode_to_vec(key, BINCODE_CONFIG).unwrap();
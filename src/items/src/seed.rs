@@ -5,6 +5,7 @@ use bincode::{Decode, Encode};
 use seahash::SeaHasher;
 use serde::{Deserialize, Serialize};
 use std::hash::Hasher;
+use strum_macros::EnumIter;
 
 use crate::BINCODE_CONFIG;
 use crate::Item;
@@ -20,7 +21,18 @@ pub struct Seed {
 }
 
 #[derive(
-    Copy, Eq, Hash, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default,
+    Copy,
+    Eq,
+    Hash,
+    PartialEq,
+    Debug,
+    Clone,
+    EnumIter,
+    Encode,
+    Decode,
+    Serialize,
+    Deserialize,
+    Default,
 )]
 pub enum SeedKind {
     #[default]
@@ -55,9 +67,11 @@ impl Seed {
 
     /// 随机生成新种子
     pub fn random_new() -> Self {
-        use rand::Rng;
-        let mut rng = rand::rng();
+        Self::random_with(&mut rand::rng())
+    }
 
+    /// 使用给定的随机数源生成随机种子
+    pub fn random_with(rng: &mut impl rand::Rng) -> Self {
         let kinds = [
             SeedKind::Earthroot,
             SeedKind::Fadeleaf,
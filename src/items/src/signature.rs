@@ -0,0 +1,171 @@
+//src/items/src/signature.rs
+use bincode::serde::encode_to_vec;
+use bincode::{Decode, Encode};
+use seahash::SeaHasher;
+use serde::{Deserialize, Serialize};
+use std::hash::Hasher;
+
+use crate::BINCODE_CONFIG;
+use crate::Item;
+use crate::ItemCategory;
+use crate::ItemKind;
+use crate::ItemRarity;
+use crate::ItemTrait;
+use crate::wand::Wand;
+
+/// 职业专属物品种类（每个职业开局携带一件）
+#[derive(Copy, Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub enum SignatureKind {
+    /// 破损的纹章 - 战士：提供吸收伤害的护盾
+    BrokenSeal,
+    /// 法师之杖 - 法师：吸收一根法杖并释放它的效果
+    MagesStaff,
+    /// 暗影斗篷 - 盗贼：消耗充能隐匿身形
+    CloakOfShadows,
+    /// 灵能弓 - 女猎手：射出无限的灵能箭
+    SpiritBow,
+}
+
+impl SignatureKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignatureKind::BrokenSeal => "破损的纹章",
+            SignatureKind::MagesStaff => "法师之杖",
+            SignatureKind::CloakOfShadows => "暗影斗篷",
+            SignatureKind::SpiritBow => "灵能弓",
+        }
+    }
+
+    /// 自身的充能上限（法师之杖使用所吸收法杖的充能，灵能弓无需充能）
+    pub fn max_charges(&self) -> u32 {
+        match self {
+            SignatureKind::BrokenSeal => 1,
+            SignatureKind::CloakOfShadows => 3,
+            SignatureKind::MagesStaff | SignatureKind::SpiritBow => 0,
+        }
+    }
+
+    /// 恢复一点充能所需的回合数
+    pub fn recharge_turns(&self) -> u32 {
+        match self {
+            SignatureKind::BrokenSeal => 40,
+            SignatureKind::CloakOfShadows => 15,
+            SignatureKind::MagesStaff | SignatureKind::SpiritBow => 0,
+        }
+    }
+}
+
+/// 职业专属物品
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct Signature {
+    pub kind: SignatureKind,
+    /// 当前充能
+    pub charges: u32,
+    /// 距离恢复下一点充能的回合数
+    pub recharge_in: u32,
+    /// 法师之杖吸收的法杖
+    pub absorbed: Option<Wand>,
+}
+
+impl Signature {
+    /// 创建充能全满的专属物品
+    pub fn new(kind: SignatureKind) -> Self {
+        Self {
+            kind,
+            charges: kind.max_charges(),
+            recharge_in: 0,
+            absorbed: None,
+        }
+    }
+
+    /// 消耗一点充能，没有充能时返回 false
+    pub fn use_charge(&mut self) -> bool {
+        if self.charges == 0 {
+            return false;
+        }
+        self.charges -= 1;
+        if self.recharge_in == 0 {
+            self.recharge_in = self.kind.recharge_turns();
+        }
+        true
+    }
+
+    /// 经过一回合，恢复了一点充能时返回 true
+    pub fn tick(&mut self) -> bool {
+        if self.charges >= self.kind.max_charges() || self.recharge_in == 0 {
+            return false;
+        }
+        self.recharge_in -= 1;
+        if self.recharge_in > 0 {
+            return false;
+        }
+        self.charges += 1;
+        if self.charges < self.kind.max_charges() {
+            self.recharge_in = self.kind.recharge_turns();
+        }
+        true
+    }
+
+    /// 法师之杖吸收一根法杖，原先吸收的法杖随之消散
+    ///
+    /// 被吸收的法杖会被鉴定、解除诅咒，并额外获得 `bonus_charges` 点充能上限。
+    pub fn absorb(&mut self, mut wand: Wand, bonus_charges: u8) {
+        wand.identify();
+        wand.remove_curse();
+        wand.max_charges += bonus_charges;
+        wand.charges = wand.max_charges;
+        self.absorbed = Some(wand);
+    }
+
+    /// 名称，法师之杖附带吸收的法杖
+    pub fn name(&self) -> String {
+        match &self.absorbed {
+            Some(wand) => format!("{}（{}）", self.kind.name(), wand.name()),
+            None => self.kind.name().to_string(),
+        }
+    }
+}
+
+impl ItemTrait for Signature {
+    /// 专属物品独一无二，不能堆叠
+    fn is_stackable(&self) -> bool {
+        false
+    }
+
+    fn max_stack(&self) -> u32 {
+        1
+    }
+
+    fn display_name(&self) -> String {
+        self.name()
+    }
+
+    fn category(&self) -> ItemCategory {
+        match self.kind {
+            SignatureKind::MagesStaff => ItemCategory::Wand,
+            SignatureKind::SpiritBow => ItemCategory::Weapon,
+            SignatureKind::BrokenSeal | SignatureKind::CloakOfShadows => ItemCategory::Misc,
+        }
+    }
+
+    fn rarity(&self) -> ItemRarity {
+        ItemRarity::Legendary
+    }
+
+    fn sort_value(&self) -> u32 {
+        90
+    }
+
+    fn stacking_id(&self) -> u64 {
+        let mut hasher = SeaHasher::new();
+        let bytes = encode_to_vec((self.kind, &self.absorbed), BINCODE_CONFIG).unwrap();
+        hasher.write(&bytes);
+        hasher.finish()
+    }
+}
+
+impl From<Signature> for Item {
+    fn from(signature: Signature) -> Self {
+        Item::new(ItemKind::Signature(signature))
+    }
+}
@@ -15,6 +15,7 @@ use crate::Item;
 use crate::ItemCategory;
 use crate::ItemKind;
 use crate::ItemTrait;
+use crate::scroll::ScrollKind;
 
 /// 魔法石系统（10种对应卷轴）
 #[derive(Eq, Hash, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize)]
@@ -57,9 +58,11 @@ impl Stone {
 
     /// 随机生成新魔法石
     pub fn random_new() -> Self {
-        use rand::Rng;
-        let mut rng = rand::rng();
+        Self::random_with(&mut rand::rng())
+    }
 
+    /// 使用给定的随机数源生成随机魔法石
+    pub fn random_with(rng: &mut impl rand::Rng) -> Self {
         let kinds = [
             StoneKind::Upgrade,
             StoneKind::RemoveCurse,
@@ -100,15 +103,15 @@ impl Stone {
         }
         self.used = true;
         Some(match self.kind {
-            StoneKind::Upgrade => "临时强化一件装备（持续30回合）".to_string(),
-            StoneKind::RemoveCurse => "解除装备诅咒（范围效果）".to_string(),
-            StoneKind::Identify => "鉴定背包内所有物品".to_string(),
-            StoneKind::MagicMapping => "显示当前楼层完整地图".to_string(),
-            StoneKind::MirrorImage => "创造2个分身协助战斗".to_string(),
-            StoneKind::Teleportation => "随机传送到本层某处".to_string(),
-            StoneKind::Lullaby => "使周围敌人陷入沉睡".to_string(),
-            StoneKind::Rage => "激怒所有敌人互相攻击".to_string(),
-            StoneKind::Recharging => "立即恢复所有魔杖能量".to_string(),
+            StoneKind::Upgrade => "强化一件物品".to_string(),
+            StoneKind::RemoveCurse => "解除一件物品的诅咒".to_string(),
+            StoneKind::Identify => "鉴定一件物品".to_string(),
+            StoneKind::MagicMapping => "显示落点附近的地图".to_string(),
+            StoneKind::MirrorImage => "在落点创造1个分身协助战斗".to_string(),
+            StoneKind::Teleportation => "闪现到落点".to_string(),
+            StoneKind::Lullaby => "使落点周围的生物陷入沉睡".to_string(),
+            StoneKind::Rage => "激怒落点周围的敌人互相攻击".to_string(),
+            StoneKind::Recharging => "为一根法杖恢复能量".to_string(),
             StoneKind::Transmutation => "随机改变一个物品的类型".to_string(),
         })
     }
@@ -137,6 +140,36 @@ pub enum StoneKind {
     Transmutation, // 对应变形卷轴
 }
 
+impl StoneKind {
+    /// 作用于背包中某件物品的魔法石；其余魔法石需要投掷到目标格子上
+    pub fn targets_item(&self) -> bool {
+        matches!(
+            self,
+            StoneKind::Upgrade
+                | StoneKind::RemoveCurse
+                | StoneKind::Identify
+                | StoneKind::Recharging
+                | StoneKind::Transmutation
+        )
+    }
+
+    /// 与卷轴种类一一对应的魔法石种类（炼金时卷轴化为魔法石）
+    pub fn from_scroll(kind: ScrollKind) -> Self {
+        match kind {
+            ScrollKind::Upgrade => StoneKind::Upgrade,
+            ScrollKind::RemoveCurse => StoneKind::RemoveCurse,
+            ScrollKind::Identify => StoneKind::Identify,
+            ScrollKind::MagicMapping => StoneKind::MagicMapping,
+            ScrollKind::MirrorImage => StoneKind::MirrorImage,
+            ScrollKind::Teleportation => StoneKind::Teleportation,
+            ScrollKind::Lullaby => StoneKind::Lullaby,
+            ScrollKind::Rage => StoneKind::Rage,
+            ScrollKind::Recharging => StoneKind::Recharging,
+            ScrollKind::Transmutation => StoneKind::Transmutation,
+        }
+    }
+}
+
 impl Default for Stone {
     fn default() -> Self {
         Stone {
@@ -132,6 +132,17 @@ impl Throwable {
         }
     }
 
+    /// 命中或落地后损坏（消耗）的概率
+    pub fn break_chance(&self) -> f64 {
+        match self.kind {
+            ThrowableKind::Dart => 0.2,
+            ThrowableKind::Shuriken => 0.15,
+            ThrowableKind::Javelin => 0.1,
+            ThrowableKind::Bomb => 1.0, // 炸弹命中即引爆
+            ThrowableKind::Chakram | ThrowableKind::Boomerang => 0.0,
+        }
+    }
+
     fn stacking_key(&self) -> (ThrowableKind, u32, u8) {
         (self.kind, 0, self.range)
     }
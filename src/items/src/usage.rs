@@ -0,0 +1,85 @@
+//src/items/src/usage.rs
+
+use crate::potion::PotionKind;
+use crate::signature::SignatureKind;
+use crate::{Item, ItemKind};
+
+/// 直接食用药草恢复的生命值
+pub const HERB_HEAL_AMOUNT: u32 = 8;
+
+/// 物品在背包中被“使用”时的方式
+///
+/// 游戏层只按这里的结果分派到对应的结算逻辑，不再另外维护一套物品类型映射。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemUsage {
+    /// 穿戴（武器、护甲、戒指）
+    Equip,
+    /// 喝下药水
+    Drink(PotionKind),
+    /// 阅读卷轴
+    Read,
+    /// 吃下食物，恢复饱食度
+    Eat,
+    /// 直接恢复生命
+    Heal(u32),
+    /// 种在脚下
+    Plant,
+    /// 使用魔法石（作用于物品或投向地块）
+    Apply,
+    /// 挥动法杖
+    Zap,
+    /// 投掷
+    Throw,
+    /// 射击（不消耗物品本身）
+    Shoot,
+}
+
+impl ItemUsage {
+    /// 背包中触发该用法的按键与说明
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ItemUsage::Equip => "E     装备",
+            ItemUsage::Drink(_) => "Enter 喝下",
+            ItemUsage::Read => "Enter 阅读",
+            ItemUsage::Eat | ItemUsage::Heal(_) => "Enter 食用",
+            ItemUsage::Plant => "Enter 种在脚下",
+            ItemUsage::Apply => "Enter 使用",
+            ItemUsage::Zap => "Z     挥动法杖",
+            ItemUsage::Throw => "T     投掷",
+            ItemUsage::Shoot => "T     射击",
+        }
+    }
+}
+
+/// 可以在背包中使用的物品
+pub trait Usable {
+    /// 物品的主要用法，杂项物品等无法直接使用时返回 None
+    fn usage(&self) -> Option<ItemUsage>;
+
+    /// 是否可以装备
+    fn is_equippable(&self) -> bool {
+        matches!(self.usage(), Some(ItemUsage::Equip))
+    }
+}
+
+impl Usable for Item {
+    fn usage(&self) -> Option<ItemUsage> {
+        match &self.kind {
+            ItemKind::Weapon(_) | ItemKind::Armor(_) | ItemKind::Ring(_) => Some(ItemUsage::Equip),
+            ItemKind::Potion(p) => Some(ItemUsage::Drink(p.kind)),
+            ItemKind::Scroll(_) => Some(ItemUsage::Read),
+            ItemKind::Food(_) => Some(ItemUsage::Eat),
+            ItemKind::Herb(_) => Some(ItemUsage::Heal(HERB_HEAL_AMOUNT)),
+            ItemKind::Seed(_) => Some(ItemUsage::Plant),
+            ItemKind::Stone(_) => Some(ItemUsage::Apply),
+            ItemKind::Wand(_) => Some(ItemUsage::Zap),
+            ItemKind::Throwable(_) => Some(ItemUsage::Throw),
+            ItemKind::Misc(_) => None,
+            ItemKind::Signature(s) => Some(match s.kind {
+                SignatureKind::MagesStaff if s.absorbed.is_some() => ItemUsage::Zap,
+                SignatureKind::SpiritBow => ItemUsage::Shoot,
+                _ => ItemUsage::Apply,
+            }),
+        }
+    }
+}
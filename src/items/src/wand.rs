@@ -6,6 +6,7 @@
 
 use bincode::serde::encode_to_vec;
 use bincode::{Decode, Encode};
+use strum_macros::EnumIter;
 use serde::{Deserialize, Serialize};
 
 use crate::BINCODE_CONFIG;
@@ -82,6 +83,24 @@ impl Wand {
         wand
     }
 
+    /// 解除诅咒
+    pub fn remove_curse(&mut self) {
+        self.cursed = false;
+    }
+
+    /// 强化法杖（等级与充能上限各+1，同时解除诅咒）
+    pub fn upgrade(&mut self) {
+        self.level += 1;
+        self.max_charges += 1;
+        self.charges = (self.charges + 1).min(self.max_charges);
+        self.cursed = false;
+    }
+
+    /// 充满充能，`overcharge` 为允许超出上限的额外充能
+    pub fn recharge(&mut self, overcharge: u8) {
+        self.charges = self.charges.max(self.max_charges + overcharge);
+    }
+
     /// 计算法杖价值（考虑类型、等级、充能和诅咒状态）
     pub fn value(&self) -> u32 {
         // 基础价值
@@ -181,18 +200,23 @@ impl Wand {
     }
 
     /// 自然充能（每回合恢复概率）
-    pub fn natural_recharge(&mut self) {
-        if self.charges < self.max_charges {
-            let recharge_chance = match self.level {
-                0 => 0.1,
-                1 => 0.15,
-                2 => 0.2,
-                _ => 0.25,
-            };
+    pub fn natural_recharge<R: rand::Rng>(&mut self, rng: &mut R) -> bool {
+        if self.charges >= self.max_charges {
+            return false;
+        }
 
-            if rand::random::<f32>() < recharge_chance {
-                self.charges += 1;
-            }
+        let recharge_chance = match self.level {
+            0 => 0.1,
+            1 => 0.15,
+            2 => 0.2,
+            _ => 0.25,
+        };
+
+        if rng.random_bool(recharge_chance) {
+            self.charges += 1;
+            true
+        } else {
+            false
         }
     }
 
@@ -203,7 +227,7 @@ impl Wand {
 }
 
 /// 法杖种类枚举（8种）
-#[derive(Copy, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default)]
+#[derive(Copy, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default, EnumIter)]
 pub enum WandKind {
     #[default]
     MagicMissile, // 魔法飞弹（基础法杖）
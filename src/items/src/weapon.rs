@@ -18,6 +18,9 @@ use crate::ItemTrait;
 pub mod kind;
 pub mod tier;
 
+pub use kind::WeaponKind;
+pub use tier::{Tier, WeaponSpec};
+
 /// 武器附魔效果（完全还原Shattered PD的附魔系统）
 #[derive(PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize)]
 pub enum WeaponEnhance {
@@ -31,6 +34,23 @@ pub enum WeaponEnhance {
     Chilling,   // 冰冻：减速敌人
 }
 
+impl WeaponEnhance {
+    /// 致命附魔的斩杀阈值（目标剩余生命低于最大生命的该比例时必杀）
+    pub const GRIM_THRESHOLD: f32 = 0.2;
+
+    /// 命中时的触发概率（被动附魔返回0，由其他属性体现）
+    pub fn proc_chance(&self) -> f32 {
+        match self {
+            WeaponEnhance::Burning => 0.25,
+            WeaponEnhance::Stunning => 0.20,
+            WeaponEnhance::Vampiric => 1.0,
+            WeaponEnhance::Grim => 1.0,
+            WeaponEnhance::Chilling => 0.25,
+            WeaponEnhance::Lucky | WeaponEnhance::Projecting => 0.0,
+        }
+    }
+}
+
 /// 武器改造方向（还原Shattered PD的武器改造系统）
 #[derive(PartialEq, Debug, Encode, Decode, Serialize, Deserialize, Clone, Default)]
 pub enum WeaponMod {
@@ -60,14 +80,17 @@ pub struct Weapon {
 }
 
 impl Weapon {
-    /// 创建新武器（根据品阶初始化基础属性）
+    /// 目录命中率的基准，每高出5%折算一点命中加成
+    const BASE_HIT_CHANCE: f32 = 0.8;
+
+    /// 创建新武器：优先取该品阶目录中同类型的武器，目录里没有时按品阶通用属性生成
     pub fn new(tier: u32, kind: WeaponKind) -> Self {
-        let (damage, str_req, name) = match tier {
-            1 => ((1, 6), 10, "短剑"),     // 一阶武器
-            2 => ((3, 12), 13, "长剑"),    // 二阶武器
-            3 => ((6, 18), 16, "巨剑"),    // 三阶武器
-            4 => ((10, 25), 19, "符文剑"), // 四阶武器
-            5 => ((15, 35), 22, "圣剑"),   // 五阶武器
+        let (damage, str_req) = match tier {
+            1 => ((1, 6), 10),   // 一阶武器
+            2 => ((3, 12), 13),  // 二阶武器
+            3 => ((6, 18), 16),  // 三阶武器
+            4 => ((10, 25), 19), // 四阶武器
+            5 => ((15, 35), 22), // 五阶武器
             _ => {
                 return Weapon {
                     name: "Unknown".to_string(),
@@ -86,43 +109,70 @@ impl Weapon {
             }
         };
 
+        let tier = Tier::from_u32(tier);
+        if let Some(spec) = tier.catalog().iter().find(|spec| spec.kind == kind) {
+            return Self::from_spec(tier, spec);
+        }
+
+        // 目录外的组合沿用该类型在其他品阶的名称与命中率，伤害与力量需求按品阶通用值
+        let spec = Self::catalog()
+            .map(|(_, spec)| spec)
+            .find(|spec| spec.kind == kind)
+            .expect("每种武器类型都在目录中");
+        Self::from_spec(
+            tier,
+            &WeaponSpec {
+                damage,
+                str_requirement: str_req,
+                ..*spec
+            },
+        )
+    }
+
+    /// 按目录条目创建武器
+    pub fn from_spec(tier: Tier, spec: &WeaponSpec) -> Self {
         Self {
-            name: name.to_string(),
-            tier: Tier::from_u32(tier),
-            damage,
-            hit_chance: 0.8,
-            str_requirement: str_req,
+            name: spec.name.to_string(),
+            tier,
+            damage: spec.damage,
+            hit_chance: spec.hit_chance,
+            str_requirement: spec.str_requirement,
             enchanted: None,
             modifier: WeaponMod::Accuracy,
             upgrade_level: 0,
             cursed: false,
             identified: false,
-            kind,
-            base_value: Self::base_value_for_tier(tier), // 根据品阶设置基础价值
+            kind: spec.kind,
+            base_value: Self::base_value_for_tier(tier.to_u32()),
         }
     }
 
-    /// 随机生成新武器（5%概率为诅咒武器）
-    pub fn random_new() -> Self {
-        use rand::Rng;
-        let mut rng = rand::rng();
+    /// 全部品阶的武器目录
+    pub fn catalog() -> impl Iterator<Item = (Tier, &'static WeaponSpec)> {
+        [Tier::One, Tier::Two, Tier::Three, Tier::Four, Tier::Five]
+            .into_iter()
+            .flat_map(|tier| tier.catalog().iter().map(move |spec| (tier, spec)))
+    }
 
-        let tier = rng.random_range(1..=5);
-        let kinds = [
-            WeaponKind::Sword,
-            WeaponKind::Dagger,
-            WeaponKind::Greataxe,
-            WeaponKind::Spear,
-            WeaponKind::Mace,
-            WeaponKind::Whip,
-        ];
-        let kind = kinds[rng.random_range(0..kinds.len())];
+    /// 按深度从武器目录随机生成新武器（5%概率为诅咒武器）
+    pub fn random_new(depth: usize) -> Self {
+        Self::random_for_depth(depth, &mut rand::rng())
+    }
 
-        let mut weapon = Weapon::new(tier, kind);
+    /// 使用给定的随机数生成器，按深度从武器目录随机生成新武器
+    pub fn random_for_depth(depth: usize, rng: &mut impl Rng) -> Self {
+        use rand::seq::IndexedRandom;
+
+        let tier = Tier::random_for_depth(depth, rng);
+        let spec = tier
+            .catalog()
+            .choose(rng)
+            .expect("每个品阶的武器目录都不为空");
+        let mut weapon = Weapon::from_spec(tier, spec);
 
         // 10%概率有附魔
         if rng.random_bool(0.1) {
-            weapon.add_random_enhancement();
+            weapon.add_random_enhancement_with(rng);
         }
 
         // 随机改造方向
@@ -134,9 +184,12 @@ impl Weapon {
         ];
         weapon.modifier = mods[rng.random_range(0..mods.len())].clone();
 
-        // 5%概率被诅咒
+        // 5%概率被诅咒（诅咒附带的附魔同样取自传入的随机数源）
         if rng.random_bool(0.05) {
-            weapon.cursed = true;
+            if weapon.enchanted.is_none() {
+                weapon.add_random_enhancement_with(rng);
+            }
+            weapon.curse();
         }
 
         weapon
@@ -201,6 +254,22 @@ impl Weapon {
         }
     }
 
+    /// 诅咒武器：没有附魔时附上一个随机附魔，诅咒会让附魔效果反噬持有者
+    pub fn curse(&mut self) {
+        self.cursed = true;
+        if self.enchanted.is_none() {
+            self.add_random_enhancement();
+        }
+    }
+
+    /// 解除诅咒，反噬的附魔随诅咒一同消散
+    pub fn remove_curse(&mut self) {
+        if self.cursed {
+            self.cursed = false;
+            self.enchanted = None;
+        }
+    }
+
     /// 强化武器（还原Shattered PD的强化系统）
     pub fn upgrade(&mut self) {
         self.upgrade_level += 1;
@@ -259,18 +328,25 @@ impl Weapon {
             WeaponKind::Spear => 0.03,
             WeaponKind::Mace => 0.0,
             WeaponKind::Whip => 0.07,
+            WeaponKind::Quarterstaff => 0.03,
+            WeaponKind::Greatsword => 0.08,
         };
 
-        // 幸运附魔增加暴击率
-        if let Some(WeaponEnhance::Lucky) = self.enchanted {
-            base_bonus + 0.15
-        } else {
-            base_bonus
+        // 幸运附魔增加暴击率（诅咒时反而降低）
+        match (&self.enchanted, self.cursed) {
+            (Some(WeaponEnhance::Lucky), false) => base_bonus + 0.15,
+            (Some(WeaponEnhance::Lucky), true) => base_bonus - 0.10,
+            _ => base_bonus,
         }
     }
 
     /// 添加随机附魔（还原Shattered PD的附魔概率）
     pub fn add_random_enhancement(&mut self) {
+        self.add_random_enhancement_with(&mut rand::rng());
+    }
+
+    /// 使用给定的随机数源添加随机附魔
+    pub fn add_random_enhancement_with(&mut self, rng: &mut impl Rng) {
         let enhancements = [
             WeaponEnhance::Burning,    // 25%概率点燃敌人3回合
             WeaponEnhance::Stunning,   // 20%概率眩晕敌人2回合
@@ -283,7 +359,7 @@ impl Weapon {
 
         // 确保100%获得一个随机附魔
         self.enchanted =
-            Some(enhancements[rand::rng().random_range(0..enhancements.len())].clone());
+            Some(enhancements[rng.random_range(0..enhancements.len())].clone());
     }
 
     /// 武器鉴定逻辑（还原Shattered PD的鉴定机制）
@@ -308,7 +384,7 @@ impl Weapon {
         }
     }
 
-    /// 获取武器命中加成（基于武器类型和改造方向）
+    /// 获取武器命中加成（基于武器类型、目录命中率和改造方向）
     pub fn accuracy_bonus(&self) -> i32 {
         let base_bonus = match self.kind {
             WeaponKind::Sword => 0,
@@ -317,9 +393,20 @@ impl Weapon {
             WeaponKind::Spear => 2,
             WeaponKind::Mace => -2,
             WeaponKind::Whip => 3,
+            WeaponKind::Quarterstaff => 1,
+            WeaponKind::Greatsword => -1,
+        };
+        let catalog_bonus = ((self.hit_chance - Self::BASE_HIT_CHANCE) * 20.0).round() as i32;
+
+        // 诅咒的投射附魔会让攻击偏离目标
+        let curse_penalty = match (&self.enchanted, self.cursed) {
+            (Some(WeaponEnhance::Projecting), true) => -3,
+            _ => 0,
         };
 
         base_bonus
+            + catalog_bonus
+            + curse_penalty
             + match self.modifier {
                 WeaponMod::Damage => 0,
                 WeaponMod::Speed => 1,
@@ -330,87 +417,27 @@ impl Weapon {
 
     /// 获取武器攻击距离
     pub fn range(&self) -> u32 {
-        let base_range = match self.kind {
-            WeaponKind::Spear => 2,
-            _ => 1,
-        };
+        let base_range = self.kind.reach();
 
-        // 如果有投射附魔则增加1距离
-        if let Some(WeaponEnhance::Projecting) = self.enchanted {
+        // 如果有投射附魔则增加1距离（诅咒时无效）
+        if let (Some(WeaponEnhance::Projecting), false) = (&self.enchanted, self.cursed) {
             base_range + 1
         } else {
             base_range
         }
     }
 
+    /// 偷袭伤害的额外倍率
+    pub fn surprise_multiplier(&self) -> f32 {
+        self.kind.surprise_multiplier()
+    }
+
     /// 判断是否为远程武器
     pub fn is_ranged(&self) -> bool {
         self.range() > 1
     }
 }
 
-/// 武器类型枚举（还原Shattered PD的武器分类）
-#[derive(Copy, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default)]
-pub enum WeaponKind {
-    #[default]
-    Sword, // 剑类：平衡型
-    Dagger,   // 匕首：高速低伤（+25%攻速）
-    Greataxe, // 巨斧：低速高伤（+30%伤害）
-    Spear,    // 长矛：中距攻击（攻击范围+1）
-    Mace,     // 钉锤：破甲效果
-    Whip,     // 长鞭：高命中
-}
-
-/// 武器品阶（还原Shattered PD的5阶系统）
-#[derive(Copy, PartialEq, Debug, Encode, Decode, Serialize, Deserialize, Clone, Default)]
-pub enum Tier {
-    #[default]
-    One, // 普通（白色）
-    Two,   // 优秀（绿色）
-    Three, // 稀有（蓝色）
-    Four,  // 史诗（紫色）
-    Five,  // 传奇（橙色）
-}
-
-impl Tier {
-    pub fn from_u32(value: u32) -> Self {
-        match value {
-            1 => Tier::One,
-            2 => Tier::Two,
-            3 => Tier::Three,
-            4 => Tier::Four,
-            5 => Tier::Five,
-            _ => Tier::One,
-        }
-    }
-
-    pub fn to_u32(&self) -> u32 {
-        match self {
-            Tier::One => 1,
-            Tier::Two => 2,
-            Tier::Three => 3,
-            Tier::Four => 4,
-            Tier::Five => 5,
-        }
-    }
-}
-
-impl fmt::Display for Tier {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Tier::One => "I",
-                Tier::Two => "II",
-                Tier::Three => "III",
-                Tier::Four => "IV",
-                Tier::Five => "V",
-            }
-        )
-    }
-}
-
 impl Default for Weapon {
     fn default() -> Self {
         Weapon {
@@ -449,6 +476,11 @@ impl fmt::Display for Weapon {
         // 添加武器类型
         info.push_str(&format!("\n类型: {}", self.kind));
 
+        // 添加武器特性
+        if let Some(description) = self.kind.trait_description() {
+            info.push_str(&format!("\n特性: {}", description));
+        }
+
         // 添加命中率
         info.push_str(&format!("\n命中率: {:.0}%", self.hit_chance * 100.0));
 
@@ -492,20 +524,6 @@ impl fmt::Display for Weapon {
     }
 }
 
-impl fmt::Display for WeaponKind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            WeaponKind::Sword => "剑类",
-            WeaponKind::Dagger => "匕首",
-            WeaponKind::Greataxe => "巨斧",
-            WeaponKind::Spear => "长矛",
-            WeaponKind::Mace => "钉锤",
-            WeaponKind::Whip => "长鞭",
-        };
-        write!(f, "{}", name)
-    }
-}
-
 impl fmt::Display for WeaponMod {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let name = match self {
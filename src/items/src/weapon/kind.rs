@@ -1 +1,90 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
+/// 武器类型枚举（还原Shattered PD的武器分类）
+#[derive(Copy, PartialEq, Debug, Clone, Encode, Decode, Serialize, Deserialize, Default)]
+pub enum WeaponKind {
+    #[default]
+    Sword, // 剑类：平衡型
+    Dagger,       // 匕首：偷袭伤害更高
+    Greataxe,     // 巨斧：高伤，但挥砍耗能更多
+    Spear,        // 长矛：攻击距离2
+    Mace,         // 钉锤：破甲效果
+    Whip,         // 长鞭：高命中
+    Quarterstaff, // 长棍：持握时可格挡伤害
+    Greatsword,   // 巨剑：沉重的双手剑
+}
+
+impl WeaponKind {
+    /// 所有武器类型
+    pub const ALL: [WeaponKind; 8] = [
+        WeaponKind::Sword,
+        WeaponKind::Dagger,
+        WeaponKind::Greataxe,
+        WeaponKind::Spear,
+        WeaponKind::Mace,
+        WeaponKind::Whip,
+        WeaponKind::Quarterstaff,
+        WeaponKind::Greatsword,
+    ];
+
+    /// 偷袭时在潜行伤害之上的额外倍率
+    pub fn surprise_multiplier(&self) -> f32 {
+        match self {
+            WeaponKind::Dagger => 1.5,
+            _ => 1.0,
+        }
+    }
+
+    /// 基础攻击距离
+    pub fn reach(&self) -> u32 {
+        match self {
+            WeaponKind::Spear => 2,
+            _ => 1,
+        }
+    }
+
+    /// 挥砍额外耗能比例（叠加在普通攻击耗能上）
+    pub fn swing_cost(&self) -> f32 {
+        match self {
+            WeaponKind::Greataxe => 0.5,
+            _ => 0.0,
+        }
+    }
+
+    /// 持握时每次被近战命中格挡的伤害
+    pub fn block(&self) -> u32 {
+        match self {
+            WeaponKind::Quarterstaff => 2,
+            _ => 0,
+        }
+    }
+
+    /// 武器特性的简短说明
+    pub fn trait_description(&self) -> Option<&'static str> {
+        match self {
+            WeaponKind::Dagger => Some("偷袭时伤害提高50%"),
+            WeaponKind::Spear => Some("可以攻击两格外的敌人"),
+            WeaponKind::Greataxe => Some("挥砍沉重，攻击耗能增加50%"),
+            WeaponKind::Quarterstaff => Some("持握时每次被近战命中格挡2点伤害"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WeaponKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            WeaponKind::Sword => "剑类",
+            WeaponKind::Dagger => "匕首",
+            WeaponKind::Greataxe => "巨斧",
+            WeaponKind::Spear => "长矛",
+            WeaponKind::Mace => "钉锤",
+            WeaponKind::Whip => "长鞭",
+            WeaponKind::Quarterstaff => "长棍",
+            WeaponKind::Greatsword => "巨剑",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -1 +1,104 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
+use super::kind::WeaponKind;
+
+pub mod five;
+pub mod four;
+pub mod one;
+pub mod three;
+pub mod two;
+
+/// 武器目录条目：一种具体武器的基础属性
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeaponSpec {
+    pub name: &'static str,
+    pub kind: WeaponKind,
+    pub damage: (u32, u32),
+    pub hit_chance: f32,
+    pub str_requirement: u8,
+}
+
+/// 武器品阶（还原Shattered PD的5阶系统）
+#[derive(Copy, PartialEq, Debug, Encode, Decode, Serialize, Deserialize, Clone, Default)]
+pub enum Tier {
+    #[default]
+    One, // 普通（白色）
+    Two,   // 优秀（绿色）
+    Three, // 稀有（蓝色）
+    Four,  // 史诗（紫色）
+    Five,  // 传奇（橙色）
+}
+
+impl Tier {
+    /// 各深度区间（每5层一段）生成一至五阶武器的权重
+    const DEPTH_WEIGHTS: [[u32; 5]; 5] = [
+        [60, 30, 8, 2, 0],
+        [20, 45, 25, 8, 2],
+        [5, 20, 45, 22, 8],
+        [0, 10, 30, 40, 20],
+        [0, 0, 15, 35, 50],
+    ];
+
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Tier::One,
+            2 => Tier::Two,
+            3 => Tier::Three,
+            4 => Tier::Four,
+            5 => Tier::Five,
+            _ => Tier::One,
+        }
+    }
+
+    pub fn to_u32(&self) -> u32 {
+        match self {
+            Tier::One => 1,
+            Tier::Two => 2,
+            Tier::Three => 3,
+            Tier::Four => 4,
+            Tier::Five => 5,
+        }
+    }
+
+    /// 该品阶的武器目录
+    pub fn catalog(&self) -> &'static [WeaponSpec] {
+        match self {
+            Tier::One => one::CATALOG,
+            Tier::Two => two::CATALOG,
+            Tier::Three => three::CATALOG,
+            Tier::Four => four::CATALOG,
+            Tier::Five => five::CATALOG,
+        }
+    }
+
+    /// 按深度随机一个品阶，越深处高阶武器越常见
+    pub fn random_for_depth(depth: usize, rng: &mut impl rand::Rng) -> Self {
+        let weights = Self::DEPTH_WEIGHTS[((depth.max(1) - 1) / 5).min(4)];
+        let mut roll = rng.random_range(0..weights.iter().sum::<u32>());
+        for (i, weight) in weights.iter().enumerate() {
+            if roll < *weight {
+                return Tier::from_u32(i as u32 + 1);
+            }
+            roll -= weight;
+        }
+        Tier::One
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Tier::One => "I",
+                Tier::Two => "II",
+                Tier::Three => "III",
+                Tier::Four => "IV",
+                Tier::Five => "V",
+            }
+        )
+    }
+}
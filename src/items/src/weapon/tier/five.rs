@@ -0,0 +1,31 @@
+use super::WeaponSpec;
+use crate::weapon::kind::WeaponKind;
+
+pub const HOLY_SWORD: WeaponSpec = WeaponSpec {
+    name: "圣剑",
+    kind: WeaponKind::Sword,
+    damage: (15, 35),
+    hit_chance: 0.8,
+    str_requirement: 22,
+};
+
+/// 巨剑：沉重的双手剑
+pub const GREATSWORD: WeaponSpec = WeaponSpec {
+    name: "巨剑",
+    kind: WeaponKind::Greatsword,
+    damage: (16, 40),
+    hit_chance: 0.75,
+    str_requirement: 22,
+};
+
+/// 巨斧：伤害最高的武器，每次挥砍都要多花力气
+pub const GREATAXE: WeaponSpec = WeaponSpec {
+    name: "巨斧",
+    kind: WeaponKind::Greataxe,
+    damage: (18, 45),
+    hit_chance: 0.7,
+    str_requirement: 23,
+};
+
+/// 五阶武器目录
+pub const CATALOG: &[WeaponSpec] = &[HOLY_SWORD, GREATSWORD, GREATAXE];
@@ -0,0 +1,29 @@
+use super::WeaponSpec;
+use crate::weapon::kind::WeaponKind;
+
+pub const RUNE_SWORD: WeaponSpec = WeaponSpec {
+    name: "符文剑",
+    kind: WeaponKind::Sword,
+    damage: (10, 25),
+    hit_chance: 0.8,
+    str_requirement: 19,
+};
+
+pub const WAR_HAMMER: WeaponSpec = WeaponSpec {
+    name: "战锤",
+    kind: WeaponKind::Mace,
+    damage: (9, 24),
+    hit_chance: 0.75,
+    str_requirement: 19,
+};
+
+pub const HALBERD: WeaponSpec = WeaponSpec {
+    name: "长戟",
+    kind: WeaponKind::Spear,
+    damage: (8, 26),
+    hit_chance: 0.7,
+    str_requirement: 19,
+};
+
+/// 四阶武器目录
+pub const CATALOG: &[WeaponSpec] = &[RUNE_SWORD, WAR_HAMMER, HALBERD];
@@ -0,0 +1,29 @@
+use super::WeaponSpec;
+use crate::weapon::kind::WeaponKind;
+
+pub const SHORTSWORD: WeaponSpec = WeaponSpec {
+    name: "短剑",
+    kind: WeaponKind::Sword,
+    damage: (1, 6),
+    hit_chance: 0.8,
+    str_requirement: 10,
+};
+
+pub const CUDGEL: WeaponSpec = WeaponSpec {
+    name: "短棍",
+    kind: WeaponKind::Mace,
+    damage: (2, 5),
+    hit_chance: 0.75,
+    str_requirement: 10,
+};
+
+pub const LEATHER_WHIP: WeaponSpec = WeaponSpec {
+    name: "皮鞭",
+    kind: WeaponKind::Whip,
+    damage: (1, 5),
+    hit_chance: 0.85,
+    str_requirement: 9,
+};
+
+/// 一阶武器目录
+pub const CATALOG: &[WeaponSpec] = &[SHORTSWORD, CUDGEL, LEATHER_WHIP];
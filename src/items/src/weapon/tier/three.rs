@@ -0,0 +1,29 @@
+use super::WeaponSpec;
+use crate::weapon::kind::WeaponKind;
+
+pub const BROADSWORD: WeaponSpec = WeaponSpec {
+    name: "阔剑",
+    kind: WeaponKind::Sword,
+    damage: (6, 18),
+    hit_chance: 0.8,
+    str_requirement: 16,
+};
+
+pub const MACE: WeaponSpec = WeaponSpec {
+    name: "钉锤",
+    kind: WeaponKind::Mace,
+    damage: (5, 16),
+    hit_chance: 0.75,
+    str_requirement: 16,
+};
+
+pub const CHAIN_WHIP: WeaponSpec = WeaponSpec {
+    name: "链鞭",
+    kind: WeaponKind::Whip,
+    damage: (4, 15),
+    hit_chance: 0.9,
+    str_requirement: 15,
+};
+
+/// 三阶武器目录
+pub const CATALOG: &[WeaponSpec] = &[BROADSWORD, MACE, CHAIN_WHIP];
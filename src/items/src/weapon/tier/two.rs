@@ -0,0 +1,39 @@
+use super::WeaponSpec;
+use crate::weapon::kind::WeaponKind;
+
+pub const LONGSWORD: WeaponSpec = WeaponSpec {
+    name: "长剑",
+    kind: WeaponKind::Sword,
+    damage: (3, 12),
+    hit_chance: 0.8,
+    str_requirement: 13,
+};
+
+/// 匕首：伤害不高，但偷袭时格外致命
+pub const DAGGER: WeaponSpec = WeaponSpec {
+    name: "匕首",
+    kind: WeaponKind::Dagger,
+    damage: (2, 8),
+    hit_chance: 0.85,
+    str_requirement: 11,
+};
+
+/// 长矛：能刺中两格外的敌人
+pub const SPEAR: WeaponSpec = WeaponSpec {
+    name: "长矛",
+    kind: WeaponKind::Spear,
+    damage: (3, 14),
+    hit_chance: 0.75,
+    str_requirement: 14,
+};
+
+pub const QUARTERSTAFF: WeaponSpec = WeaponSpec {
+    name: "长棍",
+    kind: WeaponKind::Quarterstaff,
+    damage: (3, 10),
+    hit_chance: 0.8,
+    str_requirement: 12,
+};
+
+/// 二阶武器目录
+pub const CATALOG: &[WeaponSpec] = &[LONGSWORD, DAGGER, SPEAR, QUARTERSTAFF];
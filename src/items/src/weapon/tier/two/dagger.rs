@@ -1,3 +0,0 @@
-use bincode::{Decode, Encode};
-use serde::{Deserialize, Serialize};
-
@@ -2,7 +2,9 @@ pub mod core;
 pub mod ecs;
 pub mod event_bus;
 pub mod input;
+pub mod loot;
 pub mod systems;
+pub mod targeting;
 pub mod turn_system;
 
 pub mod hero_adapter;
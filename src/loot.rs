@@ -0,0 +1,90 @@
+//! 战利品生成
+//!
+//! 把敌人与 Boss 的掉落表转换为实际物品：
+//! - 掉落内容与品质取自独立的战利品随机数流，不会打乱战斗与地牢的随机序列
+//! - 装备品阶随深度提升，金币数量随深度增加
+//! - 财富加成提高稀有掉落的概率与金币数量
+
+use combat::boss::{Boss, BossType};
+use combat::enemy::{DropItem, Enemy, EnemyKind};
+use items::{
+    Armor, Item, ItemKind, MiscItem, MiscKind, Potion, Ring, Scroll, Weapon, potion::PotionKind,
+    scroll::ScrollKind,
+};
+use rand::Rng;
+
+/// 每深入一层金币数量增加的比例
+const GOLD_PER_DEPTH: f32 = 0.2;
+
+/// 普通敌人死亡时的掉落
+pub fn enemy_drops(kind: EnemyKind, depth: usize, luck: f32, rng: &mut impl Rng) -> Vec<Item> {
+    Enemy::new(kind, 0, 0)
+        .drop_items(rng, luck)
+        .into_iter()
+        .map(|drop| convert(drop, depth, luck, rng))
+        .collect()
+}
+
+/// Boss 死亡时的掉落：金币、若干装备与消耗品，以及一件强化过的稀有武器
+pub fn boss_drops(boss_type: BossType, depth: usize, luck: f32, rng: &mut impl Rng) -> Vec<Item> {
+    let loot = Boss::new(boss_type, 0, 0).generate_loot(rng);
+    let mut drops = vec![gold(loot.gold, depth, luck)];
+    drops.extend((0..loot.equipment_count).map(|_| equipment(depth, rng)));
+    drops.extend((0..loot.consumables_count).map(|_| consumable(rng)));
+    if loot.has_unique_item {
+        drops.push(unique(depth, rng));
+    }
+    drops
+}
+
+/// 把一条掉落记录转换为物品
+fn convert(drop: DropItem, depth: usize, luck: f32, rng: &mut impl Rng) -> Item {
+    match drop {
+        DropItem::Gold(amount) => gold(amount, depth, luck),
+        DropItem::HealthPotion => Item::new(ItemKind::Potion(PotionKind::Healing.into())),
+        DropItem::Weapon(_) => Item::new(ItemKind::Weapon(Weapon::random_for_depth(depth, rng))),
+        DropItem::Armor(_) => Item::new(ItemKind::Armor(Armor::random_for_depth(depth, rng))),
+        DropItem::Scroll(name) => {
+            let kind = match name.as_str() {
+                "Identify" => ScrollKind::Identify,
+                "Magic Mapping" => ScrollKind::MagicMapping,
+                _ => Scroll::random_with(rng).kind,
+            };
+            Item::new(ItemKind::Scroll(kind.into()))
+        }
+        DropItem::Key => MiscItem::new(MiscKind::Key).into(),
+        DropItem::Artifact => unique(depth, rng),
+    }
+}
+
+/// 一堆金币，数量随深度与财富加成增加
+fn gold(amount: u32, depth: usize, luck: f32) -> Item {
+    let scale = (1.0 + depth.saturating_sub(1) as f32 * GOLD_PER_DEPTH) * (1.0 + luck.max(0.0));
+    MiscItem::new(MiscKind::Gold((amount as f32 * scale).round() as u32)).into()
+}
+
+/// 一件按深度生成的武器、护甲或戒指
+fn equipment(depth: usize, rng: &mut impl Rng) -> Item {
+    match rng.random_range(0..3) {
+        0 => Item::new(ItemKind::Weapon(Weapon::random_for_depth(depth, rng))),
+        1 => Item::new(ItemKind::Armor(Armor::random_for_depth(depth, rng))),
+        _ => Item::new(ItemKind::Ring(Ring::random_with(rng))),
+    }
+}
+
+/// 一瓶药水或一张卷轴
+fn consumable(rng: &mut impl Rng) -> Item {
+    if rng.random_bool(0.5) {
+        Item::new(ItemKind::Potion(Potion::random_with(rng)))
+    } else {
+        Item::new(ItemKind::Scroll(Scroll::random_with(rng)))
+    }
+}
+
+/// 稀有掉落：比当前深度高一段的武器，不带诅咒并强化一级
+fn unique(depth: usize, rng: &mut impl Rng) -> Item {
+    let mut item = Item::new(ItemKind::Weapon(Weapon::random_for_depth(depth + 5, rng)));
+    item.remove_curse();
+    item.upgrade();
+    item
+}
@@ -3,9 +3,11 @@ pub mod ecs;
 pub mod event_bus;
 pub mod game_loop;
 pub mod input;
+pub mod loot;
 pub mod render; // 模块化渲染组件
 pub mod renderer;
 pub mod systems;
+pub mod targeting;
 pub mod turn_system;
 
 use anyhow::Context;
@@ -0,0 +1,162 @@
+//! 炼金釜界面渲染器
+//!
+//! 左侧列出背包中可以投入炼金釜的材料，右侧显示已投入的材料、
+//! 预期产物（只有本局发现过的配方才会显示）以及已知配方列表。
+
+use crate::ecs::{Inventory, Resources};
+use crate::systems::{InventorySystem, find_player_entity};
+use hecs::World;
+use items::alchemy;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+/// 炼金釜界面渲染器
+pub struct AlchemyRenderer;
+
+impl AlchemyRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 渲染炼金界面（材料列表、投入的材料与产物预览、已知配方）
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        world: &World,
+        resources: &Resources,
+        selected_item: usize,
+        chosen: &[Option<usize>],
+    ) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // 标题
+                Constraint::Min(10),   // 内容
+                Constraint::Length(1), // 底部提示
+            ])
+            .split(area);
+
+        let title = Paragraph::new("⚗ 炼金釜")
+            .style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(title, layout[0]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(layout[1]);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(7), Constraint::Min(5)])
+            .split(columns[1]);
+
+        let player = find_player_entity(world);
+        let slot_name = |slot: usize| {
+            player
+                .and_then(|p| world.get::<&Inventory>(p).ok())
+                .and_then(|inventory| inventory.items.get(slot).and_then(|s| s.item.clone()))
+                .map(|item| (item.name.clone(), item.quantity))
+        };
+        let chosen: Vec<usize> = chosen.iter().flatten().copied().collect();
+
+        // 材料列表
+        let slots = player
+            .map(|p| InventorySystem::alchemy_slots(world, p))
+            .unwrap_or_default();
+        let lines: Vec<ListItem> = if slots.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "背包中没有可以炼制的材料",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            slots
+                .iter()
+                .enumerate()
+                .map(|(index, &slot)| {
+                    let (name, quantity) = slot_name(slot).unwrap_or_default();
+                    let used = chosen.iter().filter(|&&c| c == slot).count();
+                    let mut spans = vec![Span::styled(name, Style::default().fg(Color::White))];
+                    if quantity > 1 {
+                        spans.push(Span::styled(
+                            format!(" x{}", quantity),
+                            Style::default().fg(Color::Rgb(120, 120, 120)),
+                        ));
+                    }
+                    if used > 0 {
+                        spans.push(Span::styled(
+                            format!(" [已投入 {}]", used),
+                            Style::default().fg(Color::Magenta),
+                        ));
+                    }
+                    let item = ListItem::new(Line::from(spans));
+                    if index == selected_item {
+                        item.style(Style::default().bg(Color::Rgb(50, 50, 70)))
+                    } else {
+                        item
+                    }
+                })
+                .collect()
+        };
+        let list = List::new(lines).block(Block::default().borders(Borders::ALL).title("材料"));
+        frame.render_widget(list, columns[0]);
+
+        // 投入的材料与产物预览
+        let mut pot_lines: Vec<Line> = chosen
+            .iter()
+            .map(|&slot| {
+                let name = slot_name(slot).map(|(name, _)| name).unwrap_or_default();
+                Line::from(format!("• {}", name))
+            })
+            .collect();
+        if pot_lines.is_empty() {
+            pot_lines.push(Line::styled("（空）", Style::default().fg(Color::DarkGray)));
+        } else {
+            let recipe = player
+                .and_then(|p| InventorySystem::alchemy_ingredients(world, p, &chosen))
+                .and_then(|ingredients| alchemy::find_recipe(&ingredients))
+                .filter(|recipe| resources.item_knowledge.knows_recipe(recipe));
+            pot_lines.push(match recipe {
+                Some(recipe) => Line::styled(
+                    format!("→ {}", recipe.product.name()),
+                    Style::default().fg(Color::Green),
+                ),
+                None => Line::styled("→ ???", Style::default().fg(Color::DarkGray)),
+            });
+        }
+        let pot = Paragraph::new(pot_lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("釜中（最多 {} 样）", alchemy::MAX_INGREDIENTS)),
+        );
+        frame.render_widget(pot, right[0]);
+
+        // 已知配方
+        let recipes = alchemy::recipes();
+        let known: Vec<Line> = recipes
+            .iter()
+            .filter(|recipe| resources.item_knowledge.knows_recipe(recipe))
+            .map(|recipe| Line::styled(recipe.describe(), Style::default().fg(Color::Green)))
+            .collect();
+        let title = format!("已知配方 {}/{}", known.len(), recipes.len());
+        let known = Paragraph::new(known).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(known, right[1]);
+
+        let hint = Paragraph::new("Enter: 投入  ←: 取出  b: 炼制  Esc: 离开")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(hint, layout[2]);
+    }
+}
+
+impl Default for AlchemyRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
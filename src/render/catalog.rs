@@ -0,0 +1,93 @@
+//! 物品图鉴渲染器
+//!
+//! 列出本局所有药水、卷轴、戒指的外观，以及已经鉴定出的真实名称。
+//! 数据来自 `Resources::item_knowledge`。
+
+use crate::ecs::Resources;
+use items::ItemCategory;
+use items::knowledge::CatalogEntry;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+/// 物品图鉴渲染器
+pub struct CatalogRenderer;
+
+impl CatalogRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 渲染物品图鉴（药水、卷轴、戒指三栏）
+    pub fn render(&self, frame: &mut Frame, area: Rect, resources: &Resources) {
+        let entries = resources.item_knowledge.catalog();
+        let known = entries.iter().filter(|entry| entry.name.is_some()).count();
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // 标题
+                Constraint::Min(10),   // 图鉴内容
+                Constraint::Length(1), // 底部提示
+            ])
+            .split(area);
+
+        let title = Paragraph::new(format!("📖 物品图鉴（已鉴定 {}/{}）", known, entries.len()))
+            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(title, layout[0]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(34),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ])
+            .split(layout[1]);
+        let sections = [
+            (ItemCategory::Potion, "药水"),
+            (ItemCategory::Scroll, "卷轴"),
+            (ItemCategory::Ring, "戒指"),
+        ];
+        for ((category, label), column) in sections.into_iter().zip(columns.iter()) {
+            let lines: Vec<Line> = entries
+                .iter()
+                .filter(|entry| entry.category == category)
+                .map(Self::entry_line)
+                .collect();
+            let list = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(label));
+            frame.render_widget(list, *column);
+        }
+
+        let hint = Paragraph::new("Esc: 返回")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(hint, layout[2]);
+    }
+
+    fn entry_line(entry: &CatalogEntry) -> Line<'static> {
+        match &entry.name {
+            Some(name) => Line::from(vec![
+                Span::styled(entry.appearance.clone(), Style::default().fg(Color::Gray)),
+                Span::raw(" → "),
+                Span::styled(name.clone(), Style::default().fg(Color::Green)),
+            ]),
+            None => Line::from(vec![
+                Span::styled(entry.appearance.clone(), Style::default().fg(Color::Gray)),
+                Span::styled(" → ???", Style::default().fg(Color::DarkGray)),
+            ]),
+        }
+    }
+}
+
+impl Default for CatalogRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -315,6 +315,7 @@ impl ClassSelectionRenderer {
                 items::ItemKind::Potion(_) => ("🧪", Color::Magenta),
                 items::ItemKind::Scroll(_) => ("📜", Color::Cyan),
                 items::ItemKind::Seed(_) => ("🌱", Color::Green),
+                items::ItemKind::Signature(_) => ("✨", Color::Yellow),
                 _ => ("📦", Color::White),
             };
 
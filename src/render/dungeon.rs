@@ -3,7 +3,10 @@
 //! 负责渲染地牢地图、实体（玩家、怪物、物品）和 FOV 效果。
 //! 直接从 ECS World 读取数据，使用 FOVSystem 计算的可见性信息。
 
-use crate::ecs::{Actor, Color, Player, Position, Renderable, TerrainType, Tile, Viewshed};
+use crate::ecs::{
+    Actor, Blob, Color, ECSItem, Plant, Player, Position, Renderable, TerrainType, Tile, Viewshed,
+};
+use crate::targeting::TargetPreview;
 use hecs::World;
 use ratatui::{
     Frame,
@@ -13,7 +16,10 @@ use ratatui::{
     style::{Color as TuiColor, Style},
     widgets::{Block, Borders, Widget},
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// 同一格有多件物品时显示的物品堆符号
+const PILE_SYMBOL: char = '&';
 
 /// 地牢渲染器
 ///
@@ -57,6 +63,43 @@ impl DungeonRenderer {
         frame.render_widget(dungeon_widget, inner_area);
     }
 
+    /// 瞄准模式渲染
+    ///
+    /// 在普通地牢画面上叠加弹道（黄）、作用范围（橙）与光标（红）
+    pub fn render_targeting(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        world: &World,
+        preview: &TargetPreview,
+        cursor: &Position,
+    ) {
+        self.render(frame, area, world);
+
+        let inner_area = Block::default().borders(Borders::ALL).inner(area);
+        let buf = frame.buffer_mut();
+        let mut highlight = |pos: &Position, color: TuiColor| {
+            let screen_x = inner_area.left() as i32 + pos.x;
+            let screen_y = inner_area.top() as i32 + pos.y;
+            if screen_x < inner_area.left() as i32
+                || screen_x >= inner_area.right() as i32
+                || screen_y < inner_area.top() as i32
+                || screen_y >= inner_area.bottom() as i32
+            {
+                return;
+            }
+            buf[(screen_x as u16, screen_y as u16)].set_bg(color);
+        };
+
+        for pos in &preview.area {
+            highlight(pos, TuiColor::Rgb(120, 60, 0));
+        }
+        for pos in &preview.path {
+            highlight(pos, TuiColor::Rgb(100, 100, 0));
+        }
+        highlight(cursor, TuiColor::Rgb(160, 0, 0));
+    }
+
     /// 获取当前地牢深度
     fn get_dungeon_depth(&self, world: &World) -> i32 {
         // 从玩家位置获取深度
@@ -90,9 +133,12 @@ impl<'a> Widget for DungeonWidget<'a> {
         // 获取玩家位置和视野数据
         let (player_pos, visible_set, memory_set) = self.get_player_vision();
 
-        // 渲染地图块（Tiles）
-        for (_, (pos, tile, _renderable)) in
-            self.world.query::<(&Position, &Tile, &Renderable)>().iter()
+        // 渲染地图块（Tiles）；地面物品单独渲染，不覆盖脚下的地形
+        for (_, (pos, tile, _renderable)) in self
+            .world
+            .query::<(&Position, &Tile, &Renderable)>()
+            .without::<&ECSItem>()
+            .iter()
         {
             // 只渲染当前层级
             if let Some(ref player_pos) = player_pos {
@@ -134,6 +180,100 @@ impl<'a> Widget for DungeonWidget<'a> {
             cell.set_char(symbol).set_fg(color);
         }
 
+        // 渲染气体与火焰（以背景色覆盖可见的格子）
+        for (_, (pos, blob)) in self.world.query::<(&Position, &Blob)>().iter() {
+            if let Some(ref player_pos) = player_pos {
+                if pos.z != player_pos.z {
+                    continue;
+                }
+            }
+            if !self.show_all && !visible_set.contains(&(pos.x, pos.y)) {
+                continue;
+            }
+
+            let screen_x = area.left() as i32 + pos.x;
+            let screen_y = area.top() as i32 + pos.y;
+            if screen_x < area.left() as i32
+                || screen_x >= area.right() as i32
+                || screen_y < area.top() as i32
+                || screen_y >= area.bottom() as i32
+            {
+                continue;
+            }
+
+            buf[(screen_x as u16, screen_y as u16)].set_bg(self.convert_color(&blob.kind.color()));
+        }
+
+        // 渲染种下的植物（只在可见时显示）
+        for (_, (pos, plant)) in self.world.query::<(&Position, &Plant)>().iter() {
+            if let Some(ref player_pos) = player_pos {
+                if pos.z != player_pos.z {
+                    continue;
+                }
+            }
+            if !self.show_all && !visible_set.contains(&(pos.x, pos.y)) {
+                continue;
+            }
+
+            let screen_x = area.left() as i32 + pos.x;
+            let screen_y = area.top() as i32 + pos.y;
+            if screen_x < area.left() as i32
+                || screen_x >= area.right() as i32
+                || screen_y < area.top() as i32
+                || screen_y >= area.bottom() as i32
+            {
+                continue;
+            }
+
+            buf[(screen_x as u16, screen_y as u16)]
+                .set_char('♣')
+                .set_fg(self.convert_color(&plant.color()));
+        }
+
+        // 渲染地面物品：一件时显示物品符号，多件时显示物品堆符号
+        let mut ground_items: HashMap<(i32, i32), (char, Color, usize)> = HashMap::new();
+        for (_, (pos, renderable, _item)) in self
+            .world
+            .query::<(&Position, &Renderable, &ECSItem)>()
+            .iter()
+        {
+            if let Some(ref player_pos) = player_pos {
+                if pos.z != player_pos.z {
+                    continue;
+                }
+            }
+            ground_items
+                .entry((pos.x, pos.y))
+                .and_modify(|(_, _, count)| *count += 1)
+                .or_insert((renderable.symbol, renderable.fg_color.clone(), 1));
+        }
+        for ((x, y), (symbol, color, count)) in ground_items {
+            let is_visible = self.show_all || visible_set.contains(&(x, y));
+            if !is_visible && !memory_set.contains(&(x, y)) {
+                continue;
+            }
+
+            let screen_x = area.left() as i32 + x;
+            let screen_y = area.top() as i32 + y;
+            if screen_x < area.left() as i32
+                || screen_x >= area.right() as i32
+                || screen_y < area.top() as i32
+                || screen_y >= area.bottom() as i32
+            {
+                continue;
+            }
+
+            let (symbol, mut color) = if count > 1 {
+                (PILE_SYMBOL, TuiColor::Yellow)
+            } else {
+                (symbol, self.convert_color(&color))
+            };
+            if !is_visible {
+                color = self.darken_color(color);
+            }
+            buf[(screen_x as u16, screen_y as u16)].set_char(symbol).set_fg(color);
+        }
+
         // 渲染实体（Actor：玩家和怪物）
         for (_, (pos, renderable, _actor)) in self
             .world
@@ -207,9 +347,12 @@ impl<'a> DungeonWidget<'a> {
             TerrainType::StairsDown => ('▼', TuiColor::Cyan),
             TerrainType::StairsUp => ('▲', TuiColor::Magenta),
             TerrainType::Water => ('≈', TuiColor::Blue),
+            TerrainType::Grass => ('"', TuiColor::Green),
             TerrainType::Trap => ('⚠', TuiColor::Red),
             TerrainType::Barrel => ('⚱', TuiColor::Yellow),
             TerrainType::Empty => (' ', TuiColor::Black),
+            TerrainType::Ice => ('░', TuiColor::Rgb(170, 220, 255)),
+            TerrainType::AlchemyPot => ('⚗', TuiColor::Magenta),
         }
     }
 
@@ -3,9 +3,12 @@
 //! 显示玩家状态信息：生命值、等级、金币、饱食度等。
 //! 直接从 ECS World 读取 Player 实体的组件数据。
 
-use crate::ecs::{Actor, Hunger, Player, PlayerProgress, Stats, Wealth};
+use crate::ecs::{
+    Actor, Hunger, Inventory, Player, PlayerProgress, Quickslot, QUICKSLOT_COUNT, Shielding,
+    Stats, Wealth,
+};
 use hecs::World;
-use hero::class::Class;
+use hero::{Hero, class::Class};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -19,6 +22,8 @@ use ratatui::{
 /// 布局：
 /// ```
 /// | 职业+等级 | ======= 生命值 ======= | 💰金币 | 🍖饱食度 |
+/// | 1:治疗药水 x2  2:魔法飞弹法杖 [2/3]  3:-  ...                |
+/// | ======================= 经验值 ======================= |
 /// ```
 pub struct HudRenderer;
 
@@ -43,11 +48,12 @@ impl HudRenderer {
 
         let (stats, wealth, hunger, progress, actor_name) = player_data.unwrap();
 
-        // 主布局：顶部状态栏 + 底部经验条
+        // 主布局：顶部状态栏 + 快捷栏 + 底部经验条
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(2), // 主状态栏
+                Constraint::Length(1), // 主状态栏
+                Constraint::Length(1), // 快捷栏
                 Constraint::Length(1), // 经验条
             ])
             .split(area);
@@ -66,8 +72,14 @@ impl HudRenderer {
         // 1. 渲染等级和职业
         self.render_level(frame, chunks[0], &stats, &progress, &actor_name);
 
-        // 2. 渲染生命值条
-        self.render_health(frame, chunks[1], &stats);
+        // 2. 渲染生命值条（护盾显示在生命值之后）
+        let shield = world
+            .query::<(&Shielding, &Player)>()
+            .iter()
+            .next()
+            .map(|(_, (shield, _))| shield.amount)
+            .unwrap_or(0);
+        self.render_health(frame, chunks[1], &stats, shield);
 
         // 3. 渲染金币
         self.render_gold(frame, chunks[2], &wealth);
@@ -75,8 +87,11 @@ impl HudRenderer {
         // 4. 渲染饱食度
         self.render_hunger(frame, chunks[3], &hunger);
 
-        // 5. 渲染经验条（使用 Stats 中的经验值）
-        self.render_experience(frame, main_chunks[1], &stats);
+        // 5. 渲染快捷栏
+        self.render_quickslots(frame, main_chunks[1], world);
+
+        // 6. 渲染经验条（使用 Stats 中的经验值）
+        self.render_experience(frame, main_chunks[2], &stats);
     }
 
     /// 从 ECS World 获取玩家数据
@@ -128,9 +143,13 @@ impl HudRenderer {
         frame.render_widget(Paragraph::new(text).alignment(Alignment::Center), area);
     }
 
-    fn render_health(&self, frame: &mut Frame, area: Rect, stats: &Stats) {
+    fn render_health(&self, frame: &mut Frame, area: Rect, stats: &Stats, shield: u32) {
         let ratio = stats.hp as f64 / stats.max_hp.max(1) as f64;
-        let label = format!("{}/{}", stats.hp, stats.max_hp);
+        let label = if shield > 0 {
+            format!("{}/{} +{}", stats.hp, stats.max_hp, shield)
+        } else {
+            format!("{}/{}", stats.hp, stats.max_hp)
+        };
 
         // 根据生命值比例选择颜色
         let color = match ratio {
@@ -181,10 +200,66 @@ impl HudRenderer {
         frame.render_widget(Paragraph::new(text).alignment(Alignment::Center), area);
     }
 
+    /// 快捷栏：显示每格物品的数量与法杖充能，已用完的物品显示为灰色
+    fn render_quickslots(&self, frame: &mut Frame, area: Rect, world: &World) {
+        let data = world
+            .query::<(&Inventory, &Quickslot, &Player)>()
+            .iter()
+            .next()
+            .map(|(_, (inventory, quickslot, _))| (inventory.clone(), quickslot.clone()));
+        let Some((inventory, quickslot)) = data else {
+            let hint = Span::styled("快捷栏为空（在物品栏中按 F 放入物品）", Style::default().fg(Color::DarkGray));
+            frame.render_widget(Paragraph::new(Line::from(hint)), area);
+            return;
+        };
+
+        let mut spans = Vec::new();
+        for index in 0..QUICKSLOT_COUNT {
+            spans.push(Span::styled(format!("{}:", index + 1), Style::default().fg(Color::Gray)));
+            let Some(entry) = quickslot.entry(index) else {
+                spans.push(Span::styled("-  ", Style::default().fg(Color::DarkGray)));
+                continue;
+            };
+            let item = quickslot
+                .resolve(&inventory, index)
+                .and_then(|slot| inventory.items[slot].item.as_ref());
+            match item {
+                Some(item) => {
+                    let mut text = item.name.clone();
+                    if item.quantity > 1 {
+                        text.push_str(&format!(" x{}", item.quantity));
+                    }
+                    match &item.kind {
+                        items::ItemKind::Wand(wand) => {
+                            text.push_str(&format!(" [{}/{}]", wand.charges, wand.max_charges));
+                        }
+                        items::ItemKind::Signature(signature) => match &signature.absorbed {
+                            Some(wand) => text.push_str(&format!(" [{}/{}]", wand.charges, wand.max_charges)),
+                            None if signature.kind.max_charges() > 0 => text.push_str(&format!(
+                                " [{}/{}]",
+                                signature.charges,
+                                signature.kind.max_charges()
+                            )),
+                            None => {}
+                        },
+                        _ => {}
+                    }
+                    spans.push(Span::styled(text, Style::default().fg(Color::White)));
+                }
+                None => spans.push(Span::styled(
+                    format!("{} x0", entry.name),
+                    Style::default().fg(Color::DarkGray),
+                )),
+            }
+            spans.push(Span::raw("  "));
+        }
+
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
     fn render_experience(&self, frame: &mut Frame, area: Rect, stats: &Stats) {
-        // 计算经验值比例（简单估算：下一级需要 level * 100 经验）
         let current_exp = stats.experience;
-        let next_level_exp = stats.level * 100;
+        let next_level_exp = Hero::max_exp(stats.level);
 
         let exp_ratio = if next_level_exp > 0 {
             (current_exp as f64 / next_level_exp as f64).min(1.0)
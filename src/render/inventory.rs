@@ -2,15 +2,20 @@
 //!
 //!    渲染玩家的背包和装备栏。
 //!    直接从    ECS    World    读取    Player    的    Inventory    组件。
+//!    背包按物品分类分为多个标签页，页内按 `sort_value` 排序，
+//!    右侧详情栏显示选中物品的说明、与当前装备的对比和可用操作。
+//!    脚下有多件物品时，在地图上弹出拾取选择框。
 
-use crate::ecs::{ECSItem, Inventory, ItemSlot, Player};
+use crate::ecs::{ECSItem, Equipment, Inventory, Player, PlayerProgress, Position, Quickslot};
+use crate::systems::{InventorySystem, find_player_entity};
 use hecs::World;
+use items::{ItemTrait, ItemUsage, Usable};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Tabs, Wrap},
 };
 
 ///    物品栏渲染器
@@ -22,7 +27,90 @@ impl InventoryRenderer {
     }
 
     ///    渲染物品栏
-    pub fn render(&self, frame: &mut Frame, area: Rect, world: &World) {
+    pub fn render(&self, frame: &mut Frame, area: Rect, world: &World, selected_item: usize) {
+        self.render_with_hint(
+            frame,
+            area,
+            world,
+            selected_item,
+            "←→: 切换分类 | ↑↓: 选择 | 1-5: 卸下装备 | Esc: 关闭",
+        );
+    }
+
+    ///    渲染物品选择界面（为卷轴等物品选择目标）
+    pub fn render_item_picker(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        world: &World,
+        source_slot: usize,
+        selected_item: usize,
+    ) {
+        let source_name = self
+            .get_player_inventory(world)
+            .and_then(|inventory| inventory.items.get(source_slot)?.item.as_ref().map(|i| i.name.clone()))
+            .unwrap_or_default();
+        let hint = format!("选择使用{}的目标 | ←→: 切换分类 | Enter: 确认 | Esc: 取消", source_name);
+        self.render_with_hint(frame, area, world, selected_item, &hint);
+    }
+
+    ///    渲染拾取选择框：列出玩家脚下的物品堆，覆盖在地图区域中央
+    pub fn render_pile_chooser(&self, frame: &mut Frame, area: Rect, world: &World, selected_item: usize) {
+        let pile: Vec<ECSItem> = find_player_entity(world)
+            .and_then(|player| world.get::<&Position>(player).ok().map(|pos| (*pos).clone()))
+            .map(|pos| {
+                InventorySystem::ground_items_at(world, &pos)
+                    .into_iter()
+                    .filter_map(|entity| world.get::<&ECSItem>(entity).ok().map(|item| (*item).clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let width = area.width.min(40);
+        let height = (pile.len() as u16 + 2).min(area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let lines: Vec<ListItem> = pile
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let line = Line::from(vec![
+                    Span::raw(format!("{} ", self.get_item_icon(item))),
+                    Span::styled(
+                        InventorySystem::pile_entry_name(item),
+                        Style::default().fg(self.get_item_color(item)),
+                    ),
+                ]);
+                if index == selected_item {
+                    ListItem::new(line).style(Style::default().bg(Color::Rgb(50, 50, 70)).add_modifier(Modifier::BOLD))
+                } else {
+                    ListItem::new(line)
+                }
+            })
+            .collect();
+        let list = List::new(lines).block(
+            Block::default()
+                .title("拾起哪一件？(Enter 拾起 | Esc 取消)")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+        frame.render_widget(Clear, popup);
+        frame.render_widget(list, popup);
+    }
+
+    fn render_with_hint(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        world: &World,
+        selected_item: usize,
+        hint: &str,
+    ) {
         //    获取玩家物品栏
         let inventory = self.get_player_inventory(world);
 
@@ -53,12 +141,13 @@ impl InventoryRenderer {
             ])
             .split(area);
 
-        // 分割区域：左边装备栏，右边物品栏
+        // 分割区域：左边装备栏，中间物品栏，右边物品详情
         let main_layout = Layout::default()
             .direction(ratatui::layout::Direction::Horizontal)
             .constraints([
-                Constraint::Percentage(30), // 装备栏
-                Constraint::Percentage(70), // 物品栏
+                Constraint::Percentage(28), // 装备栏
+                Constraint::Percentage(40), // 物品栏
+                Constraint::Percentage(32), // 物品详情
             ])
             .split(main_chunks[0]);
 
@@ -69,7 +158,7 @@ impl InventoryRenderer {
         let block = Block::default()
             .title(format!(
                 "═══ 📦 背包 ({}/{}) ═══",
-                inventory.items.len(),
+                inventory.main_slots_used(),
                 inventory.max_slots
             ))
             .title_alignment(Alignment::Center)
@@ -94,11 +183,14 @@ impl InventoryRenderer {
             .alignment(Alignment::Center);
             frame.render_widget(empty_text, inner_area);
         } else {
-            self.render_items(frame, inner_area, &inventory.items);
+            self.render_items(frame, inner_area, &inventory, selected_item);
         }
 
+        // 渲染物品详情
+        self.render_details(frame, main_layout[2], world, &inventory, selected_item);
+
         // 渲染底部提示
-        let hints = Paragraph::new("按数字键使用物品 | D: 丢弃 | E: 装备 | Esc: 关闭")
+        let hints = Paragraph::new(hint)
             .style(Style::default().fg(Color::Gray))
             .block(
                 Block::default()
@@ -111,7 +203,9 @@ impl InventoryRenderer {
     }
 
     ///    渲染装备栏
-    fn render_equipment(&self, frame: &mut Frame, area: Rect, _world: &World) {
+    fn render_equipment(&self, frame: &mut Frame, area: Rect, world: &World) {
+        use crate::ecs::{EquipSlot, Equipment};
+
         let block = Block::default()
             .title("═══ ⚔️ 装备 ═══")
             .title_alignment(Alignment::Center)
@@ -122,22 +216,38 @@ impl InventoryRenderer {
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
 
-        // 装备槽位
-        let equipment_slots = vec![
-            ("武器", "⚔️", Color::Red),
-            ("头盔", "🪖", Color::LightBlue),
-            ("护甲", "🛡️", Color::Blue),
-            ("戒指", "💍", Color::Magenta),
-            ("饰品", "📿", Color::Cyan),
+        let equipment = world
+            .query::<(&Equipment, &Player)>()
+            .iter()
+            .next()
+            .map(|(_, (equipment, _))| equipment.clone())
+            .unwrap_or_default();
+
+        // 装备槽位（顺序与卸下装备的数字键一致）
+        let equipment_slots = [
+            (EquipSlot::Weapon, "武器", "⚔️", Color::Red),
+            (EquipSlot::Armor, "护甲", "🛡️", Color::Blue),
+            (EquipSlot::Ring(0), "戒指", "💍", Color::Magenta),
+            (EquipSlot::Ring(1), "戒指", "💍", Color::Magenta),
+            (EquipSlot::Artifact, "神器", "📿", Color::Cyan),
         ];
 
         let equipment_lines: Vec<Line> = equipment_slots
             .iter()
-            .map(|(slot, icon, color)| {
+            .enumerate()
+            .map(|(index, (slot, label, icon, color))| {
+                let content = match equipment.slot_name(*slot) {
+                    Some(name) if equipment.is_cursed(*slot) => {
+                        Span::styled(name, Style::default().fg(Color::LightRed))
+                    }
+                    Some(name) => Span::styled(name, Style::default().fg(Color::White)),
+                    None => Span::styled("空", Style::default().fg(Color::DarkGray)),
+                };
                 Line::from(vec![
+                    Span::styled(format!("[{}] ", index + 1), Style::default().fg(Color::Gray)),
                     Span::styled(format!("{} ", icon), Style::default().fg(*color)),
-                    Span::styled(format!("{}: ", slot), Style::default().fg(Color::Gray)),
-                    Span::styled("空", Style::default().fg(Color::DarkGray)),
+                    Span::styled(format!("{}: ", label), Style::default().fg(Color::Gray)),
+                    content,
                 ])
             })
             .collect();
@@ -155,79 +265,299 @@ impl InventoryRenderer {
             .map(|(_, (inventory, _player))| inventory.clone())
     }
 
-    ///    渲染物品列表
-    fn render_items(&self, frame: &mut Frame, area: Rect, items: &[ItemSlot]) {
-        let item_lines: Vec<ListItem> = items
+    ///    渲染分类标签与当前分类中的物品列表
+    fn render_items(&self, frame: &mut Frame, area: Rect, inventory: &Inventory, selected_item: usize) {
+        let tabs = inventory.category_tabs();
+        let current = tabs
             .iter()
-            .enumerate()
-            .map(|(index, slot)| {
-                let (name, color, quantity, icon) = match &slot.item {
-                    None => ("空".to_string(), Color::DarkGray, 1, "□"),
-                    Some(item) => {
-                        let color = self.get_item_color(item);
-                        let quantity = item.quantity;
-                        let icon = self.get_item_icon(item);
-                        (item.name.clone(), color, quantity, icon)
-                    }
-                };
+            .position(|(_, slots)| slots.contains(&selected_item))
+            .unwrap_or(0);
+
+        let chunks = Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(1)])
+            .split(area);
+
+        let titles: Vec<Line> = tabs
+            .iter()
+            .map(|(category, slots)| Line::from(format!("{}({})", category.display_name(), slots.len())))
+            .collect();
+        let tab_bar = Tabs::new(titles)
+            .select(current)
+            .style(Style::default().fg(Color::Gray))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .divider("|")
+            .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(Color::Rgb(80, 80, 80))));
+        frame.render_widget(tab_bar, chunks[0]);
+
+        let slots = tabs.get(current).map(|(_, slots)| slots.as_slice()).unwrap_or_default();
+        let containers = inventory.slot_containers();
+        let item_lines: Vec<ListItem> = slots
+            .iter()
+            .filter_map(|&index| Some((index, inventory.items.get(index)?.item.as_ref()?)))
+            .map(|(index, item)| {
+                let color = self.get_item_color(item);
+                let icon = self.get_item_icon(item);
 
-                let quantity_str = if quantity > 1 {
-                    format!(" x{}", quantity)
+                let mut quantity_str = if item.quantity > 1 {
+                    format!(" x{}", item.quantity)
                 } else {
                     String::new()
                 };
 
+                // 法杖显示剩余充能
+                if let items::ItemKind::Wand(wand) = &item.kind {
+                    quantity_str.push_str(&format!(" [{}/{}]", wand.charges, wand.max_charges));
+                }
+
+                // 容器显示已用格数，容器中的物品缩进显示在容器下方
+                if matches!(&item.kind, items::ItemKind::Misc(misc) if misc.kind.is_container()) {
+                    let stored = containers.iter().filter(|&&c| c == Some(index)).count();
+                    quantity_str.push_str(&format!(" [{}/{}]", stored, items::misc::CONTAINER_CAPACITY));
+                }
+                let indent = if containers[index].is_some() { "  └ " } else { "" };
+
                 let line = Line::from(vec![
-                    Span::styled(
-                        format!("[{}] ", index + 1),
-                        Style::default().fg(Color::Gray),
-                    ),
+                    Span::raw(indent),
                     Span::styled(format!("{} ", icon), Style::default().fg(color)),
                     Span::styled(
-                        name,
+                        item.name.clone(),
                         Style::default().fg(color).add_modifier(Modifier::BOLD),
                     ),
                     Span::styled(quantity_str, Style::default().fg(Color::Rgb(120, 120, 120))),
                 ]);
 
-                ListItem::new(line)
+                if index == selected_item {
+                    ListItem::new(line).style(Style::default().bg(Color::Rgb(50, 50, 70)))
+                } else {
+                    ListItem::new(line)
+                }
             })
             .collect();
 
         let list = List::new(item_lines);
-        frame.render_widget(list, area);
+        frame.render_widget(list, chunks[1]);
     }
 
-    ///    根据物品类型获取颜色
-    fn get_item_color(&self, item: &ECSItem) -> Color {
-        use crate::ecs::ItemType;
-        use items::ItemTrait;
+    ///    渲染选中物品的详情：说明、与当前装备的对比、可用操作
+    fn render_details(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        world: &World,
+        inventory: &Inventory,
+        selected_item: usize,
+    ) {
+        let block = Block::default()
+            .title("═══ 🔍 详情 ═══")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Green));
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some(item) = inventory.items.get(selected_item).and_then(|slot| slot.item.as_ref()) else {
+            return;
+        };
+
+        let label = Style::default().fg(Color::Gray);
+        let mut lines = vec![
+            Line::styled(
+                item.name.clone(),
+                Style::default().fg(item.rarity().color()).add_modifier(Modifier::BOLD),
+            ),
+            Line::from(vec![
+                Span::styled(format!("{} · ", item.category().display_name()), label),
+                Span::styled(item.rarity().display_name(), Style::default().fg(item.rarity().color())),
+            ]),
+            Line::from(""),
+        ];
 
-        if let Ok(reference_item) = item.to_items_item() {
-            return reference_item.rarity().color();
+        lines.extend(Self::describe(item).into_iter().map(Line::from));
+        if item.quantity > 1 {
+            lines.push(Line::styled(format!("数量: {}", item.quantity), label));
+        }
+        lines.push(Line::styled(format!("价值: {} 金币", item.value()), label));
+        if item.is_identified() && item.is_cursed() {
+            lines.push(Line::styled("⚠ 这件物品被诅咒了", Style::default().fg(Color::LightRed)));
         }
 
-        match &item.item_type {
-            ItemType::Weapon { .. } => Color::Red,
-            ItemType::Armor { .. } => Color::Blue,
-            ItemType::Consumable { .. } => Color::Green,
-            ItemType::Throwable { .. } => Color::LightMagenta,
-            ItemType::Key => Color::LightYellow,
-            ItemType::Quest => Color::Magenta,
+        // 与当前装备比较
+        let player = world.query::<&Player>().iter().next().map(|(entity, _)| entity);
+        if let Some(player) = player {
+            let equipment = world.get::<&Equipment>(player).map(|e| (*e).clone()).unwrap_or_default();
+            let strength = world.get::<&PlayerProgress>(player).map(|p| p.strength).unwrap_or(10);
+            let mut candidate = equipment.clone();
+            if candidate.equip(item.item.clone()).is_ok() {
+                let before = equipment.bonuses_for_strength(strength);
+                let after = candidate.bonuses_for_strength(strength);
+                lines.push(Line::from(""));
+                lines.push(Line::styled("与当前装备相比:", label));
+                let diffs = [
+                    ("攻击", after.attack - before.attack),
+                    ("防御", after.defense - before.defense),
+                    ("命中", after.accuracy - before.accuracy),
+                    ("闪避", after.evasion - before.evasion),
+                ];
+                let changed: Vec<Line> = diffs
+                    .iter()
+                    .filter(|(_, diff)| *diff != 0)
+                    .map(|(name, diff)| {
+                        let color = if *diff > 0 { Color::Green } else { Color::Red };
+                        Line::styled(format!("  {} {:+}", name, diff), Style::default().fg(color))
+                    })
+                    .collect();
+                if changed.is_empty() {
+                    lines.push(Line::styled("  属性不变", Style::default().fg(Color::DarkGray)));
+                } else {
+                    lines.extend(changed);
+                }
+            }
         }
+
+        // 可用操作
+        let quickslotted = Quickslot::key_of(item).map(|key| {
+            player
+                .and_then(|p| world.get::<&Quickslot>(p).ok())
+                .is_some_and(|quickslot| quickslot.index_of(&key).is_some())
+        });
+        lines.push(Line::from(""));
+        lines.push(Line::styled("可用操作:", label));
+        for action in Self::actions(item, quickslotted) {
+            lines.push(Line::styled(format!("  {}", action), Style::default().fg(Color::Cyan)));
+        }
+
+        let details = Paragraph::new(lines).wrap(Wrap { trim: false });
+        frame.render_widget(details, inner_area);
+    }
+
+    ///    物品的说明文字
+    fn describe(item: &items::Item) -> Vec<String> {
+        match &item.kind {
+            items::ItemKind::Weapon(w) => [
+                format!("伤害: {}-{}", w.damage.0 + w.damage_bonus(), w.damage.1 + w.damage_bonus()),
+                format!("力量需求: {}", w.str_requirement),
+            ]
+            .into_iter()
+            .chain(w.kind.trait_description().map(|t| format!("特性: {}", t)))
+            .collect(),
+            items::ItemKind::Armor(a) => vec![
+                format!("防御: {}", a.defense()),
+                format!("力量需求: {}", a.str_requirement),
+            ],
+            items::ItemKind::Ring(r) if r.identified => vec![format!("等级: {:+}", r.level)],
+            items::ItemKind::Ring(_) => vec!["未鉴定的戒指，戴上一段时间后才能看清".to_string()],
+            items::ItemKind::Potion(p) if p.identified => vec![p.effect()],
+            items::ItemKind::Potion(_) => vec!["未知的药水，喝下或摔碎后才能认出".to_string()],
+            items::ItemKind::Scroll(s) if s.identified => vec!["阅读后立即生效".to_string()],
+            items::ItemKind::Scroll(_) => vec!["未知的卷轴，阅读后才能认出".to_string()],
+            items::ItemKind::Wand(w) => vec![
+                format!("充能: {}/{}", w.charges, w.max_charges),
+                format!("基础伤害: {}", w.base_damage()),
+            ],
+            items::ItemKind::Stone(s) => vec![s.clone().use_effect().unwrap_or_else(|| "已经失去了魔力".to_string())],
+            items::ItemKind::Seed(s) => vec![format!("种下后: {}", s.effect_description())],
+            items::ItemKind::Throwable(t) => vec![
+                format!("伤害: {}-{}", t.damage.0, t.damage.1),
+                format!("射程: {}", t.range),
+            ],
+            items::ItemKind::Food(_) => vec!["食用后恢复饱食度".to_string()],
+            items::ItemKind::Herb(_) => vec!["可以直接食用，也能投入炼金釜".to_string()],
+            items::ItemKind::Misc(_) if item.description != "..." => vec![item.description.clone()],
+            items::ItemKind::Misc(misc) if misc.kind.is_container() => vec![
+                format!("可收纳: {}", Self::container_contents(misc.kind)),
+                format!("最多 {} 格，不占用背包格子", items::misc::CONTAINER_CAPACITY),
+                "里面的物品不会被火焰或冰冻损坏".to_string(),
+            ],
+            items::ItemKind::Misc(_) => Vec::new(),
+            items::ItemKind::Signature(s) => Self::describe_signature(s),
+        }
+    }
+
+    ///    职业专属物品的说明文字
+    fn describe_signature(signature: &items::Signature) -> Vec<String> {
+        let mut lines = match signature.kind {
+            items::SignatureKind::BrokenSeal => vec!["使用后获得吸收伤害的护盾，护盾随等级增强".to_string()],
+            items::SignatureKind::CloakOfShadows => vec!["使用后隐匿身形，敌人无法察觉你".to_string()],
+            items::SignatureKind::MagesStaff => match &signature.absorbed {
+                Some(wand) => vec![
+                    format!("吸收的法杖: {}", wand.name()),
+                    format!("充能: {}/{}", wand.charges, wand.max_charges),
+                ],
+                None => vec!["选择一根法杖吸收，之后可以释放它的效果".to_string()],
+            },
+            items::SignatureKind::SpiritBow => vec![
+                format!("射程: {}", hero::class::huntress::SPIRIT_BOW_RANGE),
+                "灵能箭取之不尽，伤害随等级提升".to_string(),
+            ],
+        };
+        if signature.kind.max_charges() > 0 {
+            lines.push(format!("充能: {}/{}", signature.charges, signature.kind.max_charges()));
+            if signature.recharge_in > 0 {
+                lines.push(format!("{} 回合后恢复一点充能", signature.recharge_in));
+            }
+        }
+        lines
+    }
+
+    ///    容器可以收纳的物品种类
+    fn container_contents(kind: items::MiscKind) -> &'static str {
+        match kind {
+            items::MiscKind::SeedPouch => "种子、药草",
+            items::MiscKind::ScrollHolder => "卷轴",
+            items::MiscKind::PotionBandolier => "药水",
+            items::MiscKind::WandHolster => "法杖",
+            _ => "无",
+        }
+    }
+
+    ///    选中物品可用的操作及其按键；`quickslotted` 为物品是否已在快捷栏中（不能放入时为 None）
+    fn actions(item: &items::Item, quickslotted: Option<bool>) -> Vec<&'static str> {
+        let mut actions: Vec<&'static str> = item
+            .usage()
+            .filter(|usage| *usage != ItemUsage::Throw)
+            .map(|usage| usage.hint())
+            .into_iter()
+            .collect();
+        // 射击与投掷共用同一个按键
+        if item.usage() != Some(ItemUsage::Shoot) {
+            actions.push(ItemUsage::Throw.hint());
+        }
+        actions.push("Del   丢弃");
+        if item.quantity > 1 {
+            actions.push("V     拆分一半");
+        }
+        match quickslotted {
+            Some(true) => actions.push("F     移出快捷栏"),
+            Some(false) => actions.push("F     放入快捷栏"),
+            None => {}
+        }
+        actions
+    }
+
+    ///    根据物品类型获取颜色
+    fn get_item_color(&self, item: &ECSItem) -> Color {
+        item.rarity().color()
     }
 
     /// 根据物品类型获取图标
     fn get_item_icon(&self, item: &ECSItem) -> &str {
-        use crate::ecs::ItemType;
-
-        match &item.item_type {
-            ItemType::Weapon { .. } => "⚔️",
-            ItemType::Armor { .. } => "🛡️",
-            ItemType::Consumable { .. } => "🧪",
-            ItemType::Throwable { .. } => "🎯",
-            ItemType::Key => "🔑",
-            ItemType::Quest => "📜",
+        match &item.kind {
+            items::ItemKind::Weapon(_) => "⚔️",
+            items::ItemKind::Armor(_) => "🛡️",
+            items::ItemKind::Potion(_)
+            | items::ItemKind::Scroll(_)
+            | items::ItemKind::Food(_)
+            | items::ItemKind::Stone(_)
+            | items::ItemKind::Herb(_) => "🧪",
+            items::ItemKind::Throwable(_) => "🎯",
+            items::ItemKind::Wand(_) => "🪄",
+            items::ItemKind::Ring(_) => "💍",
+            items::ItemKind::Signature(_) => "✨",
+            items::ItemKind::Misc(misc) if misc.kind == items::MiscKind::Key => "🔑",
+            items::ItemKind::Misc(misc) if misc.kind.is_container() => "👝",
+            items::ItemKind::Seed(_) | items::ItemKind::Misc(_) => "📜",
         }
     }
 }
@@ -327,12 +327,22 @@ impl MenuRenderer {
             "",
             "战斗与交互:",
             "  Shift + 方向键 - 攻击指定方向",
-            "  1-9         - 使用对应物品",
+            "  f           - 瞄准远程攻击",
+            "  z           - 瞄准释放职业技能",
+            "  Tab/Enter   - 瞄准时切换目标/确认",
+            "  t/z（物品栏）- 投掷/挥动选中物品",
+            "  p（物品栏）  - 在脚下种下选中的种子",
+            "  f（物品栏）  - 放入/移出快捷栏",
+            "  v（物品栏）  - 把选中的一堆物品拆成两半",
+            "  1-9         - 使用快捷栏中的物品",
+            "  x           - 物品图鉴（已鉴定的药水/卷轴/戒指）",
+            "  g           - 拾起脚下的物品（多件时可选择）",
             "  d           - 丢弃物品",
             "",
             "地牢探索:",
             "  >           - 下楼梯",
             "  <           - 上楼梯",
+            "  走向 ⚗      - 打开炼金釜（Enter 投入材料，b 炼制）",
             "",
             "游戏控制:",
             "  Esc         - 暂停/返回",
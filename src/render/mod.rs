@@ -8,10 +8,14 @@
 //! - `game_over` - 游戏结束界面渲染
 //! - `boss` - Boss 战斗 UI 渲染
 //! - `class_selection` - 职业选择界面渲染
+//! - `catalog` - 物品图鉴渲染
+//! - `alchemy` - 炼金釜界面渲染
 //!
 //! 所有渲染器直接操作 ECS World 和 Resources，确保架构统一。
 
+pub mod alchemy;
 pub mod boss;
+pub mod catalog;
 pub mod class_selection;
 pub mod dungeon;
 pub mod game_over;
@@ -19,7 +23,9 @@ pub mod hud;
 pub mod inventory;
 pub mod menu;
 
+pub use alchemy::AlchemyRenderer;
 pub use boss::BossUI;
+pub use catalog::CatalogRenderer;
 pub use class_selection::ClassSelectionRenderer;
 pub use dungeon::DungeonRenderer;
 pub use game_over::GameOverRenderer;
@@ -2,8 +2,8 @@
 
 use crate::ecs::*;
 use crate::render::{
-    ClassSelectionRenderer, DungeonRenderer, GameOverRenderer, HudRenderer, InventoryRenderer,
-    MenuRenderer,
+    AlchemyRenderer, CatalogRenderer, ClassSelectionRenderer, DungeonRenderer, GameOverRenderer, HudRenderer,
+    InventoryRenderer, MenuRenderer,
 };
 use anyhow;
 
@@ -66,6 +66,8 @@ pub struct RatatuiRenderer {
     menu_renderer: MenuRenderer,
     game_over_renderer: GameOverRenderer,
     class_selection_renderer: ClassSelectionRenderer,
+    catalog_renderer: CatalogRenderer,
+    alchemy_renderer: AlchemyRenderer,
 }
 
 /// Cached rendering data for optimization
@@ -90,6 +92,8 @@ impl RatatuiRenderer {
             menu_renderer: MenuRenderer::new(),
             game_over_renderer: GameOverRenderer::new(),
             class_selection_renderer: ClassSelectionRenderer::new(),
+            catalog_renderer: CatalogRenderer::new(),
+            alchemy_renderer: AlchemyRenderer::new(),
         })
     }
 
@@ -124,14 +128,46 @@ impl RatatuiRenderer {
                     Self::render_character_info(f, f.area(), &ecs_world.world);
                 }
 
+                GameStatus::ItemCatalog => {
+                    self.catalog_renderer
+                        .render(f, f.area(), &ecs_world.resources);
+                }
+
+                GameStatus::Alchemy {
+                    selected_item,
+                    chosen,
+                } => {
+                    self.alchemy_renderer.render(
+                        f,
+                        f.area(),
+                        &ecs_world.world,
+                        &ecs_world.resources,
+                        selected_item,
+                        &chosen,
+                    );
+                }
+
                 GameStatus::ClassSelection { .. } => {
                     self.class_selection_renderer
                         .render(f, f.area(), &ecs_world.resources);
                 }
 
-                GameStatus::Inventory { .. } => {
+                GameStatus::Inventory { selected_item } => {
                     self.inventory_renderer
-                        .render(f, f.area(), &ecs_world.world);
+                        .render(f, f.area(), &ecs_world.world, selected_item);
+                }
+
+                GameStatus::ItemPicker {
+                    source_slot,
+                    selected_item,
+                } => {
+                    self.inventory_renderer.render_item_picker(
+                        f,
+                        f.area(),
+                        &ecs_world.world,
+                        source_slot,
+                        selected_item,
+                    );
                 }
 
                 // === 游戏结束状态 ===
@@ -150,8 +186,49 @@ impl RatatuiRenderer {
                         .render_confirm_quit(f, f.area(), &ecs_world.resources);
                 }
 
-                // === 正常游戏状态 ===
-                GameStatus::Running => {
+                // === 瞄准模式 ===
+                GameStatus::Targeting {
+                    purpose,
+                    cursor_x,
+                    cursor_y,
+                } => {
+                    let chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([
+                            Constraint::Length(3),
+                            Constraint::Min(10),
+                            Constraint::Length(5),
+                        ])
+                        .split(f.area());
+
+                    self.hud_renderer.render(f, chunks[0], &ecs_world.world);
+
+                    let player = crate::systems::find_player_entity(&ecs_world.world);
+                    let cursor = Position::new(
+                        cursor_x,
+                        cursor_y,
+                        player
+                            .and_then(|p| ecs_world.world.get::<&Position>(p).ok().map(|pos| pos.z))
+                            .unwrap_or(0),
+                    );
+                    let preview = player
+                        .map(|p| crate::targeting::preview(&ecs_world.world, p, purpose, &cursor))
+                        .unwrap_or_default();
+                    self.dungeon_renderer.render_targeting(
+                        f,
+                        chunks[1],
+                        &ecs_world.world,
+                        &preview,
+                        &cursor,
+                    );
+
+                    let mut messages = ecs_world.resources.game_state.message_log.clone();
+                    messages.push("瞄准中：Tab 切换目标，Enter 确认，Esc 取消".to_string());
+                    Self::render_message_log(f, chunks[2], &messages);
+                }
+
+                // === 正常游戏状态（拾取选择框覆盖在地图上） ===
+                GameStatus::Running | GameStatus::PickupChooser { .. } => {
                     // Create main layout
                     let chunks = Layout::default()
                         .direction(Direction::Vertical)
@@ -167,6 +244,16 @@ impl RatatuiRenderer {
 
                     // 渲染地牢
                     self.dungeon_renderer.render(f, chunks[1], &ecs_world.world);
+                    if let GameStatus::PickupChooser { selected_item } =
+                        ecs_world.resources.game_state.game_state
+                    {
+                        self.inventory_renderer.render_pile_chooser(
+                            f,
+                            chunks[1],
+                            &ecs_world.world,
+                            selected_item,
+                        );
+                    }
 
                     // 渲染消息日志（改进版）
                     Self::render_message_log(
@@ -182,7 +269,9 @@ impl RatatuiRenderer {
 
     /// 渲染角色信息界面
     fn render_character_info(frame: &mut Frame<'_>, area: Rect, world: &hecs::World) {
-        use crate::ecs::{Actor, Hunger, Player, PlayerProgress, Stats, Wealth};
+        use crate::ecs::{
+            Actor, BaseStats, Equipment, EquipmentBonus, Hunger, Player, PlayerProgress, Stats, Wealth,
+        };
 
         // 获取玩家数据
         let player_data = world
@@ -260,7 +349,7 @@ impl RatatuiRenderer {
             Line::from(vec![
                 Span::styled("经验: ", Style::default().fg(TuiColor::Gray)),
                 Span::styled(
-                    format!("{}/{}", stats.experience, stats.level * 100),
+                    format!("{}/{}", stats.experience, hero::Hero::max_exp(stats.level)),
                     Style::default().fg(TuiColor::Magenta),
                 ),
             ]),
@@ -312,40 +401,50 @@ impl RatatuiRenderer {
             );
         frame.render_widget(basic_paragraph, content_chunks[0]);
 
-        // 右侧：战斗属性
-        let combat_info = vec![
-            Line::from(""),
+        // 右侧：战斗属性（基础值 + 装备加成 = 总值）
+        let (base, bonus) = world
+            .query::<(&Player, Option<&BaseStats>)>()
+            .iter()
+            .next()
+            .map(|(entity, (_, base))| {
+                (
+                    base.cloned().unwrap_or_else(|| BaseStats::from_stats(&stats)),
+                    Equipment::bonuses_of(world, entity),
+                )
+            })
+            .unwrap_or_else(|| (BaseStats::from_stats(&stats), EquipmentBonus::default()));
+
+        let stat_line = |label: &'static str, base: i32, bonus: i32, total: u32, color: TuiColor| {
             Line::from(vec![
-                Span::styled("攻击力: ", Style::default().fg(TuiColor::Gray)),
+                Span::styled(label, Style::default().fg(TuiColor::Gray)),
                 Span::styled(
-                    format!("{}", stats.attack),
-                    Style::default()
-                        .fg(TuiColor::Red)
-                        .add_modifier(Modifier::BOLD),
+                    format!("{}", total),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
                 ),
-            ]),
-            Line::from(vec![
-                Span::styled("防御力: ", Style::default().fg(TuiColor::Gray)),
                 Span::styled(
-                    format!("{}", stats.defense),
-                    Style::default()
-                        .fg(TuiColor::Blue)
-                        .add_modifier(Modifier::BOLD),
+                    format!(" ({} {:+})", base, bonus),
+                    Style::default().fg(TuiColor::DarkGray),
                 ),
-            ]),
+            ])
+        };
+
+        let combat_info = vec![
             Line::from(""),
+            stat_line("攻击力: ", base.attack as i32, bonus.attack, stats.attack, TuiColor::Red),
+            stat_line("防御力: ", base.defense as i32, bonus.defense, stats.defense, TuiColor::Blue),
+            Line::from(""),
+            stat_line("命中率: ", base.accuracy as i32, bonus.accuracy, stats.accuracy, TuiColor::Yellow),
+            stat_line("闪避率: ", base.evasion as i32, bonus.evasion, stats.evasion, TuiColor::Cyan),
             Line::from(vec![
-                Span::styled("命中率: ", Style::default().fg(TuiColor::Gray)),
-                Span::styled(
-                    format!("{}", stats.accuracy),
-                    Style::default().fg(TuiColor::Yellow),
-                ),
-            ]),
-            Line::from(vec![
-                Span::styled("闪避率: ", Style::default().fg(TuiColor::Gray)),
+                Span::styled("暴击/急速/财富: ", Style::default().fg(TuiColor::Gray)),
                 Span::styled(
-                    format!("{}", stats.evasion),
-                    Style::default().fg(TuiColor::Cyan),
+                    format!(
+                        "+{:.0}% / +{:.0}% / +{:.0}%",
+                        bonus.crit * 100.0,
+                        bonus.haste * 100.0,
+                        bonus.wealth * 100.0
+                    ),
+                    Style::default().fg(TuiColor::LightMagenta),
                 ),
             ]),
             Line::from(""),
@@ -69,6 +69,31 @@ pub struct EntityStateData {
     pub active_effects: Vec<StatusEffectData>,
 }
 
+/// Quickslot entry: item kind key plus the name shown once the stack runs out
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct QuickslotData {
+    pub key: String,
+    pub name: String,
+}
+
+/// Player equipment slots (items worn outside the inventory)
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode, Serialize, Deserialize)]
+pub struct EquipmentData {
+    pub weapon: Option<items::Weapon>,
+    pub armor: Option<items::Armor>,
+    pub rings: [Option<items::Ring>; 2],
+    pub artifact: Option<items::Item>,
+}
+
+/// Player stats before equipment bonuses are applied
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+pub struct BaseStatsData {
+    pub attack: u32,
+    pub defense: u32,
+    pub accuracy: u32,
+    pub evasion: u32,
+}
+
 /// Serializable status effect data
 #[derive(Debug, Clone, Encode, Decode, Serialize, Deserialize)]
 pub struct StatusEffectData {
@@ -128,6 +153,34 @@ pub struct SaveData {
     /// Non-player entity states (v2+)
     #[serde(default)]
     pub entities: Vec<EntityStateData>,
+
+    /// Per-run item appearances and identified kinds (v3+)
+    #[serde(default)]
+    pub item_knowledge: items::ItemKnowledge,
+
+    /// Player quickslot assignments (v4+)
+    #[serde(default)]
+    pub quickslots: Vec<Option<QuickslotData>>,
+
+    /// Player inventory in slot order, stored as full items (v5+)
+    #[serde(default)]
+    pub inventory: Vec<items::Item>,
+
+    /// Player equipment (v6+)
+    #[serde(default)]
+    pub equipment: EquipmentData,
+
+    /// Player stats without equipment bonuses (v6+)
+    #[serde(default)]
+    pub base_stats: Option<BaseStatsData>,
+
+    /// Remaining damage shield (v6+)
+    #[serde(default)]
+    pub shield: u32,
+
+    /// Turn at which each active skill is ready again (v6+)
+    #[serde(default)]
+    pub skill_cooldowns: Vec<u32>,
 }
 
 fn default_player_energy() -> u32 {
@@ -135,7 +188,7 @@ fn default_player_energy() -> u32 {
 }
 
 /// Current save format version
-pub const SAVE_VERSION: u32 = 2;
+pub const SAVE_VERSION: u32 = 6;
 
 fn default_version() -> u32 {
     1 // Legacy saves default to version 1
@@ -144,7 +197,7 @@ fn default_version() -> u32 {
 impl SaveData {
     /// Migrate legacy save data to current version
     pub fn migrate(&mut self) {
-        if self.version < SAVE_VERSION {
+        while self.version < SAVE_VERSION {
             match self.version {
                 1 => {
                     // Migrate from v1 to v2: Initialize turn state and clock state
@@ -162,8 +215,26 @@ impl SaveData {
 
                     self.version = 2;
                 }
+                2 => {
+                    // Migrate from v2 to v3: item knowledge already defaults to a fresh mapping
+                    self.version = 3;
+                }
+                3 => {
+                    // Migrate from v3 to v4: quickslots start out empty
+                    self.version = 4;
+                }
+                4 => {
+                    // Migrate from v4 to v5: the inventory is rebuilt from the hero's bag on load
+                    self.version = 5;
+                }
+                5 => {
+                    // Migrate from v5 to v6: nothing was equipped in older saves, and the
+                    // base stats are taken from the saved stats on load
+                    self.version = 6;
+                }
                 _ => {
                     // Unknown version, skip migration
+                    break;
                 }
             }
         }
@@ -435,6 +506,30 @@ mod tests {
             player_energy: 75,
             player_hunger_last_turn: 20,
             entities: vec![],
+            item_knowledge: items::ItemKnowledge::from_seed(4242),
+            quickslots: vec![
+                None,
+                Some(QuickslotData {
+                    key: "potion:Healing:false".to_string(),
+                    name: "治疗药水".to_string(),
+                }),
+            ],
+            inventory: vec![items::Item::new(items::ItemKind::Wand(items::Wand::new(
+                items::wand::WandKind::Frost,
+                2,
+            )))],
+            equipment: EquipmentData {
+                weapon: Some(items::Weapon::new(2, items::weapon::WeaponKind::Spear)),
+                ..Default::default()
+            },
+            base_stats: Some(BaseStatsData {
+                attack: 8,
+                defense: 3,
+                accuracy: 70,
+                evasion: 20,
+            }),
+            shield: 6,
+            skill_cooldowns: vec![0, 57],
         };
 
         let cfg = config::standard();
@@ -451,5 +546,12 @@ mod tests {
         assert_eq!(decoded.clock_state.elapsed_time_secs, 128.5);
         assert_eq!(decoded.turn_state.current_phase, TurnPhase::PlayerTurn);
         assert_eq!(decoded.player_energy, 75);
+        assert_eq!(decoded.item_knowledge, items::ItemKnowledge::from_seed(4242));
+        assert_eq!(decoded.quickslots[1].as_ref().map(|q| q.name.as_str()), Some("治疗药水"));
+        assert_eq!(decoded.inventory, save_data.inventory);
+        assert_eq!(decoded.equipment, save_data.equipment);
+        assert_eq!(decoded.base_stats, save_data.base_stats);
+        assert_eq!(decoded.shield, 6);
+        assert_eq!(decoded.skill_cooldowns, vec![0, 57]);
     }
 }
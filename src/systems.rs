@@ -1,9 +1,10 @@
 use crate::ecs::{
-    AI, AIState, AIType, Actor, AftermathEvent, CombatIntent, CombatOutcome, Color,
-    ConsumableEffect, Direction, ECSItem, ECSWorld, EffectType, Energy, Faction, GameOverReason,
-    GameStatus, Hunger, Inventory, ItemSlot, ItemType, NavigateDirection, Player, PlayerAction,
-    PlayerProgress, Position, Renderable, Resources, StatType, Stats, StatusEffects, TerrainType,
-    Tile, Viewshed, Wealth,
+    AI, AIState, AIType, Actor, AftermathEvent, BaseStats, Blob, BlobKind, CombatIntent, CombatOutcome, Color,
+    Direction, ECSItem, ECSWorld, EffectType, Energy, EquipSlot, Equipment,
+    Faction,
+    GameOverReason, GameStatus, Hunger, Inventory, NavigateDirection, Plant, Player,
+    PlayerAction, PlayerProgress, Position, Quickslot, QuickslotEntry, Renderable, Resources, Shielding, SkillCooldowns, Stats,
+    StatusEffects, TargetingPurpose, TerrainType, Tile, Viewshed, Wealth,
 };
 use crate::event_bus::LogLevel;
 use hecs::{Entity, World};
@@ -97,6 +98,11 @@ impl System for MovementSystem {
                         // Calculate target position
                         let target_pos = Self::calculate_target_position(&current_pos, direction);
 
+                        // 走向炼金釜时打开炼金界面（不消耗回合）
+                        if Self::try_open_alchemy(world, resources, &target_pos) {
+                            continue;
+                        }
+
                         // Validate movement
                         match Self::validate_movement(world, player_entity, &target_pos) {
                             MovementResult::Success => {
@@ -159,7 +165,7 @@ enum MovementResult {
 
 impl MovementSystem {
     /// Calculate target position from current position and direction
-    fn calculate_target_position(current_pos: &Position, direction: Direction) -> Position {
+    pub(crate) fn calculate_target_position(current_pos: &Position, direction: Direction) -> Position {
         match direction {
             Direction::North => Position::new(current_pos.x, current_pos.y - 1, current_pos.z),
             Direction::South => Position::new(current_pos.x, current_pos.y + 1, current_pos.z),
@@ -257,7 +263,7 @@ impl MovementSystem {
     }
 
     /// Check if a tile at the position is passable
-    fn is_tile_passable(world: &World, target_pos: &Position) -> bool {
+    pub(crate) fn is_tile_passable(world: &World, target_pos: &Position) -> bool {
         let mut found_any = false;
         for (_, (pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
             if pos.x == target_pos.x && pos.y == target_pos.y && pos.z == target_pos.z {
@@ -273,8 +279,25 @@ impl MovementSystem {
         found_any
     }
 
+    /// Open the alchemy screen when the target position holds an alchemy pot
+    fn try_open_alchemy(world: &World, resources: &mut Resources, target_pos: &Position) -> bool {
+        let is_pot = world.query::<(&Position, &Tile)>().iter().any(|(_, (pos, tile))| {
+            pos.x == target_pos.x
+                && pos.y == target_pos.y
+                && pos.z == target_pos.z
+                && matches!(tile.terrain_type, TerrainType::AlchemyPot)
+        });
+        if is_pot {
+            resources.game_state.game_state = GameStatus::Alchemy {
+                selected_item: 0,
+                chosen: [None; items::alchemy::MAX_INGREDIENTS],
+            };
+        }
+        is_pot
+    }
+
     /// Find an entity (actor) at the given position
-    fn find_entity_at_position(world: &World, target_pos: &Position) -> Option<Entity> {
+    pub(crate) fn find_entity_at_position(world: &World, target_pos: &Position) -> Option<Entity> {
         for (entity, (pos, _actor)) in world.query::<(&Position, &Actor)>().iter() {
             if pos.x == target_pos.x && pos.y == target_pos.y && pos.z == target_pos.z {
                 return Some(entity);
@@ -323,17 +346,27 @@ impl MovementSystem {
 
     /// Check for items at the position and log pickup opportunity
     fn check_items(world: &World, resources: &mut Resources, _entity: Entity, pos: &Position) {
-        // Look for items at this position
-        for (_item_entity, (item_pos, item, _tile)) in
-            world.query::<(&Position, &ECSItem, &Tile)>().iter()
-        {
-            if item_pos.x == pos.x && item_pos.y == pos.y && item_pos.z == pos.z {
-                resources.game_state.message_log.push(format!(
-                    "这里有 {}",
-                    item.name
-                ));
-                // Note: Actual pickup is handled by InventorySystem
-            }
+        // Note: Actual pickup is handled by InventorySystem (PlayerAction::PickUp)
+        if let Some(message) = Self::describe_ground_items(world, pos) {
+            resources.game_state.message_log.push(message);
+        }
+    }
+
+    /// 描述某一格地面上的物品：一件时说出名称，多件时列出整堆物品
+    fn describe_ground_items(world: &World, pos: &Position) -> Option<String> {
+        let names: Vec<String> = InventorySystem::ground_items_at(world, pos)
+            .into_iter()
+            .filter_map(|entity| {
+                world
+                    .get::<&ECSItem>(entity)
+                    .ok()
+                    .map(|item| InventorySystem::pile_entry_name(&item))
+            })
+            .collect();
+        match names.as_slice() {
+            [] => None,
+            [name] => Some(format!("这里有 {}（按 g 拾起）", name)),
+            _ => Some(format!("这里有一堆物品：{}（按 g 选择拾起）", names.join("、"))),
         }
     }
 
@@ -381,6 +414,11 @@ impl MovementSystem {
                         // Calculate target position
                         let target_pos = Self::calculate_target_position(&current_pos, direction);
 
+                        // 走向炼金釜时打开炼金界面（不消耗回合）
+                        if Self::try_open_alchemy(&ecs_world.world, &mut ecs_world.resources, &target_pos) {
+                            continue;
+                        }
+
                         // Validate movement
                         match Self::validate_movement(&ecs_world.world, player_entity, &target_pos) {
                             MovementResult::Success => {
@@ -471,11 +509,17 @@ impl MovementSystem {
         // Check for traps and emit events
         Self::check_traps_with_events(ecs_world, entity, to_pos);
 
+        // 金币自动拾起，剩下的物品再提示
+        InventorySystem::collect_gold_at(ecs_world, entity, to_pos);
+
         // Check for items and emit events
         Self::check_items_with_events(ecs_world, entity, to_pos);
 
         // Check for doors
         Self::check_doors_with_events(ecs_world, entity, to_pos);
+
+        // Plants and other environmental effects
+        DungeonSystem::process_environmental_effects(ecs_world, entity, to_pos);
     }
 
     /// Check traps and emit events
@@ -525,22 +569,18 @@ impl MovementSystem {
         use crate::event_bus::GameEvent;
 
         // Collect item names at this position first
-        let item_names: Vec<String> = ecs_world.world
-            .query::<(&Position, &ECSItem, &Tile)>()
-            .iter()
-            .filter(|(_, (item_pos, _, _))| {
-                item_pos.x == pos.x && item_pos.y == pos.y && item_pos.z == pos.z
-            })
-            .map(|(_, (_, item, _))| item.name.clone())
+        let item_names: Vec<String> = InventorySystem::ground_items_at(&ecs_world.world, pos)
+            .into_iter()
+            .filter_map(|entity| ecs_world.world.get::<&ECSItem>(entity).ok().map(|item| item.name.clone()))
             .collect();
 
+        // 物品堆的内容合并成一条消息
+        if let Some(message) = Self::describe_ground_items(&ecs_world.world, pos) {
+            ecs_world.resources.game_state.message_log.push(message);
+        }
+
         // Now emit events for each item
         for item_name in item_names {
-            ecs_world.resources.game_state.message_log.push(format!(
-                "这里有 {}",
-                item_name
-            ));
-            
             // Emit item pickup opportunity event
             ecs_world.publish_event(GameEvent::LogMessage {
                 message: format!("发现了 {}", item_name),
@@ -574,7 +614,7 @@ impl MovementSystem {
 }
 
 /// Helper function to find the player entity
-fn find_player_entity(world: &World) -> Option<Entity> {
+pub(crate) fn find_player_entity(world: &World) -> Option<Entity> {
     world
         .query::<&Player>()
         .iter()
@@ -660,6 +700,13 @@ impl AISystem {
             .world
             .query::<(&Position, &Player)>()
             .iter()
+            // 隐身的玩家不会被敌人察觉
+            .filter(|(entity, _)| {
+                !world
+                    .world
+                    .get::<&StatusEffects>(*entity)
+                    .is_ok_and(|effects| effects.has_effect(EffectType::Invisibility))
+            })
             .map(|(entity, (pos, _))| {
                 let hp = world
                     .world
@@ -670,8 +717,23 @@ impl AISystem {
             })
             .collect();
 
+        // 玩家的仆从（被腐化的敌人、召唤物）以敌人为目标
+        let enemy_positions: Vec<(Entity, Position, Option<u32>)> = world
+            .world
+            .query::<(&Position, &Actor, &Stats)>()
+            .iter()
+            .filter(|(_, (_, actor, _))| matches!(actor.faction, Faction::Enemy))
+            .map(|(entity, (pos, _, stats))| (entity, pos.clone(), Some(stats.hp)))
+            .collect();
+
         // Generate intents for each AI entity
         for (ai_entity, ai_type, ai_state, ai_pos, _energy) in ai_entities {
+            let is_ally = world
+                .world
+                .get::<&Actor>(ai_entity)
+                .is_ok_and(|actor| matches!(actor.faction, Faction::Player));
+            let targets = if is_ally { &enemy_positions } else { &player_positions };
+
             // Generate intent based on AI type and current state
             let intent = Self::generate_intent(
                 &world.world,
@@ -679,7 +741,7 @@ impl AISystem {
                 &ai_type,
                 &ai_state,
                 &ai_pos,
-                &player_positions,
+                targets,
             );
 
             if let Some(action_intent) = intent {
@@ -1013,6 +1075,9 @@ impl AISystem {
                             to_x: new_pos.x,
                             to_y: new_pos.y,
                         });
+
+                        // 敌人同样会踩中植物
+                        DungeonSystem::process_environmental_effects(ecs_world, intent.entity, &new_pos);
                     }
 
                     // Consume energy
@@ -1166,6 +1231,10 @@ impl CombatSystem {
     pub fn run_with_events(world: &mut ECSWorld) -> SystemResult {
         use crate::event_bus::GameEvent;
 
+        // 0. 将玩家的定向攻击转换为战斗意图，并结算指向目标的职业技能
+        Self::queue_directed_attacks(world);
+        Self::resolve_skill_actions(world);
+
         // 1. 取出所有待处理的战斗意图
         let mut intents = std::mem::take(&mut world.resources.combat_intents);
         
@@ -1196,6 +1265,317 @@ impl CombatSystem {
         SystemResult::Continue
     }
     
+    /// 将 `PlayerAction::Attack` 转换为战斗意图
+    ///
+    /// 沿弹道搜索武器攻击距离内的第一个敌对目标；
+    /// 投射附魔等会延长 `Weapon::range`，墙壁或非敌对单位会阻断攻击。
+    fn queue_directed_attacks(world: &mut ECSWorld) {
+        let actions = std::mem::take(&mut world.resources.input_buffer.pending_actions);
+        let mut remaining = Vec::new();
+
+        for action in actions {
+            let PlayerAction::Attack(offset) = &action else {
+                remaining.push(action);
+                continue;
+            };
+            let Some(player) = find_player_entity(&world.world) else {
+                remaining.push(action);
+                continue;
+            };
+            let Some(player_pos) = world.world.get::<&Position>(player).ok().map(|p| (*p).clone())
+            else {
+                remaining.push(action);
+                continue;
+            };
+
+            let reach = crate::targeting::purpose_range(
+                &world.world,
+                player,
+                TargetingPurpose::RangedAttack,
+            );
+            if offset.x.abs().max(offset.y.abs()) > reach {
+                world
+                    .resources
+                    .game_state
+                    .message_log
+                    .push("目标超出攻击距离".to_string());
+                continue;
+            }
+
+            // 单位偏移表示方向键攻击，沿该方向搜索整个攻击距离；
+            // 否则为瞄准模式给出的具体目标
+            let aim = if offset.x.abs().max(offset.y.abs()) <= 1 {
+                Position::new(
+                    player_pos.x + offset.x.signum() * reach,
+                    player_pos.y + offset.y.signum() * reach,
+                    player_pos.z,
+                )
+            } else {
+                Position::new(player_pos.x + offset.x, player_pos.y + offset.y, player_pos.z)
+            };
+            let path = crate::targeting::trace_projectile(&world.world, &player_pos, &aim, reach);
+            let target = path.hit.filter(|entity| {
+                world
+                    .world
+                    .get::<&Actor>(*entity)
+                    .map(|a| MovementSystem::is_hostile(Faction::Player, a.faction.clone()))
+                    .unwrap_or(false)
+            });
+
+            match target {
+                Some(defender) => {
+                    world.resources.combat_intents.push(CombatIntent::new(
+                        player,
+                        defender,
+                        player_pos,
+                        path.landing,
+                        true,
+                    ));
+                    world.resources.input_buffer.completed_actions.push(action);
+                }
+                None => {
+                    world
+                        .resources
+                        .game_state
+                        .message_log
+                        .push("攻击范围内没有目标".to_string());
+                }
+            }
+        }
+
+        world.resources.input_buffer.pending_actions = remaining;
+    }
+
+    /// 结算 `PlayerAction::UseSkill`：对瞄准的位置释放需要目标的职业主动技能
+    fn resolve_skill_actions(world: &mut ECSWorld) {
+        use crate::event_bus::GameEvent;
+
+        let actions = std::mem::take(&mut world.resources.input_buffer.pending_actions);
+        let mut remaining = Vec::new();
+
+        for action in actions {
+            let PlayerAction::UseSkill(index, target) = &action else {
+                remaining.push(action);
+                continue;
+            };
+            let Some(player) = find_player_entity(&world.world) else {
+                remaining.push(action);
+                continue;
+            };
+
+            match Self::cast_targeted_skill(world, player, *index, target) {
+                Ok(()) => world.resources.input_buffer.completed_actions.push(action),
+                Err((skill_name, reason)) => {
+                    world.publish_event(GameEvent::SkillUseFailed {
+                        entity: player.id(),
+                        skill_name,
+                        reason,
+                    });
+                }
+            }
+        }
+
+        world.resources.input_buffer.pending_actions = remaining;
+    }
+
+    /// 释放需要目标的技能，失败时返回技能名与原因
+    fn cast_targeted_skill(
+        world: &mut ECSWorld,
+        caster: Entity,
+        index: usize,
+        target: &Position,
+    ) -> Result<(), (String, String)> {
+        use crate::event_bus::GameEvent;
+        use hero::abilities::SkillEffect;
+
+        let Some(skill) = crate::targeting::class_skill(&world.world, caster, index) else {
+            return Err(("未知技能".to_string(), "当前职业没有该技能".to_string()));
+        };
+        let fail = |reason: &str| Err((skill.name.clone(), reason.to_string()));
+
+        if !skill.requires_target {
+            return fail("该技能不需要目标");
+        }
+        let turn = world.resources.clock.turn_count;
+        let ready = world
+            .world
+            .get::<&SkillCooldowns>(caster)
+            .map(|cooldowns| cooldowns.is_ready(index, turn))
+            .unwrap_or(true);
+        if !ready {
+            return fail("技能冷却中");
+        }
+        let Some(caster_pos) = world.world.get::<&Position>(caster).ok().map(|p| (*p).clone())
+        else {
+            return fail("无法确定施法位置");
+        };
+        let range = skill.range as i32;
+        if (target.x - caster_pos.x).abs().max((target.y - caster_pos.y).abs()) > range {
+            return fail("目标超出技能范围");
+        }
+
+        let path = crate::targeting::trace_projectile(&world.world, &caster_pos, target, range);
+        let Some(victim) = path.hit.filter(|entity| {
+            world
+                .world
+                .get::<&Actor>(*entity)
+                .map(|a| MovementSystem::is_hostile(Faction::Player, a.faction.clone()))
+                .unwrap_or(false)
+        }) else {
+            return fail("目标位置没有敌人");
+        };
+
+        for effect in &skill.effects {
+            match *effect {
+                SkillEffect::Dash { .. } => {
+                    // 冲到目标前一格
+                    if let Some(stop) = path.tiles.len().checked_sub(2).map(|i| path.tiles[i].clone()) {
+                        if let Ok(mut pos) = world.world.get::<&mut Position>(caster) {
+                            *pos = stop.clone();
+                        }
+                        world.publish_event(GameEvent::EntityMoved {
+                            entity: caster.id(),
+                            from_x: caster_pos.x,
+                            from_y: caster_pos.y,
+                            to_x: stop.x,
+                            to_y: stop.y,
+                        });
+                    }
+                }
+                SkillEffect::Damage { amount, ignore_armor } => {
+                    let defense = world.world.get::<&Stats>(victim).map(|s| s.defense).unwrap_or(0);
+                    let damage = if ignore_armor {
+                        amount
+                    } else {
+                        amount.saturating_sub(defense).max(1)
+                    };
+                    Self::deal_direct_damage(world, caster, victim, damage);
+                }
+                SkillEffect::StatusEffect { effect_type, duration } => {
+                    Self::apply_status_effect(
+                        world,
+                        victim,
+                        ::combat::effect::Effect::with_source(effect_type, duration, &skill.name),
+                    );
+                }
+                SkillEffect::Heal { amount } => {
+                    if let Ok(mut stats) = world.world.get::<&mut Stats>(caster) {
+                        stats.hp = (stats.hp + amount).min(stats.max_hp);
+                    }
+                }
+                // 暴击、护盾与属性增减由对应的状态系统处理，不在目标结算中生效
+                SkillEffect::GuaranteedCrit
+                | SkillEffect::Shield { .. }
+                | SkillEffect::StatBuff { .. } => {}
+            }
+        }
+
+        if world.world.get::<&SkillCooldowns>(caster).is_err() {
+            let _ = world.world.insert_one(caster, SkillCooldowns::default());
+        }
+        if let Ok(mut cooldowns) = world.world.get::<&mut SkillCooldowns>(caster) {
+            cooldowns.start(index, turn, skill.cooldown);
+        }
+
+        let class = world
+            .world
+            .get::<&Stats>(caster)
+            .ok()
+            .and_then(|stats| stats.class.as_ref().map(|c| c.to_string()))
+            .unwrap_or_default();
+        world.publish_event(GameEvent::ClassSkillUsed {
+            entity: caster.id(),
+            skill_name: skill.name.clone(),
+            class,
+            energy_cost: skill.energy_cost,
+        });
+
+        Ok(())
+    }
+
+    /// 技能与法术造成的直接伤害（不经过命中判定），击杀时排入死亡处理
+    fn deal_direct_damage(ecs_world: &mut ECSWorld, source: Entity, victim: Entity, damage: u32) {
+        use crate::event_bus::GameEvent;
+
+        // 同一回合内已被击杀的目标不再受伤，避免重复的死亡事件与经验
+        match ecs_world.world.get::<&Stats>(victim) {
+            Ok(stats) if stats.hp > 0 => {}
+            _ => return,
+        }
+        let damage = damage - Self::absorb_with_shield(ecs_world, victim, damage);
+        if damage == 0 {
+            return;
+        }
+        let killed = match ecs_world.world.get::<&mut Stats>(victim) {
+            Ok(mut stats) => {
+                stats.hp = stats.hp.saturating_sub(damage);
+                stats.hp == 0
+            }
+            Err(_) => return,
+        };
+        ecs_world.publish_event(GameEvent::DamageDealt {
+            attacker: source.id(),
+            victim: victim.id(),
+            damage,
+            is_critical: false,
+        });
+        if killed {
+            let victim_name = ecs_world
+                .world
+                .get::<&Actor>(victim)
+                .map(|a| a.name.clone())
+                .unwrap_or_else(|_| "Unknown".to_string());
+            ecs_world.publish_event(GameEvent::EntityDied {
+                entity: victim.id(),
+                entity_name: victim_name,
+            });
+            Self::queue_death(ecs_world, victim, Some(source));
+        }
+    }
+
+    /// 护盾吸收伤害，返回被吸收的数值；护盾耗尽后移除
+    fn absorb_with_shield(ecs_world: &mut ECSWorld, entity: Entity, damage: u32) -> u32 {
+        use crate::event_bus::GameEvent;
+
+        if damage == 0 {
+            return 0;
+        }
+        let (absorbed, remaining) = match ecs_world.world.get::<&mut Shielding>(entity) {
+            Ok(mut shield) => {
+                let absorbed = damage.min(shield.amount);
+                shield.amount -= absorbed;
+                (absorbed, shield.amount)
+            }
+            Err(_) => return 0,
+        };
+        if remaining == 0 {
+            let _ = ecs_world.world.remove_one::<Shielding>(entity);
+        }
+        if absorbed > 0 {
+            ecs_world.publish_event(GameEvent::CombatShieldAbsorbed {
+                entity: entity.id(),
+                damage_absorbed: absorbed,
+                shield_remaining: remaining,
+            });
+        }
+        absorbed
+    }
+
+    /// 受击方承受一次近战命中：持握长棍时先格挡一部分伤害，余下的再由护盾吸收
+    fn mitigate_melee_hit(ecs_world: &mut ECSWorld, victim: Entity, equipment: &Equipment, damage: u32) -> u32 {
+        let blocked = equipment.weapon.as_ref().map_or(0, |weapon| weapon.kind.block()).min(damage);
+        let damage = damage - blocked;
+        damage - Self::absorb_with_shield(ecs_world, victim, damage)
+    }
+
+    /// 获取实体当前的装备（无装备组件时视为空手无甲）
+    fn equipment_of(world: &World, entity: Entity) -> Equipment {
+        world
+            .get::<&Equipment>(entity)
+            .map(|equipment| (*equipment).clone())
+            .unwrap_or_default()
+    }
+
     /// 解析单个战斗意图并返回结构化结果
     fn resolve_combat_intent(world: &mut ECSWorld, intent: &CombatIntent) -> CombatOutcome {
         use crate::event_bus::GameEvent;
@@ -1215,8 +1595,13 @@ impl CombatSystem {
             .map(|s| (*s).clone());
         
         if let (Some(mut att_stats), Some(mut def_stats)) = (attacker_stats, defender_stats) {
-            let mut attacker = SimpleCombatant::new(&mut att_stats);
-            let mut defender = SimpleCombatant::new(&mut def_stats);
+            let (att_hp, def_hp) = (att_stats.hp, def_stats.hp);
+            let attacker_equipment = Self::equipment_of(&world.world, intent.attacker);
+            let defender_equipment = Self::equipment_of(&world.world, intent.defender);
+            let mut attacker =
+                SimpleCombatant::new(&mut att_stats).with_equipment(&attacker_equipment);
+            let mut defender =
+                SimpleCombatant::new(&mut def_stats).with_equipment(&defender_equipment);
             
             // 构建视野阻挡检测
             let z = intent.attacker_pos.z;
@@ -1248,6 +1633,61 @@ impl CombatSystem {
             };
             
             let combat_result = ::combat::Combat::perform_attack_with_ambush(&mut params);
+
+            // 格挡与护盾先于生命值承受每一次命中：按减免后的伤害重新结算生命值
+            att_stats.hp = att_hp;
+            def_stats.hp = def_hp;
+            let mut hits = Vec::new();
+            for ev in &combat_result.events {
+                if let ::combat::CombatEvent::DamageDealt { attacker, victim, damage, is_critical } = ev {
+                    let (victim_entity, victim_equipment, victim_stats) = if *victim == intent.attacker.id() {
+                        (intent.attacker, &attacker_equipment, &mut att_stats)
+                    } else {
+                        (intent.defender, &defender_equipment, &mut def_stats)
+                    };
+                    let damage = Self::mitigate_melee_hit(world, victim_entity, victim_equipment, *damage);
+                    victim_stats.hp = victim_stats.hp.saturating_sub(damage);
+                    hits.push((*attacker, *victim, damage, *is_critical));
+                }
+            }
+
+            // 每次命中：出手方武器判定附魔，受击方护甲判定刻印
+            for &(attacker, _, damage, _) in &hits {
+                let (striker, struck, striker_stats, struck_stats) =
+                    if attacker == intent.attacker.id() {
+                        (
+                            (intent.attacker, &attacker_equipment),
+                            (intent.defender, &defender_equipment),
+                            &mut att_stats,
+                            &mut def_stats,
+                        )
+                    } else {
+                        (
+                            (intent.defender, &defender_equipment),
+                            (intent.attacker, &attacker_equipment),
+                            &mut def_stats,
+                            &mut att_stats,
+                        )
+                    };
+                if let Some(weapon) = &striker.1.weapon {
+                    Self::apply_weapon_enchantment(
+                        world,
+                        weapon,
+                        (striker.0, striker_stats),
+                        (struck.0, struck_stats),
+                        damage,
+                    );
+                }
+                if let Some(armor) = &struck.1.armor {
+                    Self::apply_armor_glyph(
+                        world,
+                        armor,
+                        (struck.0, struck_stats),
+                        (striker.0, striker_stats),
+                        damage,
+                    );
+                }
+            }
             
             // 应用伤害到实际实体
             if let Ok(mut stats) = world.world.get::<&mut Stats>(intent.attacker) {
@@ -1257,33 +1697,41 @@ impl CombatSystem {
                 stats.hp = def_stats.hp;
             }
             
-            // 发布战斗事件
+            // 发布战斗事件（伤害按扣除护盾后的数值）
+            let mut hits = hits.into_iter();
             for ev in &combat_result.events {
                 match ev {
                     ::combat::CombatEvent::CombatStarted { .. } => {
                         // Already published above
                     }
-                    ::combat::CombatEvent::DamageDealt {
-                        attacker,
-                        victim,
-                        damage,
-                        is_critical,
-                    } => {
+                    ::combat::CombatEvent::DamageDealt { .. } => {
+                        let Some((attacker, victim, damage, is_critical)) = hits.next() else {
+                            continue;
+                        };
                         world.publish_event(GameEvent::CombatHit {
-                            attacker: *attacker,
-                            defender: *victim,
-                            damage: *damage,
-                            is_critical: *is_critical,
+                            attacker,
+                            defender: victim,
+                            damage,
+                            is_critical,
                             is_ambush: false, // TODO: track ambush state
                         });
                         world.publish_event(GameEvent::DamageDealt {
-                            attacker: *attacker,
-                            victim: *victim,
-                            damage: *damage,
-                            is_critical: *is_critical,
+                            attacker,
+                            victim,
+                            damage,
+                            is_critical,
                         });
                     }
                     ::combat::CombatEvent::EntityDied { entity, entity_name } => {
+                        // 护盾挡下致命一击时不算死亡
+                        let survived = if *entity == intent.attacker.id() {
+                            att_stats.hp > 0
+                        } else {
+                            def_stats.hp > 0
+                        };
+                        if survived {
+                            continue;
+                        }
                         world.publish_event(GameEvent::EntityDied {
                             entity: *entity,
                             entity_name: entity_name.clone(),
@@ -1309,14 +1757,6 @@ impl CombatSystem {
             }
             if def_stats.hp == 0 {
                 Self::queue_death(world, intent.defender, Some(intent.attacker));
-                
-                // 授予经验
-                if combat_result.experience > 0 {
-                    world.resources.aftermath_queue.push(AftermathEvent::ExperienceGain {
-                        entity: intent.attacker,
-                        amount: combat_result.experience,
-                    });
-                }
             }
             
             // 返回结构化结果
@@ -1376,64 +1816,395 @@ impl CombatSystem {
         }
     }
     
-    /// 将死亡事件加入后续处理队列
-    fn queue_death(world: &mut ECSWorld, entity: Entity, killer: Option<Entity>) {
-        let entity_id = entity.id();
-        let entity_name = world.world.get::<&Actor>(entity)
-            .map(|a| a.name.clone())
-            .unwrap_or_else(|_| "Unknown".to_string());
-        let position = world.world.get::<&Position>(entity)
-            .ok()
-            .map(|p| (*p).clone());
-        
-        world.resources.aftermath_queue.push(AftermathEvent::Death {
-            entity,
-            entity_id,
-            entity_name,
-            killer,
-        });
-        
-        // 战利品掉落
-        if let Some(pos) = position {
-            world.resources.aftermath_queue.push(AftermathEvent::LootDrop {
-                entity,
-                position: pos,
-            });
-        }
-    }
-}
-
-// Helper struct to implement the Combatant trait for ECS entities
-struct SimpleCombatant<'a> {
-    stats: &'a mut Stats,
-    name: String,
-    weapon: Option<crate::ecs::ECSItem>,
-}
+    /// 根据武器附魔在一次命中后触发额外效果
+    ///
+    /// 触发判定使用 `Resources::rng`，相同种子下结果可复现。
+    /// 诅咒武器的附魔会反噬持有者：燃烧、击晕、冰冻作用于自身，
+    /// 吸血变为失血，致命附魔反而治疗目标。
+    fn apply_weapon_enchantment(
+        world: &mut ECSWorld,
+        weapon: &::items::Weapon,
+        (wielder, wielder_stats): (Entity, &mut Stats),
+        (victim, victim_stats): (Entity, &mut Stats),
+        damage: u32,
+    ) {
+        use crate::event_bus::GameEvent;
+        use ::items::weapon::WeaponEnhance;
+        use rand::Rng;
 
-impl<'a> SimpleCombatant<'a> {
-    fn new(stats: &'a mut Stats) -> Self {
-        Self {
-            stats,
-            name: "Entity".to_string(),
-            weapon: None,
+        let Some(enchant) = weapon.enchanted.clone() else {
+            return;
+        };
+        let chance = enchant.proc_chance() as f64;
+        if chance <= 0.0 || !world.resources.rng.random_bool(chance) {
+            return;
         }
-    }
-}
 
-impl<'a> ::combat::Combatant for SimpleCombatant<'a> {
-    fn id(&self) -> u32 {
-        0 // 在ECS上下文中，这将由ECS Entity ID替换
+        match enchant {
+            WeaponEnhance::Burning | WeaponEnhance::Stunning | WeaponEnhance::Chilling => {
+                let (effect_type, turns) = match enchant {
+                    WeaponEnhance::Burning => (EffectType::Burning, 3),
+                    WeaponEnhance::Stunning => (EffectType::Paralysis, 2),
+                    _ => (EffectType::Slow, 3),
+                };
+                let target = if weapon.cursed { wielder } else { victim };
+                Self::apply_status_effect(
+                    world,
+                    target,
+                    ::combat::effect::Effect::with_source(effect_type, turns, &weapon.name),
+                );
+            }
+            WeaponEnhance::Vampiric => {
+                let amount = (damage / 10).max(1);
+                if weapon.cursed {
+                    // 失血不会直接致死
+                    let lost = amount.min(wielder_stats.hp.saturating_sub(1));
+                    wielder_stats.hp -= lost;
+                    world.publish_event(GameEvent::LogMessage {
+                        message: format!("{}反噬，失去了 {} 点生命", weapon.name, lost),
+                        level: LogLevel::Warning,
+                    });
+                } else if wielder_stats.hp > 0 {
+                    let healed = amount.min(wielder_stats.max_hp - wielder_stats.hp);
+                    wielder_stats.hp += healed;
+                    world.publish_event(GameEvent::CombatLifesteal {
+                        attacker: wielder.id(),
+                        defender: victim.id(),
+                        damage,
+                        healed,
+                    });
+                }
+            }
+            WeaponEnhance::Grim => {
+                if victim_stats.hp == 0 {
+                    return;
+                }
+                if weapon.cursed {
+                    victim_stats.hp = (victim_stats.hp + damage).min(victim_stats.max_hp);
+                    world.publish_event(GameEvent::LogMessage {
+                        message: format!("{}的诅咒治愈了目标", weapon.name),
+                        level: LogLevel::Warning,
+                    });
+                } else if (victim_stats.hp as f32)
+                    < victim_stats.max_hp as f32 * WeaponEnhance::GRIM_THRESHOLD
+                {
+                    victim_stats.hp = 0;
+                    let victim_name = world
+                        .world
+                        .get::<&Actor>(victim)
+                        .map(|a| a.name.clone())
+                        .unwrap_or_else(|_| "Unknown".to_string());
+                    world.publish_event(GameEvent::LogMessage {
+                        message: format!("{}斩杀了{}！", weapon.name, victim_name),
+                        level: LogLevel::Info,
+                    });
+                    world.publish_event(GameEvent::EntityDied {
+                        entity: victim.id(),
+                        entity_name: victim_name,
+                    });
+                }
+            }
+            // 幸运与投射为被动附魔，分别体现在暴击率与攻击距离上
+            WeaponEnhance::Lucky | WeaponEnhance::Projecting => {}
+        }
     }
 
-    fn hp(&self) -> u32 {
-        self.stats.hp
-    }
+    /// 受击方护甲刻印在一次命中后触发的效果
+    fn apply_armor_glyph(
+        world: &mut ECSWorld,
+        armor: &::items::Armor,
+        (wearer, wearer_stats): (Entity, &mut Stats),
+        (striker, striker_stats): (Entity, &mut Stats),
+        damage: u32,
+    ) {
+        use crate::event_bus::GameEvent;
+        use ::items::armor::GlyphEffect;
 
-    fn max_hp(&self) -> u32 {
-        self.stats.max_hp
-    }
+        let Some(effect) = armor.trigger_glyph(damage, &mut world.resources.rng) else {
+            return;
+        };
 
-    fn attack_power(&self) -> u32 {
+        match effect {
+            GlyphEffect::None => {}
+            GlyphEffect::ReflectDamage(amount) => {
+                if striker_stats.hp == 0 {
+                    return;
+                }
+                let reflected = amount.min(striker_stats.hp);
+                striker_stats.hp -= reflected;
+                world.publish_event(GameEvent::CombatReflected {
+                    attacker: striker.id(),
+                    defender: wearer.id(),
+                    reflected_damage: reflected,
+                });
+                if striker_stats.hp == 0 {
+                    let striker_name = world
+                        .world
+                        .get::<&Actor>(striker)
+                        .map(|a| a.name.clone())
+                        .unwrap_or_else(|_| "Unknown".to_string());
+                    world.publish_event(GameEvent::EntityDied {
+                        entity: striker.id(),
+                        entity_name: striker_name,
+                    });
+                }
+            }
+            GlyphEffect::Knockback(distance) => {
+                Self::knock_back(world, wearer, striker, distance);
+            }
+            GlyphEffect::Charm(turns) => {
+                Self::apply_status_effect(
+                    world,
+                    striker,
+                    ::combat::effect::Effect::with_source(EffectType::Paralysis, turns, "魅惑刻印"),
+                );
+            }
+            GlyphEffect::Immunity(immunities) => {
+                let purged = world
+                    .world
+                    .get::<&mut StatusEffects>(wearer)
+                    .map(|mut effects| {
+                        let before = effects.effects.len();
+                        let had_burning = effects.has_effect(EffectType::Burning);
+                        effects
+                            .effects
+                            .retain(|e| !Self::is_immune_to(immunities, e.effect_type()));
+                        (before != effects.effects.len(), had_burning)
+                    })
+                    .unwrap_or((false, false));
+
+                // 硫磺刻印在熄灭火焰时恢复生命
+                if let (Some(::items::armor::ArmorGlyph::Brimstone), (true, true)) =
+                    (&armor.glyph, purged)
+                {
+                    let healed = (damage / 2)
+                        .max(1)
+                        .min(wearer_stats.max_hp - wearer_stats.hp);
+                    wearer_stats.hp += healed;
+                }
+                if purged.0 {
+                    world.publish_event(GameEvent::LogMessage {
+                        message: format!("{}的刻印驱散了负面状态", armor.name()),
+                        level: LogLevel::Info,
+                    });
+                }
+            }
+            GlyphEffect::Invisibility(turns) => {
+                // 伪装只在原地不动时生效：本回合玩家没有完成移动动作
+                let standing_still = world.world.get::<&Player>(wearer).is_err()
+                    || !world
+                        .resources
+                        .input_buffer
+                        .completed_actions
+                        .iter()
+                        .any(|action| matches!(action, PlayerAction::Move(_)));
+                if standing_still {
+                    Self::apply_status_effect(
+                        world,
+                        wearer,
+                        ::combat::effect::Effect::with_source(
+                            EffectType::Invisibility,
+                            turns,
+                            "伪装刻印",
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// 刻印免疫是否涵盖该状态
+    fn is_immune_to(immunities: &[::items::armor::GlyphImmunity], effect_type: EffectType) -> bool {
+        use ::items::armor::GlyphImmunity;
+
+        immunities.iter().any(|immunity| {
+            matches!(
+                (immunity, effect_type),
+                (GlyphImmunity::Burning, EffectType::Burning)
+                    | (GlyphImmunity::Frost, EffectType::Frost)
+                    | (GlyphImmunity::Paralysis, EffectType::Paralysis)
+                    | (GlyphImmunity::Poison, EffectType::Poison)
+            )
+        })
+    }
+
+    /// 将目标沿远离来源的方向击退，遇到墙壁或其他单位时停止
+    fn knock_back(world: &mut ECSWorld, source: Entity, target: Entity, distance: u32) {
+        use crate::event_bus::GameEvent;
+
+        let (Some(source_pos), Some(target_pos)) = (
+            world.world.get::<&Position>(source).ok().map(|p| (*p).clone()),
+            world.world.get::<&Position>(target).ok().map(|p| (*p).clone()),
+        ) else {
+            return;
+        };
+        let (dx, dy) = (
+            (target_pos.x - source_pos.x).signum(),
+            (target_pos.y - source_pos.y).signum(),
+        );
+        if dx == 0 && dy == 0 {
+            return;
+        }
+
+        let mut landing = target_pos.clone();
+        for _ in 0..distance {
+            let next = Position::new(landing.x + dx, landing.y + dy, landing.z);
+            if !MovementSystem::is_tile_passable(&world.world, &next)
+                || MovementSystem::find_entity_at_position(&world.world, &next).is_some()
+            {
+                break;
+            }
+            landing = next;
+        }
+
+        if landing != target_pos {
+            if let Ok(mut pos) = world.world.get::<&mut Position>(target) {
+                *pos = landing.clone();
+            }
+            world.publish_event(GameEvent::EntityMoved {
+                entity: target.id(),
+                from_x: target_pos.x,
+                from_y: target_pos.y,
+                to_x: landing.x,
+                to_y: landing.y,
+            });
+        }
+    }
+
+    /// 为实体添加状态效果（必要时创建 `StatusEffects` 组件）并发布事件
+    ///
+    /// 护甲刻印提供的免疫会阻止对应状态。
+    fn apply_status_effect(world: &mut ECSWorld, entity: Entity, effect: ::combat::effect::Effect) {
+        use crate::event_bus::GameEvent;
+
+        let immune = world
+            .world
+            .get::<&Equipment>(entity)
+            .ok()
+            .and_then(|equipment| {
+                equipment
+                    .armor
+                    .as_ref()
+                    .map(|armor| Self::is_immune_to(armor.glyph_immunities(), effect.effect_type()))
+            })
+            .unwrap_or(false);
+        if immune {
+            return;
+        }
+
+        let effect_type = effect.effect_type();
+        let status = format!("{:?}", effect_type);
+        let duration = effect.turns();
+        let intensity = effect.intensity();
+
+        let inserted = if let Ok(mut effects) = world.world.get::<&mut StatusEffects>(entity) {
+            effects.add_effect(effect);
+            true
+        } else {
+            let mut effects = StatusEffects::new();
+            effects.add_effect(effect);
+            world.world.insert_one(entity, effects).is_ok()
+        };
+
+        if inserted {
+            world.publish_event(GameEvent::StatusApplied {
+                entity: entity.id(),
+                status,
+                duration,
+                intensity,
+            });
+            InventorySystem::damage_exposed_item(world, entity, effect_type);
+        }
+    }
+    
+    /// 将死亡事件加入后续处理队列
+    fn queue_death(world: &mut ECSWorld, entity: Entity, killer: Option<Entity>) {
+        let entity_id = entity.id();
+        let entity_name = world.world.get::<&Actor>(entity)
+            .map(|a| a.name.clone())
+            .unwrap_or_else(|_| "Unknown".to_string());
+        let position = world.world.get::<&Position>(entity)
+            .ok()
+            .map(|p| (*p).clone());
+        
+        // 战利品掉落（先于死亡处理，此时尸体仍在）
+        if let Some(pos) = position {
+            world.resources.aftermath_queue.push(AftermathEvent::LootDrop {
+                entity,
+                position: pos,
+            });
+        }
+
+        world.resources.aftermath_queue.push(AftermathEvent::Death {
+            entity,
+            entity_id,
+            entity_name,
+            killer,
+        });
+
+        // 玩家击杀敌人获得经验（无论近战、投掷还是法术）；
+        // 死于状态效果等没有凶手的敌人同样算作玩家的击杀
+        let is_enemy = world
+            .world
+            .get::<&Actor>(entity)
+            .is_ok_and(|actor| matches!(actor.faction, Faction::Enemy));
+        let hero = killer.or_else(|| find_player_entity(&world.world).filter(|_| is_enemy));
+        if let Some(killer) = hero.filter(|killer| world.world.get::<&Player>(*killer).is_ok()) {
+            let victim = world.world.get::<&Stats>(entity).map(|s| (s.experience, s.level));
+            let hero_level = world.world.get::<&Stats>(killer).map(|s| s.level);
+            if let (Ok((base, enemy_level)), Ok(hero_level)) = (victim, hero_level) {
+                let amount = crate::ecs::kill_experience(base, hero_level, enemy_level);
+                if amount > 0 {
+                    world.resources.aftermath_queue.push(AftermathEvent::ExperienceGain {
+                        entity: killer,
+                        amount,
+                    });
+                }
+            }
+        }
+        
+    }
+}
+
+// Helper struct to implement the Combatant trait for ECS entities
+struct SimpleCombatant<'a> {
+    stats: &'a mut Stats,
+    name: String,
+    weapon: Option<::items::Weapon>,
+    ring_crit: f32,
+}
+
+impl<'a> SimpleCombatant<'a> {
+    fn new(stats: &'a mut Stats) -> Self {
+        Self {
+            stats,
+            name: "Entity".to_string(),
+            weapon: None,
+            ring_crit: 0.0,
+        }
+    }
+
+    fn with_equipment(mut self, equipment: &Equipment) -> Self {
+        self.weapon = equipment.weapon.clone();
+        self.ring_crit = equipment.bonuses().crit;
+        self
+    }
+}
+
+impl<'a> ::combat::Combatant for SimpleCombatant<'a> {
+    fn id(&self) -> u32 {
+        0 // 在ECS上下文中，这将由ECS Entity ID替换
+    }
+
+    fn hp(&self) -> u32 {
+        self.stats.hp
+    }
+
+    fn max_hp(&self) -> u32 {
+        self.stats.max_hp
+    }
+
+    fn attack_power(&self) -> u32 {
         self.stats.attack
     }
 
@@ -1450,11 +2221,11 @@ impl<'a> ::combat::Combatant for SimpleCombatant<'a> {
     }
 
     fn crit_bonus(&self) -> f32 {
-        0.0
+        self.weapon.as_ref().map(|w| w.crit_bonus()).unwrap_or(0.0) + self.ring_crit
     }
 
     fn weapon(&self) -> Option<&::items::Weapon> {
-        None
+        self.weapon.as_ref()
     }
 
     fn is_alive(&self) -> bool {
@@ -1466,7 +2237,7 @@ impl<'a> ::combat::Combatant for SimpleCombatant<'a> {
     }
 
     fn attack_distance(&self) -> u32 {
-        1
+        self.weapon.as_ref().map(|w| w.range()).unwrap_or(1)
     }
 
     fn take_damage(&mut self, amount: u32) -> bool {
@@ -1535,20 +2306,19 @@ impl AftermathSystem {
                     }
                 }
                 AftermathEvent::LootDrop { entity, position } => {
-                    // TODO: Implement loot drop logic
-                    // For now, just log it
-                    world.publish_event(GameEvent::LogMessage {
-                        message: format!("战利品掉落在 ({}, {})", position.x, position.y),
-                        level: LogLevel::Debug,
-                    });
+                    Self::drop_loot(world, entity, &position);
                 }
                 AftermathEvent::ExperienceGain { entity, amount } => {
-                    // Award experience to entity
-                    // TODO: Implement experience system - for now just log it
                     world.publish_event(GameEvent::LogMessage {
                         message: format!("获得 {} 点经验", amount),
                         level: LogLevel::Info,
                     });
+                    for new_level in crate::ecs::gain_experience(&mut world.world, entity, amount) {
+                        world.publish_event(GameEvent::LevelUp {
+                            entity: entity.id(),
+                            new_level,
+                        });
+                    }
                 }
             }
         }
@@ -1557,6 +2327,45 @@ impl AftermathSystem {
     }
 }
 
+impl AftermathSystem {
+    /// 敌人与 Boss 的战利品落在死亡位置；掉落表取出后移除，同一具尸体只掉落一次
+    fn drop_loot(world: &mut ECSWorld, entity: Entity, position: &Position) {
+        use crate::event_bus::GameEvent;
+
+        let boss = world
+            .world
+            .get::<&crate::ecs::BossComponent>(entity)
+            .ok()
+            .map(|boss| boss.boss_type.clone());
+        let enemy = world.world.remove_one::<crate::ecs::LootTable>(entity).ok();
+        if boss.is_none() && enemy.is_none() {
+            return;
+        }
+        let _ = world.world.remove_one::<crate::ecs::BossComponent>(entity);
+
+        let depth = world.resources.game_state.depth;
+        let luck = find_player_entity(&world.world)
+            .map(|player| CombatSystem::equipment_of(&world.world, player).bonuses().wealth)
+            .unwrap_or(0.0);
+        let rng = &mut world.resources.loot_rng;
+        let drops = match (boss, enemy) {
+            (Some(boss_type), _) => crate::loot::boss_drops(boss_type, depth, luck, rng),
+            (None, Some(table)) => crate::loot::enemy_drops(table.kind, depth, luck, rng),
+            (None, None) => return,
+        };
+
+        for item in drops {
+            let mut item = ECSItem::new(item);
+            item.apply_knowledge(&world.resources.item_knowledge);
+            world.publish_event(GameEvent::LogMessage {
+                message: format!("{}掉落在地上", item.name),
+                level: LogLevel::Debug,
+            });
+            InventorySystem::spawn_ground_item(world, position, item);
+        }
+    }
+}
+
 pub struct FOVSystem;
 
 impl System for FOVSystem {
@@ -1937,8 +2746,10 @@ impl EffectSystem {
                         reason: "death".to_string(),
                     });
                     
-                    // Despawn entity
-                    let _ = ecs_world.world.despawn(entity);
+                    // 尸体交给 AftermathSystem 处理：掉落战利品、结算经验后再移除
+                    status_effects.effects.clear();
+                    let _ = ecs_world.world.insert(entity, (status_effects, stats));
+                    CombatSystem::queue_death(ecs_world, entity, None);
                     continue;
                 }
             }
@@ -2057,462 +2868,225 @@ impl System for EnergySystem {
     }
 }
 
-pub struct InventorySystem;
+/// 法杖与职业专属物品的自然充能系统（回合结束时运行）
+pub struct WandRechargeSystem;
 
-impl System for InventorySystem {
+impl System for WandRechargeSystem {
     fn name(&self) -> &str {
-        "InventorySystem"
+        "WandRechargeSystem"
     }
 
     fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
-        // Process pending player actions for inventory management
-        let actions_to_process = std::mem::take(&mut resources.input_buffer.pending_actions);
-        let mut new_actions = Vec::new();
-
-        for action in actions_to_process {
-            match action {
-                PlayerAction::UseItem(slot_index) => {
-                    if let Some(player_entity) = find_player_entity(world) {
-                        let player_id = player_entity.id();
-
-                        // Get player's inventory
-                        if let Ok(mut inventory) = world.get::<&mut Inventory>(player_entity) {
-                            if slot_index < inventory.items.len() {
-                                if let Some(ref item) = inventory.items[slot_index].item {
-                                    // Check if this is food first (from detailed_data)
-                                    let is_food = item.detailed_data.as_ref().and_then(|data| {
-                                        use items::Item;
-                                        bincode::decode_from_slice::<Item, _>(
-                                            data,
-                                            bincode::config::standard()
-                                        ).ok().and_then(|(item, _)| {
-                                            match &item.kind {
-                                                items::ItemKind::Food(_) => Some(true),
-                                                _ => None,
-                                            }
-                                        })
-                                    }).unwrap_or(false);
-
-                                    if is_food {
-                                        // Handle food consumption
-                                        if let Some(ref data) = item.detailed_data {
-                                            use items::Item;
-                                            if let Ok((mut food_item, _)) = bincode::decode_from_slice::<Item, _>(
-                                                data,
-                                                bincode::config::standard()
-                                            ) {
-                                                if let items::ItemKind::Food(mut food) = food_item.kind {
-                                                    // Get energy/satiety from food
-                                                    let satiety_restored = (food.eat() / 35).min(10) as u8; // Convert energy to satiety (350 energy = 10 satiety)
-                                                    
-                                                    // Apply food effect to hunger
-                                                    if let Ok(mut hunger) = world.get::<&mut Hunger>(player_entity) {
-                                                        hunger.feed(satiety_restored);
-                                                        
-                                                        let message = format!(
-                                                            "You eat the {}, restoring {} satiety.",
-                                                            item.name, satiety_restored
-                                                        );
-                                                        resources.game_state.message_log.push(message);
-                                                        if resources.game_state.message_log.len() > 10 {
-                                                            resources.game_state.message_log.remove(0);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        
-                                        // Remove consumed food
-                                        inventory.items.remove(slot_index);
-                                    } else {
-                                        // Check if this is a consumable item (potion/scroll)
-                                        match &item.item_type {
-                                            ItemType::Consumable { effect } => {
-                                            match effect {
-                                                ConsumableEffect::Healing { amount } => {
-                                                    // Apply healing to player
-                                                    if let Ok(mut stats) =
-                                                        world.get::<&mut Stats>(player_entity)
-                                                    {
-                                                        stats.hp =
-                                                            (stats.hp + amount).min(stats.max_hp);
-                                                        let message = format!(
-                                                            "You drink a {}, healing {} HP.",
-                                                            item.name, amount
-                                                        );
-
-                                                        // Add message to game state log (original behavior)
-                                                        resources
-                                                            .game_state
-                                                            .message_log
-                                                            .push(message);
-                                                        if resources.game_state.message_log.len()
-                                                            > 10
-                                                        {
-                                                            resources
-                                                                .game_state
-                                                                .message_log
-                                                                .remove(0);
-                                                        }
-                                                    }
-                                                }
-                                                ConsumableEffect::Damage { amount } => {
-                                                    // Apply damage to player (negative effect)
-                                                    if let Ok(mut stats) =
-                                                        world.get::<&mut Stats>(player_entity)
-                                                    {
-                                                        stats.hp = stats.hp.saturating_sub(*amount);
-                                                        let message = format!(
-                                                            "You drink a {}, taking {} damage!",
-                                                            item.name, amount
-                                                        );
-
-                                                        // Add message to game state log (original behavior)
-                                                        resources
-                                                            .game_state
-                                                            .message_log
-                                                            .push(message);
-                                                        if resources.game_state.message_log.len()
-                                                            > 10
-                                                        {
-                                                            resources
-                                                                .game_state
-                                                                .message_log
-                                                                .remove(0);
-                                                        }
-                                                    }
-                                                }
-                                                ConsumableEffect::Buff {
-                                                    stat,
-                                                    value,
-                                                    duration: _,
-                                                } => {
-                                                    // Apply stat buff to player
-                                                    if let Ok(mut stats) =
-                                                        world.get::<&mut Stats>(player_entity)
-                                                    {
-                                                        match stat {
-                                                            StatType::Hp => {
-                                                                stats.max_hp = (stats.max_hp as i32
-                                                                    + value)
-                                                                    as u32
-                                                            }
-                                                            StatType::Attack => {
-                                                                stats.attack = (stats.attack as i32
-                                                                    + value)
-                                                                    as u32
-                                                            }
-                                                            StatType::Defense => {
-                                                                stats.defense =
-                                                                    (stats.defense as i32 + value)
-                                                                        as u32
-                                                            }
-                                                            StatType::Accuracy => {
-                                                                stats.accuracy =
-                                                                    (stats.accuracy as i32 + value)
-                                                                        as u32
-                                                            }
-                                                            StatType::Evasion => {
-                                                                stats.evasion =
-                                                                    (stats.evasion as i32 + value)
-                                                                        as u32
-                                                            }
-                                                        }
-                                                        let message = format!(
-                                                            "You feel {}!",
-                                                            match stat {
-                                                                StatType::Hp =>
-                                                                    format!("healthier ({})", value),
-                                                                StatType::Attack =>
-                                                                    format!("stronger ({})", value),
-                                                                StatType::Defense =>
-                                                                    format!("tougher ({})", value),
-                                                                StatType::Accuracy => format!(
-                                                                    "more accurate ({})",
-                                                                    value
-                                                                ),
-                                                                StatType::Evasion => format!(
-                                                                    "more evasive ({})",
-                                                                    value
-                                                                ),
-                                                            }
-                                                        );
-
-                                                        // Add message to game state log (original behavior)
-                                                        resources
-                                                            .game_state
-                                                            .message_log
-                                                            .push(message);
-                                                        if resources.game_state.message_log.len()
-                                                            > 10
-                                                        {
-                                                            resources
-                                                                .game_state
-                                                                .message_log
-                                                                .remove(0);
-                                                        }
-                                                    }
-                                                }
-                                                ConsumableEffect::Teleport => {
-                                                    // Teleport player to random location in level
-                                                    if let Ok(mut pos) =
-                                                        world.get::<&mut Position>(player_entity)
-                                                    {
-                                                        use rand::Rng;
-                                                        // Use proper RNG for random position
-                                                        pos.x = 5 + resources.rng.gen_range(0..15); // Random position between 5-19
-                                                        pos.y = 5 + resources.rng.gen_range(0..15); // Random position between 5-19
-                                                        let message =
-                                                            "You teleport randomly!".to_string();
-
-                                                        // Add message to game state log (original behavior)
-                                                        resources
-                                                            .game_state
-                                                            .message_log
-                                                            .push(message);
-                                                        if resources.game_state.message_log.len()
-                                                            > 10
-                                                        {
-                                                            resources
-                                                                .game_state
-                                                                .message_log
-                                                                .remove(0);
-                                                        }
-                                                    }
-                                                }
-                                                ConsumableEffect::Identify => {
-                                                    // For now, just add a message
-                                                    let message =
-                                                        "You feel more perceptive.".to_string();
-
-                                                    // Add message to game state log (original behavior)
-                                                    resources.game_state.message_log.push(message);
-                                                    if resources.game_state.message_log.len() > 10 {
-                                                        resources.game_state.message_log.remove(0);
-                                                    }
-                                                }
-                                            }
-
-                                            // Remove the consumed item from inventory
-                                            inventory.items.remove(slot_index);
-                                        }
-                                        _ => {
-                                            let message = "Cannot use this item.".to_string();
-
-                                            // Add message to game state log (original behavior)
-                                            resources.game_state.message_log.push(message);
-                                            if resources.game_state.message_log.len() > 10 {
-                                                resources.game_state.message_log.remove(0);
-                                            }
-                                        }
-                                    }
-                                    } // end of else (non-food consumable handling)
-                                } else {
-                                    let message = "No item in this slot.".to_string();
-
-                                    // Add message to game state log (original behavior)
-                                    resources.game_state.message_log.push(message);
-                                    if resources.game_state.message_log.len() > 10 {
-                                        resources.game_state.message_log.remove(0);
-                                    }
-                                }
-                            } else {
-                                let message = "Invalid inventory slot.".to_string();
-                                new_actions.push(action);
-
-                                // Add message to game state log (original behavior)
-                                resources.game_state.message_log.push(message);
-                                if resources.game_state.message_log.len() > 10 {
-                                    resources.game_state.message_log.remove(0);
-                                }
-                            }
-                        } else {
-                            new_actions.push(action);
-                        }
-                    } else {
-                        new_actions.push(action);
+        for (_, inventory) in world.query::<&mut Inventory>().iter() {
+            for item in inventory.items.iter_mut().filter_map(|slot| slot.item.as_mut()) {
+                match &mut item.kind {
+                    items::ItemKind::Wand(wand) => {
+                        wand.natural_recharge(&mut resources.rng);
                     }
-                }
-                PlayerAction::DropItem(slot_index) => {
-                    // Extract item data first to avoid borrow conflicts
-                    let drop_result: Option<(Position, ECSItem, u32)> = if let Some(player_entity) =
-                        find_player_entity(world)
-                    {
-                        let player_id = player_entity.id();
-
-                        // Get the player's position and item to drop (in separate operations)
-                        let player_pos = match world.get::<&Position>(player_entity) {
-                            Ok(pos) => Position::new(pos.x, pos.y, pos.z),
-                            Err(_) => {
-                                new_actions.push(action);
-                                continue;
-                            }
-                        };
-
-                        // Get and remove the item
-                        if let Ok(mut inventory) = world.get::<&mut Inventory>(player_entity) {
-                            if slot_index < inventory.items.len() {
-                                if let Some(item_to_drop) = inventory.items.remove(slot_index).item
-                                {
-                                    Some((player_pos.clone(), item_to_drop, player_id)) // Clone the position to get owned value
-                                } else {
-                                    // Add message to game state log (original behavior)
-                                    resources
-                                        .game_state
-                                        .message_log
-                                        .push("No item in this slot to drop.".to_string());
-                                    if resources.game_state.message_log.len() > 10 {
-                                        resources.game_state.message_log.remove(0);
-                                    }
-                                    new_actions.push(action);
-                                    None
-                                }
-                            } else {
-                                // Add message to game state log (original behavior)
-                                resources
-                                    .game_state
-                                    .message_log
-                                    .push("Invalid inventory slot.".to_string());
-                                if resources.game_state.message_log.len() > 10 {
-                                    resources.game_state.message_log.remove(0);
-                                }
-                                new_actions.push(action);
-                                None
-                            }
-                        } else {
-                            new_actions.push(action);
-                            None
-                        }
-                    } else {
-                        new_actions.push(action);
-                        None
-                    };
-
-                    // Now spawn the item if we have the data
-                    if let Some((player_pos, item_to_drop, player_id)) = drop_result {
-                        world.spawn((
-                            Position::new(player_pos.x, player_pos.y, player_pos.z),
-                            Renderable {
-                                symbol: item_to_drop.name.chars().next().unwrap_or('?'),
-                                fg_color: Color::Yellow,
-                                bg_color: Some(Color::Black),
-                                order: 1,
-                            },
-                            ECSItem {
-                                name: item_to_drop.name.clone(),
-                                item_type: item_to_drop.item_type.clone(),
-                                value: item_to_drop.value,
-                                identified: item_to_drop.identified,
-                                quantity: item_to_drop.quantity,
-                                level: item_to_drop.level,
-                                cursed: item_to_drop.cursed,
-                                charges: item_to_drop.charges,
-                                detailed_data: item_to_drop.detailed_data.clone(),
-                            },
-                            Tile {
-                                terrain_type: TerrainType::Empty,
-                                is_passable: true,
-                                blocks_sight: false,
-                                has_items: true,
-                                has_monster: false,
-                            },
-                        ));
-
-                        // Add message to game state log (original behavior)
-                        resources
-                            .game_state
-                            .message_log
-                            .push(format!("You dropped {}.", item_to_drop.name));
-                        if resources.game_state.message_log.len() > 10 {
-                            resources.game_state.message_log.remove(0);
+                    items::ItemKind::Signature(signature) => {
+                        signature.tick();
+                        // 法师之杖中的法杖照常充能
+                        if let Some(wand) = signature.absorbed.as_mut() {
+                            wand.natural_recharge(&mut resources.rng);
                         }
                     }
-                }
-                // For non-inventory actions, add back to queue for other systems to handle
-                _ => {
-                    new_actions.push(action);
+                    _ => {}
                 }
             }
         }
+        SystemResult::Continue
+    }
+}
 
-        // Process item pickup
-        {
-            // Collect players and items first to resolve borrowing conflicts
-            let pickup_actions: Vec<_> = {
-                let mut actions = Vec::new();
-                for (player_entity, (player_pos, _actor)) in
-                    world.query::<(&Position, &Actor)>().iter()
-                {
-                    if world.get::<&Player>(player_entity).is_err() {
-                        continue;
-                    }
+/// 气体与火焰的扩散、衰减及对身处其中生物的效果
+pub struct BlobSystem;
 
-                    let mut items_for_player = Vec::new();
-                    for (item_entity, (pos, item)) in world.query::<(&Position, &ECSItem)>().iter()
-                    {
-                        if pos.x == player_pos.x && pos.y == player_pos.y && pos.z == player_pos.z {
-                            items_for_player.push((item_entity, item.clone(), item.name.clone()));
-                        }
-                    }
+impl System for BlobSystem {
+    fn name(&self) -> &str {
+        "BlobSystem"
+    }
 
-                    let mut available_slots = world
-                        .get::<&Inventory>(player_entity)
-                        .ok()
-                        .map(|inventory| inventory.max_slots.saturating_sub(inventory.items.len()))
-                        .unwrap_or(0);
+    fn run(&mut self, _world: &mut World, _resources: &mut Resources) -> SystemResult {
+        // 需要发布事件，由 run_with_events 处理
+        SystemResult::Continue
+    }
+}
 
-                    if available_slots == 0 {
-                        resources
-                            .game_state
-                            .message_log
-                            .push("Your inventory is full!".to_string());
-                        if resources.game_state.message_log.len() > 10 {
-                            resources.game_state.message_log.remove(0);
-                        }
-                        continue;
-                    }
+impl BlobSystem {
+    /// 浓度达到该值的气体才会向周围扩散
+    const SPREAD_THRESHOLD: u32 = 4;
 
-                    for (item_entity, item_clone, item_name) in items_for_player {
-                        if available_slots == 0 {
-                            break;
-                        }
-                        actions.push((player_entity, item_entity, item_clone, item_name));
-                        available_slots -= 1;
-                    }
-                }
-                actions
+    pub fn run_with_events(ecs_world: &mut ECSWorld) -> SystemResult {
+        let blobs: Vec<(Entity, Position, BlobKind, u32)> = ecs_world
+            .world
+            .query::<(&Position, &Blob)>()
+            .iter()
+            .map(|(entity, (pos, blob))| (entity, pos.clone(), blob.kind, blob.volume))
+            .collect();
+
+        let mut spreads = Vec::new();
+        for (entity, pos, kind, volume) in blobs {
+            let (effect_type, turns) = kind.status();
+            for victim in InventorySystem::creatures_near(&ecs_world.world, &pos, 0) {
+                CombatSystem::apply_status_effect(
+                    ecs_world,
+                    victim,
+                    ::combat::effect::Effect::with_source(effect_type, turns, kind.name()),
+                );
+            }
+
+            let neighbours = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .into_iter()
+                .map(|(dx, dy)| Position::new(pos.x + dx, pos.y + dy, pos.z));
+            if kind == BlobKind::Fire {
+                // 火焰烧尽脚下的草并蔓延到相邻的草地
+                if Self::burn_grass(&mut ecs_world.world, &pos) {
+                    spreads.extend(
+                        neighbours
+                            .filter(|next| matches!(Self::terrain_at(&ecs_world.world, next), Some(TerrainType::Grass)))
+                            .map(|next| (next, kind, volume.max(Self::SPREAD_THRESHOLD))),
+                    );
+                }
+            } else if volume >= Self::SPREAD_THRESHOLD {
+                spreads.extend(
+                    neighbours
+                        .filter(|next| MovementSystem::is_tile_passable(&ecs_world.world, next))
+                        .map(|next| (next, kind, volume / 2)),
+                );
+            }
+
+            if volume <= 1 {
+                let _ = ecs_world.world.despawn(entity);
+            } else if let Ok(mut blob) = ecs_world.world.get::<&mut Blob>(entity) {
+                blob.volume = volume - 1;
+            }
+        }
+
+        for (pos, kind, volume) in spreads {
+            Self::spawn_blob(&mut ecs_world.world, &pos, kind, volume);
+        }
+
+        SystemResult::Continue
+    }
+
+    /// 在指定位置生成气体或火焰；已有同类时取较高浓度
+    ///
+    /// 墙壁上不会生成，火焰无法在水面上燃烧。
+    pub fn spawn_blob(world: &mut World, pos: &Position, kind: BlobKind, volume: u32) {
+        if volume == 0 || !MovementSystem::is_tile_passable(world, pos) {
+            return;
+        }
+        if kind == BlobKind::Fire
+            && matches!(Self::terrain_at(world, pos), Some(TerrainType::Water | TerrainType::Ice))
+        {
+            return;
+        }
+
+        let existing = world
+            .query::<(&Position, &Blob)>()
+            .iter()
+            .find(|(_, (p, blob))| **p == *pos && blob.kind == kind)
+            .map(|(entity, _)| entity);
+        match existing {
+            Some(entity) => {
+                if let Ok(mut blob) = world.get::<&mut Blob>(entity) {
+                    blob.volume = blob.volume.max(volume);
+                }
+            }
+            None => {
+                world.spawn((pos.clone(), Blob { kind, volume }));
+            }
+        }
+    }
+
+    /// 清除范围内的所有气体与火焰，返回清除的格子数
+    pub fn clear_blobs(world: &mut World, center: &Position, radius: i32) -> usize {
+        let cleared: Vec<Entity> = world
+            .query::<(&Position, &Blob)>()
+            .iter()
+            .filter(|(_, (pos, _))| {
+                pos.z == center.z && (pos.x - center.x).abs() <= radius && (pos.y - center.y).abs() <= radius
+            })
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in &cleared {
+            let _ = world.despawn(*entity);
+        }
+        cleared.len()
+    }
+
+    fn terrain_at(world: &World, pos: &Position) -> Option<TerrainType> {
+        world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .find(|(_, (p, _))| **p == *pos)
+            .map(|(_, (_, tile))| tile.terrain_type.clone())
+    }
+
+    /// 将草地烧成地板，返回是否烧掉了草
+    fn burn_grass(world: &mut World, pos: &Position) -> bool {
+        for (_, (p, tile)) in world.query_mut::<(&Position, &mut Tile)>() {
+            if *p == *pos && matches!(tile.terrain_type, TerrainType::Grass) {
+                tile.terrain_type = TerrainType::Floor;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+pub struct InventorySystem;
+
+impl System for InventorySystem {
+    fn name(&self) -> &str {
+        "InventorySystem"
+    }
+
+    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
+        // Process pending player actions for inventory management
+        let actions_to_process = std::mem::take(&mut resources.input_buffer.pending_actions);
+        let mut new_actions = Vec::new();
+
+        for action in actions_to_process {
+            let Some(player_entity) = find_player_entity(world) else {
+                new_actions.push(action);
+                continue;
             };
+            if !matches!(
+                action,
+                PlayerAction::UseItem(_)
+                    | PlayerAction::PickUp
+                    | PlayerAction::PickUpItem(_)
+                    | PlayerAction::DropItem(_)
+            ) {
+                // For non-inventory actions, add back to queue for other systems to handle
+                new_actions.push(action);
+                continue;
+            }
 
-            for (player_entity, item_entity, item, item_name) in pickup_actions {
-                let mut picked_up = false;
-                if let Ok(mut inventory) = world.get::<&mut Inventory>(player_entity) {
-                    if inventory.items.len() < inventory.max_slots {
-                        inventory.items.push(ItemSlot {
-                            item: Some(item),
-                            quantity: 1,
-                        });
-                        picked_up = true;
-                    } else {
-                        resources
-                            .game_state
-                            .message_log
-                            .push("Your inventory is full!".to_string());
-                        if resources.game_state.message_log.len() > 10 {
-                            resources.game_state.message_log.remove(0);
-                        }
-                    }
+            // 物品的使用、拾取与丢弃统一交给事件版本的处理函数结算
+            let mut ecs_world = ECSWorld {
+                world: std::mem::take(world),
+                resources: std::mem::take(resources),
+                ..ECSWorld::new()
+            };
+            match action {
+                PlayerAction::UseItem(slot_index) => {
+                    InventorySystem::handle_use_item(&mut ecs_world, player_entity, slot_index);
                 }
-                if picked_up {
-                    let _ = world.despawn(item_entity);
-                    resources
-                        .game_state
-                        .message_log
-                        .push(format!("You picked up {}.", item_name));
-                    if resources.game_state.message_log.len() > 10 {
-                        resources.game_state.message_log.remove(0);
-                    }
+                PlayerAction::PickUp => {
+                    InventorySystem::handle_pick_up(&mut ecs_world, player_entity);
+                }
+                PlayerAction::PickUpItem(index) => {
+                    InventorySystem::handle_pick_up_item(&mut ecs_world, player_entity, index);
+                }
+                PlayerAction::DropItem(slot_index) => {
+                    InventorySystem::handle_drop_item(&mut ecs_world, player_entity, slot_index);
                 }
+                _ => {}
             }
+            *world = ecs_world.world;
+            *resources = ecs_world.resources;
         }
 
         // Put unprocessed actions back in the buffer
@@ -2531,19 +3105,67 @@ impl InventorySystem {
         
         let actions_to_process = std::mem::take(&mut ecs_world.resources.input_buffer.pending_actions);
         let mut new_actions = Vec::new();
+        let knowledge_before = ecs_world.resources.item_knowledge.clone();
         
         for action in actions_to_process {
             match action {
+                // 使用失败（或转入物品选择）时不重新排队，避免每帧重复提示
                 PlayerAction::UseItem(slot_index) => {
                     if let Some(player_entity) = find_player_entity(&ecs_world.world) {
-                        let result = Self::handle_use_item(ecs_world, player_entity, slot_index);
-                        
-                        if result {
+                        if Self::handle_use_item(ecs_world, player_entity, slot_index) {
                             // Mark action as completed for energy deduction
                             ecs_world.resources.input_buffer.completed_actions.push(action);
-                        } else {
-                            // Failed to use item, re-queue action
-                            new_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+
+                PlayerAction::UseItemOn(slot_index, target_slot) => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::handle_use_item_on(ecs_world, player_entity, slot_index, target_slot) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+
+                PlayerAction::PlantSeed(slot_index) => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::handle_plant_seed(ecs_world, player_entity, slot_index) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+
+                PlayerAction::Brew(ref slots) => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::handle_brew(ecs_world, player_entity, slots) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+                
+                // 拾取失败或转入拾取选择时不重新排队
+                PlayerAction::PickUp => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::handle_pick_up(ecs_world, player_entity) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+
+                PlayerAction::PickUpItem(index) => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::handle_pick_up_item(ecs_world, player_entity, index) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
                         }
                     } else {
                         new_actions.push(action);
@@ -2567,13 +3189,10 @@ impl InventorySystem {
                 
                 PlayerAction::EquipItem(slot_index) => {
                     if let Some(player_entity) = find_player_entity(&ecs_world.world) {
-                        let result = Self::handle_equip_item(ecs_world, player_entity, slot_index);
-                        
-                        if result {
+                        // 装备失败（背包已满、诅咒等）时不重新排队，避免每帧重复提示
+                        if Self::handle_equip_item(ecs_world, player_entity, slot_index) {
                             // Mark action as completed for energy deduction
                             ecs_world.resources.input_buffer.completed_actions.push(action);
-                        } else {
-                            new_actions.push(action);
                         }
                     } else {
                         new_actions.push(action);
@@ -2582,13 +3201,10 @@ impl InventorySystem {
                 
                 PlayerAction::UnequipItem(slot_index) => {
                     if let Some(player_entity) = find_player_entity(&ecs_world.world) {
-                        let result = Self::handle_unequip_item(ecs_world, player_entity, slot_index);
-                        
-                        if result {
+                        // 装备失败（背包已满、诅咒等）时不重新排队，避免每帧重复提示
+                        if Self::handle_unequip_item(ecs_world, player_entity, slot_index) {
                             // Mark action as completed for energy deduction
                             ecs_world.resources.input_buffer.completed_actions.push(action);
-                        } else {
-                            new_actions.push(action);
                         }
                     } else {
                         new_actions.push(action);
@@ -2610,6 +3226,27 @@ impl InventorySystem {
                     }
                 }
                 
+                // 瞄准模式确认的投掷与法杖，失败时不重新排队，避免反复尝试
+                PlayerAction::ThrowItemAt(slot_index, ref target) => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::throw_item_at(ecs_world, player_entity, slot_index, target) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+
+                PlayerAction::ZapWand(slot_index, ref target) => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        if Self::handle_zap_wand(ecs_world, player_entity, slot_index, target) {
+                            ecs_world.resources.input_buffer.completed_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+                
                 // Non-inventory actions: pass to other systems
                 _ => {
                     new_actions.push(action);
@@ -2617,9 +3254,11 @@ impl InventorySystem {
             }
         }
         
-        // Handle automatic item pickup
-        Self::process_auto_pickup(ecs_world);
-        
+        // 鉴定会改变物品的 stacking_id，等本轮动作都结算完再合并，避免格子下标中途变化
+        if ecs_world.resources.item_knowledge != knowledge_before {
+            Self::merge_inventory_stacks(ecs_world);
+        }
+
         // Put unprocessed actions back in the buffer
         ecs_world.resources.input_buffer.pending_actions = new_actions;
         
@@ -2647,22 +3286,56 @@ impl InventorySystem {
         };
         
         if let Some(item) = item_opt {
-            // Check if this is food first
-            let is_food = item.detailed_data.as_ref().and_then(|data| {
-                use items::Item;
-                bincode::decode_from_slice::<Item, _>(data, bincode::config::standard())
-                    .ok()
-                    .and_then(|(item, _)| match &item.kind {
-                        items::ItemKind::Food(_) => Some(true),
-                        _ => None,
-                    })
-            }).unwrap_or(false);
-            
-            if is_food {
-                return Self::handle_food_consumption(ecs_world, player_entity, slot_index, &item);
-            } else {
-                return Self::handle_consumable(ecs_world, player_entity, slot_index, &item);
-            }
+            use items::{ItemUsage, Usable};
+
+            return match (item.usage(), &item.kind) {
+                (Some(ItemUsage::Eat), items::ItemKind::Food(food)) => {
+                    Self::handle_food_consumption(ecs_world, player_entity, slot_index, &item.name, food)
+                }
+                (Some(ItemUsage::Read), items::ItemKind::Scroll(scroll)) => {
+                    Self::read_scroll(ecs_world, player_entity, slot_index, scroll, None)
+                }
+                // 使用种子即种在脚下
+                (Some(ItemUsage::Plant), _) => Self::handle_plant_seed(ecs_world, player_entity, slot_index),
+                (Some(ItemUsage::Apply), items::ItemKind::Stone(stone)) => {
+                    Self::use_stone(ecs_world, player_entity, slot_index, stone, None)
+                }
+                (Some(ItemUsage::Drink(kind)), _) => {
+                    let used = Self::drink_potion(ecs_world, player_entity, slot_index, kind, &item.name);
+                    // 喝下药水后认出它的种类
+                    if used {
+                        Self::learn_item_kind(ecs_world, &item.kind);
+                    }
+                    used
+                }
+                (Some(ItemUsage::Heal(amount)), _) => {
+                    Self::handle_healing(ecs_world, player_entity, slot_index, &item.name, amount)
+                }
+                (Some(ItemUsage::Apply), items::ItemKind::Signature(signature)) => {
+                    Self::use_signature(ecs_world, player_entity, slot_index, signature, None)
+                }
+                // 法师之杖与灵能弓需要先选择目标位置，本次不消耗回合
+                (Some(ItemUsage::Zap), _) => {
+                    MenuSystem::begin_targeting(
+                        &ecs_world.world,
+                        &mut ecs_world.resources,
+                        TargetingPurpose::Zap(slot_index),
+                    );
+                    false
+                }
+                (Some(ItemUsage::Shoot), _) => {
+                    MenuSystem::begin_targeting(
+                        &ecs_world.world,
+                        &mut ecs_world.resources,
+                        TargetingPurpose::Throw(slot_index),
+                    );
+                    false
+                }
+                _ => {
+                    ecs_world.resources.game_state.message_log.push("Cannot use this item.".to_string());
+                    false
+                }
+            };
         }
         
         ecs_world.resources.game_state.message_log.push("No item in this slot.".to_string());
@@ -2670,2036 +3343,4361 @@ impl InventorySystem {
     }
     
     /// Handle food consumption
-    fn handle_food_consumption(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize, item: &ECSItem) -> bool {
+    fn handle_food_consumption(
+        ecs_world: &mut ECSWorld,
+        player_entity: Entity,
+        slot_index: usize,
+        food_name: &str,
+        food: &items::Food,
+    ) -> bool {
         use crate::event_bus::GameEvent;
         
-        if let Some(ref data) = item.detailed_data {
-            if let Ok((mut food_item, _)) = bincode::decode_from_slice::<items::Item, _>(
-                data,
-                bincode::config::standard()
-            ) {
-                if let items::ItemKind::Food(mut food) = food_item.kind {
-                    let energy_value = food.eat();
-                    let satiety_restored = (energy_value / 35).min(10) as u8;
-                    
-                    // Apply food effect to hunger (in a separate scope to avoid borrow conflicts)
-                    {
-                        if let Ok(mut hunger) = ecs_world.world.get::<&mut Hunger>(player_entity) {
-                            hunger.feed(satiety_restored);
-                        } else {
-                            return false;
-                        }
-                    }
-                    
-                    // Publish FoodEaten event
-                    ecs_world.publish_event(GameEvent::FoodEaten {
-                        entity: player_entity.id(),
-                        food_name: item.name.clone(),
-                        satiety_restored,
-                    });
-                    
-                    // Remove consumed food from inventory
-                    if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
-                        if slot_index < inventory.items.len() {
-                            inventory.items.remove(slot_index);
-                        }
-                    }
-                    
-                    return true;
-                }
+        let energy_value = food.clone().eat();
+        let satiety_restored = (energy_value / 35).min(10) as u8;
+        
+        // Apply food effect to hunger (in a separate scope to avoid borrow conflicts)
+        {
+            if let Ok(mut hunger) = ecs_world.world.get::<&mut Hunger>(player_entity) {
+                hunger.feed(satiety_restored);
+            } else {
+                return false;
             }
         }
-        false
+        
+        // Publish FoodEaten event
+        ecs_world.publish_event(GameEvent::FoodEaten {
+            entity: player_entity.id(),
+            food_name: food_name.to_string(),
+            satiety_restored,
+        });
+        
+        // Remove consumed food from inventory
+        if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
+            if slot_index < inventory.items.len() {
+                inventory.items.remove(slot_index);
+            }
+        }
+        
+        true
     }
     
-    /// Handle consumable items (potions, scrolls, etc.)
-    fn handle_consumable(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize, item: &ECSItem) -> bool {
+    /// Handle items that restore HP directly (herbs)
+    fn handle_healing(
+        ecs_world: &mut ECSWorld,
+        player_entity: Entity,
+        slot_index: usize,
+        item_name: &str,
+        amount: u32,
+    ) -> bool {
         use crate::event_bus::GameEvent;
         
-        match &item.item_type {
-            ItemType::Consumable { effect } => {
-                let item_name = item.name.clone();
-                let mut effect_description = String::new();
-                let mut success = true;
-                
-                match effect {
-                    ConsumableEffect::Healing { amount } => {
-                        let healed = {
-                            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(player_entity) {
-                                let healed = (*amount).min(stats.max_hp - stats.hp);
-                                stats.hp = (stats.hp + amount).min(stats.max_hp);
-                                healed
-                            } else {
-                                0
-                            }
-                        };
-                        effect_description = format!("healing {} HP", healed);
-                        
-                        // Publish ItemUsed event
-                        ecs_world.publish_event(GameEvent::ItemUsed {
-                            entity: player_entity.id(),
-                            item_name: item_name.clone(),
-                            effect: effect_description.clone(),
-                        });
-                    }
-                    ConsumableEffect::Damage { amount } => {
-                        let died = {
-                            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(player_entity) {
-                                stats.hp = stats.hp.saturating_sub(*amount);
-                                stats.hp == 0
-                            } else {
-                                false
-                            }
-                        };
-                        effect_description = format!("taking {} damage", amount);
-                        
-                        ecs_world.publish_event(GameEvent::ItemUsed {
-                            entity: player_entity.id(),
-                            item_name: item_name.clone(),
-                            effect: effect_description.clone(),
-                        });
-                        
-                        // Check for death
-                        if died {
-                            ecs_world.publish_event(GameEvent::EntityDied {
-                                entity: player_entity.id(),
-                                entity_name: "Player".to_string(),
-                            });
-                        }
+        let healed = {
+            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(player_entity) {
+                let healed = amount.min(stats.max_hp - stats.hp);
+                stats.hp = (stats.hp + amount).min(stats.max_hp);
+                healed
+            } else {
+                return false;
+            }
+        };
+        
+        // Publish ItemUsed event
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: player_entity.id(),
+            item_name: item_name.to_string(),
+            effect: format!("healing {} HP", healed),
+        });
+        
+        // Remove the consumed item from inventory
+        if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
+            if slot_index < inventory.items.len() {
+                inventory.items.remove(slot_index);
+            }
+        }
+        true
+    }
+    
+    /// 需要在背包中选择目标物品才能使用的物品
+    pub(crate) fn needs_item_target(item: &items::Item) -> bool {
+        use items::scroll::ScrollKind;
+
+        match &item.kind {
+            items::ItemKind::Scroll(scroll) => match scroll.kind {
+                ScrollKind::Upgrade | ScrollKind::Transmutation => true,
+                // 预见卷轴一次鉴定全部物品
+                ScrollKind::Identify => !scroll.exotic,
+                _ => false,
+            },
+            items::ItemKind::Stone(stone) => stone.kind.targets_item() && !stone.is_depleted(),
+            // 尚未吸收法杖的法师之杖需要选择一根法杖
+            items::ItemKind::Signature(signature) => {
+                signature.kind == items::SignatureKind::MagesStaff && signature.absorbed.is_none()
+            }
+            _ => false,
+        }
+    }
+
+    /// 对背包中的另一件物品使用物品
+    fn handle_use_item_on(ecs_world: &mut ECSWorld, user: Entity, slot_index: usize, target_slot: usize) -> bool {
+        let source = Self::inventory_item(ecs_world, user, slot_index);
+        match source.map(|item| item.kind) {
+            Some(items::ItemKind::Scroll(scroll)) => {
+                Self::read_scroll(ecs_world, user, slot_index, &scroll, Some(target_slot))
+            }
+            Some(items::ItemKind::Stone(stone)) => {
+                Self::use_stone(ecs_world, user, slot_index, &stone, Some(target_slot))
+            }
+            Some(items::ItemKind::Signature(signature)) => {
+                Self::use_signature(ecs_world, user, slot_index, &signature, Some(target_slot))
+            }
+            _ => {
+                ecs_world.resources.game_state.message_log.push("无法这样使用该物品".to_string());
+                false
+            }
+        }
+    }
+
+    /// 使用职业专属物品：纹章提供护盾，斗篷隐匿身形，法师之杖吸收 `target_slot` 中的法杖
+    ///
+    /// 法师之杖没有目标时进入物品选择界面，本次不消耗回合。
+    fn use_signature(
+        ecs_world: &mut ECSWorld,
+        user: Entity,
+        slot_index: usize,
+        signature: &items::Signature,
+        target_slot: Option<usize>,
+    ) -> bool {
+        use crate::event_bus::GameEvent;
+        use hero::class::{rogue, warrior};
+        use items::SignatureKind;
+
+        let mut signature = signature.clone();
+        if signature.kind == SignatureKind::MagesStaff {
+            return Self::absorb_into_staff(ecs_world, user, slot_index, signature, target_slot);
+        }
+        if !signature.use_charge() {
+            ecs_world.resources.game_state.message_log.push(format!("{}没有充能了", signature.name()));
+            return false;
+        }
+
+        let level = ecs_world.world.get::<&Stats>(user).map(|s| s.level).unwrap_or(1);
+        let effect = match signature.kind {
+            SignatureKind::BrokenSeal => {
+                let amount = warrior::seal_shield(level);
+                // 护盾不叠加，只会补充到纹章提供的数值
+                let shield = match ecs_world.world.get::<&mut Shielding>(user) {
+                    Ok(mut shield) => {
+                        shield.amount = shield.amount.max(amount);
+                        None
                     }
-                    ConsumableEffect::Buff { stat, value, duration } => {
-                        let stat_name = {
-                            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(player_entity) {
-                                match stat {
-                                    StatType::Hp => {
-                                        stats.max_hp = (stats.max_hp as i32 + value) as u32;
-                                        "max HP"
-                                    }
-                                    StatType::Attack => {
-                                        stats.attack = (stats.attack as i32 + value) as u32;
-                                        "attack"
-                                    }
-                                    StatType::Defense => {
-                                        stats.defense = (stats.defense as i32 + value) as u32;
-                                        "defense"
-                                    }
-                                    StatType::Accuracy => {
-                                        stats.accuracy = (stats.accuracy as i32 + value) as u32;
-                                        "accuracy"
-                                    }
-                                    StatType::Evasion => {
-                                        stats.evasion = (stats.evasion as i32 + value) as u32;
-                                        "evasion"
-                                    }
-                                }
-                            } else {
-                                "unknown"
-                            }
-                        };
-                        effect_description = format!("{} {} for {} turns", stat_name, if *value > 0 { "increased" } else { "decreased" }, duration);
-                        
-                        ecs_world.publish_event(GameEvent::ItemUsed {
-                            entity: player_entity.id(),
-                            item_name: item_name.clone(),
-                            effect: effect_description.clone(),
-                        });
-                    }
-                    ConsumableEffect::Teleport => {
-                        let (old_x, old_y, new_x, new_y) = {
-                            if let Ok(mut pos) = ecs_world.world.get::<&mut Position>(player_entity) {
-                                use rand::Rng;
-                                let old_x = pos.x;
-                                let old_y = pos.y;
-                                pos.x = 5 + ecs_world.resources.rng.gen_range(0..15);
-                                pos.y = 5 + ecs_world.resources.rng.gen_range(0..15);
-                                let new_x = pos.x;
-                                let new_y = pos.y;
-                                
-                                // Mark viewshed dirty
-                                if let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(player_entity) {
-                                    viewshed.dirty = true;
-                                }
-                                
-                                (old_x, old_y, new_x, new_y)
-                            } else {
-                                (0, 0, 0, 0) // Fallback values
-                            }
-                        };
-                        
-                        effect_description = "teleporting randomly".to_string();
-                        
-                        ecs_world.publish_event(GameEvent::ItemUsed {
-                            entity: player_entity.id(),
-                            item_name: item_name.clone(),
-                            effect: effect_description.clone(),
-                        });
-                        
-                        ecs_world.publish_event(GameEvent::EntityMoved {
-                            entity: player_entity.id(),
-                            from_x: old_x,
-                            from_y: old_y,
-                            to_x: new_x,
-                            to_y: new_y,
-                        });
-                    }
-                    ConsumableEffect::Identify => {
-                        effect_description = "feeling more perceptive".to_string();
-                        
-                        ecs_world.publish_event(GameEvent::ItemUsed {
-                            entity: player_entity.id(),
-                            item_name: item_name.clone(),
-                            effect: effect_description.clone(),
-                        });
-                    }
-                }
-                
-                if success {
-                    // Remove the consumed item from inventory
-                    if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
-                        if slot_index < inventory.items.len() {
-                            inventory.items.remove(slot_index);
-                        }
-                    }
-                    return true;
+                    Err(_) => Some(Shielding { amount }),
+                };
+                if let Some(shield) = shield {
+                    let _ = ecs_world.world.insert_one(user, shield);
                 }
+                format!("纹章化作 {} 点护盾", amount)
             }
-            _ => {
-                ecs_world.resources.game_state.message_log.push("Cannot use this item.".to_string());
+            SignatureKind::CloakOfShadows => {
+                CombatSystem::apply_status_effect(
+                    ecs_world,
+                    user,
+                    ::combat::effect::Effect::with_source(
+                        EffectType::Invisibility,
+                        rogue::cloak_duration(level),
+                        signature.kind.name(),
+                    ),
+                );
+                "你隐入了阴影之中".to_string()
             }
-        }
-        
-        false
+            SignatureKind::MagesStaff | SignatureKind::SpiritBow => return false,
+        };
+
+        let item_name = signature.name();
+        Self::replace_inventory_item(ecs_world, user, slot_index, &signature.into());
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: user.id(),
+            item_name,
+            effect,
+        });
+        true
     }
-    
-    /// Handle dropping an item
-    fn handle_drop_item(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize) -> bool {
+
+    /// 法师之杖吸收背包中的一根法杖，原先吸收的法杖随之消散
+    fn absorb_into_staff(
+        ecs_world: &mut ECSWorld,
+        user: Entity,
+        slot_index: usize,
+        mut staff: items::Signature,
+        target_slot: Option<usize>,
+    ) -> bool {
         use crate::event_bus::GameEvent;
-        
-        let player_id = player_entity.id();
-        
-        // Get player position
-        let player_pos = match ecs_world.world.get::<&Position>(player_entity) {
-            Ok(pos) => Position::new(pos.x, pos.y, pos.z),
-            Err(_) => return false,
+        use hero::class::mage::STAFF_BONUS_CHARGES;
+
+        let Some(target) = target_slot else {
+            ecs_world.resources.game_state.game_state = GameStatus::ItemPicker {
+                source_slot: slot_index,
+                selected_item: 0,
+            };
+            return false;
         };
-        
-        // Get and remove the item (do this in a separate scope to avoid borrow conflicts)
-        let item_to_drop_opt = {
-            if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
-                if slot_index < inventory.items.len() {
-                    inventory.items.remove(slot_index).item
-                } else {
-                    ecs_world.resources.game_state.message_log.push("Invalid inventory slot.".to_string());
-                    None
-                }
-            } else {
-                None
-            }
+        let Some(items::ItemKind::Wand(wand)) = Self::inventory_item(ecs_world, user, target).map(|item| item.kind)
+        else {
+            ecs_world.resources.game_state.message_log.push("法师之杖只能吸收法杖".to_string());
+            return false;
         };
-        
-        if let Some(item_to_drop) = item_to_drop_opt {
-            let item_name = item_to_drop.name.clone();
-            
-            // Spawn the item on the ground
-            ecs_world.world.spawn((
-                Position::new(player_pos.x, player_pos.y, player_pos.z),
-                Renderable {
-                    symbol: item_to_drop.name.chars().next().unwrap_or('?'),
-                    fg_color: Color::Yellow,
-                    bg_color: Some(Color::Black),
-                    order: 1,
-                },
-                ECSItem {
-                    name: item_to_drop.name.clone(),
-                    item_type: item_to_drop.item_type.clone(),
-                    value: item_to_drop.value,
-                    identified: item_to_drop.identified,
-                    quantity: item_to_drop.quantity,
-                    level: item_to_drop.level,
-                    cursed: item_to_drop.cursed,
-                    charges: item_to_drop.charges,
-                    detailed_data: item_to_drop.detailed_data.clone(),
-                },
-                Tile {
-                    terrain_type: TerrainType::Empty,
-                    is_passable: true,
-                    blocks_sight: false,
-                    has_items: true,
-                    has_monster: false,
-                },
-            ));
-            
-            // Publish ItemDropped event
-            ecs_world.publish_event(GameEvent::ItemDropped {
-                entity: player_id,
-                item_name: item_name.clone(),
-            });
-            
-            return true;
-        } else {
-            ecs_world.resources.game_state.message_log.push("No item in this slot to drop.".to_string());
-        }
-        
-        false
+
+        Self::learn_item_kind(ecs_world, &items::ItemKind::Wand(wand.clone()));
+        staff.absorb(wand, STAFF_BONUS_CHARGES);
+        let mut item: items::Item = staff.into();
+        item.name = item.name();
+        Self::replace_inventory_item(ecs_world, user, slot_index, &item);
+        Self::take_one_from_slot(ecs_world, user, target);
+
+        let effect = format!("法杖融入了杖身，现在是{}", item.name);
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: user.id(),
+            item_name: items::SignatureKind::MagesStaff.name().to_string(),
+            effect,
+        });
+        true
     }
-    
-    /// Handle equipping an item
-    fn handle_equip_item(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize) -> bool {
+
+    /// 使用魔法石，`target_slot` 为作用于物品的魔法石选中的背包格子
+    ///
+    /// 作用于物品的魔法石在没有目标时进入物品选择界面；其余魔法石进入瞄准模式，
+    /// 投掷后在落点生效。两种情况下本次都不消耗魔法石。
+    fn use_stone(
+        ecs_world: &mut ECSWorld,
+        user: Entity,
+        slot_index: usize,
+        stone: &items::Stone,
+        target_slot: Option<usize>,
+    ) -> bool {
         use crate::event_bus::GameEvent;
-        
-        let player_id = player_entity.id();
-        
-        // Get the item to equip
-        let item_opt = {
-            if let Ok(inventory) = ecs_world.world.get::<&Inventory>(player_entity) {
-                if slot_index < inventory.items.len() {
-                    inventory.items[slot_index].item.clone()
-                } else {
-                    ecs_world.resources.game_state.message_log.push("Invalid inventory slot.".to_string());
-                    return false;
-                }
-            } else {
+        use items::stone::StoneKind;
+
+        if stone.is_depleted() {
+            ecs_world.resources.game_state.message_log.push(format!("{}已经失去了魔力", stone.name()));
+            return false;
+        }
+        if !stone.kind.targets_item() {
+            MenuSystem::begin_targeting(
+                &ecs_world.world,
+                &mut ecs_world.resources,
+                TargetingPurpose::Throw(slot_index),
+            );
+            return false;
+        }
+
+        let (target, mut item) = match target_slot {
+            None => {
+                ecs_world.resources.game_state.game_state = GameStatus::ItemPicker {
+                    source_slot: slot_index,
+                    selected_item: 0,
+                };
                 return false;
             }
-        };
-        
-        if let Some(item) = item_opt {
-            if !item.is_equippable() {
-                ecs_world.resources.game_state.message_log.push("Cannot equip this item.".to_string());
+            Some(target) if target == slot_index => {
+                ecs_world.resources.game_state.message_log.push("不能对魔法石自身使用".to_string());
                 return false;
             }
-            
-            let slot_name = match &item.item_type {
-                ItemType::Weapon { .. } => "weapon",
-                ItemType::Armor { .. } => "armor",
-                _ => "unknown",
-            };
-            
-            // For now, just log the action (full equipment system would need to track equipped items)
-            ecs_world.publish_event(GameEvent::ItemEquipped {
-                entity: player_id,
-                item_name: item.name.clone(),
-                slot: slot_name.to_string(),
-            });
-            
-            return true;
-        }
-        
-        ecs_world.resources.game_state.message_log.push("No item in this slot.".to_string());
-        false
-    }
-    
-    /// Handle unequipping an item
-    fn handle_unequip_item(ecs_world: &mut ECSWorld, _player_entity: Entity, _slot_index: usize) -> bool {
-        // Placeholder for unequip logic
-        ecs_world.resources.game_state.message_log.push("Unequip not yet implemented.".to_string());
-        false
-    }
-    
-    /// Handle throwing an item
-    fn handle_throw_item(ecs_world: &mut ECSWorld, _player_entity: Entity, _slot_index: usize, _direction: Direction) -> bool {
-        // Placeholder for throw logic
-        ecs_world.resources.game_state.message_log.push("Throw not yet implemented.".to_string());
-        false
+            Some(target) => match Self::inventory_item(ecs_world, user, target) {
+                Some(item) => (target, item),
+                None => {
+                    ecs_world.resources.game_state.message_log.push("没有选中任何物品".to_string());
+                    return false;
+                }
+            },
+        };
+
+        let effect = match stone.kind {
+            StoneKind::Upgrade => {
+                if !item.upgrade() {
+                    ecs_world.resources.game_state.message_log.push(format!("{}无法被强化", item.name));
+                    return false;
+                }
+                format!("{}得到了强化", item.name)
+            }
+            StoneKind::Identify => {
+                item.identify();
+                Self::learn_item_kind(ecs_world, &item.kind);
+                format!("这是{}", item.name)
+            }
+            StoneKind::RemoveCurse => {
+                if !item.remove_curse() {
+                    ecs_world.resources.game_state.message_log.push(format!("{}上没有诅咒", item.name));
+                    return false;
+                }
+                format!("{}上的诅咒消散了", item.name)
+            }
+            StoneKind::Recharging => {
+                let items::ItemKind::Wand(wand) = &mut item.kind else {
+                    ecs_world.resources.game_state.message_log.push(format!("{}无法充能", item.name));
+                    return false;
+                };
+                wand.recharge(0);
+                format!("{}充满了能量", item.name)
+            }
+            StoneKind::Transmutation => {
+                let Some(transmuted) = item.transmute(&mut ecs_world.resources.rng) else {
+                    ecs_world.resources.game_state.message_log.push(format!("{}无法被变形", item.name));
+                    return false;
+                };
+                let effect = format!("{}变成了{}", item.name, transmuted.name);
+                item = transmuted;
+                effect
+            }
+            _ => return false,
+        };
+
+        Self::replace_inventory_item(ecs_world, user, target, &item);
+        Self::take_one_from_slot(ecs_world, user, slot_index);
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: user.id(),
+            item_name: stone.name(),
+            effect,
+        });
+
+        true
     }
-    
-    /// Process automatic item pickup when player walks over items
-    fn process_auto_pickup(ecs_world: &mut ECSWorld) {
+
+    /// 投出的魔法石在落点生效（魔法石随之碎裂）
+    fn activate_stone(
+        ecs_world: &mut ECSWorld,
+        thrower: Entity,
+        stone: &items::Stone,
+        path: &crate::targeting::ProjectilePath,
+    ) {
         use crate::event_bus::GameEvent;
-        
-        // Collect players and items first to resolve borrowing conflicts
-        let pickup_actions: Vec<_> = {
-            let mut actions = Vec::new();
-            for (player_entity, (player_pos, _actor)) in
-                ecs_world.world.query::<(&Position, &Actor)>().iter()
-            {
-                if ecs_world.world.get::<&Player>(player_entity).is_err() {
-                    continue;
-                }
-                
-                let mut items_for_player = Vec::new();
-                for (item_entity, (pos, item)) in ecs_world.world.query::<(&Position, &ECSItem)>().iter() {
-                    if pos.x == player_pos.x && pos.y == player_pos.y && pos.z == player_pos.z {
-                        items_for_player.push((item_entity, item.clone(), item.name.clone()));
+        use crate::targeting::STONE_RADIUS;
+        use items::stone::StoneKind;
+
+        let landing = &path.landing;
+        let effect = match stone.kind {
+            StoneKind::Lullaby => {
+                let sleepers = Self::creatures_near(&ecs_world.world, landing, STONE_RADIUS);
+                for sleeper in &sleepers {
+                    CombatSystem::apply_status_effect(
+                        ecs_world,
+                        *sleeper,
+                        ::combat::effect::Effect::with_source(EffectType::Paralysis, 10, &stone.name()),
+                    );
+                    if let Ok(mut ai) = ecs_world.world.get::<&mut AI>(*sleeper) {
+                        ai.target = None;
+                        ai.state = AIState::Idle;
                     }
                 }
-                
-                let mut available_slots = ecs_world.world
-                    .get::<&Inventory>(player_entity)
-                    .ok()
-                    .map(|inventory| inventory.max_slots.saturating_sub(inventory.items.len()))
-                    .unwrap_or(0);
-                
-                if available_slots == 0 {
-                    ecs_world.resources.game_state.message_log
-                        .push("Your inventory is full!".to_string());
-                    continue;
-                }
-                
-                for (item_entity, item_clone, item_name) in items_for_player {
-                    if available_slots == 0 {
-                        break;
+                format!("{} 个生物陷入了沉睡", sleepers.len())
+            }
+            StoneKind::Teleportation => {
+                // 落点被生物占据时，闪现到弹道上离它最近的空地
+                let destination = path.tiles.iter().rev().find(|pos| {
+                    MovementSystem::is_tile_passable(&ecs_world.world, pos)
+                        && MovementSystem::find_entity_at_position(&ecs_world.world, pos).is_none()
+                });
+                match destination.cloned() {
+                    Some(destination) => {
+                        Self::teleport(ecs_world, thrower, destination);
+                        "闪现到了魔法石的落点".to_string()
                     }
-                    actions.push((player_entity, item_entity, item_clone, item_name));
-                    available_slots -= 1;
+                    None => "传送的力量无处可去".to_string(),
                 }
             }
-            actions
-        };
-        
-        for (player_entity, item_entity, item, item_name) in pickup_actions {
-            let mut picked_up = false;
-            if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
-                if inventory.items.len() < inventory.max_slots {
-                    inventory.items.push(ItemSlot {
-                        item: Some(item),
-                        quantity: 1,
-                    });
-                    picked_up = true;
+            StoneKind::MagicMapping => {
+                let revealed = Self::map_area(ecs_world, thrower, landing, Self::STONE_MAPPING_RADIUS);
+                format!("落点附近的布局浮现在脑海中（{} 格）", revealed)
+            }
+            StoneKind::MirrorImage => {
+                let spawned = Self::spawn_mirror_images(ecs_world, thrower, landing, 1, 1);
+                format!("{} 个镜像出现在落点", spawned)
+            }
+            StoneKind::Rage => {
+                // 范围内的敌人各自攻击离自己最近的另一个敌人
+                let nearby = Self::creatures_near(&ecs_world.world, landing, STONE_RADIUS);
+                let enemies: Vec<(Entity, Position, u32)> = nearby
+                    .into_iter()
+                    .filter_map(|entity| {
+                        let actor = ecs_world.world.get::<&Actor>(entity).ok()?;
+                        let stats = ecs_world.world.get::<&Stats>(entity).ok()?;
+                        let pos = ecs_world.world.get::<&Position>(entity).ok()?;
+                        (matches!(actor.faction, Faction::Enemy) && stats.hp > 0)
+                            .then(|| (entity, (*pos).clone(), stats.attack))
+                    })
+                    .collect();
+                let mut brawls = 0;
+                for (attacker, pos, attack) in &enemies {
+                    let victim = enemies
+                        .iter()
+                        .filter(|(other, _, _)| other != attacker)
+                        .min_by_key(|(_, other, _)| (other.x - pos.x).abs().max((other.y - pos.y).abs()))
+                        .map(|(other, _, _)| *other);
+                    if let Some(victim) = victim {
+                        CombatSystem::deal_direct_damage(ecs_world, *attacker, victim, *attack);
+                        brawls += 1;
+                    }
+                }
+                if brawls == 0 {
+                    "周围没有可以被激怒的敌人".to_string()
                 } else {
-                    ecs_world.resources.game_state.message_log
-                        .push("Your inventory is full!".to_string());
+                    format!("{} 个敌人被激怒，互相攻击起来", brawls)
                 }
             }
-            if picked_up {
-                let _ = ecs_world.world.despawn(item_entity);
-                
-                // Publish ItemPickedUp event
-                ecs_world.publish_event(GameEvent::ItemPickedUp {
-                    entity: player_entity.id(),
-                    item_name: item_name.clone(),
-                });
-            }
-        }
-    }
-}
-
-pub struct DungeonSystem;
+            _ => return,
+        };
 
-impl System for DungeonSystem {
-    fn name(&self) -> &str {
-        "DungeonSystem"
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: thrower.id(),
+            item_name: stone.name(),
+            effect,
+        });
     }
 
-    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
-        // Process pending player actions for dungeon navigation
-        let actions_to_process = std::mem::take(&mut resources.input_buffer.pending_actions);
-        let mut new_actions = Vec::new();
+    /// 把种子种在脚下；之后任何生物踩上这一格都会触发植物
+    fn handle_plant_seed(ecs_world: &mut ECSWorld, planter: Entity, slot_index: usize) -> bool {
+        use crate::event_bus::GameEvent;
 
-        for action in actions_to_process {
-            match action {
-                PlayerAction::Descend => {
-                    if let Some(player_entity) = find_player_entity(world) {
-                        // Check if player is on stairs - get the position first
-                        let player_pos_opt = match world.get::<&Position>(player_entity) {
-                            Ok(pos) => Some(pos.clone()),
-                            Err(_) => None,
-                        };
+        let Some(items::Item { kind: items::ItemKind::Seed(seed), .. }) =
+            Self::inventory_item(ecs_world, planter, slot_index)
+        else {
+            ecs_world.resources.game_state.message_log.push("只有种子可以种下".to_string());
+            return false;
+        };
+        let Ok(pos) = ecs_world.world.get::<&Position>(planter).map(|p| (*p).clone()) else {
+            return false;
+        };
 
-                        if let Some(player_pos) = player_pos_opt {
-                            // Check if there's a stairs down tile at player's position
-                            let mut on_stairs_down = false;
-                            for (_, (pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
-                                if pos.x == player_pos.x
-                                    && pos.y == player_pos.y
-                                    && pos.z == player_pos.z
-                                {
-                                    if matches!(tile.terrain_type, TerrainType::StairsDown) {
-                                        on_stairs_down = true;
-                                        break;
-                                    }
-                                }
-                            }
+        let blocked = matches!(
+            BlobSystem::terrain_at(&ecs_world.world, &pos),
+            Some(TerrainType::Water | TerrainType::Ice | TerrainType::StairsDown | TerrainType::StairsUp)
+        ) || ecs_world
+            .world
+            .query::<(&Position, &Plant)>()
+            .iter()
+            .any(|(_, (p, _))| *p == pos);
+        if blocked {
+            ecs_world.resources.game_state.message_log.push("这里无法种下种子".to_string());
+            return false;
+        }
 
-                            if on_stairs_down {
-                                // Queue up level generation and player movement
-                                let message = "You descend to the next level...".to_string();
+        Self::take_one_from_slot(ecs_world, planter, slot_index);
+        let plant = Plant { kind: seed.kind };
+        let effect = format!("{}在脚下生根发芽", plant.name());
+        ecs_world.world.spawn((pos, plant));
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: planter.id(),
+            item_name: seed.name(),
+            effect,
+        });
 
-                                // Message already added above in the game state log
-                                // resources.game_state.message_log.push(message);
-                                // if resources.game_state.message_log.len() > 10 {
-                                //     resources.game_state.message_log.remove(0);
-                                // }
+        true
+    }
 
-                                resources.game_state.depth = (player_pos.z + 1) as usize;
+    /// 背包中可以投入炼金釜的格子
+    pub(crate) fn alchemy_slots(world: &World, entity: Entity) -> Vec<usize> {
+        let Ok(inventory) = world.get::<&Inventory>(entity) else {
+            return Vec::new();
+        };
+        inventory
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| {
+                slot.item
+                    .as_ref()
+                    .map(|item| item.item.clone())
+                    .and_then(|item| items::alchemy::Ingredient::from_item(&item))
+                    .is_some()
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
 
-                                // Move player to new level
-                                if let Ok(mut pos) = world.get::<&mut Position>(player_entity) {
-                                    pos.z += 1;
-                                    // Place player at stairs up position
-                                    // For now, we'll place them at a default position (10, 10) on the new level
-                                    pos.x = 10;
-                                    pos.y = 10;
-                                }
+    /// 背包格子对应的炼金材料，任一格子不是材料或数量不足时返回 None
+    pub(crate) fn alchemy_ingredients(
+        world: &World,
+        entity: Entity,
+        slots: &[usize],
+    ) -> Option<Vec<items::alchemy::Ingredient>> {
+        let inventory = world.get::<&Inventory>(entity).ok()?;
+        slots
+            .iter()
+            .map(|&slot| {
+                let ecs_item = inventory.items.get(slot)?.item.as_ref()?;
+                let needed = slots.iter().filter(|&&s| s == slot).count() as u32;
+                if ecs_item.quantity.max(1) < needed {
+                    return None;
+                }
+                items::alchemy::Ingredient::from_item(ecs_item)
+            })
+            .collect()
+    }
 
-                                // Add message to game state log (original behavior)
-                                resources
-                                    .game_state
-                                    .message_log
-                                    .push("You descend to the next level...".to_string());
-                                if resources.game_state.message_log.len() > 10 {
-                                    resources.game_state.message_log.remove(0);
-                                }
+    /// 在炼金釜中炼制：材料匹配某条配方时消耗材料并得到产物，首次炼成时记住配方
+    ///
+    /// 没有匹配的配方时材料不会被消耗，也不消耗回合。
+    fn handle_brew(ecs_world: &mut ECSWorld, brewer: Entity, slots: &[usize]) -> bool {
+        use crate::event_bus::GameEvent;
 
-                                // Add generation of new level after all actions are processed
-                                // We'll generate it in a separate pass
-                            } else {
-                                resources
-                                    .game_state
-                                    .message_log
-                                    .push("You need to stand on stairs to descend.".to_string());
-                                if resources.game_state.message_log.len() > 10 {
-                                    resources.game_state.message_log.remove(0);
-                                }
-                                new_actions.push(action);
-                            }
-                        } else {
-                            new_actions.push(action);
-                        }
-                    } else {
-                        new_actions.push(action);
-                    }
-                }
-                PlayerAction::Ascend => {
-                    if let Some(player_entity) = find_player_entity(world) {
-                        // Check if player is on stairs - get the position first
-                        let player_pos_opt = match world.get::<&Position>(player_entity) {
-                            Ok(pos) => Some(pos.clone()),
-                            Err(_) => None,
-                        };
+        if slots.is_empty() {
+            ecs_world.resources.game_state.message_log.push("炼金釜里什么也没有".to_string());
+            return false;
+        }
+        let Some(ingredients) = Self::alchemy_ingredients(&ecs_world.world, brewer, slots) else {
+            ecs_world.resources.game_state.message_log.push("这些东西不能放进炼金釜".to_string());
+            return false;
+        };
+        let Some(recipe) = items::alchemy::find_recipe(&ingredients) else {
+            ecs_world
+                .resources
+                .game_state
+                .message_log
+                .push("材料在釜中翻滚了一阵，什么也没有炼成".to_string());
+            return false;
+        };
 
-                        if let Some(player_pos) = player_pos_opt {
-                            // Check if there's a stairs up tile at player's position
-                            let mut on_stairs_up = false;
-                            for (_, (pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
-                                if pos.x == player_pos.x
-                                    && pos.y == player_pos.y
-                                    && pos.z == player_pos.z
-                                {
-                                    if matches!(tile.terrain_type, TerrainType::StairsUp) {
-                                        on_stairs_up = true;
-                                        break;
-                                    }
-                                }
-                            }
+        let ingredient_names: Vec<String> = {
+            let inventory = ecs_world.world.get::<&Inventory>(brewer).ok();
+            slots
+                .iter()
+                .filter_map(|&slot| {
+                    let inventory = inventory.as_ref()?;
+                    Some(inventory.items.get(slot)?.item.as_ref()?.name.clone())
+                })
+                .collect()
+        };
 
-                            if on_stairs_up {
-                                if player_pos.z > 0 {
-                                    // Move player to new level
-                                    if let Ok(mut pos) = world.get::<&mut Position>(player_entity) {
-                                        pos.z -= 1;
-                                        // Place player at stairs down position at previous level
-                                        pos.x = 10;
-                                        pos.y = 10;
-                                    }
+        // 从后往前取出材料，避免格子被移除后序号错位
+        let mut consumed = slots.to_vec();
+        consumed.sort_unstable_by(|a, b| b.cmp(a));
+        for slot in consumed {
+            Self::take_one_from_slot(ecs_world, brewer, slot);
+        }
 
-                                    let message = "You ascend to the previous level...".to_string();
+        let product = recipe.product.create();
+        Self::learn_item_kind(ecs_world, &product.kind);
+        let item_name = product.name.clone();
+        Self::store_in_inventory(ecs_world, brewer, ECSItem::new(product));
 
-                                    // Add message to game state log (original behavior)
-                                    resources.game_state.message_log.push(message);
-                                    if resources.game_state.message_log.len() > 10 {
-                                        resources.game_state.message_log.remove(0);
-                                    }
+        let log = &mut ecs_world.resources.game_state.message_log;
+        log.push(format!("炼制出了 {}", item_name));
+        if ecs_world.resources.item_knowledge.learn_recipe(&recipe) {
+            log.push(format!("发现了新配方：{}", recipe.describe()));
+        }
+        ecs_world.publish_event(GameEvent::ItemCrafted {
+            entity: brewer.id(),
+            item_name,
+            ingredients: ingredient_names,
+        });
 
-                                    resources.game_state.depth = (player_pos.z - 1) as usize;
+        true
+    }
 
-                                    // Add message to game state log (original behavior)
-                                    resources
-                                        .game_state
-                                        .message_log
-                                        .push("You ascend to the previous level...".to_string());
-                                    if resources.game_state.message_log.len() > 10 {
-                                        resources.game_state.message_log.remove(0);
-                                    }
+    /// 背包指定格子中物品的完整数据
+    fn inventory_item(ecs_world: &ECSWorld, entity: Entity, slot_index: usize) -> Option<items::Item> {
+        let inventory = ecs_world.world.get::<&Inventory>(entity).ok()?;
+        Some(inventory.items.get(slot_index)?.item.as_ref()?.item.clone())
+    }
 
-                                    // Generate level for the new depth after actions are processed
-                                } else {
-                                    // Player is at dungeon level 0, can't go higher
-                                    let message = "You can't go up from here.".to_string();
+    /// 用新的物品数据替换背包格子中的物品（数量保持不变）
+    fn replace_inventory_item(ecs_world: &mut ECSWorld, entity: Entity, slot_index: usize, item: &items::Item) {
+        if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(entity) {
+            if let Some(ecs_item) = inventory.items.get_mut(slot_index).and_then(|slot| slot.item.as_mut()) {
+                let quantity = ecs_item.quantity;
+                ecs_item.item = item.clone();
+                ecs_item.quantity = quantity;
+                ecs_item.apply_knowledge(&ecs_world.resources.item_knowledge);
+            }
+        }
+    }
 
-                                    // Add message to game state log (original behavior)
-                                    resources.game_state.message_log.push(message);
-                                    if resources.game_state.message_log.len() > 10 {
-                                        resources.game_state.message_log.remove(0);
-                                    }
-                                }
-                            } else {
-                                let message = "You need to stand on stairs to ascend.".to_string();
-                                new_actions.push(action);
+    /// 阅读卷轴，`target_slot` 为需要目标的卷轴选中的背包格子
+    ///
+    /// 需要目标却没有给出时进入物品选择界面，本次不消耗卷轴。
+    fn read_scroll(
+        ecs_world: &mut ECSWorld,
+        reader: Entity,
+        slot_index: usize,
+        scroll: &items::Scroll,
+        target_slot: Option<usize>,
+    ) -> bool {
+        use crate::event_bus::GameEvent;
+        use items::scroll::ScrollKind;
+
+        let needs_target = Self::needs_item_target(&items::Item::new(items::ItemKind::Scroll(scroll.clone())));
+        let target = match (needs_target, target_slot) {
+            (true, None) => {
+                ecs_world.resources.game_state.game_state = GameStatus::ItemPicker {
+                    source_slot: slot_index,
+                    selected_item: 0,
+                };
+                return false;
+            }
+            (true, Some(target)) if target == slot_index => {
+                ecs_world.resources.game_state.message_log.push("不能对卷轴自身使用".to_string());
+                return false;
+            }
+            (true, Some(target)) => match Self::inventory_item(ecs_world, reader, target) {
+                Some(item) => Some((target, item)),
+                None => {
+                    ecs_world.resources.game_state.message_log.push("没有选中任何物品".to_string());
+                    return false;
+                }
+            },
+            (false, _) => None,
+        };
 
-                                // Add message to game state log (original behavior)
-                                resources.game_state.message_log.push(message);
-                                if resources.game_state.message_log.len() > 10 {
-                                    resources.game_state.message_log.remove(0);
-                                }
-                            }
-                        } else {
-                            new_actions.push(action);
+        let effect = match (scroll.kind, target) {
+            (ScrollKind::Upgrade, Some((target, mut item))) => {
+                let done = if scroll.exotic { item.enchant() } else { item.upgrade() };
+                if !done {
+                    ecs_world.resources.game_state.message_log.push(format!("{}无法被强化", item.name));
+                    return false;
+                }
+                Self::replace_inventory_item(ecs_world, reader, target, &item);
+                if scroll.exotic {
+                    format!("{}获得了新的魔力", item.name)
+                } else {
+                    format!("{}得到了强化", item.name)
+                }
+            }
+            (ScrollKind::Identify, Some((target, mut item))) => {
+                item.identify();
+                Self::learn_item_kind(ecs_world, &item.kind);
+                Self::replace_inventory_item(ecs_world, reader, target, &item);
+                format!("这是{}", item.name)
+            }
+            (ScrollKind::Identify, None) => {
+                let count = Self::identify_inventory(ecs_world, reader);
+                format!("看清了背包中的 {} 件物品", count)
+            }
+            (ScrollKind::Transmutation, Some((target, item))) => {
+                let Some(mut transmuted) = item.transmute(&mut ecs_world.resources.rng) else {
+                    ecs_world.resources.game_state.message_log.push(format!("{}无法被变形", item.name));
+                    return false;
+                };
+                // 蜕变卷轴在变形的同时强化物品
+                if scroll.exotic {
+                    transmuted.upgrade();
+                }
+                Self::replace_inventory_item(ecs_world, reader, target, &transmuted);
+                format!("{}变成了{}", item.name, transmuted.name)
+            }
+            (ScrollKind::RemoveCurse, _) => {
+                let purified = Self::remove_curses(ecs_world, reader);
+                // 圣洁卷轴同时驱散负面状态
+                if scroll.exotic {
+                    if let Ok(mut effects) = ecs_world.world.get::<&mut StatusEffects>(reader) {
+                        effects.effects.retain(|effect| !Self::is_negative_effect(effect.effect_type()));
+                    }
+                }
+                if purified.is_empty() {
+                    "没有感受到任何诅咒".to_string()
+                } else {
+                    format!("{}上的诅咒消散了", purified.join("、"))
+                }
+            }
+            (ScrollKind::MagicMapping, _) => {
+                let revealed = Self::map_level(ecs_world, reader);
+                Self::reveal_secret_doors(ecs_world, reader);
+                // 探知卷轴还能感知楼层中的生物
+                if scroll.exotic {
+                    CombatSystem::apply_status_effect(
+                        ecs_world,
+                        reader,
+                        ::combat::effect::Effect::with_source(EffectType::MindVision, 20, &scroll.name()),
+                    );
+                }
+                format!("楼层的布局浮现在脑海中（{} 格）", revealed)
+            }
+            (ScrollKind::Teleportation, _) => {
+                // 回归卷轴把读者送回本层的上楼梯
+                let destination = if scroll.exotic {
+                    Self::find_stairs_up(ecs_world, reader).or_else(|| Self::random_safe_tile(ecs_world, reader))
+                } else {
+                    Self::random_safe_tile(ecs_world, reader)
+                };
+                let Some(destination) = destination else {
+                    ecs_world.resources.game_state.message_log.push("传送的力量无处可去".to_string());
+                    return false;
+                };
+                Self::teleport(ecs_world, reader, destination);
+                "被传送到了别处".to_string()
+            }
+            (ScrollKind::Lullaby, _) => {
+                let affected = Self::nearby_enemies(ecs_world, reader, Self::SCROLL_RADIUS);
+                for enemy in &affected {
+                    if scroll.exotic && ecs_world.world.get::<&crate::ecs::BossComponent>(*enemy).is_err() {
+                        // 魅惑卷轴让敌人倒戈
+                        if let Ok(mut actor) = ecs_world.world.get::<&mut Actor>(*enemy) {
+                            actor.faction = Faction::Player;
                         }
                     } else {
-                        new_actions.push(action);
+                        CombatSystem::apply_status_effect(
+                            ecs_world,
+                            *enemy,
+                            ::combat::effect::Effect::with_source(EffectType::Paralysis, 10, &scroll.name()),
+                        );
+                    }
+                    if let Ok(mut ai) = ecs_world.world.get::<&mut AI>(*enemy) {
+                        ai.target = None;
+                        ai.state = AIState::Idle;
                     }
                 }
-                // For non-dungeon actions, add back to queue for other systems to handle
-                _ => {
-                    new_actions.push(action);
+                if scroll.exotic {
+                    format!("{} 个敌人被歌声魅惑", affected.len())
+                } else {
+                    format!("{} 个敌人陷入了沉睡", affected.len())
                 }
             }
-        }
+            (ScrollKind::Rage, _) => {
+                let enraged = Self::nearby_enemies(ecs_world, reader, i32::MAX);
+                for enemy in &enraged {
+                    if let Ok(mut ai) = ecs_world.world.get::<&mut AI>(*enemy) {
+                        ai.target = Some(reader);
+                        ai.state = AIState::Chasing;
+                    }
+                }
+                // 决斗卷轴让读者做好迎战准备
+                if scroll.exotic {
+                    CombatSystem::apply_status_effect(
+                        ecs_world,
+                        reader,
+                        ::combat::effect::Effect::with_source(EffectType::Fury, 10, &scroll.name()),
+                    );
+                }
+                format!("一声怒吼响彻楼层，{} 个敌人向你冲来", enraged.len())
+            }
+            (ScrollKind::Recharging, _) => {
+                // 魔能卷轴额外给予一点超出上限的充能
+                let overcharge = if scroll.exotic { 1 } else { 0 };
+                let recharged = Self::recharge_wands(ecs_world, reader, overcharge);
+                format!("{} 根法杖充满了能量", recharged)
+            }
+            (ScrollKind::MirrorImage, _) => {
+                let (count, strength) = if scroll.exotic { (3, 2) } else { (2, 1) };
+                let Ok(origin) = ecs_world.world.get::<&Position>(reader).map(|p| (*p).clone()) else {
+                    return false;
+                };
+                let spawned = Self::spawn_mirror_images(ecs_world, reader, &origin, count, strength);
+                format!("{} 个镜像出现在你身边", spawned)
+            }
+            (ScrollKind::Upgrade | ScrollKind::Transmutation, None) => return false,
+        };
 
-        // Put unprocessed actions back in the buffer
-        resources.input_buffer.pending_actions = new_actions;
+        Self::take_one_from_slot(ecs_world, reader, slot_index);
+        Self::learn_item_kind(ecs_world, &items::ItemKind::Scroll(scroll.clone()));
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: reader.id(),
+            item_name: scroll.name(),
+            effect,
+        });
 
-        SystemResult::Continue
+        true
     }
-}
 
-impl DungeonSystem {
-    /// Run dungeon system with event bus access for environment interactions
-    pub fn run_with_events(ecs_world: &mut ECSWorld) -> SystemResult {
-        use crate::event_bus::GameEvent;
-        use crate::turn_system::energy_costs;
+    /// 催眠等卷轴的作用半径
+    const SCROLL_RADIUS: i32 = 8;
 
-        // Process pending player actions for dungeon interactions
-        let actions_to_process =
-            std::mem::take(&mut ecs_world.resources.input_buffer.pending_actions);
-        let mut new_actions = Vec::new();
+    /// 地图之石揭示的范围
+    const STONE_MAPPING_RADIUS: i32 = 5;
 
-        for action in actions_to_process {
-            match action {
-                PlayerAction::Descend => {
-                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
-                        // Get player position as owned values
-                        let player_pos_x;
-                        let player_pos_y;
-                        let player_pos_z;
+    fn is_negative_effect(effect_type: EffectType) -> bool {
+        matches!(
+            effect_type,
+            EffectType::Burning
+                | EffectType::Poison
+                | EffectType::Paralysis
+                | EffectType::Bleeding
+                | EffectType::Slow
+                | EffectType::Ooze
+                | EffectType::Frost
+                | EffectType::Darkness
+                | EffectType::Rooted
+                | EffectType::AntiMagic
+        )
+    }
 
-                        match ecs_world.world.get::<&Position>(player_entity) {
-                            Ok(pos) => {
-                                player_pos_x = pos.x;
-                                player_pos_y = pos.y;
-                                player_pos_z = pos.z;
-                            }
-                            Err(_) => {
-                                new_actions.push(action);
-                                continue;
-                            }
-                        }
+    /// 鉴定背包中的所有物品，返回新鉴定的数量
+    fn identify_inventory(ecs_world: &mut ECSWorld, entity: Entity) -> usize {
+        let mut learned = Vec::new();
+        {
+            let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(entity) else {
+                return 0;
+            };
+            for ecs_item in inventory.items.iter_mut().filter_map(|slot| slot.item.as_mut()) {
+                if ecs_item.is_identified() {
+                    continue;
+                }
+                ecs_item.identify();
+                learned.push(ecs_item.kind.clone());
+            }
+        }
+        for kind in &learned {
+            Self::learn_item_kind(ecs_world, kind);
+        }
+        learned.len()
+    }
 
-                        // Check if there's a stairs down tile at player's position
-                        let mut on_stairs_down = false;
-                        for (_, (pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter()
-                        {
-                            if pos.x == player_pos_x
-                                && pos.y == player_pos_y
-                                && pos.z == player_pos_z
-                            {
-                                if matches!(tile.terrain_type, TerrainType::StairsDown) {
-                                    on_stairs_down = true;
-                                    break;
-                                }
-                            }
-                        }
+    /// 将所在楼层的全部地形记入视野记忆，返回新揭示的格子数
+    fn map_level(ecs_world: &mut ECSWorld, entity: Entity) -> usize {
+        let Ok(center) = ecs_world.world.get::<&Position>(entity).map(|p| (*p).clone()) else {
+            return 0;
+        };
+        Self::map_area(ecs_world, entity, &center, i32::MAX)
+    }
 
-                        if on_stairs_down {
-                            let old_level = player_pos_z as usize;
-                            let new_level = (player_pos_z + 1) as usize;
-
-                            // Move player to new level
-                            if let Ok(mut pos) = ecs_world.world.get::<&mut Position>(player_entity)
-                            {
-                                pos.z += 1;
-                                pos.x = 10;
-                                pos.y = 10;
-                            }
-
-                            // Reset player's viewshed to trigger FOV recalculation
-                            if let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(player_entity) {
-                                viewshed.dirty = true;
-                                viewshed.visible_tiles.clear();
-                            }
-
-                            // Update game state depth
-                            ecs_world.resources.game_state.depth = new_level;
-
-                            // Publish LevelChanged event
-                            ecs_world.publish_event(GameEvent::LevelChanged {
-                                old_level,
-                                new_level,
-                            });
+    /// 将 `center` 周围一定范围内的地形记入视野记忆，返回新揭示的格子数
+    fn map_area(ecs_world: &mut ECSWorld, entity: Entity, center: &Position, radius: i32) -> usize {
+        let tiles: Vec<Position> = ecs_world
+            .world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .filter(|(_, (pos, _))| {
+                pos.z == center.z && (pos.x - center.x).abs() <= radius && (pos.y - center.y).abs() <= radius
+            })
+            .map(|(_, (pos, _))| pos.clone())
+            .collect();
 
-                            // Publish action completed event
-                            ecs_world.publish_event(GameEvent::ActionCompleted {
-                                entity: player_entity.id() as u32,
-                                action_type: "Descend".to_string(),
-                                success: true,
-                            });
+        let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(entity) else {
+            return 0;
+        };
+        let mut revealed = 0;
+        for tile in tiles {
+            if !viewshed.memory.contains(&tile) {
+                viewshed.memory.push(tile);
+                revealed += 1;
+            }
+        }
+        viewshed.dirty = true;
+        revealed
+    }
 
-                            // Add to completed actions for energy deduction
-                            ecs_world
-                                .resources
-                                .input_buffer
-                                .completed_actions
-                                .push(PlayerAction::Descend);
+    /// 揭示当前楼层的所有暗门：地图上对应的墙壁变为门，并为每扇暗门发布发现事件
+    fn reveal_secret_doors(ecs_world: &mut ECSWorld, entity: Entity) -> usize {
+        use crate::event_bus::GameEvent;
 
-                            // Add message to game state log
-                            ecs_world
-                                .resources
-                                .game_state
-                                .message_log
-                                .push("You descend to the next level...".to_string());
-                            if ecs_world.resources.game_state.message_log.len() > 10 {
-                                ecs_world.resources.game_state.message_log.remove(0);
-                            }
-                        } else {
-                            ecs_world
-                                .resources
-                                .game_state
-                                .message_log
-                                .push("You need to stand on stairs to descend.".to_string());
-                            if ecs_world.resources.game_state.message_log.len() > 10 {
-                                ecs_world.resources.game_state.message_log.remove(0);
-                            }
-                            
-                            // Publish action failed event
-                            ecs_world.publish_event(GameEvent::ActionFailed {
-                                entity: player_entity.id() as u32,
-                                action_type: "Descend".to_string(),
-                                reason: "Not on stairs".to_string(),
-                            });
-                            
-                            new_actions.push(action);
-                        }
-                    } else {
-                        new_actions.push(action);
+        let Ok(z) = ecs_world.world.get::<&Position>(entity).map(|p| p.z) else {
+            return 0;
+        };
+        let mut revealed = Vec::new();
+        crate::ecs::with_dungeon_mut(&mut ecs_world.world, |dungeon| {
+            revealed = dungeon.current_level_mut().reveal_secret_doors();
+        });
+        for event in &revealed {
+            let dungeon::InteractionEvent::SecretRevealed(x, y) = *event else {
+                continue;
+            };
+            for (_, (pos, tile, renderable)) in
+                ecs_world.world.query_mut::<(&Position, &mut Tile, Option<&mut Renderable>)>()
+            {
+                if (pos.x, pos.y, pos.z) == (x, y, z) {
+                    tile.terrain_type = TerrainType::Door;
+                    if let Some(renderable) = renderable {
+                        renderable.symbol = '+';
                     }
                 }
-                PlayerAction::Ascend => {
-                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
-                        // Get player position as owned value
-                        let player_pos_x;
-                        let player_pos_y;
-                        let player_pos_z;
-
-                        match ecs_world.world.get::<&Position>(player_entity) {
-                            Ok(pos) => {
-                                player_pos_x = pos.x;
-                                player_pos_y = pos.y;
-                                player_pos_z = pos.z;
-                            }
-                            Err(_) => {
-                                new_actions.push(action);
-                                continue;
-                            }
-                        }
-
-                        // Check if there's a stairs up tile at player's position
-                        let mut on_stairs_up = false;
-                        for (_, (pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter()
-                        {
-                            if pos.x == player_pos_x
-                                && pos.y == player_pos_y
-                                && pos.z == player_pos_z
-                            {
-                                if matches!(tile.terrain_type, TerrainType::StairsUp) {
-                                    on_stairs_up = true;
-                                    break;
-                                }
-                            }
-                        }
-
-                        if on_stairs_up {
-                            if player_pos_z > 0 {
-                                let old_level = player_pos_z as usize;
-                                let new_level = (player_pos_z - 1) as usize;
-
-                                // Move player to new level
-                                if let Ok(mut pos) =
-                                    ecs_world.world.get::<&mut Position>(player_entity)
-                                {
-                                    pos.z -= 1;
-                                    pos.x = 10;
-                                    pos.y = 10;
-                                }
-
-                                // Reset player's viewshed to trigger FOV recalculation
-                                if let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(player_entity) {
-                                    viewshed.dirty = true;
-                                    viewshed.visible_tiles.clear();
-                                }
+            }
+            ecs_world.publish_event(GameEvent::SecretDiscovered {
+                entity: entity.id(),
+                x,
+                y,
+                secret_type: "暗门".to_string(),
+            });
+        }
+        revealed.len()
+    }
 
-                                // Update game state depth
-                                ecs_world.resources.game_state.depth = new_level;
+    /// 本层中可以安全落脚的随机格子（可通行、无生物、非陷阱）
+    fn random_safe_tile(ecs_world: &mut ECSWorld, entity: Entity) -> Option<Position> {
+        use rand::seq::IndexedRandom;
 
-                                // Publish LevelChanged event
-                                ecs_world.publish_event(GameEvent::LevelChanged {
-                                    old_level,
-                                    new_level,
-                                });
+        let current = ecs_world.world.get::<&Position>(entity).ok().map(|p| (*p).clone())?;
+        let candidates: Vec<Position> = ecs_world
+            .world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .filter(|(_, (pos, tile))| {
+                pos.z == current.z
+                    && tile.is_passable
+                    && !matches!(tile.terrain_type, TerrainType::Trap | TerrainType::Water)
+                    && **pos != current
+            })
+            .map(|(_, (pos, _))| pos.clone())
+            .filter(|pos| MovementSystem::find_entity_at_position(&ecs_world.world, pos).is_none())
+            .collect();
+        candidates.choose(&mut ecs_world.resources.rng).cloned()
+    }
 
-                                // Publish action completed event
-                                ecs_world.publish_event(GameEvent::ActionCompleted {
-                                    entity: player_entity.id() as u32,
-                                    action_type: "Ascend".to_string(),
-                                    success: true,
-                                });
+    /// 本层的上楼梯位置
+    fn find_stairs_up(ecs_world: &ECSWorld, entity: Entity) -> Option<Position> {
+        let depth = ecs_world.world.get::<&Position>(entity).ok()?.z;
+        ecs_world
+            .world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .find(|(_, (pos, tile))| pos.z == depth && matches!(tile.terrain_type, TerrainType::StairsUp))
+            .map(|(_, (pos, _))| pos.clone())
+    }
 
-                                // Add to completed actions for energy deduction
-                                ecs_world
-                                    .resources
-                                    .input_buffer
-                                    .completed_actions
-                                    .push(PlayerAction::Ascend);
+    /// 将实体瞬间移动到目标位置
+    fn teleport(ecs_world: &mut ECSWorld, entity: Entity, destination: Position) {
+        use crate::event_bus::GameEvent;
 
-                                // Add message to game state log
-                                ecs_world
-                                    .resources
-                                    .game_state
-                                    .message_log
-                                    .push("You ascend to the previous level...".to_string());
-                                if ecs_world.resources.game_state.message_log.len() > 10 {
-                                    ecs_world.resources.game_state.message_log.remove(0);
-                                }
-                            } else {
-                                // Player is at dungeon level 0, can't go higher
-                                let message = "You can't go up from here.".to_string();
+        let Ok(from) = ecs_world.world.get::<&Position>(entity).map(|p| (*p).clone()) else {
+            return;
+        };
+        if let Ok(mut pos) = ecs_world.world.get::<&mut Position>(entity) {
+            *pos = destination.clone();
+        }
+        if let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(entity) {
+            viewshed.dirty = true;
+        }
+        ecs_world.publish_event(GameEvent::EntityMoved {
+            entity: entity.id(),
+            from_x: from.x,
+            from_y: from.y,
+            to_x: destination.x,
+            to_y: destination.y,
+        });
+    }
 
-                                ecs_world.resources.game_state.message_log.push(message);
-                                if ecs_world.resources.game_state.message_log.len() > 10 {
-                                    ecs_world.resources.game_state.message_log.remove(0);
-                                }
-                                
-                                // Publish action failed event
-                                ecs_world.publish_event(GameEvent::ActionFailed {
-                                    entity: player_entity.id() as u32,
-                                    action_type: "Ascend".to_string(),
-                                    reason: "Already at top level".to_string(),
-                                });
-                            }
-                        } else {
-                            let message = "You need to stand on stairs to ascend.".to_string();
+    /// 同层中一定距离内的敌人
+    fn nearby_enemies(ecs_world: &ECSWorld, entity: Entity, radius: i32) -> Vec<Entity> {
+        let Ok(center) = ecs_world.world.get::<&Position>(entity).map(|p| (*p).clone()) else {
+            return Vec::new();
+        };
+        ecs_world
+            .world
+            .query::<(&Position, &Actor, &Stats)>()
+            .iter()
+            .filter(|(_, (pos, actor, stats))| {
+                matches!(actor.faction, Faction::Enemy)
+                    && stats.hp > 0
+                    && pos.z == center.z
+                    && (pos.x - center.x).abs().max((pos.y - center.y).abs()) <= radius
+            })
+            .map(|(enemy, _)| enemy)
+            .collect()
+    }
 
-                            ecs_world.resources.game_state.message_log.push(message);
-                            if ecs_world.resources.game_state.message_log.len() > 10 {
-                                ecs_world.resources.game_state.message_log.remove(0);
-                            }
-                            
-                            // Publish action failed event
-                            ecs_world.publish_event(GameEvent::ActionFailed {
-                                entity: player_entity.id() as u32,
-                                action_type: "Ascend".to_string(),
-                                reason: "Not on stairs".to_string(),
-                            });
-                            
-                            new_actions.push(action);
-                        }
-                    } else {
-                        new_actions.push(action);
-                    }
-                }
-                // For non-dungeon actions, add back to queue for other systems to handle
-                _ => {
-                    new_actions.push(action);
-                }
+    /// 为背包中的法杖充能，返回充能的法杖数量
+    fn recharge_wands(ecs_world: &mut ECSWorld, entity: Entity, overcharge: u8) -> usize {
+        let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(entity) else {
+            return 0;
+        };
+        let mut count = 0;
+        for ecs_item in inventory.items.iter_mut().filter_map(|slot| slot.item.as_mut()) {
+            if let items::ItemKind::Wand(wand) = &mut ecs_item.kind {
+                wand.recharge(overcharge);
+                count += 1;
             }
         }
-
-        // Put unprocessed actions back in the buffer
-        ecs_world.resources.input_buffer.pending_actions = new_actions;
-
-        SystemResult::Continue
+        count
     }
 
-    /// Check and handle traps at a position with proper event publishing
-    pub fn check_and_trigger_trap(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) {
-        use crate::event_bus::GameEvent;
-
-        // Collect trap data first to avoid borrow conflicts
-        let mut trap_data: Option<(String, u32)> = None;
+    /// 在 `origin` 周围召唤镜像分身，属性取自 `owner`，`strength` 为生命与攻击的倍率
+    fn spawn_mirror_images(
+        ecs_world: &mut ECSWorld,
+        owner: Entity,
+        origin: &Position,
+        count: usize,
+        strength: u32,
+    ) -> usize {
+        let Ok(stats) = ecs_world.world.get::<&Stats>(owner).map(|s| (*s).clone()) else {
+            return 0;
+        };
 
-        for (_, (trap_pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter() {
-            if trap_pos.x == pos.x && trap_pos.y == pos.y && trap_pos.z == pos.z {
-                if matches!(tile.terrain_type, TerrainType::Trap) {
-                    trap_data = Some(("尖刺陷阱".to_string(), 10)); // Simplified trap
-                    break;
-                }
-            }
-        }
+        let spots: Vec<Position> = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+            .map(|(dx, dy)| Position::new(origin.x + dx, origin.y + dy, origin.z))
+            .filter(|pos| {
+                MovementSystem::is_tile_passable(&ecs_world.world, pos)
+                    && MovementSystem::find_entity_at_position(&ecs_world.world, pos).is_none()
+            })
+            .take(count)
+            .collect();
 
-        if let Some((trap_type, damage)) = trap_data {
-            // Publish trap triggered event
-            ecs_world.publish_event(GameEvent::TrapTriggered {
-                entity: entity.id() as u32,
-                trap_type: trap_type.clone(),
-            });
+        let hp = (stats.max_hp / 10).max(1) * strength;
+        for spot in &spots {
+            ecs_world.world.spawn((
+                spot.clone(),
+                Actor {
+                    name: "镜像".to_string(),
+                    faction: Faction::Player,
+                },
+                Renderable {
+                    symbol: '@',
+                    fg_color: Color::Cyan,
+                    bg_color: Some(Color::Black),
+                    order: 5,
+                },
+                Stats {
+                    hp,
+                    max_hp: hp,
+                    attack: (stats.attack / 2).max(1) * strength,
+                    defense: 0,
+                    accuracy: stats.accuracy,
+                    evasion: stats.evasion,
+                    level: 1,
+                    experience: 0,
+                    class: None,
+                },
+                Energy {
+                    current: 100,
+                    max: 100,
+                    regeneration_rate: 1,
+                },
+                AI {
+                    ai_type: AIType::Aggressive,
+                    target: None,
+                    state: AIState::Idle,
+                },
+            ));
+        }
+        spots.len()
+    }
 
-            // Apply trap damage (separate scope to avoid borrow conflicts)
-            {
-                if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(entity) {
-                    stats.hp = stats.hp.saturating_sub(damage);
+    /// Handle dropping an item
+    fn handle_drop_item(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize) -> bool {
+        use crate::event_bus::GameEvent;
+        
+        let player_id = player_entity.id();
+        
+        // Get player position
+        let player_pos = match ecs_world.world.get::<&Position>(player_entity) {
+            Ok(pos) => Position::new(pos.x, pos.y, pos.z),
+            Err(_) => return false,
+        };
+        
+        // Get and remove the item (do this in a separate scope to avoid borrow conflicts)
+        let item_to_drop_opt = {
+            if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(player_entity) {
+                if slot_index < inventory.items.len() {
+                    inventory.items.remove(slot_index).item
+                } else {
+                    ecs_world.resources.game_state.message_log.push("Invalid inventory slot.".to_string());
+                    None
                 }
+            } else {
+                None
             }
-
-            // Publish damage event after stats update
-            ecs_world.publish_event(GameEvent::DamageDealt {
-                attacker: 0, // No attacker for environmental damage
-                victim: entity.id() as u32,
-                damage,
-                is_critical: false,
+        };
+        
+        if let Some(item_to_drop) = item_to_drop_opt {
+            let item_name = item_to_drop.name.clone();
+            
+            // Spawn the item on the ground
+            Self::spawn_ground_item(ecs_world, &player_pos, item_to_drop);
+            
+            // Publish ItemDropped event
+            ecs_world.publish_event(GameEvent::ItemDropped {
+                entity: player_id,
+                item_name: item_name.clone(),
             });
+            
+            return true;
+        } else {
+            ecs_world.resources.game_state.message_log.push("No item in this slot to drop.".to_string());
+        }
+        
+        false
+    }
+    
+    /// Handle equipping an item
+    fn handle_equip_item(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize) -> bool {
+        use crate::event_bus::GameEvent;
+        
+        let player_id = player_entity.id();
+        
+        // Get the item to equip
+        let item_opt = {
+            if let Ok(inventory) = ecs_world.world.get::<&Inventory>(player_entity) {
+                if slot_index < inventory.items.len() {
+                    inventory.items[slot_index].item.clone()
+                } else {
+                    ecs_world.resources.game_state.message_log.push("Invalid inventory slot.".to_string());
+                    return false;
+                }
+            } else {
+                return false;
+            }
+        };
+        
+        let Some(item) = item_opt else {
+            ecs_world.resources.game_state.message_log.push("No item in this slot.".to_string());
+            return false;
+        };
+        if !items::Usable::is_equippable(&*item) {
+            ecs_world.resources.game_state.message_log.push("Cannot equip this item.".to_string());
+            return false;
+        }
+        let decoded = item.item.clone();
 
+        // 被诅咒的装备占着槽位时无法替换
+        let blocking_curse = ecs_world.world.get::<&Equipment>(player_entity).ok().and_then(|equipment| {
+            let slot = equipment.slot_for(&decoded)?;
+            equipment.is_cursed(slot).then(|| equipment.slot_name(slot)).flatten()
+        });
+        if let Some(cursed_name) = blocking_curse {
             ecs_world
                 .resources
                 .game_state
                 .message_log
-                .push(format!("触发了{}，造成了 {} 点伤害", trap_type, damage));
+                .push(format!("{}被诅咒了，无法卸下", cursed_name));
+            return false;
         }
-    }
-
-    /// Check and handle door opening with proper event publishing
-    pub fn check_and_open_door(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) -> bool {
-        use crate::event_bus::GameEvent;
 
-        // Find door at position and collect its entity ID first
-        let mut door_entity_opt: Option<hecs::Entity> = None;
+        // 戴上戒指后便能认出它的种类
+        let learned_kind = matches!(decoded.kind, items::ItemKind::Ring(_)).then(|| decoded.kind.clone());
 
-        for (door_ent, (door_pos, tile)) in
-            ecs_world.world.query::<(&Position, &Tile)>().iter()
-        {
-            if door_pos.x == pos.x && door_pos.y == pos.y && door_pos.z == pos.z {
-                if matches!(tile.terrain_type, TerrainType::Door) {
-                    door_entity_opt = Some(door_ent);
-                    break;
-                }
-            }
+        // 装备中的物品离开背包，被替换下来的装备放回背包
+        Self::take_one_from_slot(ecs_world, player_entity, slot_index);
+        if ecs_world.world.get::<&Equipment>(player_entity).is_err() {
+            let _ = ecs_world.world.insert_one(player_entity, Equipment::default());
         }
-
-        // Update the door if found
-        if let Some(door_ent) = door_entity_opt {
-            if let Ok(mut tile) = ecs_world.world.get::<&mut Tile>(door_ent) {
-                // Open the door
-                tile.is_passable = true;
-                tile.blocks_sight = false;
+        let equipped = ecs_world
+            .world
+            .get::<&mut Equipment>(player_entity)
+            .map(|mut equipment| equipment.equip(decoded));
+        let (slot, previous) = match equipped {
+            Ok(Ok(result)) => result,
+            _ => {
+                Self::store_in_inventory(ecs_world, player_entity, item);
+                return false;
             }
+        };
 
-            // Publish door opened event
-            ecs_world.publish_event(GameEvent::DoorOpened {
-                entity: entity.id() as u32,
-                x: pos.x,
-                y: pos.y,
-                door_type: "木门".to_string(),
+        if let Some(previous) = previous {
+            let previous_name = previous.name();
+            Self::store_in_inventory(ecs_world, player_entity, ECSItem::new(previous));
+            ecs_world.publish_event(GameEvent::ItemUnequipped {
+                entity: player_id,
+                item_name: previous_name,
+                slot: slot.name().to_string(),
+            });
+        }
+        Self::refresh_derived_stats(&mut ecs_world.world, player_entity);
+        if let Some(kind) = learned_kind {
+            Self::learn_item_kind(ecs_world, &kind);
+        }
+
+        // 穿上后诅咒显露出来
+        let revealed_curse = ecs_world
+            .world
+            .get::<&mut Equipment>(player_entity)
+            .is_ok_and(|mut equipment| equipment.reveal_curse(slot));
+        if revealed_curse {
+            ecs_world.publish_event(GameEvent::LogMessage {
+                message: format!("{}紧紧地缠住了你，它被诅咒了！", item.name),
+                level: LogLevel::Warning,
             });
+        }
 
+        let lacks_strength = match (
+            ecs_world.world.get::<&Equipment>(player_entity),
+            ecs_world.world.get::<&PlayerProgress>(player_entity),
+        ) {
+            (Ok(equipment), Ok(progress)) => equipment.strength_deficits(progress.strength).contains(&slot),
+            _ => false,
+        };
+        if lacks_strength {
             ecs_world
                 .resources
                 .game_state
                 .message_log
-                .push("打开了门".to_string());
-
-            return true;
+                .push(format!("你的力量不足以驾驭{}，行动变得迟缓", item.name));
         }
 
-        false
+        ecs_world.publish_event(GameEvent::ItemEquipped {
+            entity: player_id,
+            item_name: item.name.clone(),
+            slot: slot.name().to_string(),
+        });
+        
+        true
     }
+    
+    /// Handle unequipping an item
+    ///
+    /// `slot_index` 为装备槽位索引，顺序见 [`EquipSlot::ALL`]。
+    fn handle_unequip_item(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize) -> bool {
+        use crate::event_bus::GameEvent;
 
-    /// Get terrain-based energy cost for movement at a position
-    pub fn get_terrain_energy_cost(world: &World, pos: &Position) -> u32 {
-        use crate::turn_system::energy_costs;
+        let Some(slot) = EquipSlot::from_index(slot_index) else {
+            ecs_world.resources.game_state.message_log.push("Invalid equipment slot.".to_string());
+            return false;
+        };
+        let occupied = ecs_world
+            .world
+            .get::<&Equipment>(player_entity)
+            .is_ok_and(|equipment| equipment.slot_name(slot).is_some());
+        if !occupied {
+            ecs_world.resources.game_state.message_log.push("该装备槽为空".to_string());
+            return false;
+        }
+        let cursed_name = ecs_world
+            .world
+            .get::<&Equipment>(player_entity)
+            .ok()
+            .filter(|equipment| equipment.is_cursed(slot))
+            .and_then(|equipment| equipment.slot_name(slot));
+        if let Some(cursed_name) = cursed_name {
+            ecs_world
+                .resources
+                .game_state
+                .message_log
+                .push(format!("{}被诅咒了，无法卸下", cursed_name));
+            return false;
+        }
+        let inventory_full = ecs_world
+            .world
+            .get::<&Inventory>(player_entity)
+            .is_ok_and(|inventory| inventory.main_slots_used() >= inventory.max_slots);
+        if inventory_full {
+            ecs_world.resources.game_state.message_log.push("Your inventory is full!".to_string());
+            return false;
+        }
 
-        // Find terrain at position
-        for (_, (tile_pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
-            if tile_pos.x == pos.x && tile_pos.y == pos.y && tile_pos.z == pos.z {
-                return energy_costs::terrain_movement_cost(&tile.terrain_type);
+        let removed = ecs_world
+            .world
+            .get::<&mut Equipment>(player_entity)
+            .ok()
+            .and_then(|mut equipment| equipment.unequip(slot));
+        let Some(removed) = removed else {
+            return false;
+        };
+        let removed_name = removed.name();
+        Self::store_in_inventory(ecs_world, player_entity, ECSItem::new(removed));
+        Self::refresh_derived_stats(&mut ecs_world.world, player_entity);
+
+        ecs_world.publish_event(GameEvent::ItemUnequipped {
+            entity: player_entity.id(),
+            item_name: removed_name,
+            slot: slot.name().to_string(),
+        });
+
+        true
+    }
+
+    /// 解除实体装备与背包物品上的诅咒，返回被净化的物品名称
+    fn remove_curses(ecs_world: &mut ECSWorld, entity: Entity) -> Vec<String> {
+        let mut purified = ecs_world
+            .world
+            .get::<&mut Equipment>(entity)
+            .map(|mut equipment| equipment.remove_curses())
+            .unwrap_or_default();
+
+        if let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(entity) {
+            for ecs_item in inventory.items.iter_mut().filter_map(|slot| slot.item.as_mut()) {
+                if ecs_item.remove_curse() {
+                    purified.push(ecs_item.name.clone());
+                }
             }
         }
 
-        // Default to normal cost if no tile found
-        energy_costs::FULL_ACTION
+        if !purified.is_empty() {
+            Self::refresh_derived_stats(&mut ecs_world.world, entity);
+        }
+        purified
     }
 
-    /// Handle environmental hazards (fire, gas, etc.) at a position
-    pub fn process_environmental_effects(ecs_world: &mut ECSWorld, _entity: Entity, pos: &Position) {
-        // Check for special terrain types that cause damage or effects
-        for (_, (tile_pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter() {
-            if tile_pos.x == pos.x && tile_pos.y == pos.y && tile_pos.z == pos.z {
-                match tile.terrain_type {
-                    TerrainType::Water => {
-                        // Water might cause slowdown (already handled by terrain cost)
-                        // Could add wet status effect here
-                    }
-                    TerrainType::Barrel => {
-                        // Barrels could explode or provide cover
-                        // Implementation depends on game design
-                    }
-                    _ => {}
+    /// 记住物品的种类；首次认出时刷新所有同类物品的名称
+    fn learn_item_kind(ecs_world: &mut ECSWorld, kind: &items::ItemKind) {
+        if ecs_world.resources.item_knowledge.learn(kind) {
+            Self::sync_item_knowledge(ecs_world);
+        }
+    }
+
+    /// 按本局的鉴定知识刷新所有背包与装备中物品的外观和名称
+    pub(crate) fn sync_item_knowledge(ecs_world: &mut ECSWorld) {
+        let knowledge = &ecs_world.resources.item_knowledge;
+        for (_, inventory) in ecs_world.world.query_mut::<&mut Inventory>() {
+            for ecs_item in inventory.items.iter_mut().filter_map(|slot| slot.item.as_mut()) {
+                ecs_item.apply_knowledge(knowledge);
+            }
+        }
+        for (_, equipment) in ecs_world.world.query_mut::<&mut Equipment>() {
+            for ring in equipment.rings.iter_mut() {
+                let Some(worn) = ring.take() else {
+                    continue;
+                };
+                let mut item = items::Item::new(items::ItemKind::Ring(worn));
+                knowledge.apply(&mut item);
+                if let items::ItemKind::Ring(worn) = item.kind {
+                    *ring = Some(worn);
                 }
             }
         }
     }
 
-    /// Generate a basic dungeon level
-    fn generate_level(&mut self, world: &mut World, resources: &mut Resources, level: i32) {
-        // Prefer using dungeon::Dungeon if present
-        if let Some(dungeon) = crate::ecs::get_dungeon_clone(world) {
-            // Remove all tiles for the level being generated
-            let tiles_to_remove: Vec<_> = world
-                .query::<(&Position, &Tile)>()
-                .iter()
-                .filter(|(_, (pos, _))| pos.z == level)
-                .map(|(e, _)| e)
-                .collect();
-            for entity in tiles_to_remove {
-                let _ = world.despawn(entity);
+    /// 合并所有背包中可以堆叠在一起的物品
+    fn merge_inventory_stacks(ecs_world: &mut ECSWorld) {
+        for (_, inventory) in ecs_world.world.query_mut::<&mut Inventory>() {
+            inventory.merge_stacks();
+        }
+    }
+
+    /// 将物品放入背包（优先并入已有堆叠），放不下的部分掉落在脚下
+    fn store_in_inventory(ecs_world: &mut ECSWorld, entity: Entity, mut item: ECSItem) {
+        item.apply_knowledge(&ecs_world.resources.item_knowledge);
+        let leftover = match ecs_world.world.get::<&mut Inventory>(entity) {
+            Ok(mut inventory) => inventory.add_item(item),
+            Err(_) => Some(item),
+        };
+
+        if let Some(item) = leftover {
+            if let Ok(pos) = ecs_world.world.get::<&Position>(entity).map(|p| (*p).clone()) {
+                ecs_world.resources.game_state.message_log.push("Your inventory is full!".to_string());
+                Self::spawn_ground_item(ecs_world, &pos, item);
             }
+        }
+    }
 
-            // Populate tiles from dungeon level data
-            let lvl = &dungeon.levels[dungeon.depth - 1];
-            for tile in &lvl.tiles {
-                let terrain = match &tile.info.terrain_type {
-                    dungeon::level::tiles::TerrainType::Floor => TerrainType::Floor,
-                    dungeon::level::tiles::TerrainType::Wall => TerrainType::Wall,
-                    dungeon::level::tiles::TerrainType::Door(_) => TerrainType::Door,
-                    dungeon::level::tiles::TerrainType::Stair(dir) => match dir {
-                        dungeon::level::tiles::StairDirection::Up => TerrainType::StairsUp,
-                        dungeon::level::tiles::StairDirection::Down => TerrainType::StairsDown,
-                    },
-                    dungeon::level::tiles::TerrainType::Water => TerrainType::Water,
-                    dungeon::level::tiles::TerrainType::Trap(_) => TerrainType::Trap,
-                    dungeon::level::tiles::TerrainType::Special => TerrainType::Empty,
-                    dungeon::level::tiles::TerrainType::Grass => TerrainType::Floor,
-                };
+    /// 根据基础属性与当前装备重新计算实体的派生属性
+    ///
+    /// 第一次调用时以当前属性作为基础属性记录下来。
+    pub(crate) fn refresh_derived_stats(world: &mut World, entity: Entity) {
+        if world.get::<&BaseStats>(entity).is_err() {
+            let Ok(base) = world.get::<&Stats>(entity).map(|stats| BaseStats::from_stats(&stats)) else {
+                return;
+            };
+            let _ = world.insert_one(entity, base);
+        }
 
-                world.spawn((
-                    Position::new(tile.x, tile.y, level),
-                    Tile {
-                        terrain_type: terrain.clone(),
-                        is_passable: tile.info.passable,
-                        blocks_sight: tile.info.blocks_sight,
-                        has_items: lvl.items.iter().any(|i| i.x == tile.x && i.y == tile.y),
-                        has_monster: lvl.enemies.iter().any(|e| e.x == tile.x && e.y == tile.y),
-                    },
-                    Renderable {
-                        symbol: match terrain {
-                            TerrainType::Floor => '.',
-                            TerrainType::Wall => '#',
-                            TerrainType::Door => '+',
-                            TerrainType::StairsDown => '>',
-                            TerrainType::Water => '~',
-                            TerrainType::Trap => '^',
-                            _ => ' ',
-                        },
-                        fg_color: Color::White,
-                        bg_color: Some(Color::Black),
-                        order: 0,
-                    },
-                ));
-            }
-
-            // Spawn enemies and items from level
-            for enemy in &lvl.enemies {
-                world.spawn((
-                    Position::new(enemy.x, enemy.y, level),
-                    Actor {
-                        name: enemy.name().to_string(),
-                        faction: Faction::Enemy,
-                    },
-                    Renderable {
-                        symbol: enemy.symbol,
-                        fg_color: Color::Green,
-                        bg_color: Some(Color::Black),
-                        order: 5,
-                    },
-                    Stats {
-                        hp: enemy.hp,
-                        max_hp: enemy.max_hp,
-                        attack: enemy.attack,
-                        defense: enemy.defense,
-                        accuracy: 70,
-                        evasion: 10,
-                        level: enemy.attack_range as u32,
-                        experience: enemy.exp_value,
-                        class: None,
-                    },
-                    Energy {
-                        current: 100,
-                        max: 100,
-                        regeneration_rate: 1,
-                    },
-                ));
-            }
-
-            for item in &lvl.items {
-                world.spawn((
-                    Position::new(item.x, item.y, level),
-                    Renderable {
-                        symbol: '!',
-                        fg_color: Color::Red,
-                        bg_color: Some(Color::Black),
-                        order: 1,
-                    },
-                    ECSItem {
-                        name: item.name.clone(),
-                        item_type: ItemType::Consumable {
-                            effect: ConsumableEffect::Healing { amount: 10 },
-                        },
-                        value: 5,
-                        identified: true,
-                        quantity: 1,
-                        level: 0,
-                        cursed: false,
-                        charges: None,
-                        detailed_data: None,
-                    },
-                    Tile {
-                        terrain_type: TerrainType::Empty,
-                        is_passable: true,
-                        blocks_sight: false,
-                        has_items: true,
-                        has_monster: false,
-                    },
-                ));
-            }
+        let bonus = Equipment::bonuses_of(world, entity);
+        let Ok(base) = world.get::<&BaseStats>(entity).map(|base| (*base).clone()) else {
             return;
+        };
+        if let Ok(mut stats) = world.get::<&mut Stats>(entity) {
+            stats.apply_equipment(&base, &bonus);
         }
+    }
+    
+    /// Handle throwing an item
+    fn handle_throw_item(ecs_world: &mut ECSWorld, player_entity: Entity, slot_index: usize, direction: Direction) -> bool {
+        let player_pos = match ecs_world.world.get::<&Position>(player_entity) {
+            Ok(pos) => (*pos).clone(),
+            Err(_) => return false,
+        };
+        let range = crate::targeting::purpose_range(
+            &ecs_world.world,
+            player_entity,
+            TargetingPurpose::Throw(slot_index),
+        );
+        let step = MovementSystem::calculate_target_position(&Position::new(0, 0, 0), direction);
+        let target = Position::new(
+            player_pos.x + step.x * range,
+            player_pos.y + step.y * range,
+            player_pos.z,
+        );
 
-        // Remove all tiles for the level being generated
-        let tiles_to_remove: Vec<_> = world
-            .query::<(&Position, &Tile)>()
-            .iter()
-            .filter(|(_, (pos, _))| pos.z == level)
-            .map(|(e, _)| e)
-            .collect();
+        Self::throw_item_at(ecs_world, player_entity, slot_index, &target)
+    }
 
-        for entity in tiles_to_remove {
-            let _ = world.despawn(entity);
+    /// 将背包中的物品投向目标位置
+    fn throw_item_at(ecs_world: &mut ECSWorld, thrower: Entity, slot_index: usize, target: &Position) -> bool {
+        use crate::event_bus::GameEvent;
+        use rand::Rng;
+
+        let thrower_pos = match ecs_world.world.get::<&Position>(thrower) {
+            Ok(pos) => (*pos).clone(),
+            Err(_) => return false,
+        };
+        if *target == thrower_pos {
+            ecs_world.resources.game_state.message_log.push("无法投向自己所在的位置".to_string());
+            return false;
         }
 
-        // Generate a basic 20x20 room layout for the level
-        for x in 5..25 {
-            for y in 5..25 {
-                let terrain_type = if x == 5 || x == 24 || y == 5 || y == 24 {
-                    TerrainType::Wall
-                } else {
-                    TerrainType::Floor
-                };
+        let range = crate::targeting::purpose_range(
+            &ecs_world.world,
+            thrower,
+            TargetingPurpose::Throw(slot_index),
+        );
+        // 灵能弓射出灵能箭，弓本身留在背包中
+        let is_bow = Self::inventory_item(ecs_world, thrower, slot_index).is_some_and(|item| {
+            matches!(&item.kind, items::ItemKind::Signature(s) if s.kind == items::SignatureKind::SpiritBow)
+        });
+        if is_bow {
+            Self::shoot_spirit_arrow(ecs_world, thrower, &thrower_pos, target, range);
+            return true;
+        }
+        let Some(thrown) = Self::take_one_from_slot(ecs_world, thrower, slot_index) else {
+            ecs_world.resources.game_state.message_log.push("No item in this slot.".to_string());
+            return false;
+        };
 
-                let renderable = Renderable {
-                    symbol: if x == 5 || x == 24 || y == 5 || y == 24 {
-                        '#'
-                    } else {
-                        '.'
-                    },
-                    fg_color: if x == 5 || x == 24 || y == 5 || y == 24 {
-                        Color::Gray
-                    } else {
-                        Color::White
-                    },
-                    bg_color: Some(Color::Black),
-                    order: 0,
-                };
+        let path = crate::targeting::trace_projectile(&ecs_world.world, &thrower_pos, target, range);
+        let (landing, victim) = (path.landing.clone(), path.hit);
+        ecs_world.publish_event(GameEvent::ItemThrown {
+            entity: thrower.id(),
+            item_name: thrown.name.clone(),
+            from_x: thrower_pos.x,
+            from_y: thrower_pos.y,
+            to_x: landing.x,
+            to_y: landing.y,
+        });
 
-                world.spawn((
-                    Position::new(x, y, level),
-                    Tile {
-                        terrain_type,
-                        is_passable: x != 5 && x != 24 && y != 5 && y != 24,
-                        blocks_sight: x == 5 || x == 24 || y == 5 || y == 24,
-                        has_items: false,
-                        has_monster: false,
-                    },
-                    renderable,
-                ));
+        match thrown.kind.clone() {
+            items::ItemKind::Throwable(throwable) => {
+                if let Some(victim) = victim {
+                    Self::resolve_ranged_hit(ecs_world, thrower, victim, &throwable);
+                }
+                if ecs_world.resources.rng.random_bool(throwable.break_chance()) {
+                    ecs_world
+                        .resources
+                        .game_state
+                        .message_log
+                        .push(format!("{}损坏了", thrown.name));
+                } else {
+                    Self::spawn_ground_item(ecs_world, &landing, thrown);
+                }
             }
+            items::ItemKind::Potion(potion) => {
+                Self::shatter_potion(ecs_world, thrower, &potion, &landing);
+            }
+            items::ItemKind::Stone(stone) if !stone.kind.targets_item() && !stone.is_depleted() => {
+                Self::activate_stone(ecs_world, thrower, &stone, &path);
+            }
+            _ => Self::spawn_ground_item(ecs_world, &landing, thrown),
         }
 
-        // Place stairs based on current level for connections
-        if level > 0 {
-            // Place stairs up (going down to the previous level)
-            world.spawn((
-                Position::new(9, 9, level),
-                Tile {
-                    terrain_type: TerrainType::StairsUp,
-                    is_passable: true,
-                    blocks_sight: false,
-                    has_items: false,
-                    has_monster: false,
-                },
-                Renderable {
-                    symbol: '<',
-                    fg_color: Color::Cyan,
-                    bg_color: Some(Color::Black),
-                    order: 1,
-                },
-            ));
-        }
-
-        // Place stairs down if not the deepest level
-        if level < (resources.config.max_depth as i32 - 1) {
-            world.spawn((
-                Position::new(15, 15, level),
-                Tile {
-                    terrain_type: TerrainType::StairsDown,
-                    is_passable: true,
-                    blocks_sight: false,
-                    has_items: false,
-                    has_monster: false,
-                },
-                Renderable {
-                    symbol: '>',
-                    fg_color: Color::Cyan,
-                    bg_color: Some(Color::Black),
-                    order: 1,
-                },
-            ));
-        }
+        true
+    }
 
-        // Add some simple monsters and items to the level
-        if level > 0 {
-            // Add content to levels other than 0
-            // Add a simple enemy
-            let enemy_pos = Position::new(12, 12, level);
-            world.spawn((
-                enemy_pos,
-                Actor {
-                    name: format!("Goblin {}", level),
-                    faction: Faction::Enemy,
-                },
-                Renderable {
-                    symbol: 'g',
-                    fg_color: Color::Green,
-                    bg_color: Some(Color::Black),
-                    order: 5,
-                },
-                Stats {
-                    hp: 30,
-                    max_hp: 30,
-                    attack: 5 + (level as u32 * 2),
-                    defense: 2 + (level as u32),
-                    accuracy: 70,
-                    evasion: 10,
-                    level: level as u32,
-                    experience: 10 + (level as u32 * 5),
-                    class: None,
-                },
-                Energy {
-                    current: 100,
-                    max: 100,
-                    regeneration_rate: 1,
-                },
-            ));
+    /// 灵能弓射出一支随等级增强的灵能箭，灵能箭命中或落地后即消散
+    fn shoot_spirit_arrow(ecs_world: &mut ECSWorld, shooter: Entity, from: &Position, target: &Position, range: i32) {
+        use crate::event_bus::GameEvent;
 
-            // Add a healing potion
-            world.spawn((
-                Position::new(14, 10, level),
-                Renderable {
-                    symbol: '!',
-                    fg_color: Color::Red,
-                    bg_color: Some(Color::Black),
-                    order: 1,
-                },
-                ECSItem {
-                    name: "Health Potion".to_string(),
-                    item_type: ItemType::Consumable {
-                        effect: ConsumableEffect::Healing { amount: 20 },
-                    },
-                    value: 10,
-                    identified: true,
-                    quantity: 1,
-                    level: 0,
-                    cursed: false,
-                    charges: None,
-                    detailed_data: None,
-                },
-                Tile {
-                    terrain_type: TerrainType::Empty,
-                    is_passable: true,
-                    blocks_sight: false,
-                    has_items: true,
-                    has_monster: false,
-                },
-            ));
+        let level = ecs_world.world.get::<&Stats>(shooter).map(|s| s.level).unwrap_or(1);
+        let arrow = hero::class::huntress::spirit_arrow(level);
+        let path = crate::targeting::trace_projectile(&ecs_world.world, from, target, range);
+        ecs_world.publish_event(GameEvent::ItemThrown {
+            entity: shooter.id(),
+            item_name: "灵能箭".to_string(),
+            from_x: from.x,
+            from_y: from.y,
+            to_x: path.landing.x,
+            to_y: path.landing.y,
+        });
+        if let Some(victim) = path.hit {
+            Self::resolve_ranged_hit(ecs_world, shooter, victim, &arrow);
         }
     }
-}
 
-pub struct InteractionSystem;
+    /// 向目标位置挥动法杖，消耗一次充能并按法杖种类结算效果
+    fn handle_zap_wand(ecs_world: &mut ECSWorld, zapper: Entity, slot_index: usize, target: &Position) -> bool {
+        use crate::event_bus::GameEvent;
+        use crate::targeting::{BOLT_RANGE, CONE_RANGE};
+        use items::wand::WandKind;
 
-impl InteractionSystem {
-    pub fn handle_interactions(world: &mut World) {
-        for (_, (pos, actor)) in world.query::<(&Position, &Actor)>().iter() {
-            if actor.faction == crate::ecs::Faction::Player {
-                let _ = (pos.x, pos.y);
-            }
+        let zapper_pos = match ecs_world.world.get::<&Position>(zapper) {
+            Ok(pos) => (*pos).clone(),
+            Err(_) => return false,
+        };
+        if *target == zapper_pos {
+            ecs_world.resources.game_state.message_log.push("无法对自己所在的位置使用法杖".to_string());
+            return false;
         }
-    }
-}
 
-impl System for InteractionSystem {
-    fn name(&self) -> &str {
-        "InteractionSystem"
-    }
-
-    fn run(&mut self, world: &mut World, _resources: &mut Resources) -> SystemResult {
-        Self::handle_interactions(world);
-        SystemResult::Continue
-    }
-}
-
-// ========== 新增：HungerSystem（饱食度系统）==========
-
-/// 饥饿系统：处理玩家的饱食度变化
-/// 
-/// 饥饿衰减规则：
-/// - 标准动作（移动、攻击、使用物品等）：每10次动作减少1点饱食度
-/// - 等待动作：每20次减少1点饱食度（消耗更少）
-/// - 饱食度为0时：每回合造成1点饥饿伤害
-///
-/// 阈值：
-/// - 饱食度 <= 2：饥饿警告（发送PlayerHungry事件）
-/// - 饱食度 = 0：挨饿状态（发送PlayerStarving事件并造成伤害）
-pub struct HungerSystem;
-
-impl HungerSystem {
-    /// 标准动作的饥饿阈值（每10次动作减少1点饱食度）
-    const HUNGER_DECAY_INTERVAL: u32 = 10;
-    
-    /// 等待动作的饥饿阈值（每20次等待减少1点饱食度）
-    const HUNGER_DECAY_INTERVAL_WAIT: u32 = 20;
-    
-    /// 饥饿伤害（每回合）
-    const STARVATION_DAMAGE: u32 = 1;
-    
-    /// 带事件总线的运行方法
-    pub fn run_with_events(ecs_world: &mut ECSWorld) -> SystemResult {
-        use crate::event_bus::GameEvent;
-
-        // 获取当前总回合数
-        let current_turn = ecs_world.resources.clock.turn_count;
-
-        // 检查是否有已完成的动作（用于判断是否触发饥饿衰减）
-        let has_completed_actions = !ecs_world.resources.input_buffer.completed_actions.is_empty();
-        let is_wait_action = ecs_world.resources.input_buffer.completed_actions
-            .iter()
-            .any(|action| matches!(action, PlayerAction::Wait));
-
-        // 收集需要处理的实体信息（避免借用冲突）
-        let mut entities_to_process = Vec::new();
-
-        for (entity, (hunger, stats)) in ecs_world.world.query::<(&Hunger, &Stats)>().iter() {
-            let is_player = ecs_world.world.get::<&Player>(entity).is_ok();
-            entities_to_process.push((entity, hunger.clone(), stats.clone(), is_player));
+        let Some(mut wand) = crate::targeting::wand_in_slot(&ecs_world.world, zapper, slot_index) else {
+            ecs_world.resources.game_state.message_log.push("这不是一根法杖".to_string());
+            return false;
+        };
+        if !wand.use_wand() {
+            ecs_world.resources.game_state.message_log.push(format!("{}没有充能了", wand.name()));
+            return false;
         }
-
-        // 处理每个实体
-        for (entity, mut hunger, mut stats, is_player) in entities_to_process {
-            // 饥饿衰减逻辑：只在玩家执行了动作时才触发
-            if has_completed_actions && is_player {
-                // 累加回合数
-                hunger.turn_accumulator += 1;
-                
-                // 根据动作类型选择不同的衰减间隔
-                let decay_interval = if is_wait_action {
-                    Self::HUNGER_DECAY_INTERVAL_WAIT
-                } else {
-                    Self::HUNGER_DECAY_INTERVAL
-                };
-                
-                // 检查是否达到饥饿衰减阈值
-                if hunger.turn_accumulator >= decay_interval {
-                    let old_satiety = hunger.satiety;
-                    hunger.satiety = hunger.satiety.saturating_sub(1);
-                    hunger.turn_accumulator = 0; // 重置累加器
-                    hunger.last_hunger_turn = current_turn;
-
-                    // 发布饥饿度变化事件
-                    ecs_world.publish_event(GameEvent::HungerChanged {
-                        entity: entity.id() as u32,
-                        old_satiety,
-                        new_satiety: hunger.satiety,
-                    });
-                    
-                    // 检查饥饿状态并发送警告事件
-                    if hunger.is_hungry() && !hunger.is_starving() {
-                        ecs_world.publish_event(GameEvent::PlayerHungry {
-                            entity: entity.id() as u32,
-                            satiety: hunger.satiety,
-                        });
+        Self::store_wand_in_slot(ecs_world, zapper, slot_index, &wand);
+
+        let damage = wand.base_damage();
+        let bolt = crate::targeting::trace_projectile(&ecs_world.world, &zapper_pos, target, BOLT_RANGE);
+        let effect = match wand.kind {
+            WandKind::MagicMissile => {
+                if let Some(victim) = bolt.hit {
+                    CombatSystem::deal_direct_damage(ecs_world, zapper, victim, damage);
+                }
+                "魔法飞弹"
+            }
+            WandKind::Frost => {
+                if let Some(victim) = bolt.hit {
+                    CombatSystem::deal_direct_damage(ecs_world, zapper, victim, damage);
+                    Self::apply_wand_status(ecs_world, victim, EffectType::Slow, 3 + wand.level as u32, &wand);
+                }
+                "寒冰射线"
+            }
+            WandKind::Fireblast => {
+                let cone = crate::targeting::cone_tiles(&ecs_world.world, &zapper_pos, target, CONE_RANGE);
+                for victim in Self::creatures_at(ecs_world, &cone, zapper) {
+                    CombatSystem::deal_direct_damage(ecs_world, zapper, victim, damage);
+                    Self::apply_wand_status(ecs_world, victim, EffectType::Burning, 4, &wand);
+                }
+                "火焰冲击"
+            }
+            WandKind::Lightning => {
+                if let Some(victim) = bolt.hit {
+                    for struck in Self::lightning_chain(ecs_world, zapper, victim, 2 + wand.level as usize) {
+                        CombatSystem::deal_direct_damage(ecs_world, zapper, struck, damage);
                     }
                 }
+                "连锁闪电"
             }
-
-            // 饥饿伤害处理（在aftermath阶段，每回合检查）
-            if hunger.is_starving() {
-                // 发布挨饿事件
-                ecs_world.publish_event(GameEvent::PlayerStarving {
-                    entity: entity.id() as u32,
-                });
-
-                // 饥饿致死：每回合掉1血
-                let damage = Self::STARVATION_DAMAGE;
-                stats.hp = stats.hp.saturating_sub(damage);
-
-                // 发布饥饿伤害事件
-                ecs_world.publish_event(GameEvent::StarvationDamage {
-                    entity: entity.id() as u32,
-                    damage,
-                });
-
-                // 检查玩家是否死亡
-                if stats.hp == 0 && is_player {
-                    // 使用专门的饿死游戏结束原因
-                    ecs_world.resources.game_state.game_state = GameStatus::GameOver {
-                        reason: GameOverReason::Starved,
-                    };
-                    
-                    ecs_world.publish_event(GameEvent::GameOver {
-                        reason: "你死于饥饿".to_string(),
-                    });
-
-                    // 将死亡加入aftermath队列
-                    ecs_world.resources.aftermath_queue.push(AftermathEvent::Death {
-                        entity,
-                        entity_id: entity.id() as u32,
-                        entity_name: "玩家".to_string(),
-                        killer: None,
-                    });
-
-                    // 更新组件
-                    let _ = ecs_world.world.insert(entity, (hunger, stats));
-                    return SystemResult::Stop;
+            WandKind::Corruption => {
+                if let Some(victim) = bolt.hit {
+                    Self::corrupt(ecs_world, victim, &wand);
+                }
+                "腐化"
+            }
+            WandKind::LivingEarth => {
+                if let Some(victim) = bolt.hit {
+                    CombatSystem::deal_direct_damage(ecs_world, zapper, victim, damage);
+                }
+                Self::summon_earth_guardian(ecs_world, zapper, &bolt, &wand);
+                "活体大地"
+            }
+            WandKind::Regrowth => {
+                let mut area = bolt.tiles.clone();
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        area.push(Position::new(bolt.landing.x + dx, bolt.landing.y + dy, bolt.landing.z));
+                    }
+                }
+                Self::grow_grass(ecs_world, &area);
+                "再生"
+            }
+            WandKind::Disintegration => {
+                // 瓦解光束穿透生物，每级额外延伸一格
+                let beam = crate::targeting::trace_beam(
+                    &ecs_world.world,
+                    &zapper_pos,
+                    target,
+                    BOLT_RANGE + wand.level as i32,
+                );
+                for victim in Self::creatures_at(ecs_world, &beam, zapper) {
+                    CombatSystem::deal_direct_damage(ecs_world, zapper, victim, damage);
                 }
+                "瓦解光束"
             }
+        };
 
-            // 更新组件
-            let _ = ecs_world.world.insert(entity, (hunger, stats));
-        }
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: zapper.id(),
+            item_name: wand.name(),
+            effect: effect.to_string(),
+        });
 
-        SystemResult::Continue
+        true
     }
-}
 
-impl System for HungerSystem {
-    fn name(&self) -> &str {
-        "HungerSystem"
+    /// 法杖附加的状态效果，诅咒法杖的持续时间减半
+    fn apply_wand_status(
+        ecs_world: &mut ECSWorld,
+        victim: Entity,
+        effect_type: EffectType,
+        duration: u32,
+        wand: &items::Wand,
+    ) {
+        let duration = if wand.cursed { (duration / 2).max(1) } else { duration };
+        CombatSystem::apply_status_effect(
+            ecs_world,
+            victim,
+            ::combat::effect::Effect::with_source(effect_type, duration, &wand.name()),
+        );
     }
 
-    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
-        // 获取当前总回合数
-        let current_turn = resources.clock.turn_count;
+    /// 指定格子上的生物（不含施法者本身）
+    fn creatures_at(ecs_world: &ECSWorld, tiles: &[Position], exclude: Entity) -> Vec<Entity> {
+        tiles
+            .iter()
+            .filter_map(|pos| MovementSystem::find_entity_at_position(&ecs_world.world, pos))
+            .filter(|entity| *entity != exclude)
+            .collect()
+    }
 
-        // 处理所有拥有 Hunger 组件的实体（主要是玩家）
-        for (entity, (hunger, stats)) in world.query::<(&mut Hunger, &mut Stats)>().iter() {
-            // 每20回合减少1点饱食度
-            if current_turn > 0 && (current_turn - hunger.last_hunger_turn) >= 20 {
-                hunger.satiety = hunger.satiety.saturating_sub(1);
-                hunger.last_hunger_turn = current_turn;
+    /// 闪电从首个目标跳跃到相邻的生物，最多跳跃 `max_jumps` 次
+    fn lightning_chain(ecs_world: &ECSWorld, zapper: Entity, first: Entity, max_jumps: usize) -> Vec<Entity> {
+        let mut struck = vec![first];
+        let mut frontier = first;
 
-                // 饥饿状态处理
-                if hunger.is_starving() {
-                    // 饥饿致死：每回合掉1血
-                    stats.hp = stats.hp.saturating_sub(1);
-                    resources
-                        .game_state
-                        .message_log
-                        .push("你正在饿死！".to_string());
+        for _ in 0..max_jumps {
+            let Ok(from) = ecs_world.world.get::<&Position>(frontier).map(|p| (*p).clone()) else {
+                break;
+            };
+            let next = ecs_world
+                .world
+                .query::<(&Position, &Actor, &Stats)>()
+                .iter()
+                .filter(|(entity, (pos, _, stats))| {
+                    *entity != zapper
+                        && stats.hp > 0
+                        && !struck.contains(entity)
+                        && pos.z == from.z
+                        && (pos.x - from.x).abs().max((pos.y - from.y).abs()) == 1
+                })
+                .map(|(entity, _)| entity)
+                .next();
 
-                    // 检查玩家是否死亡
-                    if stats.hp == 0 && world.get::<&Player>(entity).is_ok() {
-                        resources.game_state.game_state = GameStatus::GameOver {
-                            reason: GameOverReason::Died("死亡"),
-                        };
-                        resources
-                            .game_state
-                            .message_log
-                            .push("你死于饥饿...".to_string());
-                        return SystemResult::Stop;
-                    }
-                } else if hunger.is_hungry() {
-                    // 饥饿警告状态
-                    if current_turn % 40 == 0 {
-                        // 每40回合提示一次
-                        resources
-                            .game_state
-                            .message_log
-                            .push("你感到饥饿...".to_string());
-                    }
+            match next {
+                Some(entity) => {
+                    struck.push(entity);
+                    frontier = entity;
                 }
+                None => break,
             }
         }
 
-        SystemResult::Continue
+        struck
     }
-}
-
-/// 渲染系统
-///
-/// 负责协调所有渲染组件，但由于实际渲染由RatatuiRenderer处理，
-/// 这个系统主要用于标记渲染状态和清理渲染缓存。
-pub struct RenderingSystem;
 
-impl System for RenderingSystem {
-    fn name(&self) -> &str {
-        "RenderingSystem"
-    }
+    /// 腐化敌人使其成为玩家的仆从；目标越虚弱越容易成功，Boss 免疫
+    fn corrupt(ecs_world: &mut ECSWorld, victim: Entity, wand: &items::Wand) {
+        use rand::Rng;
 
-    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
-        // 标记视锥为dirty（如果需要重新计算FOV）
-        for (_, viewshed) in world.query::<&mut Viewshed>().iter() {
-            if viewshed.dirty {
-                // FOVSystem会处理实际的视锥计算
-                // 这里只是确保dirty状态被记录
-            }
+        let Ok((name, is_enemy)) = ecs_world
+            .world
+            .get::<&Actor>(victim)
+            .map(|a| (a.name.clone(), matches!(a.faction, Faction::Enemy)))
+        else {
+            return;
+        };
+        if !is_enemy || ecs_world.world.get::<&crate::ecs::BossComponent>(victim).is_ok() {
+            ecs_world.resources.game_state.message_log.push(format!("{}不受腐化影响", name));
+            return;
         }
 
-        // 清理过期的渲染缓存（如果实现的话）
-        // 这里可以添加渲染缓存清理逻辑
+        let missing_hp = ecs_world
+            .world
+            .get::<&Stats>(victim)
+            .map(|s| 1.0 - s.hp as f64 / s.max_hp.max(1) as f64)
+            .unwrap_or(0.0);
+        let mut chance = (0.3 + 0.1 * wand.level as f64 + missing_hp).min(1.0);
+        if wand.cursed {
+            chance /= 2.0;
+        }
 
-        // 更新渲染相关的资源状态
-        resources.game_state.frame_count = resources.game_state.frame_count.wrapping_add(1);
+        if !ecs_world.resources.rng.random_bool(chance) {
+            ecs_world.resources.game_state.message_log.push(format!("{}抵抗了腐化", name));
+            return;
+        }
 
-        SystemResult::Continue
+        if let Ok(mut actor) = ecs_world.world.get::<&mut Actor>(victim) {
+            actor.faction = Faction::Player;
+        }
+        if let Ok(mut ai) = ecs_world.world.get::<&mut AI>(victim) {
+            ai.target = None;
+            ai.state = AIState::Idle;
+        }
+        ecs_world.resources.game_state.message_log.push(format!("{}被腐化，成为了你的仆从", name));
     }
-}
 
-/// 菜单系统
-///
-/// 处理所有菜单相关的动作，包括菜单导航、状态切换等。
-pub struct MenuSystem;
+    /// 召唤或强化活体大地守卫
+    fn summon_earth_guardian(
+        ecs_world: &mut ECSWorld,
+        zapper: Entity,
+        bolt: &crate::targeting::ProjectilePath,
+        wand: &items::Wand,
+    ) {
+        let existing = ecs_world
+            .world
+            .query::<(&crate::ecs::EarthGuardian, &Stats)>()
+            .iter()
+            .map(|(entity, _)| entity)
+            .next();
 
-impl System for MenuSystem {
-    fn name(&self) -> &str {
-        "MenuSystem"
-    }
+        if let Some(guardian) = existing {
+            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(guardian) {
+                stats.max_hp += 5;
+                stats.hp = stats.max_hp;
+                stats.defense += 1;
+            }
+            ecs_world.resources.game_state.message_log.push("大地守卫变得更加坚固".to_string());
+            return;
+        }
 
-    fn run(&mut self, _world: &mut World, resources: &mut Resources) -> SystemResult {
-        // 收集需要处理的菜单动作，避免借用冲突
-        let menu_actions: Vec<PlayerAction> = resources
-            .input_buffer
-            .completed_actions
-            .iter()
-            .filter(|action| {
-                matches!(
-                    action,
-                    PlayerAction::OpenInventory
-                        | PlayerAction::OpenOptions
-                        | PlayerAction::OpenHelp
-                        | PlayerAction::OpenCharacterInfo
-                        | PlayerAction::CloseMenu
-                        | PlayerAction::MenuNavigate(_)
-                        | PlayerAction::MenuSelect
-                        | PlayerAction::MenuBack
-                )
-            })
-            .cloned()
-            .collect();
+        // 守卫出现在弹道上离落点最近的空地；没有空地时出现在施法者身边
+        let Ok(origin) = ecs_world.world.get::<&Position>(zapper).map(|p| (*p).clone()) else {
+            return;
+        };
+        let is_free = |pos: &Position| {
+            MovementSystem::is_tile_passable(&ecs_world.world, pos)
+                && MovementSystem::find_entity_at_position(&ecs_world.world, pos).is_none()
+        };
+        let spot = bolt.tiles.iter().rev().find(|pos| is_free(pos)).cloned().or_else(|| {
+            (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .map(|(dx, dy)| Position::new(origin.x + dx, origin.y + dy, origin.z))
+                .find(|pos| is_free(pos))
+        });
+        let Some(spot) = spot else {
+            ecs_world.resources.game_state.message_log.push("周围没有空间召唤大地守卫".to_string());
+            return;
+        };
 
-        // 处理收集到的菜单动作
-        for action in menu_actions {
-            match action {
-                PlayerAction::OpenInventory => {
-                    resources.game_state.game_state = GameStatus::Inventory { selected_item: 0 };
-                }
+        let level = wand.level as u32;
+        ecs_world.world.spawn((
+            spot,
+            Actor {
+                name: "大地守卫".to_string(),
+                faction: Faction::Player,
+            },
+            Renderable {
+                symbol: 'G',
+                fg_color: Color::Rgb(160, 110, 60),
+                bg_color: Some(Color::Black),
+                order: 5,
+            },
+            Stats {
+                hp: 15 + level * 5,
+                max_hp: 15 + level * 5,
+                attack: 3 + level,
+                defense: 4 + level,
+                accuracy: 70,
+                evasion: 5,
+                level: 1,
+                experience: 0,
+                class: None,
+            },
+            Energy {
+                current: 100,
+                max: 100,
+                regeneration_rate: 1,
+            },
+            AI {
+                ai_type: AIType::Aggressive,
+                target: None,
+                state: AIState::Idle,
+            },
+            crate::ecs::EarthGuardian { level: wand.level },
+        ));
+        ecs_world.resources.game_state.message_log.push("大地守卫从地面升起".to_string());
+    }
 
-                PlayerAction::OpenOptions => {
-                    resources.game_state.game_state = GameStatus::Options { selected_option: 0 };
-                }
+    /// 让区域内的地板长出草丛
+    fn grow_grass(ecs_world: &mut ECSWorld, area: &[Position]) {
+        for (_, (pos, tile, renderable)) in ecs_world
+            .world
+            .query::<(&Position, &mut Tile, Option<&mut Renderable>)>()
+            .iter()
+        {
+            let in_area = area.iter().any(|p| p.x == pos.x && p.y == pos.y && p.z == pos.z);
+            if !in_area || !matches!(tile.terrain_type, TerrainType::Floor) {
+                continue;
+            }
+            tile.terrain_type = TerrainType::Grass;
+            if let Some(renderable) = renderable {
+                renderable.symbol = '"';
+                renderable.fg_color = Color::Green;
+            }
+        }
+        ecs_world.resources.game_state.message_log.push("地面上长出了草丛".to_string());
+    }
 
-                PlayerAction::OpenHelp => {
-                    resources.game_state.game_state = GameStatus::Help;
-                }
+    /// 将更新后的法杖（充能等）写回背包格子；法师之杖写回其吸收的法杖
+    fn store_wand_in_slot(ecs_world: &mut ECSWorld, entity: Entity, slot_index: usize, wand: &items::Wand) {
+        let Ok(mut inventory) = ecs_world.world.get::<&mut Inventory>(entity) else {
+            return;
+        };
+        let Some(item) = inventory.items.get_mut(slot_index).and_then(|slot| slot.item.as_mut()) else {
+            return;
+        };
+        match &mut item.kind {
+            items::ItemKind::Signature(staff) => staff.absorbed = Some(wand.clone()),
+            kind => *kind = items::ItemKind::Wand(wand.clone()),
+        }
+    }
 
-                PlayerAction::OpenCharacterInfo => {
-                    resources.game_state.game_state = GameStatus::CharacterInfo;
-                }
+    /// 从背包格子中取出一个物品（堆叠时只减少数量）
+    fn take_one_from_slot(ecs_world: &mut ECSWorld, entity: Entity, slot_index: usize) -> Option<ECSItem> {
+        let mut inventory = ecs_world.world.get::<&mut Inventory>(entity).ok()?;
+        let slot = inventory.items.get_mut(slot_index)?;
+        let item = slot.item.as_mut()?;
 
-                PlayerAction::CloseMenu => {
-                    match resources.game_state.game_state {
-                        GameStatus::ConfirmQuit { return_to, .. } => {
-                            // 在确认退出对话框中按 Esc/Backspace 返回到原状态
-                            resources.game_state.game_state = match return_to {
-                                crate::ecs::ReturnTo::Running => GameStatus::Running,
-                                crate::ecs::ReturnTo::MainMenu => {
-                                    GameStatus::MainMenu { selected_option: 0 }
-                                }
-                            };
-                        }
-                        GameStatus::MainMenu { .. } => {
-                            // 在主菜单按下 Esc 不再退出，避免误触直接退出
-                            // 保持在主菜单，等待明确的退出动作（如 'q'）
-                        }
-                        GameStatus::Paused { .. } => {
-                            // 在暂停菜单按 Esc 返回游戏
-                            resources.game_state.game_state = GameStatus::Running;
-                        }
-                        GameStatus::Running => {
-                            // 游戏中按 Esc 打开暂停菜单
-                            resources.game_state.game_state =
-                                GameStatus::Paused { selected_option: 0 };
-                        }
-                        _ => {
-                            // 在其他菜单状态，返回游戏或上一级菜单
-                            resources.game_state.game_state = GameStatus::Running;
-                        }
-                    }
-                }
+        if item.quantity <= 1 {
+            return inventory.items.remove(slot_index).item;
+        }
 
-                PlayerAction::Quit => {
-                    // 触发确认退出对话框
-                    let return_to = match resources.game_state.game_state {
-                        GameStatus::MainMenu { .. } => crate::ecs::ReturnTo::MainMenu,
-                        _ => crate::ecs::ReturnTo::Running,
-                    };
-                    resources.game_state.game_state = GameStatus::ConfirmQuit {
-                        return_to,
-                        selected_option: 1, // 默认选中“否”
-                    };
-                }
+        item.quantity -= 1;
+        slot.quantity = slot.quantity.saturating_sub(1).max(1);
+        let remaining = item.quantity;
+        if let items::ItemKind::Throwable(t) = &mut item.kind {
+            t.quantity = remaining;
+        }
 
-                PlayerAction::MenuNavigate(direction) => {
-                    self.handle_menu_navigation(resources, &direction);
-                }
+        let mut single = item.clone();
+        single.quantity = 1;
+        if let items::ItemKind::Throwable(t) = &mut single.kind {
+            t.quantity = 1;
+        }
 
-                PlayerAction::MenuSelect => {
-                    self.handle_menu_selection(resources);
-                }
+        Some(single)
+    }
 
-                PlayerAction::MenuBack => {
-                    self.handle_menu_back(resources);
-                }
+    /// 着火时烧毁一张卷轴、冻僵时冻裂一瓶药水；收纳在容器中的物品不受影响
+    fn damage_exposed_item(ecs_world: &mut ECSWorld, entity: Entity, effect: EffectType) {
+        use rand::Rng;
 
-                _ => {
-                    // 其他动作不会被传递到这里
-                }
+        let (affected, verb): (fn(&items::ItemKind) -> bool, &str) = match effect {
+            EffectType::Burning => (|kind| matches!(kind, items::ItemKind::Scroll(_)), "烧成了灰烬"),
+            EffectType::Frost => (|kind| matches!(kind, items::ItemKind::Potion(_)), "冻裂了"),
+            _ => return,
+        };
+        let exposed: Vec<(usize, String)> = match ecs_world.world.get::<&Inventory>(entity) {
+            Ok(inventory) => {
+                let containers = inventory.slot_containers();
+                inventory
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| containers[*index].is_none())
+                    .filter_map(|(index, slot)| {
+                        let item = slot.item.as_ref()?;
+                        affected(&item.kind).then(|| (index, item.name.clone()))
+                    })
+                    .collect()
             }
+            Err(_) => return,
+        };
+        if exposed.is_empty() {
+            return;
         }
 
-        SystemResult::Continue
+        let (slot, name) = exposed[ecs_world.resources.rng.random_range(0..exposed.len())].clone();
+        if Self::take_one_from_slot(ecs_world, entity, slot).is_some() {
+            ecs_world.resources.game_state.message_log.push(format!("{}{}", name, verb));
+        }
     }
-}
 
-impl MenuSystem {
-    /// 开始新游戏
-    pub fn start_new_game(resources: &mut Resources) {
-        resources.game_state.game_state = GameStatus::Running;
-        resources
-            .game_state
-            .message_log
-            .push("开始新游戏！".to_string());
+    /// 投掷武器命中目标，通过战斗模块结算一次远程攻击（不会触发反击）
+    fn resolve_ranged_hit(ecs_world: &mut ECSWorld, thrower: Entity, victim: Entity, throwable: &items::Throwable) {
+        use crate::event_bus::GameEvent;
+        use rand::Rng;
 
-        // TODO: 这里应该调用游戏世界的初始化
-        // 但由于架构限制，可能需要在游戏循环中处理
-    }
+        let (Some(mut thrower_stats), Some(mut victim_stats)) = (
+            ecs_world.world.get::<&Stats>(thrower).ok().map(|s| (*s).clone()),
+            ecs_world.world.get::<&Stats>(victim).ok().map(|s| (*s).clone()),
+        ) else {
+            return;
+        };
+        if victim_stats.hp == 0 {
+            return;
+        }
 
-    /// 处理菜单导航
-    fn handle_menu_navigation(&self, resources: &mut Resources, direction: &NavigateDirection) {
-        match resources.game_state.game_state {
-            GameStatus::MainMenu {
-                ref mut selected_option,
-            } => {
-                // 主菜单导航（5个选项：开始游戏、继续游戏、游戏设置、帮助说明、退出游戏）
-                match direction {
-                    NavigateDirection::Up => {
-                        *selected_option = selected_option.saturating_sub(1);
-                    }
-                    NavigateDirection::Down => {
-                        *selected_option = (*selected_option + 1).min(4);
-                    }
-                    _ => {}
-                }
-            }
+        // 投掷伤害取决于投掷物本身而非持有者的近战攻击力
+        let (min, max) = throwable.damage;
+        thrower_stats.attack = ecs_world.resources.rng.random_range(min..=max);
 
-            GameStatus::Paused {
-                ref mut selected_option,
-            } => {
-                // 暂停菜单导航（6个选项）
-                match direction {
-                    NavigateDirection::Up => {
-                        *selected_option = selected_option.saturating_sub(1);
-                    }
-                    NavigateDirection::Down => {
-                        *selected_option = (*selected_option + 1).min(5);
-                    }
-                    _ => {}
-                }
-            }
+        let result = {
+            let mut attacker = SimpleCombatant::new(&mut thrower_stats);
+            let mut defender = SimpleCombatant::new(&mut victim_stats);
+            ::combat::Combat::resolve_attack(&mut attacker, &mut defender, false)
+        };
 
-            GameStatus::Options {
-                ref mut selected_option,
-            } => {
-                // 选项菜单导航
-                match direction {
-                    NavigateDirection::Up => {
-                        *selected_option = selected_option.saturating_sub(1);
-                    }
-                    NavigateDirection::Down => {
-                        *selected_option = (*selected_option + 1).min(4); // 5个选项
-                    }
-                    _ => {}
-                }
+        let mut hit = false;
+        for ev in &result.events {
+            if let ::combat::CombatEvent::DamageDealt { damage, is_critical, .. } = ev {
+                hit = true;
+                ecs_world.publish_event(GameEvent::CombatHit {
+                    attacker: thrower.id(),
+                    defender: victim.id(),
+                    damage: *damage,
+                    is_critical: *is_critical,
+                    is_ambush: false,
+                });
+                ecs_world.publish_event(GameEvent::DamageDealt {
+                    attacker: thrower.id(),
+                    victim: victim.id(),
+                    damage: *damage,
+                    is_critical: *is_critical,
+                });
             }
+        }
+        if !hit {
+            ecs_world.publish_event(GameEvent::CombatMiss {
+                attacker: thrower.id(),
+                defender: victim.id(),
+            });
+        }
 
-            GameStatus::Inventory {
-                ref mut selected_item,
-            } => {
-                // 物品栏导航
-                match direction {
-                    NavigateDirection::Up => {
-                        *selected_item = selected_item.saturating_sub(1);
-                    }
-                    NavigateDirection::Down => {
-                        *selected_item = (*selected_item + 1).min(9); // 最多10格物品栏
-                    }
-                    _ => {}
-                }
-            }
+        if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(victim) {
+            stats.hp = victim_stats.hp;
+        }
+        if victim_stats.hp == 0 {
+            let victim_name = ecs_world
+                .world
+                .get::<&Actor>(victim)
+                .map(|a| a.name.clone())
+                .unwrap_or_else(|_| "Unknown".to_string());
+            ecs_world.publish_event(GameEvent::EntityDied {
+                entity: victim.id(),
+                entity_name: victim_name,
+            });
+            CombatSystem::queue_death(ecs_world, victim, Some(thrower));
+        }
+    }
 
-            GameStatus::ClassSelection { ref mut cursor } => {
-                // 职业选择导航（4个职业：战士、法师、盗贼、女猎手）
-                match direction {
-                    NavigateDirection::Up => {
-                        *cursor = cursor.saturating_sub(1);
-                    }
-                    NavigateDirection::Down => {
-                        *cursor = (*cursor + 1).min(3);
+    /// 药水落地碎裂，对落点周围一格内的生物产生效果
+    ///
+    /// 毒气、麻痹气体和液态火焰在落点留下会扩散的气体或火焰；冰霜药水冻结周围
+    /// 的水面与生物；治疗药水治疗溅到的生物；其余药水碎裂后无效。
+    fn shatter_potion(ecs_world: &mut ECSWorld, thrower: Entity, potion: &items::potion::Potion, at: &Position) {
+        use crate::event_bus::GameEvent;
+        use items::potion::PotionKind;
+
+        // 异变药水碎裂的范围更大
+        let radius = if potion.exotic { 2 } else { 1 };
+        let splashed = Self::creatures_near(&ecs_world.world, at, radius);
+
+        let blob = match potion.kind {
+            PotionKind::ToxicGas => Some((BlobKind::ToxicGas, radius, 10)),
+            PotionKind::ParalyticGas => Some((BlobKind::ParalyticGas, radius, 8)),
+            PotionKind::LiquidFlame => Some((BlobKind::Fire, radius, 4)),
+            _ => None,
+        };
+
+        let effect_description = match (&potion.kind, blob) {
+            (_, Some((kind, radius, volume))) => {
+                for dy in -radius..=radius {
+                    for dx in -radius..=radius {
+                        let pos = Position::new(at.x + dx, at.y + dy, at.z);
+                        // 中心浓度最高，向外扩散
+                        let volume = if dx == 0 && dy == 0 { volume } else { volume / 2 };
+                        BlobSystem::spawn_blob(&mut ecs_world.world, &pos, kind, volume);
                     }
-                    _ => {}
                 }
+                let (effect_type, turns) = kind.status();
+                for entity in &splashed {
+                    CombatSystem::apply_status_effect(
+                        ecs_world,
+                        *entity,
+                        ::combat::effect::Effect::with_source(effect_type, turns, &potion.name()),
+                    );
+                }
+                format!("药水碎裂，{}弥漫开来", kind.name())
             }
-
-            GameStatus::ConfirmQuit {
-                ref mut selected_option,
-                ..
-            } => {
-                // 确认退出对话框的导航：在 0(是)/1(否) 之间切换
-                match direction {
-                    NavigateDirection::Left | NavigateDirection::Up => {
-                        *selected_option = 0;
-                    }
-                    NavigateDirection::Right | NavigateDirection::Down => {
-                        *selected_option = 1;
+            (PotionKind::Frost, None) => {
+                let (frozen, chilled) = Self::freeze_area(ecs_world, at, radius + 1, &potion.name());
+                format!("寒气冻结了 {} 格水面和 {} 个生物", frozen, chilled)
+            }
+            (PotionKind::Healing, None) => {
+                for entity in &splashed {
+                    if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(*entity) {
+                        stats.hp = (stats.hp + stats.max_hp / 4).min(stats.max_hp);
                     }
-                    _ => {}
                 }
+                "治疗药水溅洒开来".to_string()
             }
+            _ => "药水碎裂，没有产生效果".to_string(),
+        };
 
-            _ => {}
-        }
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: thrower.id(),
+            item_name: potion.name(),
+            effect: effect_description,
+        });
     }
 
-    /// 处理菜单选择
-    fn handle_menu_selection(&self, resources: &mut Resources) {
-        match resources.game_state.game_state {
-            GameStatus::MainMenu { selected_option } => {
-                // 主菜单选择逻辑
-                match selected_option {
-                    0 => {
-                        // 开始新游戏 - 进入职业选择界面
-                        resources.game_state.game_state = GameStatus::ClassSelection { cursor: 0 };
-                    }
-                    1 => {
-                        // 继续游戏（TODO: 实现加载存档功能）
-                        resources
-                            .game_state
-                            .message_log
-                            .push("继续游戏功能暂未实现".to_string());
-                        MenuSystem::start_new_game(resources); // 临时：直接开始新游戏
-                    }
-                    2 => {
-                        // 游戏设置
-                        resources.game_state.game_state =
-                            GameStatus::Options { selected_option: 0 };
-                    }
-                    3 => {
-                        // 帮助说明
-                        resources.game_state.game_state = GameStatus::Help;
-                    }
-                    4 => {
-                        // 退出游戏
-                        resources.game_state.game_state = GameStatus::ConfirmQuit {
-                            return_to: crate::ecs::ReturnTo::MainMenu,
-                            selected_option: 1, // 默认选中"否"
-                        };
-                    }
-                    _ => {}
-                }
+    /// 指定范围内（切比雪夫距离）的所有生物
+    fn creatures_near(world: &World, center: &Position, radius: i32) -> Vec<Entity> {
+        world
+            .query::<(&Position, &Stats)>()
+            .iter()
+            .filter(|(_, (pos, _))| {
+                pos.z == center.z && (pos.x - center.x).abs() <= radius && (pos.y - center.y).abs() <= radius
+            })
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    /// 冻结范围内的水面、扑灭火焰并冰冻生物，返回（冻结的水面数, 冰冻的生物数）
+    fn freeze_area(ecs_world: &mut ECSWorld, center: &Position, radius: i32, source: &str) -> (usize, usize) {
+        let in_range = |pos: &Position| {
+            pos.z == center.z && (pos.x - center.x).abs() <= radius && (pos.y - center.y).abs() <= radius
+        };
+
+        let mut frozen = 0;
+        for (_, (pos, tile)) in ecs_world.world.query_mut::<(&Position, &mut Tile)>() {
+            if in_range(pos) && matches!(tile.terrain_type, TerrainType::Water) {
+                tile.terrain_type = TerrainType::Ice;
+                frozen += 1;
             }
+        }
 
-            GameStatus::Paused { selected_option } => {
-                // 暂停菜单选择逻辑
-                match selected_option {
-                    0 => {
-                        // 继续游戏
-                        resources.game_state.game_state = GameStatus::Running;
-                    }
-                    1 => {
-                        // 物品栏
-                        resources.game_state.game_state =
-                            GameStatus::Inventory { selected_item: 0 };
-                    }
-                    2 => {
-                        // 角色信息
-                        resources.game_state.game_state = GameStatus::CharacterInfo;
-                    }
-                    3 => {
-                        // 游戏设置
-                        resources.game_state.game_state =
-                            GameStatus::Options { selected_option: 0 };
-                    }
-                    4 => {
-                        // 帮助说明
-                        resources.game_state.game_state = GameStatus::Help;
-                    }
-                    5 => {
-                        // 保存并退出
-                        resources.game_state.game_state = GameStatus::ConfirmQuit {
-                            return_to: crate::ecs::ReturnTo::MainMenu,
-                            selected_option: 1,
-                        };
-                    }
-                    _ => {}
+        let fires: Vec<Entity> = ecs_world
+            .world
+            .query::<(&Position, &Blob)>()
+            .iter()
+            .filter(|(_, (pos, blob))| blob.kind == BlobKind::Fire && in_range(pos))
+            .map(|(entity, _)| entity)
+            .collect();
+        for fire in fires {
+            let _ = ecs_world.world.despawn(fire);
+        }
+
+        let chilled = Self::creatures_near(&ecs_world.world, center, radius);
+        for entity in &chilled {
+            CombatSystem::apply_status_effect(
+                ecs_world,
+                *entity,
+                ::combat::effect::Effect::with_source(EffectType::Frost, 5, source),
+            );
+        }
+
+        (frozen, chilled.len())
+    }
+
+    /// 饮用药水；有害药水会在饮用者脚下碎裂并波及饮用者
+    fn drink_potion(
+        ecs_world: &mut ECSWorld,
+        drinker: Entity,
+        slot_index: usize,
+        kind: items::potion::PotionKind,
+        item_name: &str,
+    ) -> bool {
+        use crate::event_bus::GameEvent;
+        use items::potion::PotionKind;
+
+        let Ok(pos) = ecs_world.world.get::<&Position>(drinker).map(|p| (*p).clone()) else {
+            return false;
+        };
+        // 异变药水（炼金产物）持续时间翻倍，效果更强
+        let exotic = matches!(
+            Self::inventory_item(ecs_world, drinker, slot_index).map(|item| item.kind),
+            Some(items::ItemKind::Potion(potion)) if potion.exotic
+        );
+        let duration = if exotic { 40 } else { 20 };
+        let timed = |effect_type, turns| ::combat::effect::Effect::with_source(effect_type, turns, item_name);
+
+        let effect = match kind {
+            PotionKind::Healing => {
+                if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(drinker) {
+                    stats.hp = stats.max_hp;
+                }
+                if let Ok(mut effects) = ecs_world.world.get::<&mut StatusEffects>(drinker) {
+                    effects.effects.retain(|effect| {
+                        !matches!(effect.effect_type(), EffectType::Poison | EffectType::Bleeding)
+                    });
                 }
+                "伤口完全愈合了".to_string()
             }
-
-            GameStatus::Options { selected_option } => {
-                // 选项菜单选择逻辑
-                match selected_option {
-                    0 => {
-                        // 切换音效
-                        resources
-                            .game_state
-                            .message_log
-                            .push("音效切换功能暂未实现".to_string());
-                    }
-                    1 => {
-                        // 切换音乐
-                        resources
-                            .game_state
-                            .message_log
-                            .push("音乐切换功能暂未实现".to_string());
-                    }
-                    2 => {
-                        // 按键绑定
-                        resources
-                            .game_state
-                            .message_log
-                            .push("按键绑定功能暂未实现".to_string());
-                    }
-                    3 => {
-                        // 显示模式
-                        resources
-                            .game_state
-                            .message_log
-                            .push("显示模式切换功能暂未实现".to_string());
-                    }
-                    4 => {
-                        // 语言
-                        resources
-                            .game_state
-                            .message_log
-                            .push("语言切换功能暂未实现".to_string());
+            PotionKind::Experience => {
+                let mut level = crate::ecs::level_up(&mut ecs_world.world, drinker);
+                if exotic {
+                    level = crate::ecs::level_up(&mut ecs_world.world, drinker).or(level);
+                }
+                match level {
+                    Some(level) => format!("你升到了 {} 级", level),
+                    None => return false,
+                }
+            }
+            PotionKind::Strength => {
+                let strength = match ecs_world.world.get::<&mut PlayerProgress>(drinker) {
+                    Ok(mut progress) => {
+                        progress.add_strength(1);
+                        progress.strength
                     }
-                    _ => {}
+                    Err(_) => return false,
+                };
+                Self::refresh_derived_stats(&mut ecs_world.world, drinker);
+                format!("力量提升到了 {}", strength)
+            }
+            PotionKind::Haste => {
+                CombatSystem::apply_status_effect(ecs_world, drinker, timed(EffectType::Haste, duration));
+                "你的动作变得飞快".to_string()
+            }
+            PotionKind::Invisibility => {
+                CombatSystem::apply_status_effect(ecs_world, drinker, timed(EffectType::Invisibility, duration));
+                "你的身体变得透明".to_string()
+            }
+            PotionKind::Levitation => {
+                CombatSystem::apply_status_effect(ecs_world, drinker, timed(EffectType::Levitation, duration));
+                "你漂浮了起来".to_string()
+            }
+            PotionKind::MindVision => {
+                CombatSystem::apply_status_effect(ecs_world, drinker, timed(EffectType::MindVision, duration));
+                "你感知到了楼层中的生物".to_string()
+            }
+            PotionKind::Purity => {
+                if let Ok(mut effects) = ecs_world.world.get::<&mut StatusEffects>(drinker) {
+                    effects.effects.retain(|effect| !Self::is_negative_effect(effect.effect_type()));
                 }
+                let cleared = BlobSystem::clear_blobs(&mut ecs_world.world, &pos, if exotic { 4 } else { 2 });
+                format!("周围的空气变得纯净（驱散了 {} 格气体）", cleared)
             }
-
-            GameStatus::Inventory { selected_item } => {
-                // 物品栏选择逻辑
-                resources.game_state.message_log.push(format!(
-                    "选择了物品 #{} (使用功能暂未实现)",
-                    selected_item + 1
-                ));
+            PotionKind::Frost | PotionKind::ToxicGas | PotionKind::ParalyticGas | PotionKind::LiquidFlame => {
+                let potion = if exotic { items::Potion::new_exotic(kind) } else { items::Potion::new_alchemy(kind) };
+                Self::shatter_potion(ecs_world, drinker, &potion, &pos);
+                format!("{}在你手中爆发了", kind.name())
             }
+        };
 
-            GameStatus::ClassSelection { cursor } => {
-                // 职业选择确认
-                let class = match cursor {
-                    0 => hero::class::Class::Warrior,
-                    1 => hero::class::Class::Mage,
-                    2 => hero::class::Class::Rogue,
-                    3 => hero::class::Class::Huntress,
-                    _ => hero::class::Class::Warrior,
-                };
+        Self::take_one_from_slot(ecs_world, drinker, slot_index);
+        ecs_world.resources.game_state.message_log.push(effect.clone());
+        ecs_world.publish_event(GameEvent::ItemUsed {
+            entity: drinker.id(),
+            item_name: item_name.to_string(),
+            effect,
+        });
 
-                // 存储选中的职业，用于后续初始化
-                resources.game_state.selected_class = Some(class.clone());
-                resources
-                    .game_state
-                    .message_log
-                    .push(format!("选择了职业：{}", class));
+        true
+    }
 
-                // 进入游戏状态（实际的初始化将在 game_loop 中处理）
-                MenuSystem::start_new_game(resources);
-            }
+    /// 在地面上生成物品实体；同一格已有可堆叠的同类物品时直接并入
+    fn spawn_ground_item(ecs_world: &mut ECSWorld, pos: &Position, item: ECSItem) {
+        use items::ItemTrait;
 
-            GameStatus::ConfirmQuit {
-                return_to,
-                selected_option,
-            } => {
-                // 确认退出：0=是，1=否
-                if selected_option == 0 {
-                    // 退出到 GameOver
-                    resources.game_state.game_state = GameStatus::GameOver {
-                        reason: GameOverReason::Quit,
-                    };
-                } else {
-                    // 返回原状态
-                    resources.game_state.game_state = match return_to {
-                        crate::ecs::ReturnTo::Running => GameStatus::Running,
-                        crate::ecs::ReturnTo::MainMenu => {
-                            GameStatus::MainMenu { selected_option: 0 }
-                        }
-                    };
-                }
+        for (_, (ground_pos, ground)) in ecs_world.world.query_mut::<(&Position, &mut ECSItem)>() {
+            if ground_pos != pos || !ground.stacks_with(&item) {
+                continue;
             }
+            let total = ground.quantity.saturating_add(item.quantity);
+            if total <= ground.max_stack() {
+                ground.set_quantity(total);
+                return;
+            }
+        }
 
-            _ => {}
+        ecs_world.world.spawn((
+            pos.clone(),
+            Renderable {
+                symbol: item.name.chars().next().unwrap_or('?'),
+                fg_color: Color::Yellow,
+                bg_color: Some(Color::Black),
+                order: 1,
+            },
+            item,
+            Tile {
+                terrain_type: TerrainType::Empty,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: true,
+                has_monster: false,
+            },
+        ));
+    }
+    
+    /// 某一格地面上的物品实体，按生成顺序排列（即物品堆中的顺序）
+    pub(crate) fn ground_items_at(world: &World, pos: &Position) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = world
+            .query::<(&Position, &ECSItem)>()
+            .iter()
+            .filter(|(_, (item_pos, _))| *item_pos == pos)
+            .map(|(entity, _)| entity)
+            .collect();
+        entities.sort_by_key(|entity| entity.id());
+        entities
+    }
+
+    /// 物品在物品堆列表与消息中的名称（带数量）
+    pub(crate) fn pile_entry_name(item: &ECSItem) -> String {
+        if item.quantity > 1 {
+            format!("{} x{}", item.name, item.quantity)
+        } else {
+            item.name.clone()
         }
     }
 
-    /// 处理菜单返回
-    fn handle_menu_back(&self, resources: &mut Resources) {
-        match resources.game_state.game_state {
-            GameStatus::Help | GameStatus::CharacterInfo => {
-                // 从帮助/角色信息返回游戏
-                resources.game_state.game_state = GameStatus::Running;
+    /// 拾起脚下的物品：只有一件时直接拾起，多件时打开拾取选择
+    fn handle_pick_up(ecs_world: &mut ECSWorld, player_entity: Entity) -> bool {
+        let Ok(pos) = ecs_world.world.get::<&Position>(player_entity).map(|p| (*p).clone()) else {
+            return false;
+        };
+        let pile = Self::ground_items_at(&ecs_world.world, &pos);
+        match pile.as_slice() {
+            [] => {
+                ecs_world.resources.game_state.message_log.push("这里没有可以拾起的物品".to_string());
+                false
             }
-
-            GameStatus::Options { .. } | GameStatus::Inventory { .. } => {
-                // 从选项/物品栏返回游戏
-                resources.game_state.game_state = GameStatus::Running;
+            [item_entity] => Self::pick_up_ground_item(ecs_world, player_entity, *item_entity),
+            _ => {
+                ecs_world.resources.game_state.game_state = GameStatus::PickupChooser { selected_item: 0 };
+                false
             }
+        }
+    }
 
-            GameStatus::ClassSelection { .. } => {
-                // 从职业选择返回主菜单
-                resources.game_state.game_state = GameStatus::MainMenu { selected_option: 0 };
-            }
+    /// 拾起脚下物品堆中的第 `index` 件物品
+    fn handle_pick_up_item(ecs_world: &mut ECSWorld, player_entity: Entity, index: usize) -> bool {
+        let Ok(pos) = ecs_world.world.get::<&Position>(player_entity).map(|p| (*p).clone()) else {
+            return false;
+        };
+        match Self::ground_items_at(&ecs_world.world, &pos).get(index) {
+            Some(&item_entity) => Self::pick_up_ground_item(ecs_world, player_entity, item_entity),
+            None => false,
+        }
+    }
 
-            _ => {}
+    /// 拾起脚下所有的金币堆（走到金币上时自动触发）
+    pub(crate) fn collect_gold_at(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) {
+        for item_entity in Self::ground_items_at(&ecs_world.world, pos) {
+            Self::collect_gold(ecs_world, entity, item_entity);
         }
     }
-}
 
-// ========== Boss 系统 ==========
+    /// 金币不占背包格子，直接计入财富；不是金币或拾取者没有财富时返回 false
+    fn collect_gold(ecs_world: &mut ECSWorld, entity: Entity, item_entity: Entity) -> bool {
+        use crate::event_bus::GameEvent;
 
-pub struct BossSystem;
+        let amount = match ecs_world.world.get::<&ECSItem>(item_entity) {
+            Ok(item) => match &item.kind {
+                items::ItemKind::Misc(misc) => match misc.kind {
+                    items::MiscKind::Gold(amount) => amount.saturating_mul(item.quantity),
+                    _ => return false,
+                },
+                _ => return false,
+            },
+            Err(_) => return false,
+        };
+        match ecs_world.world.get::<&mut Wealth>(entity) {
+            Ok(mut wealth) => wealth.add_gold(amount),
+            Err(_) => return false,
+        }
+        let _ = ecs_world.world.despawn(item_entity);
+        ecs_world.publish_event(GameEvent::GoldCollected {
+            entity: entity.id(),
+            amount,
+        });
+        true
+    }
 
-impl System for BossSystem {
+    /// 把地面上的物品放入背包（优先并入已有堆叠）
+    ///
+    /// 背包放不下的部分留在地上；一件也没拾起时返回 false。
+    fn pick_up_ground_item(ecs_world: &mut ECSWorld, player_entity: Entity, item_entity: Entity) -> bool {
+        use crate::event_bus::GameEvent;
+
+        if Self::collect_gold(ecs_world, player_entity, item_entity) {
+            return true;
+        }
+        let Ok(mut item) = ecs_world.world.get::<&ECSItem>(item_entity).map(|item| (*item).clone()) else {
+            return false;
+        };
+        // 拾起时按本局的外观显示物品
+        item.apply_knowledge(&ecs_world.resources.item_knowledge);
+        let item_name = item.name.clone();
+        let quantity = item.quantity;
+        let leftover = match ecs_world.world.get::<&mut Inventory>(player_entity) {
+            Ok(mut inventory) => inventory.add_item(item),
+            Err(_) => return false,
+        };
+
+        let picked_up = match leftover {
+            None => {
+                let _ = ecs_world.world.despawn(item_entity);
+                true
+            }
+            Some(rest) => {
+                ecs_world.resources.game_state.message_log.push("Your inventory is full!".to_string());
+                // 只拾起了一部分时，地上留下剩余的数量
+                let partial = rest.quantity < quantity;
+                if let Ok(mut ground) = ecs_world.world.get::<&mut ECSItem>(item_entity) {
+                    *ground = rest;
+                }
+                partial
+            }
+        };
+        if picked_up {
+            ecs_world.publish_event(GameEvent::ItemPickedUp {
+                entity: player_entity.id(),
+                item_name,
+            });
+        }
+        picked_up
+    }
+}
+
+pub struct DungeonSystem;
+
+impl System for DungeonSystem {
     fn name(&self) -> &str {
-        "BossSystem"
+        "DungeonSystem"
     }
 
     fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
-        use crate::ecs::{BossComponent, BossSkillComponent};
-
-        // 收集所有 Boss 实体及其信息
-        let boss_data: Vec<(
-            Entity,
-            combat::boss::BossType,
-            combat::boss::BossPhase,
-            u32,
-            u32,
-        )> = world
-            .query::<(&BossComponent, &Stats)>()
-            .iter()
-            .map(|(entity, (boss_comp, stats))| {
-                (
-                    entity,
-                    boss_comp.boss_type.clone(),
-                    boss_comp.current_phase.clone(),
-                    stats.hp,
-                    stats.max_hp,
-                )
-            })
-            .collect();
+        // Process pending player actions for dungeon navigation
+        let actions_to_process = std::mem::take(&mut resources.input_buffer.pending_actions);
+        let mut new_actions = Vec::new();
 
-        // 找到玩家位置
-        let player_pos = if let Some(player_entity) = find_player_entity(world) {
-            world
-                .get::<&Position>(player_entity)
-                .ok()
-                .map(|p| p.clone())
-        } else {
-            None
-        };
+        for action in actions_to_process {
+            match action {
+                PlayerAction::Descend => {
+                    if let Some(player_entity) = find_player_entity(world) {
+                        // Check if player is on stairs - get the position first
+                        let player_pos_opt = match world.get::<&Position>(player_entity) {
+                            Ok(pos) => Some(pos.clone()),
+                            Err(_) => None,
+                        };
 
-        // 处理每个 Boss
-        for (boss_entity, boss_type, current_phase, hp, max_hp) in boss_data {
-            // 检查阶段转换
-            let hp_percent = hp as f32 / max_hp as f32;
-            let new_phase = combat::boss::BossPhase::from_health_percent(hp_percent);
+                        if let Some(player_pos) = player_pos_opt {
+                            // Check if there's a stairs down tile at player's position
+                            let mut on_stairs_down = false;
+                            for (_, (pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
+                                if pos.x == player_pos.x
+                                    && pos.y == player_pos.y
+                                    && pos.z == player_pos.z
+                                {
+                                    if matches!(tile.terrain_type, TerrainType::StairsDown) {
+                                        on_stairs_down = true;
+                                        break;
+                                    }
+                                }
+                            }
 
-            if new_phase != current_phase {
-                // 更新阶段
-                if let Ok(mut boss_comp) = world.get::<&mut BossComponent>(boss_entity) {
-                    boss_comp.current_phase = new_phase.clone();
-                }
+                            if on_stairs_down {
+                                // Queue up level generation and player movement
+                                let message = "You descend to the next level...".to_string();
 
-                resources.game_state.message_log.push(format!(
-                    "{}进入了{:?}阶段！",
-                    boss_type.name(),
-                    new_phase
-                ));
-            }
+                                // Message already added above in the game state log
+                                // resources.game_state.message_log.push(message);
+                                // if resources.game_state.message_log.len() > 10 {
+                                //     resources.game_state.message_log.remove(0);
+                                // }
 
-            // Boss AI：选择并使用技能
-            if let Some(player_pos) = &player_pos {
-                if let Ok(boss_pos) = world.get::<&Position>(boss_entity) {
-                    let distance = ((boss_pos.x - player_pos.x).pow(2) as f32
-                        + (boss_pos.y - player_pos.y).pow(2) as f32)
-                        .sqrt();
+                                resources.game_state.depth = (player_pos.z + 1) as usize;
 
-                    // 根据 Boss 逻辑决定是否使用技能
-                    // 这里简化处理，实际应该检查冷却时间等
-                    if distance <= 10.0 {
-                        // 在攻击范围内，可能使用技能
-                        // 技能逻辑将在 CombatSystem 或专门的 BossSkillSystem 中处理
+                                // Move player to new level
+                                if let Ok(mut pos) = world.get::<&mut Position>(player_entity) {
+                                    pos.z += 1;
+                                    // Place player at stairs up position
+                                    // For now, we'll place them at a default position (10, 10) on the new level
+                                    pos.x = 10;
+                                    pos.y = 10;
+                                }
+
+                                // Add message to game state log (original behavior)
+                                resources
+                                    .game_state
+                                    .message_log
+                                    .push("You descend to the next level...".to_string());
+                                if resources.game_state.message_log.len() > 10 {
+                                    resources.game_state.message_log.remove(0);
+                                }
+
+                                // Add generation of new level after all actions are processed
+                                // We'll generate it in a separate pass
+                            } else {
+                                resources
+                                    .game_state
+                                    .message_log
+                                    .push("You need to stand on stairs to descend.".to_string());
+                                if resources.game_state.message_log.len() > 10 {
+                                    resources.game_state.message_log.remove(0);
+                                }
+                                new_actions.push(action);
+                            }
+                        } else {
+                            new_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
                     }
                 }
-            }
+                PlayerAction::Ascend => {
+                    if let Some(player_entity) = find_player_entity(world) {
+                        // Check if player is on stairs - get the position first
+                        let player_pos_opt = match world.get::<&Position>(player_entity) {
+                            Ok(pos) => Some(pos.clone()),
+                            Err(_) => None,
+                        };
 
-            // 更新技能冷却
-            if let Ok(mut skill_comp) = world.get::<&mut BossSkillComponent>(boss_entity) {
-                skill_comp.cooldowns.tick();
+                        if let Some(player_pos) = player_pos_opt {
+                            // Check if there's a stairs up tile at player's position
+                            let mut on_stairs_up = false;
+                            for (_, (pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
+                                if pos.x == player_pos.x
+                                    && pos.y == player_pos.y
+                                    && pos.z == player_pos.z
+                                {
+                                    if matches!(tile.terrain_type, TerrainType::StairsUp) {
+                                        on_stairs_up = true;
+                                        break;
+                                    }
+                                }
+                            }
+
+                            if on_stairs_up {
+                                if player_pos.z > 0 {
+                                    // Move player to new level
+                                    if let Ok(mut pos) = world.get::<&mut Position>(player_entity) {
+                                        pos.z -= 1;
+                                        // Place player at stairs down position at previous level
+                                        pos.x = 10;
+                                        pos.y = 10;
+                                    }
+
+                                    let message = "You ascend to the previous level...".to_string();
+
+                                    // Add message to game state log (original behavior)
+                                    resources.game_state.message_log.push(message);
+                                    if resources.game_state.message_log.len() > 10 {
+                                        resources.game_state.message_log.remove(0);
+                                    }
+
+                                    resources.game_state.depth = (player_pos.z - 1) as usize;
+
+                                    // Add message to game state log (original behavior)
+                                    resources
+                                        .game_state
+                                        .message_log
+                                        .push("You ascend to the previous level...".to_string());
+                                    if resources.game_state.message_log.len() > 10 {
+                                        resources.game_state.message_log.remove(0);
+                                    }
+
+                                    // Generate level for the new depth after actions are processed
+                                } else {
+                                    // Player is at dungeon level 0, can't go higher
+                                    let message = "You can't go up from here.".to_string();
+
+                                    // Add message to game state log (original behavior)
+                                    resources.game_state.message_log.push(message);
+                                    if resources.game_state.message_log.len() > 10 {
+                                        resources.game_state.message_log.remove(0);
+                                    }
+                                }
+                            } else {
+                                let message = "You need to stand on stairs to ascend.".to_string();
+                                new_actions.push(action);
+
+                                // Add message to game state log (original behavior)
+                                resources.game_state.message_log.push(message);
+                                if resources.game_state.message_log.len() > 10 {
+                                    resources.game_state.message_log.remove(0);
+                                }
+                            }
+                        } else {
+                            new_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+                // For non-dungeon actions, add back to queue for other systems to handle
+                _ => {
+                    new_actions.push(action);
+                }
             }
         }
 
+        // Put unprocessed actions back in the buffer
+        resources.input_buffer.pending_actions = new_actions;
+
         SystemResult::Continue
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ecs::*;
-    use crate::turn_system::energy_costs;
-    use hero::class::{Class, SkillState};
-
-    fn create_test_world() -> (World, Resources) {
-        let mut world = World::new();
-        let resources = Resources::default();
+impl DungeonSystem {
+    /// Run dungeon system with event bus access for environment interactions
+    pub fn run_with_events(ecs_world: &mut ECSWorld) -> SystemResult {
+        use crate::event_bus::GameEvent;
+        use crate::turn_system::energy_costs;
 
-        // Create basic floor tiles
-        for x in 0..10 {
-            for y in 0..10 {
-                world.spawn((
-                    Position::new(x, y, 0),
-                    Tile {
-                        terrain_type: TerrainType::Floor,
-                        is_passable: true,
-                        blocks_sight: false,
-                        has_items: false,
-                        has_monster: false,
-                    },
-                ));
-            }
-        }
+        // Process pending player actions for dungeon interactions
+        let actions_to_process =
+            std::mem::take(&mut ecs_world.resources.input_buffer.pending_actions);
+        let mut new_actions = Vec::new();
 
-        // Create walls around the perimeter
-        for x in 0..10 {
-            world.spawn((
-                Position::new(x, 0, 0),
-                Tile {
-                    terrain_type: TerrainType::Wall,
+        for action in actions_to_process {
+            match action {
+                PlayerAction::Descend => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        // Get player position as owned values
+                        let player_pos_x;
+                        let player_pos_y;
+                        let player_pos_z;
+
+                        match ecs_world.world.get::<&Position>(player_entity) {
+                            Ok(pos) => {
+                                player_pos_x = pos.x;
+                                player_pos_y = pos.y;
+                                player_pos_z = pos.z;
+                            }
+                            Err(_) => {
+                                new_actions.push(action);
+                                continue;
+                            }
+                        }
+
+                        // Check if there's a stairs down tile at player's position
+                        let mut on_stairs_down = false;
+                        for (_, (pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter()
+                        {
+                            if pos.x == player_pos_x
+                                && pos.y == player_pos_y
+                                && pos.z == player_pos_z
+                            {
+                                if matches!(tile.terrain_type, TerrainType::StairsDown) {
+                                    on_stairs_down = true;
+                                    break;
+                                }
+                            }
+                        }
+
+                        if on_stairs_down {
+                            let old_level = player_pos_z as usize;
+                            let new_level = (player_pos_z + 1) as usize;
+
+                            // Move player to new level
+                            if let Ok(mut pos) = ecs_world.world.get::<&mut Position>(player_entity)
+                            {
+                                pos.z += 1;
+                                pos.x = 10;
+                                pos.y = 10;
+                            }
+
+                            // Reset player's viewshed to trigger FOV recalculation
+                            if let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(player_entity) {
+                                viewshed.dirty = true;
+                                viewshed.visible_tiles.clear();
+                            }
+
+                            // Update game state depth
+                            ecs_world.resources.game_state.depth = new_level;
+
+                            // Publish LevelChanged event
+                            ecs_world.publish_event(GameEvent::LevelChanged {
+                                old_level,
+                                new_level,
+                            });
+
+                            // Publish action completed event
+                            ecs_world.publish_event(GameEvent::ActionCompleted {
+                                entity: player_entity.id() as u32,
+                                action_type: "Descend".to_string(),
+                                success: true,
+                            });
+
+                            // Add to completed actions for energy deduction
+                            ecs_world
+                                .resources
+                                .input_buffer
+                                .completed_actions
+                                .push(PlayerAction::Descend);
+
+                            // Add message to game state log
+                            ecs_world
+                                .resources
+                                .game_state
+                                .message_log
+                                .push("You descend to the next level...".to_string());
+                            if ecs_world.resources.game_state.message_log.len() > 10 {
+                                ecs_world.resources.game_state.message_log.remove(0);
+                            }
+                        } else {
+                            ecs_world
+                                .resources
+                                .game_state
+                                .message_log
+                                .push("You need to stand on stairs to descend.".to_string());
+                            if ecs_world.resources.game_state.message_log.len() > 10 {
+                                ecs_world.resources.game_state.message_log.remove(0);
+                            }
+                            
+                            // Publish action failed event
+                            ecs_world.publish_event(GameEvent::ActionFailed {
+                                entity: player_entity.id() as u32,
+                                action_type: "Descend".to_string(),
+                                reason: "Not on stairs".to_string(),
+                            });
+                            
+                            new_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+                PlayerAction::Ascend => {
+                    if let Some(player_entity) = find_player_entity(&ecs_world.world) {
+                        // Get player position as owned value
+                        let player_pos_x;
+                        let player_pos_y;
+                        let player_pos_z;
+
+                        match ecs_world.world.get::<&Position>(player_entity) {
+                            Ok(pos) => {
+                                player_pos_x = pos.x;
+                                player_pos_y = pos.y;
+                                player_pos_z = pos.z;
+                            }
+                            Err(_) => {
+                                new_actions.push(action);
+                                continue;
+                            }
+                        }
+
+                        // Check if there's a stairs up tile at player's position
+                        let mut on_stairs_up = false;
+                        for (_, (pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter()
+                        {
+                            if pos.x == player_pos_x
+                                && pos.y == player_pos_y
+                                && pos.z == player_pos_z
+                            {
+                                if matches!(tile.terrain_type, TerrainType::StairsUp) {
+                                    on_stairs_up = true;
+                                    break;
+                                }
+                            }
+                        }
+
+                        if on_stairs_up {
+                            if player_pos_z > 0 {
+                                let old_level = player_pos_z as usize;
+                                let new_level = (player_pos_z - 1) as usize;
+
+                                // Move player to new level
+                                if let Ok(mut pos) =
+                                    ecs_world.world.get::<&mut Position>(player_entity)
+                                {
+                                    pos.z -= 1;
+                                    pos.x = 10;
+                                    pos.y = 10;
+                                }
+
+                                // Reset player's viewshed to trigger FOV recalculation
+                                if let Ok(mut viewshed) = ecs_world.world.get::<&mut Viewshed>(player_entity) {
+                                    viewshed.dirty = true;
+                                    viewshed.visible_tiles.clear();
+                                }
+
+                                // Update game state depth
+                                ecs_world.resources.game_state.depth = new_level;
+
+                                // Publish LevelChanged event
+                                ecs_world.publish_event(GameEvent::LevelChanged {
+                                    old_level,
+                                    new_level,
+                                });
+
+                                // Publish action completed event
+                                ecs_world.publish_event(GameEvent::ActionCompleted {
+                                    entity: player_entity.id() as u32,
+                                    action_type: "Ascend".to_string(),
+                                    success: true,
+                                });
+
+                                // Add to completed actions for energy deduction
+                                ecs_world
+                                    .resources
+                                    .input_buffer
+                                    .completed_actions
+                                    .push(PlayerAction::Ascend);
+
+                                // Add message to game state log
+                                ecs_world
+                                    .resources
+                                    .game_state
+                                    .message_log
+                                    .push("You ascend to the previous level...".to_string());
+                                if ecs_world.resources.game_state.message_log.len() > 10 {
+                                    ecs_world.resources.game_state.message_log.remove(0);
+                                }
+                            } else {
+                                // Player is at dungeon level 0, can't go higher
+                                let message = "You can't go up from here.".to_string();
+
+                                ecs_world.resources.game_state.message_log.push(message);
+                                if ecs_world.resources.game_state.message_log.len() > 10 {
+                                    ecs_world.resources.game_state.message_log.remove(0);
+                                }
+                                
+                                // Publish action failed event
+                                ecs_world.publish_event(GameEvent::ActionFailed {
+                                    entity: player_entity.id() as u32,
+                                    action_type: "Ascend".to_string(),
+                                    reason: "Already at top level".to_string(),
+                                });
+                            }
+                        } else {
+                            let message = "You need to stand on stairs to ascend.".to_string();
+
+                            ecs_world.resources.game_state.message_log.push(message);
+                            if ecs_world.resources.game_state.message_log.len() > 10 {
+                                ecs_world.resources.game_state.message_log.remove(0);
+                            }
+                            
+                            // Publish action failed event
+                            ecs_world.publish_event(GameEvent::ActionFailed {
+                                entity: player_entity.id() as u32,
+                                action_type: "Ascend".to_string(),
+                                reason: "Not on stairs".to_string(),
+                            });
+                            
+                            new_actions.push(action);
+                        }
+                    } else {
+                        new_actions.push(action);
+                    }
+                }
+                // For non-dungeon actions, add back to queue for other systems to handle
+                _ => {
+                    new_actions.push(action);
+                }
+            }
+        }
+
+        // Put unprocessed actions back in the buffer
+        ecs_world.resources.input_buffer.pending_actions = new_actions;
+
+        SystemResult::Continue
+    }
+
+    /// Check and handle traps at a position with proper event publishing
+    pub fn check_and_trigger_trap(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) {
+        use crate::event_bus::GameEvent;
+
+        // Collect trap data first to avoid borrow conflicts
+        let mut trap_data: Option<(String, u32)> = None;
+
+        for (_, (trap_pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter() {
+            if trap_pos.x == pos.x && trap_pos.y == pos.y && trap_pos.z == pos.z {
+                if matches!(tile.terrain_type, TerrainType::Trap) {
+                    trap_data = Some(("尖刺陷阱".to_string(), 10)); // Simplified trap
+                    break;
+                }
+            }
+        }
+
+        if let Some((trap_type, damage)) = trap_data {
+            // Publish trap triggered event
+            ecs_world.publish_event(GameEvent::TrapTriggered {
+                entity: entity.id() as u32,
+                trap_type: trap_type.clone(),
+            });
+
+            // Apply trap damage (separate scope to avoid borrow conflicts)
+            {
+                if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(entity) {
+                    stats.hp = stats.hp.saturating_sub(damage);
+                }
+            }
+
+            // Publish damage event after stats update
+            ecs_world.publish_event(GameEvent::DamageDealt {
+                attacker: 0, // No attacker for environmental damage
+                victim: entity.id() as u32,
+                damage,
+                is_critical: false,
+            });
+
+            ecs_world
+                .resources
+                .game_state
+                .message_log
+                .push(format!("触发了{}，造成了 {} 点伤害", trap_type, damage));
+        }
+    }
+
+    /// Check and handle door opening with proper event publishing
+    pub fn check_and_open_door(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) -> bool {
+        use crate::event_bus::GameEvent;
+
+        // Find door at position and collect its entity ID first
+        let mut door_entity_opt: Option<hecs::Entity> = None;
+
+        for (door_ent, (door_pos, tile)) in
+            ecs_world.world.query::<(&Position, &Tile)>().iter()
+        {
+            if door_pos.x == pos.x && door_pos.y == pos.y && door_pos.z == pos.z {
+                if matches!(tile.terrain_type, TerrainType::Door) {
+                    door_entity_opt = Some(door_ent);
+                    break;
+                }
+            }
+        }
+
+        // Update the door if found
+        if let Some(door_ent) = door_entity_opt {
+            if let Ok(mut tile) = ecs_world.world.get::<&mut Tile>(door_ent) {
+                // Open the door
+                tile.is_passable = true;
+                tile.blocks_sight = false;
+            }
+
+            // Publish door opened event
+            ecs_world.publish_event(GameEvent::DoorOpened {
+                entity: entity.id() as u32,
+                x: pos.x,
+                y: pos.y,
+                door_type: "木门".to_string(),
+            });
+
+            ecs_world
+                .resources
+                .game_state
+                .message_log
+                .push("打开了门".to_string());
+
+            return true;
+        }
+
+        false
+    }
+
+    /// Get terrain-based energy cost for movement at a position
+    pub fn get_terrain_energy_cost(world: &World, pos: &Position) -> u32 {
+        use crate::turn_system::energy_costs;
+
+        // Find terrain at position
+        for (_, (tile_pos, tile)) in world.query::<(&Position, &Tile)>().iter() {
+            if tile_pos.x == pos.x && tile_pos.y == pos.y && tile_pos.z == pos.z {
+                return energy_costs::terrain_movement_cost(&tile.terrain_type);
+            }
+        }
+
+        // Default to normal cost if no tile found
+        energy_costs::FULL_ACTION
+    }
+
+    /// Handle environmental hazards (fire, gas, etc.) at a position
+    pub fn process_environmental_effects(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) {
+        // 踩上植物会触发它（漂浮的生物不会踩到）
+        Self::trigger_plant(ecs_world, entity, pos);
+
+        // Check for special terrain types that cause damage or effects
+        for (_, (tile_pos, tile)) in ecs_world.world.query::<(&Position, &Tile)>().iter() {
+            if tile_pos.x == pos.x && tile_pos.y == pos.y && tile_pos.z == pos.z {
+                match tile.terrain_type {
+                    TerrainType::Water => {
+                        // Water might cause slowdown (already handled by terrain cost)
+                        // Could add wet status effect here
+                    }
+                    TerrainType::Barrel => {
+                        // Barrels could explode or provide cover
+                        // Implementation depends on game design
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// 触发该格子上的植物，植物随即枯萎
+    fn trigger_plant(ecs_world: &mut ECSWorld, entity: Entity, pos: &Position) {
+        use items::seed::SeedKind;
+
+        let levitating = ecs_world
+            .world
+            .get::<&StatusEffects>(entity)
+            .is_ok_and(|effects| effects.has_effect(EffectType::Levitation));
+        if levitating {
+            return;
+        }
+        let Some((plant_entity, plant)) = ecs_world
+            .world
+            .query::<(&Position, &Plant)>()
+            .iter()
+            .find(|(_, (p, _))| *p == pos)
+            .map(|(e, (_, plant))| (e, plant.clone()))
+        else {
+            return;
+        };
+        let _ = ecs_world.world.despawn(plant_entity);
+
+        let victim = ecs_world
+            .world
+            .get::<&Actor>(entity)
+            .map(|a| a.name.clone())
+            .unwrap_or_else(|_| "某物".to_string());
+        let source = plant.name();
+        let timed = |effect_type, turns| ::combat::effect::Effect::with_source(effect_type, turns, &source);
+
+        let message = match plant.kind {
+            SeedKind::Earthroot => {
+                CombatSystem::apply_status_effect(ecs_world, entity, timed(EffectType::Barkskin, 10));
+                format!("{}被坚硬的树皮包裹", victim)
+            }
+            SeedKind::Fadeleaf => match InventorySystem::random_safe_tile(ecs_world, entity) {
+                Some(destination) => {
+                    InventorySystem::teleport(ecs_world, entity, destination);
+                    format!("{}消失在一阵微光中", victim)
+                }
+                None => format!("{}周围泛起微光，但什么也没发生", victim),
+            },
+            SeedKind::Firebloom => {
+                BlobSystem::spawn_blob(&mut ecs_world.world, pos, BlobKind::Fire, 4);
+                CombatSystem::apply_status_effect(ecs_world, entity, timed(EffectType::Burning, 3));
+                format!("{}脚下的火焰花猛然燃起", victim)
+            }
+            SeedKind::Icecap => {
+                InventorySystem::freeze_area(ecs_world, pos, 1, &source);
+                format!("{}周围的一切都被冻结了", victim)
+            }
+            SeedKind::Sorrowmoss => {
+                BlobSystem::spawn_blob(&mut ecs_world.world, pos, BlobKind::ToxicGas, 8);
+                CombatSystem::apply_status_effect(ecs_world, entity, timed(EffectType::Poison, 5));
+                format!("哀伤苔向{}喷出毒雾", victim)
+            }
+            SeedKind::Dreamfoil => {
+                if let Ok(mut effects) = ecs_world.world.get::<&mut StatusEffects>(entity) {
+                    effects.effects.retain(|effect| !InventorySystem::is_negative_effect(effect.effect_type()));
+                }
+                format!("{}感到身心一阵清明", victim)
+            }
+            SeedKind::Stormvine => {
+                let damage = 4 + ecs_world.resources.game_state.depth as u32;
+                for target in InventorySystem::creatures_near(&ecs_world.world, pos, 1) {
+                    CombatSystem::deal_direct_damage(ecs_world, entity, target, damage);
+                }
+                format!("风暴藤召来闪电，劈中了{}周围的一切", victim)
+            }
+            SeedKind::Rotberry => {
+                for target in InventorySystem::creatures_near(&ecs_world.world, pos, 1) {
+                    CombatSystem::apply_status_effect(ecs_world, target, timed(EffectType::Slow, 5));
+                }
+                format!("腐浆果爆开，诅咒能量拖慢了{}周围的生物", victim)
+            }
+        };
+        ecs_world.resources.game_state.message_log.push(message);
+    }
+
+    /// Generate a basic dungeon level
+    fn generate_level(&mut self, world: &mut World, resources: &mut Resources, level: i32) {
+        // Prefer using dungeon::Dungeon if present
+        if let Some(dungeon) = crate::ecs::get_dungeon_clone(world) {
+            // Remove all tiles for the level being generated
+            let tiles_to_remove: Vec<_> = world
+                .query::<(&Position, &Tile)>()
+                .iter()
+                .filter(|(_, (pos, _))| pos.z == level)
+                .map(|(e, _)| e)
+                .collect();
+            for entity in tiles_to_remove {
+                let _ = world.despawn(entity);
+            }
+
+            // Populate tiles from dungeon level data
+            let lvl = &dungeon.levels[dungeon.depth - 1];
+            for tile in &lvl.tiles {
+                let terrain = match &tile.info.terrain_type {
+                    dungeon::level::tiles::TerrainType::Floor => TerrainType::Floor,
+                    dungeon::level::tiles::TerrainType::Wall => TerrainType::Wall,
+                    // 暗门在被发现前和墙壁没有区别
+                    dungeon::level::tiles::TerrainType::Door(dungeon::level::tiles::DoorState::Hidden) => {
+                        TerrainType::Wall
+                    }
+                    dungeon::level::tiles::TerrainType::Door(_) => TerrainType::Door,
+                    dungeon::level::tiles::TerrainType::Stair(dir) => match dir {
+                        dungeon::level::tiles::StairDirection::Up => TerrainType::StairsUp,
+                        dungeon::level::tiles::StairDirection::Down => TerrainType::StairsDown,
+                    },
+                    dungeon::level::tiles::TerrainType::Water => TerrainType::Water,
+                    dungeon::level::tiles::TerrainType::Trap(_) => TerrainType::Trap,
+                    dungeon::level::tiles::TerrainType::Special => TerrainType::Empty,
+                    dungeon::level::tiles::TerrainType::AlchemyPot => TerrainType::AlchemyPot,
+                    dungeon::level::tiles::TerrainType::Grass => TerrainType::Grass,
+                };
+
+                world.spawn((
+                    Position::new(tile.x, tile.y, level),
+                    Tile {
+                        terrain_type: terrain.clone(),
+                        is_passable: tile.info.passable,
+                        blocks_sight: tile.info.blocks_sight,
+                        has_items: lvl.items.iter().any(|i| i.x == tile.x && i.y == tile.y),
+                        has_monster: lvl.enemies.iter().any(|e| e.x == tile.x && e.y == tile.y),
+                    },
+                    Renderable {
+                        symbol: match terrain {
+                            TerrainType::Floor => '.',
+                            TerrainType::Wall => '#',
+                            TerrainType::Door => '+',
+                            TerrainType::StairsDown => '>',
+                            TerrainType::Water => '~',
+                            TerrainType::Grass => '"',
+                            TerrainType::Trap => '^',
+                            _ => ' ',
+                        },
+                        fg_color: Color::White,
+                        bg_color: Some(Color::Black),
+                        order: 0,
+                    },
+                ));
+            }
+
+            // Spawn enemies and items from level
+            for enemy in &lvl.enemies {
+                world.spawn((
+                    Position::new(enemy.x, enemy.y, level),
+                    Actor {
+                        name: enemy.name().to_string(),
+                        faction: Faction::Enemy,
+                    },
+                    Renderable {
+                        symbol: enemy.symbol,
+                        fg_color: Color::Green,
+                        bg_color: Some(Color::Black),
+                        order: 5,
+                    },
+                    Stats {
+                        hp: enemy.hp,
+                        max_hp: enemy.max_hp,
+                        attack: enemy.attack,
+                        defense: enemy.defense,
+                        accuracy: 70,
+                        evasion: 10,
+                        level: enemy.level(),
+                        experience: enemy.exp_value,
+                        class: None,
+                    },
+                    Energy {
+                        current: 100,
+                        max: 100,
+                        regeneration_rate: 1,
+                    },
+                    crate::ecs::LootTable {
+                        kind: enemy.kind.clone(),
+                    },
+                ));
+            }
+
+            for item in &lvl.items {
+                world.spawn((
+                    Position::new(item.x, item.y, level),
+                    Renderable {
+                        symbol: '!',
+                        fg_color: Color::Red,
+                        bg_color: Some(Color::Black),
+                        order: 1,
+                    },
+                    ECSItem::new(item.clone()),
+                    Tile {
+                        terrain_type: TerrainType::Empty,
+                        is_passable: true,
+                        blocks_sight: false,
+                        has_items: true,
+                        has_monster: false,
+                    },
+                ));
+            }
+            return;
+        }
+
+        // Remove all tiles for the level being generated
+        let tiles_to_remove: Vec<_> = world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .filter(|(_, (pos, _))| pos.z == level)
+            .map(|(e, _)| e)
+            .collect();
+
+        for entity in tiles_to_remove {
+            let _ = world.despawn(entity);
+        }
+
+        // Generate a basic 20x20 room layout for the level
+        for x in 5..25 {
+            for y in 5..25 {
+                let terrain_type = if x == 5 || x == 24 || y == 5 || y == 24 {
+                    TerrainType::Wall
+                } else {
+                    TerrainType::Floor
+                };
+
+                let renderable = Renderable {
+                    symbol: if x == 5 || x == 24 || y == 5 || y == 24 {
+                        '#'
+                    } else {
+                        '.'
+                    },
+                    fg_color: if x == 5 || x == 24 || y == 5 || y == 24 {
+                        Color::Gray
+                    } else {
+                        Color::White
+                    },
+                    bg_color: Some(Color::Black),
+                    order: 0,
+                };
+
+                world.spawn((
+                    Position::new(x, y, level),
+                    Tile {
+                        terrain_type,
+                        is_passable: x != 5 && x != 24 && y != 5 && y != 24,
+                        blocks_sight: x == 5 || x == 24 || y == 5 || y == 24,
+                        has_items: false,
+                        has_monster: false,
+                    },
+                    renderable,
+                ));
+            }
+        }
+
+        // Place stairs based on current level for connections
+        if level > 0 {
+            // Place stairs up (going down to the previous level)
+            world.spawn((
+                Position::new(9, 9, level),
+                Tile {
+                    terrain_type: TerrainType::StairsUp,
+                    is_passable: true,
+                    blocks_sight: false,
+                    has_items: false,
+                    has_monster: false,
+                },
+                Renderable {
+                    symbol: '<',
+                    fg_color: Color::Cyan,
+                    bg_color: Some(Color::Black),
+                    order: 1,
+                },
+            ));
+        }
+
+        // Place stairs down if not the deepest level
+        if level < (resources.config.max_depth as i32 - 1) {
+            world.spawn((
+                Position::new(15, 15, level),
+                Tile {
+                    terrain_type: TerrainType::StairsDown,
+                    is_passable: true,
+                    blocks_sight: false,
+                    has_items: false,
+                    has_monster: false,
+                },
+                Renderable {
+                    symbol: '>',
+                    fg_color: Color::Cyan,
+                    bg_color: Some(Color::Black),
+                    order: 1,
+                },
+            ));
+        }
+
+        // Add some simple monsters and items to the level
+        if level > 0 {
+            // Add content to levels other than 0
+            // Add a simple enemy
+            let enemy_pos = Position::new(12, 12, level);
+            world.spawn((
+                enemy_pos,
+                Actor {
+                    name: format!("Goblin {}", level),
+                    faction: Faction::Enemy,
+                },
+                Renderable {
+                    symbol: 'g',
+                    fg_color: Color::Green,
+                    bg_color: Some(Color::Black),
+                    order: 5,
+                },
+                Stats {
+                    hp: 30,
+                    max_hp: 30,
+                    attack: 5 + (level as u32 * 2),
+                    defense: 2 + (level as u32),
+                    accuracy: 70,
+                    evasion: 10,
+                    level: level as u32,
+                    experience: 10 + (level as u32 * 5),
+                    class: None,
+                },
+                Energy {
+                    current: 100,
+                    max: 100,
+                    regeneration_rate: 1,
+                },
+            ));
+
+            // Add a healing potion
+            world.spawn((
+                Position::new(14, 10, level),
+                Renderable {
+                    symbol: '!',
+                    fg_color: Color::Red,
+                    bg_color: Some(Color::Black),
+                    order: 1,
+                },
+                ECSItem::new(items::Item::new(items::ItemKind::Potion(items::Potion::new_alchemy(
+                    items::potion::PotionKind::Healing,
+                )))),
+                Tile {
+                    terrain_type: TerrainType::Empty,
+                    is_passable: true,
+                    blocks_sight: false,
+                    has_items: true,
+                    has_monster: false,
+                },
+            ));
+        }
+    }
+}
+
+pub struct InteractionSystem;
+
+impl InteractionSystem {
+    pub fn handle_interactions(world: &mut World) {
+        for (_, (pos, actor)) in world.query::<(&Position, &Actor)>().iter() {
+            if actor.faction == crate::ecs::Faction::Player {
+                let _ = (pos.x, pos.y);
+            }
+        }
+    }
+}
+
+impl System for InteractionSystem {
+    fn name(&self) -> &str {
+        "InteractionSystem"
+    }
+
+    fn run(&mut self, world: &mut World, _resources: &mut Resources) -> SystemResult {
+        Self::handle_interactions(world);
+        SystemResult::Continue
+    }
+}
+
+// ========== 新增：HungerSystem（饱食度系统）==========
+
+/// 饥饿系统：处理玩家的饱食度变化
+/// 
+/// 饥饿衰减规则：
+/// - 标准动作（移动、攻击、使用物品等）：每10次动作减少1点饱食度
+/// - 等待动作：每20次减少1点饱食度（消耗更少）
+/// - 饱食度为0时：每回合造成1点饥饿伤害
+///
+/// 阈值：
+/// - 饱食度 <= 2：饥饿警告（发送PlayerHungry事件）
+/// - 饱食度 = 0：挨饿状态（发送PlayerStarving事件并造成伤害）
+pub struct HungerSystem;
+
+impl HungerSystem {
+    /// 标准动作的饥饿阈值（每10次动作减少1点饱食度）
+    const HUNGER_DECAY_INTERVAL: u32 = 10;
+    
+    /// 等待动作的饥饿阈值（每20次等待减少1点饱食度）
+    const HUNGER_DECAY_INTERVAL_WAIT: u32 = 20;
+    
+    /// 饥饿伤害（每回合）
+    const STARVATION_DAMAGE: u32 = 1;
+    
+    /// 带事件总线的运行方法
+    pub fn run_with_events(ecs_world: &mut ECSWorld) -> SystemResult {
+        use crate::event_bus::GameEvent;
+
+        // 获取当前总回合数
+        let current_turn = ecs_world.resources.clock.turn_count;
+
+        // 检查是否有已完成的动作（用于判断是否触发饥饿衰减）
+        let has_completed_actions = !ecs_world.resources.input_buffer.completed_actions.is_empty();
+        let is_wait_action = ecs_world.resources.input_buffer.completed_actions
+            .iter()
+            .any(|action| matches!(action, PlayerAction::Wait));
+
+        // 收集需要处理的实体信息（避免借用冲突）
+        let mut entities_to_process = Vec::new();
+
+        for (entity, (hunger, stats)) in ecs_world.world.query::<(&Hunger, &Stats)>().iter() {
+            let is_player = ecs_world.world.get::<&Player>(entity).is_ok();
+            entities_to_process.push((entity, hunger.clone(), stats.clone(), is_player));
+        }
+
+        // 处理每个实体
+        for (entity, mut hunger, mut stats, is_player) in entities_to_process {
+            // 饥饿衰减逻辑：只在玩家执行了动作时才触发
+            if has_completed_actions && is_player {
+                // 累加回合数
+                hunger.turn_accumulator += 1;
+                
+                // 根据动作类型选择不同的衰减间隔
+                let decay_interval = if is_wait_action {
+                    Self::HUNGER_DECAY_INTERVAL_WAIT
+                } else {
+                    Self::HUNGER_DECAY_INTERVAL
+                };
+                
+                // 检查是否达到饥饿衰减阈值
+                if hunger.turn_accumulator >= decay_interval {
+                    let old_satiety = hunger.satiety;
+                    hunger.satiety = hunger.satiety.saturating_sub(1);
+                    hunger.turn_accumulator = 0; // 重置累加器
+                    hunger.last_hunger_turn = current_turn;
+
+                    // 发布饥饿度变化事件
+                    ecs_world.publish_event(GameEvent::HungerChanged {
+                        entity: entity.id() as u32,
+                        old_satiety,
+                        new_satiety: hunger.satiety,
+                    });
+                    
+                    // 检查饥饿状态并发送警告事件
+                    if hunger.is_hungry() && !hunger.is_starving() {
+                        ecs_world.publish_event(GameEvent::PlayerHungry {
+                            entity: entity.id() as u32,
+                            satiety: hunger.satiety,
+                        });
+                    }
+                }
+            }
+
+            // 饥饿伤害处理（在aftermath阶段，每回合检查）
+            if hunger.is_starving() {
+                // 发布挨饿事件
+                ecs_world.publish_event(GameEvent::PlayerStarving {
+                    entity: entity.id() as u32,
+                });
+
+                // 饥饿致死：每回合掉1血
+                let damage = Self::STARVATION_DAMAGE;
+                stats.hp = stats.hp.saturating_sub(damage);
+
+                // 发布饥饿伤害事件
+                ecs_world.publish_event(GameEvent::StarvationDamage {
+                    entity: entity.id() as u32,
+                    damage,
+                });
+
+                // 检查玩家是否死亡
+                if stats.hp == 0 && is_player {
+                    // 使用专门的饿死游戏结束原因
+                    ecs_world.resources.game_state.game_state = GameStatus::GameOver {
+                        reason: GameOverReason::Starved,
+                    };
+                    
+                    ecs_world.publish_event(GameEvent::GameOver {
+                        reason: "你死于饥饿".to_string(),
+                    });
+
+                    // 将死亡加入aftermath队列
+                    ecs_world.resources.aftermath_queue.push(AftermathEvent::Death {
+                        entity,
+                        entity_id: entity.id() as u32,
+                        entity_name: "玩家".to_string(),
+                        killer: None,
+                    });
+
+                    // 更新组件
+                    let _ = ecs_world.world.insert(entity, (hunger, stats));
+                    return SystemResult::Stop;
+                }
+            }
+
+            // 更新组件
+            let _ = ecs_world.world.insert(entity, (hunger, stats));
+        }
+
+        SystemResult::Continue
+    }
+}
+
+impl System for HungerSystem {
+    fn name(&self) -> &str {
+        "HungerSystem"
+    }
+
+    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
+        // 获取当前总回合数
+        let current_turn = resources.clock.turn_count;
+
+        // 处理所有拥有 Hunger 组件的实体（主要是玩家）
+        for (entity, (hunger, stats)) in world.query::<(&mut Hunger, &mut Stats)>().iter() {
+            // 每20回合减少1点饱食度
+            if current_turn > 0 && (current_turn - hunger.last_hunger_turn) >= 20 {
+                hunger.satiety = hunger.satiety.saturating_sub(1);
+                hunger.last_hunger_turn = current_turn;
+
+                // 饥饿状态处理
+                if hunger.is_starving() {
+                    // 饥饿致死：每回合掉1血
+                    stats.hp = stats.hp.saturating_sub(1);
+                    resources
+                        .game_state
+                        .message_log
+                        .push("你正在饿死！".to_string());
+
+                    // 检查玩家是否死亡
+                    if stats.hp == 0 && world.get::<&Player>(entity).is_ok() {
+                        resources.game_state.game_state = GameStatus::GameOver {
+                            reason: GameOverReason::Died("死亡"),
+                        };
+                        resources
+                            .game_state
+                            .message_log
+                            .push("你死于饥饿...".to_string());
+                        return SystemResult::Stop;
+                    }
+                } else if hunger.is_hungry() {
+                    // 饥饿警告状态
+                    if current_turn % 40 == 0 {
+                        // 每40回合提示一次
+                        resources
+                            .game_state
+                            .message_log
+                            .push("你感到饥饿...".to_string());
+                    }
+                }
+            }
+        }
+
+        SystemResult::Continue
+    }
+}
+
+/// 渲染系统
+///
+/// 负责协调所有渲染组件，但由于实际渲染由RatatuiRenderer处理，
+/// 这个系统主要用于标记渲染状态和清理渲染缓存。
+pub struct RenderingSystem;
+
+impl System for RenderingSystem {
+    fn name(&self) -> &str {
+        "RenderingSystem"
+    }
+
+    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
+        // 标记视锥为dirty（如果需要重新计算FOV）
+        for (_, viewshed) in world.query::<&mut Viewshed>().iter() {
+            if viewshed.dirty {
+                // FOVSystem会处理实际的视锥计算
+                // 这里只是确保dirty状态被记录
+            }
+        }
+
+        // 清理过期的渲染缓存（如果实现的话）
+        // 这里可以添加渲染缓存清理逻辑
+
+        // 更新渲染相关的资源状态
+        resources.game_state.frame_count = resources.game_state.frame_count.wrapping_add(1);
+
+        SystemResult::Continue
+    }
+}
+
+/// 菜单系统
+///
+/// 处理所有菜单相关的动作，包括菜单导航、状态切换等。
+pub struct MenuSystem;
+
+impl System for MenuSystem {
+    fn name(&self) -> &str {
+        "MenuSystem"
+    }
+
+    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
+        // 收集需要处理的菜单动作，避免借用冲突
+        let menu_actions: Vec<PlayerAction> = resources
+            .input_buffer
+            .completed_actions
+            .iter()
+            .filter(|action| {
+                matches!(
+                    action,
+                    PlayerAction::OpenInventory
+                        | PlayerAction::OpenOptions
+                        | PlayerAction::OpenHelp
+                        | PlayerAction::OpenCharacterInfo
+                        | PlayerAction::OpenCatalog
+                        | PlayerAction::CloseMenu
+                        | PlayerAction::MenuNavigate(_)
+                        | PlayerAction::MenuSelect
+                        | PlayerAction::MenuBack
+                        | PlayerAction::BeginTargeting(_)
+                        | PlayerAction::MoveCursor(_)
+                        | PlayerAction::CycleTarget
+                        | PlayerAction::ConfirmTarget
+                        | PlayerAction::UseQuickslot(_)
+                        | PlayerAction::AssignQuickslot(_)
+                        | PlayerAction::SplitStack(_)
+                )
+            })
+            .cloned()
+            .collect();
+
+        // 处理收集到的菜单动作
+        for action in menu_actions {
+            match action {
+                PlayerAction::OpenInventory => {
+                    // 打开时选中第一个分类中排在最前的物品
+                    let selected_item = find_player_entity(world)
+                        .and_then(|player| world.get::<&Inventory>(player).ok())
+                        .and_then(|inventory| inventory.category_tabs().first().map(|(_, slots)| slots[0]))
+                        .unwrap_or(0);
+                    resources.game_state.game_state = GameStatus::Inventory { selected_item };
+                }
+
+                PlayerAction::OpenOptions => {
+                    resources.game_state.game_state = GameStatus::Options { selected_option: 0 };
+                }
+
+                PlayerAction::OpenHelp => {
+                    resources.game_state.game_state = GameStatus::Help;
+                }
+
+                PlayerAction::OpenCharacterInfo => {
+                    resources.game_state.game_state = GameStatus::CharacterInfo;
+                }
+
+                PlayerAction::OpenCatalog => {
+                    resources.game_state.game_state = GameStatus::ItemCatalog;
+                }
+
+                PlayerAction::CloseMenu => {
+                    match resources.game_state.game_state {
+                        GameStatus::ConfirmQuit { return_to, .. } => {
+                            // 在确认退出对话框中按 Esc/Backspace 返回到原状态
+                            resources.game_state.game_state = match return_to {
+                                crate::ecs::ReturnTo::Running => GameStatus::Running,
+                                crate::ecs::ReturnTo::MainMenu => {
+                                    GameStatus::MainMenu { selected_option: 0 }
+                                }
+                            };
+                        }
+                        GameStatus::MainMenu { .. } => {
+                            // 在主菜单按下 Esc 不再退出，避免误触直接退出
+                            // 保持在主菜单，等待明确的退出动作（如 'q'）
+                        }
+                        GameStatus::Paused { .. } => {
+                            // 在暂停菜单按 Esc 返回游戏
+                            resources.game_state.game_state = GameStatus::Running;
+                        }
+                        GameStatus::ItemPicker { .. } => {
+                            self.handle_menu_back(resources);
+                        }
+                        GameStatus::Running => {
+                            // 游戏中按 Esc 打开暂停菜单
+                            resources.game_state.game_state =
+                                GameStatus::Paused { selected_option: 0 };
+                        }
+                        _ => {
+                            // 在其他菜单状态，返回游戏或上一级菜单
+                            resources.game_state.game_state = GameStatus::Running;
+                        }
+                    }
+                }
+
+                PlayerAction::Quit => {
+                    // 触发确认退出对话框
+                    let return_to = match resources.game_state.game_state {
+                        GameStatus::MainMenu { .. } => crate::ecs::ReturnTo::MainMenu,
+                        _ => crate::ecs::ReturnTo::Running,
+                    };
+                    resources.game_state.game_state = GameStatus::ConfirmQuit {
+                        return_to,
+                        selected_option: 1, // 默认选中“否”
+                    };
+                }
+
+                PlayerAction::MenuNavigate(direction) => match resources.game_state.game_state {
+                    GameStatus::Alchemy { .. } => Self::navigate_alchemy(world, resources, direction),
+                    GameStatus::Inventory { .. } | GameStatus::ItemPicker { .. } => {
+                        Self::navigate_inventory(world, resources, direction);
+                    }
+                    GameStatus::PickupChooser { .. } => Self::navigate_pile(world, resources, direction),
+                    _ => self.handle_menu_navigation(resources, &direction),
+                },
+
+                PlayerAction::MenuSelect => match resources.game_state.game_state {
+                    GameStatus::Inventory { selected_item } => {
+                        Self::use_selected_item(world, resources, selected_item);
+                    }
+                    GameStatus::ItemPicker {
+                        source_slot,
+                        selected_item,
+                    } => {
+                        resources
+                            .input_buffer
+                            .pending_actions
+                            .push(PlayerAction::UseItemOn(source_slot, selected_item));
+                        resources.game_state.game_state = GameStatus::Running;
+                    }
+                    GameStatus::PickupChooser { selected_item } => {
+                        resources
+                            .input_buffer
+                            .pending_actions
+                            .push(PlayerAction::PickUpItem(selected_item));
+                        resources.game_state.game_state = GameStatus::Running;
+                    }
+                    GameStatus::Alchemy { .. } => {
+                        Self::add_alchemy_ingredient(world, resources);
+                    }
+                    _ => self.handle_menu_selection(resources),
+                },
+
+                PlayerAction::MenuBack => {
+                    self.handle_menu_back(resources);
+                }
+
+                PlayerAction::BeginTargeting(purpose) => {
+                    Self::begin_targeting(world, resources, purpose);
+                }
+
+                PlayerAction::UseQuickslot(index) => {
+                    Self::use_quickslot(world, resources, index);
+                }
+
+                PlayerAction::AssignQuickslot(slot) => {
+                    Self::assign_quickslot(world, resources, slot);
+                }
+
+                PlayerAction::SplitStack(slot) => {
+                    Self::split_stack(world, resources, slot);
+                }
+
+                PlayerAction::MoveCursor(direction) => {
+                    if let GameStatus::Targeting {
+                        ref mut cursor_x,
+                        ref mut cursor_y,
+                        ..
+                    } = resources.game_state.game_state
+                    {
+                        let moved = MovementSystem::calculate_target_position(
+                            &Position::new(*cursor_x, *cursor_y, 0),
+                            direction,
+                        );
+                        *cursor_x = moved.x;
+                        *cursor_y = moved.y;
+                    }
+                }
+
+                PlayerAction::CycleTarget => {
+                    if let GameStatus::Targeting {
+                        ref mut cursor_x,
+                        ref mut cursor_y,
+                        ..
+                    } = resources.game_state.game_state
+                    {
+                        let next = find_player_entity(world).and_then(|player| {
+                            crate::targeting::next_target(
+                                world,
+                                player,
+                                &Position::new(*cursor_x, *cursor_y, 0),
+                            )
+                        });
+                        if let Some(next) = next {
+                            *cursor_x = next.x;
+                            *cursor_y = next.y;
+                        }
+                    }
+                }
+
+                PlayerAction::ConfirmTarget => {
+                    Self::confirm_target(world, resources);
+                }
+
+                _ => {
+                    // 其他动作不会被传递到这里
+                }
+            }
+        }
+
+        SystemResult::Continue
+    }
+}
+
+impl MenuSystem {
+    /// 使用物品栏中选中的物品；需要目标的物品先进入物品选择
+    fn use_selected_item(world: &World, resources: &mut Resources, selected_item: usize) {
+        let needs_target = find_player_entity(world)
+            .and_then(|player| {
+                let inventory = world.get::<&Inventory>(player).ok()?;
+                let item = inventory.items.get(selected_item)?.item.as_ref()?.item.clone();
+                Some(InventorySystem::needs_item_target(&item))
+            })
+            .unwrap_or(false);
+
+        resources.game_state.game_state = if needs_target {
+            GameStatus::ItemPicker {
+                source_slot: selected_item,
+                selected_item: 0,
+            }
+        } else {
+            resources
+                .input_buffer
+                .pending_actions
+                .push(PlayerAction::UseItem(selected_item));
+            GameStatus::Running
+        };
+    }
+
+    /// 使用快捷栏中的物品：法杖、投掷武器与灵能弓进入瞄准模式，其余物品与物品栏中的使用方式相同
+    fn use_quickslot(world: &World, resources: &mut Resources, index: usize) {
+        let Some(player) = find_player_entity(world) else {
+            return;
+        };
+        let Some(entry) = world
+            .get::<&Quickslot>(player)
+            .ok()
+            .and_then(|quickslot| quickslot.entry(index).cloned())
+        else {
+            resources.game_state.message_log.push(format!("快捷栏第 {} 格是空的", index + 1));
+            return;
+        };
+        let found = world.get::<&Quickslot>(player).ok().and_then(|quickslot| {
+            let inventory = world.get::<&Inventory>(player).ok()?;
+            let slot = quickslot.resolve(&inventory, index)?;
+            let item = inventory.items[slot].item.as_ref()?.item.clone();
+            Some((slot, item))
+        });
+        let Some((slot, item)) = found else {
+            resources.game_state.message_log.push(format!("{}已经用完了", entry.name));
+            return;
+        };
+
+        let usage = items::Usable::usage(&item);
+        match (item.kind, usage) {
+            (items::ItemKind::Wand(_), _) | (_, Some(items::ItemUsage::Zap)) => {
+                Self::begin_targeting(world, resources, TargetingPurpose::Zap(slot))
+            }
+            (items::ItemKind::Throwable(_), _) | (_, Some(items::ItemUsage::Shoot)) => {
+                Self::begin_targeting(world, resources, TargetingPurpose::Throw(slot))
+            }
+            _ => Self::use_selected_item(world, resources, slot),
+        }
+    }
+
+    /// 把物品栏中选中的物品放入快捷栏；已经在快捷栏中的物品则移出
+    fn assign_quickslot(world: &mut World, resources: &mut Resources, slot: usize) {
+        let Some(player) = find_player_entity(world) else {
+            return;
+        };
+        let Some(item) = world.get::<&Inventory>(player).ok().and_then(|inventory| {
+            Some(inventory.items.get(slot)?.item.as_ref()?.item.clone())
+        }) else {
+            return;
+        };
+        let Some(key) = Quickslot::key_of(&item) else {
+            resources.game_state.message_log.push(format!("{}不能放入快捷栏", item.name));
+            return;
+        };
+        if world.get::<&Quickslot>(player).is_err() {
+            let _ = world.insert_one(player, Quickslot::default());
+        }
+        let Ok(mut quickslot) = world.get::<&mut Quickslot>(player) else {
+            return;
+        };
+
+        let message = if let Some(index) = quickslot.index_of(&key) {
+            quickslot.clear(index);
+            format!("{}移出了快捷栏", item.name)
+        } else {
+            match quickslot.assign(QuickslotEntry { key, name: item.name.clone() }) {
+                Some(index) => format!("{}放入了快捷栏第 {} 格", item.name, index + 1),
+                None => "快捷栏已满".to_string(),
+            }
+        };
+        resources.game_state.message_log.push(message);
+    }
+
+    /// 把背包格子中的一堆物品拆成两堆，新的一堆紧跟在原格子之后
+    fn split_stack(world: &mut World, resources: &mut Resources, slot: usize) {
+        let Some(player) = find_player_entity(world) else {
+            return;
+        };
+        let Ok(mut inventory) = world.get::<&mut Inventory>(player) else {
+            return;
+        };
+        let Some((name, quantity)) = inventory
+            .items
+            .get(slot)
+            .and_then(|entry| Some((entry.item.as_ref()?.name.clone(), entry.quantity)))
+        else {
+            return;
+        };
+
+        let message = if quantity < 2 {
+            format!("{}只有一个，无法拆分", name)
+        } else if inventory.split_stack(slot) {
+            format!("把{}分成了两堆", name)
+        } else {
+            "背包已满，无法拆分".to_string()
+        };
+        resources.game_state.message_log.push(message);
+    }
+
+    /// 物品栏导航：上下在当前分类中按排序选择物品，左右切换分类标签页
+    fn navigate_inventory(world: &World, resources: &mut Resources, direction: NavigateDirection) {
+        let tabs = find_player_entity(world)
+            .and_then(|player| world.get::<&Inventory>(player).ok().map(|inventory| inventory.category_tabs()))
+            .unwrap_or_default();
+        let (GameStatus::Inventory { selected_item } | GameStatus::ItemPicker { selected_item, .. }) =
+            &mut resources.game_state.game_state
+        else {
+            return;
+        };
+        if tabs.is_empty() {
+            return;
+        }
+
+        let (tab, position) = tabs
+            .iter()
+            .enumerate()
+            .find_map(|(tab, (_, slots))| slots.iter().position(|s| s == selected_item).map(|p| (tab, p)))
+            .unwrap_or((0, 0));
+        let slots = &tabs[tab].1;
+        *selected_item = match direction {
+            NavigateDirection::Up => slots[position.saturating_sub(1)],
+            NavigateDirection::Down => slots[(position + 1).min(slots.len() - 1)],
+            NavigateDirection::PageUp => slots[0],
+            NavigateDirection::PageDown => slots[slots.len() - 1],
+            NavigateDirection::Left => tabs[(tab + tabs.len() - 1) % tabs.len()].1[0],
+            NavigateDirection::Right => tabs[(tab + 1) % tabs.len()].1[0],
+        };
+    }
+
+    /// 炼金界面导航：上下选择材料，向左取出最后投入的材料
+    fn navigate_alchemy(world: &World, resources: &mut Resources, direction: NavigateDirection) {
+        let count = find_player_entity(world)
+            .map(|player| InventorySystem::alchemy_slots(world, player).len())
+            .unwrap_or(0);
+        if let GameStatus::Alchemy {
+            ref mut selected_item,
+            ref mut chosen,
+        } = resources.game_state.game_state
+        {
+            match direction {
+                NavigateDirection::Up => *selected_item = selected_item.saturating_sub(1),
+                NavigateDirection::Down => {
+                    *selected_item = (*selected_item + 1).min(count.saturating_sub(1));
+                }
+                NavigateDirection::Left => {
+                    if let Some(last) = chosen.iter_mut().rev().find(|slot| slot.is_some()) {
+                        *last = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 拾取选择导航：在脚下的物品堆中上下选择
+    fn navigate_pile(world: &World, resources: &mut Resources, direction: NavigateDirection) {
+        let count = find_player_entity(world)
+            .and_then(|player| world.get::<&Position>(player).ok().map(|pos| (*pos).clone()))
+            .map(|pos| InventorySystem::ground_items_at(world, &pos).len())
+            .unwrap_or(0);
+        if let GameStatus::PickupChooser { ref mut selected_item } = resources.game_state.game_state {
+            match direction {
+                NavigateDirection::Up => *selected_item = selected_item.saturating_sub(1),
+                NavigateDirection::Down => {
+                    *selected_item = (*selected_item + 1).min(count.saturating_sub(1));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 将选中的材料投入炼金釜（同一格子最多投入其数量那么多次）
+    fn add_alchemy_ingredient(world: &World, resources: &mut Resources) {
+        let Some(player) = find_player_entity(world) else {
+            return;
+        };
+        let GameStatus::Alchemy {
+            selected_item,
+            ref mut chosen,
+        } = resources.game_state.game_state
+        else {
+            return;
+        };
+        let Some(&slot) = InventorySystem::alchemy_slots(world, player).get(selected_item) else {
+            return;
+        };
+
+        let quantity = world
+            .get::<&Inventory>(player)
+            .ok()
+            .and_then(|inventory| inventory.items.get(slot).and_then(|s| s.item.as_ref()).map(|i| i.quantity))
+            .unwrap_or(0)
+            .max(1);
+        let used = chosen.iter().filter(|c| **c == Some(slot)).count() as u32;
+        let message = if used >= quantity {
+            Some("这件材料已经全部投入了")
+        } else if let Some(free) = chosen.iter_mut().find(|c| c.is_none()) {
+            *free = Some(slot);
+            None
+        } else {
+            Some("炼金釜已经装满了")
+        };
+        if let Some(message) = message {
+            resources.game_state.message_log.push(message.to_string());
+        }
+    }
+
+    /// 进入瞄准模式，光标初始位于最近的可见敌人
+    fn begin_targeting(world: &World, resources: &mut Resources, purpose: TargetingPurpose) {
+        let Some(player) = find_player_entity(world) else {
+            return;
+        };
+
+        let slot_item = |slot: usize| {
+            world
+                .get::<&Inventory>(player)
+                .ok()
+                .and_then(|inventory| inventory.items.get(slot).and_then(|s| s.item.clone()))
+        };
+        let invalid = match purpose {
+            TargetingPurpose::Throw(slot) => slot_item(slot).is_none().then_some("没有可投掷的物品"),
+            TargetingPurpose::Zap(slot) => crate::targeting::wand_in_slot(world, player, slot)
+                .is_none()
+                .then_some("这不是一根法杖"),
+            TargetingPurpose::Skill(index) => match crate::targeting::class_skill(world, player, index) {
+                None => Some("当前职业没有该技能"),
+                Some(skill) if !skill.requires_target => Some("该技能不需要目标"),
+                Some(_) => None,
+            },
+            TargetingPurpose::RangedAttack => None,
+        };
+        if let Some(reason) = invalid {
+            resources.game_state.message_log.push(reason.to_string());
+            return;
+        }
+
+        if let Some(cursor) = crate::targeting::initial_cursor(world, player) {
+            resources.game_state.game_state = GameStatus::Targeting {
+                purpose,
+                cursor_x: cursor.x,
+                cursor_y: cursor.y,
+            };
+        }
+    }
+
+    /// 确认瞄准目标，生成对应的玩家动作并返回游戏
+    fn confirm_target(world: &World, resources: &mut Resources) {
+        let GameStatus::Targeting {
+            purpose,
+            cursor_x,
+            cursor_y,
+        } = resources.game_state.game_state
+        else {
+            return;
+        };
+        let Some(player_pos) = find_player_entity(world)
+            .and_then(|player| world.get::<&Position>(player).ok().map(|p| (*p).clone()))
+        else {
+            return;
+        };
+        if cursor_x == player_pos.x && cursor_y == player_pos.y {
+            resources.game_state.message_log.push("请选择其他位置作为目标".to_string());
+            return;
+        }
+
+        let target = Position::new(cursor_x, cursor_y, player_pos.z);
+        let action = match purpose {
+            TargetingPurpose::Throw(slot) => PlayerAction::ThrowItemAt(slot, target),
+            TargetingPurpose::Zap(slot) => PlayerAction::ZapWand(slot, target),
+            TargetingPurpose::Skill(index) => PlayerAction::UseSkill(index, target),
+            TargetingPurpose::RangedAttack => PlayerAction::Attack(Position::new(
+                cursor_x - player_pos.x,
+                cursor_y - player_pos.y,
+                0,
+            )),
+        };
+        resources.input_buffer.pending_actions.push(action);
+        resources.game_state.game_state = GameStatus::Running;
+    }
+
+    /// 开始新游戏
+    pub fn start_new_game(resources: &mut Resources) {
+        resources.game_state.game_state = GameStatus::Running;
+        resources
+            .game_state
+            .message_log
+            .push("开始新游戏！".to_string());
+
+        // TODO: 这里应该调用游戏世界的初始化
+        // 但由于架构限制，可能需要在游戏循环中处理
+    }
+
+    /// 处理菜单导航
+    fn handle_menu_navigation(&self, resources: &mut Resources, direction: &NavigateDirection) {
+        match resources.game_state.game_state {
+            GameStatus::MainMenu {
+                ref mut selected_option,
+            } => {
+                // 主菜单导航（5个选项：开始游戏、继续游戏、游戏设置、帮助说明、退出游戏）
+                match direction {
+                    NavigateDirection::Up => {
+                        *selected_option = selected_option.saturating_sub(1);
+                    }
+                    NavigateDirection::Down => {
+                        *selected_option = (*selected_option + 1).min(4);
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::Paused {
+                ref mut selected_option,
+            } => {
+                // 暂停菜单导航（6个选项）
+                match direction {
+                    NavigateDirection::Up => {
+                        *selected_option = selected_option.saturating_sub(1);
+                    }
+                    NavigateDirection::Down => {
+                        *selected_option = (*selected_option + 1).min(5);
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::Options {
+                ref mut selected_option,
+            } => {
+                // 选项菜单导航
+                match direction {
+                    NavigateDirection::Up => {
+                        *selected_option = selected_option.saturating_sub(1);
+                    }
+                    NavigateDirection::Down => {
+                        *selected_option = (*selected_option + 1).min(4); // 5个选项
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::ClassSelection { ref mut cursor } => {
+                // 职业选择导航（4个职业：战士、法师、盗贼、女猎手）
+                match direction {
+                    NavigateDirection::Up => {
+                        *cursor = cursor.saturating_sub(1);
+                    }
+                    NavigateDirection::Down => {
+                        *cursor = (*cursor + 1).min(3);
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::ConfirmQuit {
+                ref mut selected_option,
+                ..
+            } => {
+                // 确认退出对话框的导航：在 0(是)/1(否) 之间切换
+                match direction {
+                    NavigateDirection::Left | NavigateDirection::Up => {
+                        *selected_option = 0;
+                    }
+                    NavigateDirection::Right | NavigateDirection::Down => {
+                        *selected_option = 1;
+                    }
+                    _ => {}
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    /// 处理菜单选择
+    fn handle_menu_selection(&self, resources: &mut Resources) {
+        match resources.game_state.game_state {
+            GameStatus::MainMenu { selected_option } => {
+                // 主菜单选择逻辑
+                match selected_option {
+                    0 => {
+                        // 开始新游戏 - 进入职业选择界面
+                        resources.game_state.game_state = GameStatus::ClassSelection { cursor: 0 };
+                    }
+                    1 => {
+                        // 继续游戏（TODO: 实现加载存档功能）
+                        resources
+                            .game_state
+                            .message_log
+                            .push("继续游戏功能暂未实现".to_string());
+                        MenuSystem::start_new_game(resources); // 临时：直接开始新游戏
+                    }
+                    2 => {
+                        // 游戏设置
+                        resources.game_state.game_state =
+                            GameStatus::Options { selected_option: 0 };
+                    }
+                    3 => {
+                        // 帮助说明
+                        resources.game_state.game_state = GameStatus::Help;
+                    }
+                    4 => {
+                        // 退出游戏
+                        resources.game_state.game_state = GameStatus::ConfirmQuit {
+                            return_to: crate::ecs::ReturnTo::MainMenu,
+                            selected_option: 1, // 默认选中"否"
+                        };
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::Paused { selected_option } => {
+                // 暂停菜单选择逻辑
+                match selected_option {
+                    0 => {
+                        // 继续游戏
+                        resources.game_state.game_state = GameStatus::Running;
+                    }
+                    1 => {
+                        // 物品栏
+                        resources.game_state.game_state =
+                            GameStatus::Inventory { selected_item: 0 };
+                    }
+                    2 => {
+                        // 角色信息
+                        resources.game_state.game_state = GameStatus::CharacterInfo;
+                    }
+                    3 => {
+                        // 游戏设置
+                        resources.game_state.game_state =
+                            GameStatus::Options { selected_option: 0 };
+                    }
+                    4 => {
+                        // 帮助说明
+                        resources.game_state.game_state = GameStatus::Help;
+                    }
+                    5 => {
+                        // 保存并退出
+                        resources.game_state.game_state = GameStatus::ConfirmQuit {
+                            return_to: crate::ecs::ReturnTo::MainMenu,
+                            selected_option: 1,
+                        };
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::Options { selected_option } => {
+                // 选项菜单选择逻辑
+                match selected_option {
+                    0 => {
+                        // 切换音效
+                        resources
+                            .game_state
+                            .message_log
+                            .push("音效切换功能暂未实现".to_string());
+                    }
+                    1 => {
+                        // 切换音乐
+                        resources
+                            .game_state
+                            .message_log
+                            .push("音乐切换功能暂未实现".to_string());
+                    }
+                    2 => {
+                        // 按键绑定
+                        resources
+                            .game_state
+                            .message_log
+                            .push("按键绑定功能暂未实现".to_string());
+                    }
+                    3 => {
+                        // 显示模式
+                        resources
+                            .game_state
+                            .message_log
+                            .push("显示模式切换功能暂未实现".to_string());
+                    }
+                    4 => {
+                        // 语言
+                        resources
+                            .game_state
+                            .message_log
+                            .push("语言切换功能暂未实现".to_string());
+                    }
+                    _ => {}
+                }
+            }
+
+            GameStatus::ClassSelection { cursor } => {
+                // 职业选择确认
+                let class = match cursor {
+                    0 => hero::class::Class::Warrior,
+                    1 => hero::class::Class::Mage,
+                    2 => hero::class::Class::Rogue,
+                    3 => hero::class::Class::Huntress,
+                    _ => hero::class::Class::Warrior,
+                };
+
+                // 存储选中的职业，用于后续初始化
+                resources.game_state.selected_class = Some(class.clone());
+                resources
+                    .game_state
+                    .message_log
+                    .push(format!("选择了职业：{}", class));
+
+                // 进入游戏状态（实际的初始化将在 game_loop 中处理）
+                MenuSystem::start_new_game(resources);
+            }
+
+            GameStatus::ConfirmQuit {
+                return_to,
+                selected_option,
+            } => {
+                // 确认退出：0=是，1=否
+                if selected_option == 0 {
+                    // 退出到 GameOver
+                    resources.game_state.game_state = GameStatus::GameOver {
+                        reason: GameOverReason::Quit,
+                    };
+                } else {
+                    // 返回原状态
+                    resources.game_state.game_state = match return_to {
+                        crate::ecs::ReturnTo::Running => GameStatus::Running,
+                        crate::ecs::ReturnTo::MainMenu => {
+                            GameStatus::MainMenu { selected_option: 0 }
+                        }
+                    };
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    /// 处理菜单返回
+    fn handle_menu_back(&self, resources: &mut Resources) {
+        match resources.game_state.game_state {
+            GameStatus::Help | GameStatus::CharacterInfo | GameStatus::ItemCatalog => {
+                // 从帮助/角色信息返回游戏
+                resources.game_state.game_state = GameStatus::Running;
+            }
+
+            GameStatus::Options { .. }
+            | GameStatus::Inventory { .. }
+            | GameStatus::Alchemy { .. }
+            | GameStatus::PickupChooser { .. } => {
+                // 从选项/物品栏/炼金釜/拾取选择返回游戏
+                resources.game_state.game_state = GameStatus::Running;
+            }
+
+            GameStatus::ItemPicker { source_slot, .. } => {
+                // 取消选择，回到物品栏
+                resources.game_state.game_state = GameStatus::Inventory {
+                    selected_item: source_slot,
+                };
+            }
+
+            GameStatus::ClassSelection { .. } => {
+                // 从职业选择返回主菜单
+                resources.game_state.game_state = GameStatus::MainMenu { selected_option: 0 };
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// ========== Boss 系统 ==========
+
+pub struct BossSystem;
+
+impl System for BossSystem {
+    fn name(&self) -> &str {
+        "BossSystem"
+    }
+
+    fn run(&mut self, world: &mut World, resources: &mut Resources) -> SystemResult {
+        use crate::ecs::{BossComponent, BossSkillComponent};
+
+        // 收集所有 Boss 实体及其信息
+        let boss_data: Vec<(
+            Entity,
+            combat::boss::BossType,
+            combat::boss::BossPhase,
+            u32,
+            u32,
+        )> = world
+            .query::<(&BossComponent, &Stats)>()
+            .iter()
+            .map(|(entity, (boss_comp, stats))| {
+                (
+                    entity,
+                    boss_comp.boss_type.clone(),
+                    boss_comp.current_phase.clone(),
+                    stats.hp,
+                    stats.max_hp,
+                )
+            })
+            .collect();
+
+        // 找到玩家位置
+        let player_pos = if let Some(player_entity) = find_player_entity(world) {
+            world
+                .get::<&Position>(player_entity)
+                .ok()
+                .map(|p| p.clone())
+        } else {
+            None
+        };
+
+        // 处理每个 Boss
+        for (boss_entity, boss_type, current_phase, hp, max_hp) in boss_data {
+            // 检查阶段转换
+            let hp_percent = hp as f32 / max_hp as f32;
+            let new_phase = combat::boss::BossPhase::from_health_percent(hp_percent);
+
+            if new_phase != current_phase {
+                // 更新阶段
+                if let Ok(mut boss_comp) = world.get::<&mut BossComponent>(boss_entity) {
+                    boss_comp.current_phase = new_phase.clone();
+                }
+
+                resources.game_state.message_log.push(format!(
+                    "{}进入了{:?}阶段！",
+                    boss_type.name(),
+                    new_phase
+                ));
+            }
+
+            // Boss AI：选择并使用技能
+            if let Some(player_pos) = &player_pos {
+                if let Ok(boss_pos) = world.get::<&Position>(boss_entity) {
+                    let distance = ((boss_pos.x - player_pos.x).pow(2) as f32
+                        + (boss_pos.y - player_pos.y).pow(2) as f32)
+                        .sqrt();
+
+                    // 根据 Boss 逻辑决定是否使用技能
+                    // 这里简化处理，实际应该检查冷却时间等
+                    if distance <= 10.0 {
+                        // 在攻击范围内，可能使用技能
+                        // 技能逻辑将在 CombatSystem 或专门的 BossSkillSystem 中处理
+                    }
+                }
+            }
+
+            // 更新技能冷却
+            if let Ok(mut skill_comp) = world.get::<&mut BossSkillComponent>(boss_entity) {
+                skill_comp.cooldowns.tick();
+            }
+        }
+
+        SystemResult::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::*;
+    use crate::turn_system::energy_costs;
+    use hero::class::{Class, SkillState};
+
+    fn create_test_world() -> (World, Resources) {
+        let mut world = World::new();
+        let resources = Resources::default();
+
+        // Create basic floor tiles
+        for x in 0..10 {
+            for y in 0..10 {
+                world.spawn((
+                    Position::new(x, y, 0),
+                    Tile {
+                        terrain_type: TerrainType::Floor,
+                        is_passable: true,
+                        blocks_sight: false,
+                        has_items: false,
+                        has_monster: false,
+                    },
+                ));
+            }
+        }
+
+        // Create walls around the perimeter
+        for x in 0..10 {
+            world.spawn((
+                Position::new(x, 0, 0),
+                Tile {
+                    terrain_type: TerrainType::Wall,
                     is_passable: false,
                     blocks_sight: true,
                     has_items: false,
@@ -4743,6 +7741,12 @@ mod tests {
         (world, resources)
     }
 
+    /// 带有 10x10 测试地图的 ECSWorld（事件总线等其余部分取默认值）
+    fn create_test_ecs_world() -> ECSWorld {
+        let (world, resources) = create_test_world();
+        ECSWorld { world, resources, ..ECSWorld::new() }
+    }
+
     fn create_player(world: &mut World, x: i32, y: i32) -> Entity {
         world.spawn((
             Position::new(x, y, 0),
@@ -4951,52 +7955,285 @@ mod tests {
         let player = create_player(&mut world, 5, 5);
         let enemy = create_enemy(&mut world, 5, 6);
         
-        // Create a combat intent
-        let player_pos = world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
-        let enemy_pos = world.get::<&Position>(enemy).map(|p| (*p).clone()).unwrap();
+        // Create a combat intent
+        let player_pos = world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
+        let enemy_pos = world.get::<&Position>(enemy).map(|p| (*p).clone()).unwrap();
+        
+        let intent = CombatIntent::new(player, enemy, player_pos, enemy_pos, true);
+        resources.combat_intents.push(intent);
+        
+        // Verify intent was queued
+        assert_eq!(resources.combat_intents.len(), 1);
+        assert_eq!(resources.combat_intents[0].attacker, player);
+        assert_eq!(resources.combat_intents[0].defender, enemy);
+        assert_eq!(resources.combat_intents[0].priority, 1000); // Player priority
+    }
+    
+    #[test]
+    fn test_combat_intent_priority_ordering() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        let enemy1 = create_enemy(&mut world, 5, 6);
+        let enemy2 = create_enemy(&mut world, 6, 5);
+        
+        let player_pos = world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
+        let enemy1_pos = world.get::<&Position>(enemy1).map(|p| (*p).clone()).unwrap();
+        let enemy2_pos = world.get::<&Position>(enemy2).map(|p| (*p).clone()).unwrap();
+        
+        // Queue AI intent first (lower priority)
+        let ai_intent = CombatIntent::new(enemy1, player, enemy1_pos, player_pos.clone(), false);
+        resources.combat_intents.push(ai_intent);
+        
+        // Queue player intent second (higher priority)
+        let player_intent = CombatIntent::new(player, enemy2, player_pos, enemy2_pos, true);
+        resources.combat_intents.push(player_intent);
+        
+        // Sort by priority (as CombatSystem does)
+        resources.combat_intents.sort_by(|a, b| b.priority.cmp(&a.priority));
+        
+        // Player intent should be first
+        assert_eq!(resources.combat_intents[0].is_player, true);
+        assert_eq!(resources.combat_intents[1].is_player, false);
+    }
+    
+    #[test]
+    fn test_multi_attacker_engagement() {
+        let mut ecs_world = ECSWorld::new();
+        
+        // Create player and multiple enemies
+        let player = ecs_world.world.spawn((
+            Player,
+            Actor {
+                name: "Player".to_string(),
+                faction: Faction::Player,
+            },
+            Stats {
+                hp: 100,
+                max_hp: 100,
+                attack: 10,
+                defense: 5,
+                accuracy: 10,
+                evasion: 5,
+                level: 1,
+                experience: 0,
+                class: None,
+            },
+            Position::new(5, 5, 0),
+            Energy { current: 100, max: 100, regeneration_rate: 10 },
+            Viewshed {
+                range: 8,
+                visible_tiles: vec![],
+                memory: vec![],
+                dirty: false,
+                algorithm: FovAlgorithm::RayCasting,
+            },
+        ));
+        
+        let enemy1 = ecs_world.world.spawn((
+            Actor {
+                name: "Goblin1".to_string(),
+                faction: Faction::Enemy,
+            },
+            Stats {
+                hp: 20,
+                max_hp: 20,
+                attack: 5,
+                defense: 2,
+                accuracy: 8,
+                evasion: 3,
+                level: 1,
+                experience: 5,
+                class: None,
+            },
+            Position::new(5, 6, 0),
+        ));
+        
+        let enemy2 = ecs_world.world.spawn((
+            Actor {
+                name: "Goblin2".to_string(),
+                faction: Faction::Enemy,
+            },
+            Stats {
+                hp: 20,
+                max_hp: 20,
+                attack: 5,
+                defense: 2,
+                accuracy: 8,
+                evasion: 3,
+                level: 1,
+                experience: 5,
+                class: None,
+            },
+            Position::new(6, 5, 0),
+        ));
+        
+        // Create floor tiles
+        ecs_world.world.spawn((
+            Position::new(5, 5, 0),
+            Tile {
+                terrain_type: TerrainType::Floor,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: false,
+                has_monster: false,
+            },
+        ));
+        
+        ecs_world.world.spawn((
+            Position::new(5, 6, 0),
+            Tile {
+                terrain_type: TerrainType::Floor,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: false,
+                has_monster: false,
+            },
+        ));
+        
+        ecs_world.world.spawn((
+            Position::new(6, 5, 0),
+            Tile {
+                terrain_type: TerrainType::Floor,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: false,
+                has_monster: false,
+            },
+        ));
+        
+        // Queue combat intents: both enemies attack player
+        let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
+        let enemy1_pos = ecs_world.world.get::<&Position>(enemy1).map(|p| (*p).clone()).unwrap();
+        let enemy2_pos = ecs_world.world.get::<&Position>(enemy2).map(|p| (*p).clone()).unwrap();
+        
+        ecs_world.resources.combat_intents.push(
+            CombatIntent::new(enemy1, player, enemy1_pos.clone(), player_pos.clone(), false)
+        );
+        ecs_world.resources.combat_intents.push(
+            CombatIntent::new(enemy2, player, enemy2_pos, player_pos, false)
+        );
+        
+        // Run combat system
+        CombatSystem::run_with_events(&mut ecs_world);
+        
+        // Verify player took damage from both attacks
+        let player_stats = ecs_world.world.get::<&Stats>(player).unwrap();
+        assert!(player_stats.hp < 100, "Player should have taken damage from multiple attackers");
+        
+        // Verify aftermath queue has events if enemies died
+        // (depends on combat resolution)
+    }
+    
+    #[test]
+    fn test_counterattack_mechanics() {
+        let mut ecs_world = ECSWorld::new();
+        
+        // Create player and enemy
+        let player = ecs_world.world.spawn((
+            Player,
+            Actor {
+                name: "Player".to_string(),
+                faction: Faction::Player,
+            },
+            Stats {
+                hp: 50,
+                max_hp: 50,
+                attack: 15,
+                defense: 5,
+                accuracy: 12,
+                evasion: 8,
+                level: 1,
+                experience: 0,
+                class: None,
+            },
+            Position::new(5, 5, 0),
+            Energy { current: 100, max: 100, regeneration_rate: 10 },
+            Viewshed {
+                range: 8,
+                visible_tiles: vec![],
+                memory: vec![],
+                dirty: false,
+                algorithm: FovAlgorithm::RayCasting,
+            },
+        ));
+        
+        let enemy = ecs_world.world.spawn((
+            Actor {
+                name: "Orc".to_string(),
+                faction: Faction::Enemy,
+            },
+            Stats {
+                hp: 30,
+                max_hp: 30,
+                attack: 10,
+                defense: 3,
+                accuracy: 10,
+                evasion: 5,
+                level: 1,
+                experience: 0,
+                class: None,
+            },
+            Position::new(5, 6, 0),
+        ));
+        
+        // Create floor tiles
+        ecs_world.world.spawn((
+            Position::new(5, 5, 0),
+            Tile {
+                terrain_type: TerrainType::Floor,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: false,
+                has_monster: false,
+            },
+        ));
         
-        let intent = CombatIntent::new(player, enemy, player_pos, enemy_pos, true);
-        resources.combat_intents.push(intent);
+        ecs_world.world.spawn((
+            Position::new(5, 6, 0),
+            Tile {
+                terrain_type: TerrainType::Floor,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: false,
+                has_monster: false,
+            },
+        ));
         
-        // Verify intent was queued
-        assert_eq!(resources.combat_intents.len(), 1);
-        assert_eq!(resources.combat_intents[0].attacker, player);
-        assert_eq!(resources.combat_intents[0].defender, enemy);
-        assert_eq!(resources.combat_intents[0].priority, 1000); // Player priority
-    }
-    
-    #[test]
-    fn test_combat_intent_priority_ordering() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-        let enemy1 = create_enemy(&mut world, 5, 6);
-        let enemy2 = create_enemy(&mut world, 6, 5);
+        // Queue player attacking enemy
+        let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
+        let enemy_pos = ecs_world.world.get::<&Position>(enemy).map(|p| (*p).clone()).unwrap();
         
-        let player_pos = world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
-        let enemy1_pos = world.get::<&Position>(enemy1).map(|p| (*p).clone()).unwrap();
-        let enemy2_pos = world.get::<&Position>(enemy2).map(|p| (*p).clone()).unwrap();
+        ecs_world.resources.combat_intents.push(
+            CombatIntent::new(player, enemy, player_pos, enemy_pos, true)
+        );
         
-        // Queue AI intent first (lower priority)
-        let ai_intent = CombatIntent::new(enemy1, player, enemy1_pos, player_pos.clone(), false);
-        resources.combat_intents.push(ai_intent);
+        // Get initial HP values
+        let initial_player_hp = ecs_world.world.get::<&Stats>(player).unwrap().hp;
+        let initial_enemy_hp = ecs_world.world.get::<&Stats>(enemy).unwrap().hp;
         
-        // Queue player intent second (higher priority)
-        let player_intent = CombatIntent::new(player, enemy2, player_pos, enemy2_pos, true);
-        resources.combat_intents.push(player_intent);
+        // Run combat system
+        CombatSystem::run_with_events(&mut ecs_world);
         
-        // Sort by priority (as CombatSystem does)
-        resources.combat_intents.sort_by(|a, b| b.priority.cmp(&a.priority));
+        // Process events to apply combat effects
+        ecs_world.process_events();
         
-        // Player intent should be first
-        assert_eq!(resources.combat_intents[0].is_player, true);
-        assert_eq!(resources.combat_intents[1].is_player, false);
+        // Get final HP values
+        let final_player_hp = ecs_world.world.get::<&Stats>(player).unwrap().hp;
+        let final_enemy_hp = ecs_world.world.get::<&Stats>(enemy).unwrap().hp;
+        
+        // Verify combat occurred (HP should change)
+        // Note: The combat module includes counterattack logic
+        assert!(
+            final_player_hp != initial_player_hp || final_enemy_hp != initial_enemy_hp,
+            "Combat should have caused HP changes"
+        );
     }
     
     #[test]
-    fn test_multi_attacker_engagement() {
+    fn test_critical_hit_detection() {
         let mut ecs_world = ECSWorld::new();
         
-        // Create player and multiple enemies
+        // Create player with high crit chance (though SimpleCombatant has 0 crit bonus)
         let player = ecs_world.world.spawn((
             Player,
             Actor {
@@ -5006,9 +8243,9 @@ mod tests {
             Stats {
                 hp: 100,
                 max_hp: 100,
-                attack: 10,
+                attack: 20,
                 defense: 5,
-                accuracy: 10,
+                accuracy: 15,
                 evasion: 5,
                 level: 1,
                 experience: 0,
@@ -5025,14 +8262,14 @@ mod tests {
             },
         ));
         
-        let enemy1 = ecs_world.world.spawn((
+        let enemy = ecs_world.world.spawn((
             Actor {
-                name: "Goblin1".to_string(),
+                name: "Target".to_string(),
                 faction: Faction::Enemy,
             },
             Stats {
-                hp: 20,
-                max_hp: 20,
+                hp: 50,
+                max_hp: 50,
                 attack: 5,
                 defense: 2,
                 accuracy: 8,
@@ -5044,13 +8281,54 @@ mod tests {
             Position::new(5, 6, 0),
         ));
         
-        let enemy2 = ecs_world.world.spawn((
+        // Create floor tiles
+        for x in 4..=6 {
+            for y in 4..=7 {
+                ecs_world.world.spawn((
+                    Position::new(x, y, 0),
+                    Tile {
+                        terrain_type: TerrainType::Floor,
+                        is_passable: true,
+                        blocks_sight: false,
+                        has_items: false,
+                        has_monster: false,
+                    },
+                ));
+            }
+        }
+        
+        // Perform multiple attacks to test for critical hits
+        for _ in 0..10 {
+            let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
+            let enemy_pos = ecs_world.world.get::<&Position>(enemy).map(|p| (*p).clone()).unwrap();
+            
+            // Reset enemy HP for each test
+            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(enemy) {
+                stats.hp = 50;
+            }
+            
+            ecs_world.resources.combat_intents.push(
+                CombatIntent::new(player, enemy, player_pos, enemy_pos, true)
+            );
+            
+            CombatSystem::run_with_events(&mut ecs_world);
+            
+            // Check if any CombatHit events were published with is_critical = true
+            // This would require inspecting the event bus
+        }
+    }
+    
+    #[test]
+    fn test_aftermath_death_handling() {
+        let mut ecs_world = ECSWorld::new();
+        
+        let enemy = ecs_world.world.spawn((
             Actor {
-                name: "Goblin2".to_string(),
+                name: "Goblin".to_string(),
                 faction: Faction::Enemy,
             },
             Stats {
-                hp: 20,
+                hp: 0, // Already dead
                 max_hp: 20,
                 attack: 5,
                 defense: 2,
@@ -5060,71 +8338,182 @@ mod tests {
                 experience: 5,
                 class: None,
             },
-            Position::new(6, 5, 0),
-        ));
-        
-        // Create floor tiles
-        ecs_world.world.spawn((
-            Position::new(5, 5, 0),
-            Tile {
-                terrain_type: TerrainType::Floor,
-                is_passable: true,
-                blocks_sight: false,
-                has_items: false,
-                has_monster: false,
-            },
-        ));
-        
-        ecs_world.world.spawn((
             Position::new(5, 6, 0),
-            Tile {
-                terrain_type: TerrainType::Floor,
-                is_passable: true,
-                blocks_sight: false,
-                has_items: false,
-                has_monster: false,
-            },
         ));
         
-        ecs_world.world.spawn((
-            Position::new(6, 5, 0),
-            Tile {
-                terrain_type: TerrainType::Floor,
-                is_passable: true,
-                blocks_sight: false,
-                has_items: false,
-                has_monster: false,
-            },
-        ));
+        // Queue death event
+        ecs_world.resources.aftermath_queue.push(AftermathEvent::Death {
+            entity: enemy,
+            entity_id: enemy.id(),
+            entity_name: "Goblin".to_string(),
+            killer: None,
+        });
         
-        // Queue combat intents: both enemies attack player
-        let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
-        let enemy1_pos = ecs_world.world.get::<&Position>(enemy1).map(|p| (*p).clone()).unwrap();
-        let enemy2_pos = ecs_world.world.get::<&Position>(enemy2).map(|p| (*p).clone()).unwrap();
+        // Run aftermath system
+        AftermathSystem::run_with_events(&mut ecs_world);
         
-        ecs_world.resources.combat_intents.push(
-            CombatIntent::new(enemy1, player, enemy1_pos.clone(), player_pos.clone(), false)
-        );
-        ecs_world.resources.combat_intents.push(
-            CombatIntent::new(enemy2, player, enemy2_pos, player_pos, false)
+        // Verify entity was despawned
+        assert!(ecs_world.world.get::<&Actor>(enemy).is_err(), "Dead enemy should be despawned");
+    }
+    
+    #[test]
+    fn test_aftermath_experience_gain() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+
+        // 1 级升 2 级需要 10 点，2 级升 3 级需要 15 点
+        ecs_world.resources.aftermath_queue.push(AftermathEvent::ExperienceGain {
+            entity: player,
+            amount: 27,
+        });
+        AftermathSystem::run_with_events(&mut ecs_world);
+        ecs_world.process_events();
+
+        let stats = ecs_world.world.get::<&Stats>(player).unwrap();
+        assert_eq!((stats.level, stats.experience), (3, 2));
+        assert!(ecs_world.resources.game_state.message_log.iter().any(|m| m.contains("升到了 3 级")));
+    }
+
+    #[test]
+    fn test_kills_award_experience_diminishing_with_level_gap() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 4);
+
+        CombatSystem::deal_direct_damage(&mut ecs_world, player, enemy, 30);
+        AftermathSystem::run_with_events(&mut ecs_world);
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().level, 2);
+
+        assert_eq!(crate::ecs::kill_experience(10, 3, 1), 10);
+        assert_eq!(crate::ecs::kill_experience(10, 5, 1), 6);
+        assert_eq!(crate::ecs::kill_experience(10, 8, 1), 0);
+    }
+
+    #[test]
+    fn test_status_effect_deaths_award_experience_and_loot() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 4);
+        ecs_world.world.get::<&mut Stats>(enemy).unwrap().hp = 1;
+        let mut effects = StatusEffects::new();
+        effects.add_effect(::combat::effect::Effect::new(EffectType::Burning, 3));
+        ecs_world
+            .world
+            .insert(enemy, (effects, LootTable { kind: ::combat::enemy::EnemyKind::Rat }))
+            .unwrap();
+
+        ecs_world.resources.clock.turn_count = 1;
+        EffectSystem::run_with_events(&mut ecs_world, EffectPhase::EndOfTurn);
+        AftermathSystem::run_with_events(&mut ecs_world);
+        assert!(ecs_world.world.get::<&Stats>(enemy).is_err());
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().level, 2);
+        assert!(ecs_world.world.query::<(&Position, &ECSItem)>().iter().any(|(_, (pos, _))| (pos.x, pos.y) == (5, 4)));
+    }
+
+    #[test]
+    fn test_enemies_drop_gold_where_they_die() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 4);
+        ecs_world
+            .world
+            .insert_one(enemy, LootTable { kind: ::combat::enemy::EnemyKind::Rat })
+            .unwrap();
+
+        CombatSystem::deal_direct_damage(&mut ecs_world, player, enemy, 30);
+        AftermathSystem::run_with_events(&mut ecs_world);
+        let gold_dropped = ecs_world
+            .world
+            .query::<(&Position, &ECSItem)>()
+            .iter()
+            .any(|(_, (pos, item))| {
+                *pos == Position::new(5, 4, 0)
+                    && matches!(&item.kind, ::items::ItemKind::Misc(misc) if matches!(misc.kind, ::items::MiscKind::Gold(_)))
+            });
+        assert!(gold_dropped);
+        assert!(ecs_world.world.get::<&Stats>(enemy).is_err());
+    }
+
+    #[test]
+    fn test_wealth_bonus_raises_rare_drop_rate() {
+        use rand::SeedableRng;
+
+        // 金币之外的掉落都算稀有掉落
+        let rare_drops = |luck: f32| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+            (0..2000)
+                .map(|_| crate::loot::enemy_drops(::combat::enemy::EnemyKind::Gnoll, 1, luck, &mut rng).len() - 1)
+                .sum::<usize>()
+        };
+        assert!(rare_drops(1.0) > rare_drops(0.0));
+    }
+
+    #[test]
+    fn test_loot_is_reproducible_from_seed() {
+        use rand::SeedableRng;
+        use ::combat::boss::BossType;
+
+        let roll = || {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+            let mut drops = crate::loot::boss_drops(BossType::AbyssalLord, 20, 0.5, &mut rng);
+            for _ in 0..50 {
+                drops.extend(crate::loot::enemy_drops(::combat::enemy::EnemyKind::Warlock, 12, 0.5, &mut rng));
+            }
+            drops
+        };
+        assert_eq!(roll(), roll());
+    }
+
+    #[test]
+    fn test_boss_drops_gold_equipment_and_unique_weapon() {
+        use rand::SeedableRng;
+        use ::combat::boss::BossType;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let drops = crate::loot::boss_drops(BossType::GiantOgre, 5, 0.0, &mut rng);
+
+        // 第一件是金币，最后一件是不带诅咒、强化过的稀有武器
+        assert!(matches!(&drops[0].kind, ::items::ItemKind::Misc(misc) if matches!(misc.kind, ::items::MiscKind::Gold(100..))));
+        match &drops.last().unwrap().kind {
+            ::items::ItemKind::Weapon(weapon) => {
+                assert!(!weapon.cursed);
+                assert!(weapon.upgrade_level >= 1);
+            }
+            other => panic!("expected a weapon, got {:?}", other),
+        }
+        // 金币、1-2 件装备、2-4 件消耗品与稀有武器
+        assert!((5..=8).contains(&drops.len()));
+    }
+
+    #[test]
+    fn test_walking_onto_gold_adds_to_wealth() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        ecs_world.world.insert_one(player, Wealth::default()).unwrap();
+        let gold = |amount| ECSItem::new(::items::MiscItem::new(::items::MiscKind::Gold(amount)).into());
+        InventorySystem::spawn_ground_item(&mut ecs_world, &Position::new(5, 4, 0), gold(30));
+        InventorySystem::spawn_ground_item(&mut ecs_world, &Position::new(5, 4, 0), gold(12));
+
+        // 走到金币上自动拾起，不占背包格子
+        MovementSystem::execute_movement_with_events(
+            &mut ecs_world,
+            player,
+            &Position::new(5, 5, 0),
+            &Position::new(5, 4, 0),
         );
-        
-        // Run combat system
-        CombatSystem::run_with_events(&mut ecs_world);
-        
-        // Verify player took damage from both attacks
-        let player_stats = ecs_world.world.get::<&Stats>(player).unwrap();
-        assert!(player_stats.hp < 100, "Player should have taken damage from multiple attackers");
-        
-        // Verify aftermath queue has events if enemies died
-        // (depends on combat resolution)
+        ecs_world.process_events();
+        assert_eq!(ecs_world.world.get::<&Wealth>(player).unwrap().gold, 42);
+        assert!(ecs_world.world.get::<&Inventory>(player).unwrap().items.is_empty());
+        assert!(InventorySystem::ground_items_at(&ecs_world.world, &Position::new(5, 4, 0)).is_empty());
+
+        // 收集的金币计入成就进度
+        assert_eq!(ecs_world.resources.achievements.progress().gold_collected, 42);
     }
-    
+
     #[test]
-    fn test_counterattack_mechanics() {
+    fn test_combat_intent_skips_dead_entities() {
         let mut ecs_world = ECSWorld::new();
         
-        // Create player and enemy
         let player = ecs_world.world.spawn((
             Player,
             Actor {
@@ -5132,640 +8521,1746 @@ mod tests {
                 faction: Faction::Player,
             },
             Stats {
-                hp: 50,
-                max_hp: 50,
-                attack: 15,
+                hp: 100,
+                max_hp: 100,
+                attack: 10,
                 defense: 5,
-                accuracy: 12,
-                evasion: 8,
+                accuracy: 10,
+                evasion: 5,
                 level: 1,
                 experience: 0,
                 class: None,
             },
             Position::new(5, 5, 0),
-            Energy { current: 100, max: 100, regeneration_rate: 10 },
-            Viewshed {
-                range: 8,
-                visible_tiles: vec![],
-                memory: vec![],
-                dirty: false,
-                algorithm: FovAlgorithm::RayCasting,
-            },
         ));
         
-        let enemy = ecs_world.world.spawn((
+        let dead_enemy = ecs_world.world.spawn((
             Actor {
-                name: "Orc".to_string(),
+                name: "DeadGoblin".to_string(),
                 faction: Faction::Enemy,
             },
             Stats {
-                hp: 30,
-                max_hp: 30,
-                attack: 10,
-                defense: 3,
-                accuracy: 10,
-                evasion: 5,
+                hp: 0, // Already dead
+                max_hp: 20,
+                attack: 5,
+                defense: 2,
+                accuracy: 8,
+                evasion: 3,
                 level: 1,
-                experience: 0,
+                experience: 5,
                 class: None,
             },
             Position::new(5, 6, 0),
         ));
         
-        // Create floor tiles
-        ecs_world.world.spawn((
+        // Queue combat intent with dead enemy
+        let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
+        let enemy_pos = ecs_world.world.get::<&Position>(dead_enemy).map(|p| (*p).clone()).unwrap();
+        
+        ecs_world.resources.combat_intents.push(
+            CombatIntent::new(player, dead_enemy, player_pos, enemy_pos, true)
+        );
+        
+        // Run combat system
+        CombatSystem::run_with_events(&mut ecs_world);
+        
+        // Verify player HP didn't change (no combat occurred)
+        let player_stats = ecs_world.world.get::<&Stats>(player).unwrap();
+        assert_eq!(player_stats.hp, 100, "Player shouldn't take damage from dead enemy");
+    }
+
+    #[test]
+    fn test_trap_detection() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+
+        // Place a trap at (5, 6)
+        world.spawn((
+            Position::new(5, 6, 0),
+            Tile {
+                terrain_type: TerrainType::Trap,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: false,
+                has_monster: false,
+            },
+        ));
+
+        let initial_hp = world.get::<&Stats>(player).unwrap().hp;
+
+        // Move south onto the trap
+        resources.input_buffer.pending_actions.push(PlayerAction::Move(Direction::South));
+        let mut system = MovementSystem;
+        system.run(&mut world, &mut resources);
+
+        // Check that damage was applied
+        let current_hp = world.get::<&Stats>(player).unwrap().hp;
+        assert!(current_hp < initial_hp);
+
+        // Check that a trap message was logged
+        let has_trap_message = resources.game_state.message_log.iter()
+            .any(|msg| msg.contains("陷阱"));
+        assert!(has_trap_message);
+    }
+
+    #[test]
+    fn test_item_detection() {
+        let (mut world, mut resources) = create_test_world();
+        let _player = create_player(&mut world, 5, 5);
+
+        // Place an item at (5, 6)
+        world.spawn((
+            Position::new(5, 6, 0),
+            named_item(
+                "Health Potion",
+                ::items::ItemKind::Potion(::items::Potion::new_alchemy(::items::potion::PotionKind::Healing)),
+            ),
+            Tile {
+                terrain_type: TerrainType::Floor,
+                is_passable: true,
+                blocks_sight: false,
+                has_items: true,
+                has_monster: false,
+            },
+        ));
+
+        // Move south onto the item
+        resources.input_buffer.pending_actions.push(PlayerAction::Move(Direction::South));
+        let mut system = MovementSystem;
+        system.run(&mut world, &mut resources);
+
+        // Check that an item message was logged
+        let has_item_message = resources.game_state.message_log.iter()
+            .any(|msg| msg.contains("Health Potion"));
+        assert!(has_item_message);
+    }
+    
+    // ============ Inventory Tests ============
+    //
+    // Note: These tests use the legacy InventorySystem::run() method.
+    // To use the new event-integrated version, call InventorySystem::run_with_events()
+    // which properly marks actions as completed and publishes events.
+    
+    #[test]
+    fn test_use_healing_potion() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        
+        // Damage the player first
+        if let Ok(mut stats) = world.get::<&mut Stats>(player) {
+            stats.hp = 50; // Down from 100
+        }
+        
+        // Add a healing potion to inventory
+        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
+            inventory.items.push(ItemSlot {
+                item: Some(named_item(
+                    "Healing Potion",
+                    ::items::ItemKind::Potion(::items::Potion::new_alchemy(::items::potion::PotionKind::Healing)),
+                )),
+                quantity: 1,
+            });
+        }
+        
+        // Use the potion (slot 0)
+        resources.input_buffer.pending_actions.push(PlayerAction::UseItem(0));
+        
+        let mut system = InventorySystem;
+        system.run(&mut world, &mut resources);
+        
+        // Check that HP was restored
+        let stats = world.get::<&Stats>(player).unwrap();
+        assert_eq!(stats.hp, stats.max_hp); // 治疗药水完全恢复生命
+        
+        // Check that the potion was removed from inventory
+        let inventory = world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 0);
+        
+        // Check that action was not marked as completed (old system doesn't do this)
+        // This would be tested with run_with_events
+    }
+    
+    #[test]
+    fn test_drop_item() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        
+        // Add an item to inventory
+        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
+            inventory.items.push(ItemSlot {
+                item: Some(named_item(
+                    "Sword",
+                    ::items::ItemKind::Weapon(::items::Weapon::new(1, ::items::weapon::WeaponKind::Sword)),
+                )),
+                quantity: 1,
+            });
+        }
+        
+        // Drop the item (slot 0)
+        resources.input_buffer.pending_actions.push(PlayerAction::DropItem(0));
+        
+        let mut system = InventorySystem;
+        system.run(&mut world, &mut resources);
+        
+        // The item leaves the inventory and stays on the floor until picked up with `g`
+        assert!(world.get::<&Inventory>(player).unwrap().items.is_empty());
+        let dropped: Vec<String> = world
+            .query::<(&Position, &ECSItem)>()
+            .iter()
+            .filter(|(_, (pos, _))| pos.x == 5 && pos.y == 5)
+            .map(|(_, (_, item))| item.name.clone())
+            .collect();
+        assert_eq!(dropped, vec!["Sword".to_string()]);
+
+        // Picking it up again puts it back into the inventory
+        resources.input_buffer.pending_actions.push(PlayerAction::PickUp);
+        system.run(&mut world, &mut resources);
+        let inventory = world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 1);
+        assert_eq!(inventory.items[0].item.as_ref().unwrap().name, "Sword");
+    }
+    
+    #[test]
+    fn test_pick_up_single_item() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        
+        // Place an item at the player's position
+        world.spawn((
             Position::new(5, 5, 0),
+            named_item(
+                "Gold Coin",
+                ::items::ItemKind::Misc(::items::MiscItem::new(::items::MiscKind::Gold(1))),
+            ),
             Tile {
                 terrain_type: TerrainType::Floor,
                 is_passable: true,
                 blocks_sight: false,
-                has_items: false,
+                has_items: true,
                 has_monster: false,
             },
         ));
         
-        ecs_world.world.spawn((
-            Position::new(5, 6, 0),
+        // Standing on the item does nothing until it is picked up with `g`
+        let mut system = InventorySystem;
+        system.run(&mut world, &mut resources);
+        assert!(world.get::<&Inventory>(player).unwrap().items.is_empty());
+
+        resources.input_buffer.pending_actions.push(PlayerAction::PickUp);
+        system.run(&mut world, &mut resources);
+        
+        // Check that the item was picked up
+        let inventory = world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 1);
+        assert_eq!(inventory.items[0].item.as_ref().unwrap().name, "Gold Coin");
+        
+        // Check that the item entity was despawned
+        let item_count = world.query::<(&Position, &ECSItem)>()
+            .iter()
+            .filter(|(_, (pos, _))| pos.x == 5 && pos.y == 5)
+            .count();
+        assert_eq!(item_count, 0);
+    }
+    
+    #[test]
+    fn test_inventory_full() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        
+        // Fill up the inventory
+        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
+            inventory.max_slots = 2; // Small inventory for testing
+            for i in 0..2 {
+                inventory.items.push(ItemSlot {
+                    item: Some(named_item(
+                        &format!("Item {}", i),
+                        ::items::ItemKind::Misc(::items::MiscItem::new(::items::MiscKind::Other)),
+                    )),
+                    quantity: 1,
+                });
+            }
+        }
+        
+        // Place an item at the player's position
+        world.spawn((
+            Position::new(5, 5, 0),
+            named_item(
+                "Extra Item",
+                ::items::ItemKind::Misc(::items::MiscItem::new(::items::MiscKind::Other)),
+            ),
             Tile {
                 terrain_type: TerrainType::Floor,
                 is_passable: true,
                 blocks_sight: false,
-                has_items: false,
+                has_items: true,
                 has_monster: false,
             },
         ));
         
-        // Queue player attacking enemy
-        let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
-        let enemy_pos = ecs_world.world.get::<&Position>(enemy).map(|p| (*p).clone()).unwrap();
+        // Try to pick it up
+        resources.input_buffer.pending_actions.push(PlayerAction::PickUp);
+        let mut system = InventorySystem;
+        system.run(&mut world, &mut resources);
         
-        ecs_world.resources.combat_intents.push(
-            CombatIntent::new(player, enemy, player_pos, enemy_pos, true)
-        );
+        // Check that inventory is still full
+        let inventory = world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 2);
+        
+        // Check that the item is still on the ground
+        let item_count = world.query::<(&Position, &ECSItem)>()
+            .iter()
+            .filter(|(_, (pos, item))| pos.x == 5 && pos.y == 5 && item.name == "Extra Item")
+            .count();
+        assert_eq!(item_count, 1);
         
-        // Get initial HP values
-        let initial_player_hp = ecs_world.world.get::<&Stats>(player).unwrap().hp;
-        let initial_enemy_hp = ecs_world.world.get::<&Stats>(enemy).unwrap().hp;
+        // Check that a "full inventory" message was logged
+        let has_full_message = resources.game_state.message_log.iter()
+            .any(|msg| msg.contains("full"));
+        assert!(has_full_message);
+    }
+    
+    #[test]
+    fn test_use_teleport_scroll() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
         
-        // Run combat system
-        CombatSystem::run_with_events(&mut ecs_world);
+        // Add a teleport scroll to inventory
+        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
+            inventory.items.push(ItemSlot {
+                item: Some(named_item(
+                    "Scroll of Teleportation",
+                    ::items::ItemKind::Scroll(::items::Scroll::new(::items::scroll::ScrollKind::Teleportation)),
+                )),
+                quantity: 1,
+            });
+        }
         
-        // Process events to apply combat effects
-        ecs_world.process_events();
+        // Use the scroll (slot 0)
+        resources.input_buffer.pending_actions.push(PlayerAction::UseItem(0));
         
-        // Get final HP values
-        let final_player_hp = ecs_world.world.get::<&Stats>(player).unwrap().hp;
-        let final_enemy_hp = ecs_world.world.get::<&Stats>(enemy).unwrap().hp;
+        let mut system = InventorySystem;
+        system.run(&mut world, &mut resources);
         
-        // Verify combat occurred (HP should change)
-        // Note: The combat module includes counterattack logic
-        assert!(
-            final_player_hp != initial_player_hp || final_enemy_hp != initial_enemy_hp,
-            "Combat should have caused HP changes"
+        // Check that player position changed
+        let pos = world.get::<&Position>(player).unwrap();
+        assert!(pos.x != 5 || pos.y != 5); // Position should have changed
+        
+        // Check that the scroll was removed from inventory
+        let inventory = world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 0);
+    }
+
+    fn enchanted_weapon(enchant: ::items::weapon::WeaponEnhance, cursed: bool) -> ::items::Weapon {
+        let mut weapon = ::items::Weapon::new(1, ::items::weapon::WeaponKind::Sword);
+        weapon.enchanted = Some(enchant);
+        weapon.cursed = cursed;
+        weapon
+    }
+
+    #[test]
+    fn test_grim_enchantment_executes_low_hp_target() {
+        let mut ecs_world = ECSWorld::new();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 6);
+        let weapon = enchanted_weapon(::items::weapon::WeaponEnhance::Grim, false);
+
+        let mut att_stats = (*ecs_world.world.get::<&Stats>(player).unwrap()).clone();
+        let mut def_stats = (*ecs_world.world.get::<&Stats>(enemy).unwrap()).clone();
+        def_stats.hp = 5; // 低于 20% 最大生命
+
+        CombatSystem::apply_weapon_enchantment(
+            &mut ecs_world,
+            &weapon,
+            (player, &mut att_stats),
+            (enemy, &mut def_stats),
+            3,
         );
+        assert_eq!(def_stats.hp, 0);
+
+        // 诅咒的致命武器反而治疗目标
+        let cursed = enchanted_weapon(::items::weapon::WeaponEnhance::Grim, true);
+        def_stats.hp = 5;
+        CombatSystem::apply_weapon_enchantment(
+            &mut ecs_world,
+            &cursed,
+            (player, &mut att_stats),
+            (enemy, &mut def_stats),
+            3,
+        );
+        assert_eq!(def_stats.hp, 8);
     }
-    
+
     #[test]
-    fn test_critical_hit_detection() {
+    fn test_vampiric_enchantment_heals_wielder() {
         let mut ecs_world = ECSWorld::new();
-        
-        // Create player with high crit chance (though SimpleCombatant has 0 crit bonus)
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 6);
+        let weapon = enchanted_weapon(::items::weapon::WeaponEnhance::Vampiric, false);
+
+        let mut att_stats = (*ecs_world.world.get::<&Stats>(player).unwrap()).clone();
+        let mut def_stats = (*ecs_world.world.get::<&Stats>(enemy).unwrap()).clone();
+        att_stats.hp = 50;
+
+        CombatSystem::apply_weapon_enchantment(
+            &mut ecs_world,
+            &weapon,
+            (player, &mut att_stats),
+            (enemy, &mut def_stats),
+            20,
+        );
+        assert_eq!(att_stats.hp, 52);
+
+        let cursed = enchanted_weapon(::items::weapon::WeaponEnhance::Vampiric, true);
+        CombatSystem::apply_weapon_enchantment(
+            &mut ecs_world,
+            &cursed,
+            (player, &mut att_stats),
+            (enemy, &mut def_stats),
+            20,
+        );
+        assert_eq!(att_stats.hp, 50);
+    }
+
+    #[test]
+    fn test_directed_attack_uses_weapon_reach() {
+        let mut ecs_world = create_test_ecs_world();
         let player = ecs_world.world.spawn((
             Player,
+            Position::new(5, 3, 0),
             Actor {
                 name: "Player".to_string(),
                 faction: Faction::Player,
             },
-            Stats {
-                hp: 100,
-                max_hp: 100,
-                attack: 20,
-                defense: 5,
-                accuracy: 15,
-                evasion: 5,
-                level: 1,
-                experience: 0,
-                class: None,
-            },
-            Position::new(5, 5, 0),
-            Energy { current: 100, max: 100, regeneration_rate: 10 },
-            Viewshed {
-                range: 8,
-                visible_tiles: vec![],
-                memory: vec![],
-                dirty: false,
-                algorithm: FovAlgorithm::RayCasting,
-            },
         ));
-        
-        let enemy = ecs_world.world.spawn((
-            Actor {
-                name: "Target".to_string(),
-                faction: Faction::Enemy,
-            },
-            Stats {
-                hp: 50,
-                max_hp: 50,
-                attack: 5,
-                defense: 2,
-                accuracy: 8,
-                evasion: 3,
-                level: 1,
-                experience: 5,
-                class: None,
-            },
-            Position::new(5, 6, 0),
+        let enemy = create_enemy(&mut ecs_world.world, 5, 5);
+
+        // 徒手时两格外的敌人不在攻击范围内
+        ecs_world
+            .resources
+            .input_buffer
+            .pending_actions
+            .push(PlayerAction::Attack(Position::new(0, 1, 0)));
+        CombatSystem::queue_directed_attacks(&mut ecs_world);
+        assert!(ecs_world.resources.combat_intents.is_empty());
+
+        // 长矛可以攻击两格外的敌人
+        ecs_world
+            .world
+            .insert_one(
+                player,
+                Equipment {
+                    weapon: Some(::items::Weapon::new(1, ::items::weapon::WeaponKind::Spear)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        ecs_world
+            .resources
+            .input_buffer
+            .pending_actions
+            .push(PlayerAction::Attack(Position::new(0, 1, 0)));
+        CombatSystem::queue_directed_attacks(&mut ecs_world);
+        assert_eq!(ecs_world.resources.combat_intents.len(), 1);
+        assert_eq!(ecs_world.resources.combat_intents[0].defender, enemy);
+    }
+
+    #[test]
+    fn test_hitting_a_corpse_again_queues_no_second_death() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 4);
+
+        CombatSystem::deal_direct_damage(&mut ecs_world, player, enemy, 30);
+        CombatSystem::deal_direct_damage(&mut ecs_world, player, enemy, 30);
+        let count = |pred: fn(&AftermathEvent) -> bool| {
+            ecs_world.resources.aftermath_queue.iter().filter(|e| pred(e)).count()
+        };
+        assert_eq!(count(|e| matches!(e, AftermathEvent::Death { .. })), 1);
+        assert_eq!(count(|e| matches!(e, AftermathEvent::ExperienceGain { .. })), 1);
+    }
+
+    #[test]
+    fn test_thorns_glyph_reflects_damage() {
+        let mut ecs_world = ECSWorld::new();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 6);
+        let mut armor = ::items::Armor::new(1);
+        armor.inscribe(::items::armor::ArmorGlyph::Thorns);
+
+        let mut player_stats = (*ecs_world.world.get::<&Stats>(player).unwrap()).clone();
+        let mut enemy_stats = (*ecs_world.world.get::<&Stats>(enemy).unwrap()).clone();
+
+        CombatSystem::apply_armor_glyph(
+            &mut ecs_world,
+            &armor,
+            (player, &mut player_stats),
+            (enemy, &mut enemy_stats),
+            9,
+        );
+        assert_eq!(enemy_stats.hp, 27);
+    }
+
+    #[test]
+    fn test_stone_glyph_grants_paralysis_immunity() {
+        let mut ecs_world = ECSWorld::new();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let mut armor = ::items::Armor::new(1);
+        armor.inscribe(::items::armor::ArmorGlyph::Stone);
+        ecs_world
+            .world
+            .insert_one(player, Equipment { armor: Some(armor), ..Default::default() })
+            .unwrap();
+
+        CombatSystem::apply_status_effect(
+            &mut ecs_world,
+            player,
+            ::combat::effect::Effect::new(EffectType::Paralysis, 3),
+        );
+        CombatSystem::apply_status_effect(
+            &mut ecs_world,
+            player,
+            ::combat::effect::Effect::new(EffectType::Burning, 3),
+        );
+
+        let effects = ecs_world.world.get::<&StatusEffects>(player).unwrap();
+        assert!(!effects.has_effect(EffectType::Paralysis));
+        assert!(effects.has_effect(EffectType::Burning));
+    }
+
+    #[test]
+    fn test_knockback_stops_at_wall() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 7);
+
+        CombatSystem::knock_back(&mut ecs_world, player, enemy, 3);
+
+        let pos = ecs_world.world.get::<&Position>(enemy).unwrap();
+        assert_eq!((pos.x, pos.y), (5, 8));
+    }
+
+    fn named_item(name: &str, kind: ::items::ItemKind) -> ECSItem {
+        let mut item = ::items::Item::new(kind);
+        item.name = name.to_string();
+        ECSItem::new(item)
+    }
+
+    fn give_item(world: &mut World, entity: Entity, kind: ::items::ItemKind) {
+        let item = ECSItem::new(::items::Item::new(kind));
+        let quantity = item.quantity;
+        world
+            .get::<&mut Inventory>(entity)
+            .unwrap()
+            .items
+            .push(ItemSlot { item: Some(item), quantity });
+    }
+
+    #[test]
+    fn test_thrown_item_lands_before_wall() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Throwable(::items::Throwable::new(::items::ThrowableKind::Chakram)),
+        );
+
+        assert!(InventorySystem::handle_throw_item(&mut ecs_world, player, 0, Direction::South));
+
+        assert!(ecs_world.world.get::<&Inventory>(player).unwrap().items.is_empty());
+        let landed: Vec<_> = ecs_world
+            .world
+            .query::<(&Position, &ECSItem)>()
+            .iter()
+            .map(|(_, (pos, _))| (pos.x, pos.y))
+            .collect();
+        assert_eq!(landed, vec![(5, 8)]);
+    }
+
+    #[test]
+    fn test_thrown_toxic_potion_poisons_first_creature() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 3);
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Potion(::items::Potion::new_alchemy(
+                ::items::potion::PotionKind::ToxicGas,
+            )),
+        );
+
+        assert!(InventorySystem::handle_throw_item(&mut ecs_world, player, 0, Direction::North));
+
+        let effects = ecs_world.world.get::<&StatusEffects>(enemy).unwrap();
+        assert!(effects.has_effect(EffectType::Poison));
+        // 药水碎裂后不会留在地上
+        assert_eq!(ecs_world.world.query::<&ECSItem>().iter().count(), 0);
+    }
+
+    #[test]
+    fn test_throwing_from_stack_keeps_remaining_items() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let mut darts = ::items::Item::new(::items::ItemKind::Throwable(::items::Throwable::new(
+            ::items::ThrowableKind::Dart,
+        )));
+        darts.quantity = 3;
+        let item = ECSItem::new(darts);
+        ecs_world
+            .world
+            .get::<&mut Inventory>(player)
+            .unwrap()
+            .items
+            .push(ItemSlot { item: Some(item), quantity: 3 });
+
+        assert!(InventorySystem::handle_throw_item(&mut ecs_world, player, 0, Direction::East));
+
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items[0].item.as_ref().unwrap().quantity, 2);
+    }
+
+    #[test]
+    fn test_targeting_cycles_enemies_and_confirms_throw() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 6);
+        create_enemy(&mut world, 5, 3);
+        create_enemy(&mut world, 3, 6);
+        world.get::<&mut Viewshed>(player).unwrap().visible_tiles =
+            (1..9).flat_map(|x| (1..9).map(move |y| Position::new(x, y, 0))).collect();
+        give_item(
+            &mut world,
+            player,
+            ::items::ItemKind::Throwable(::items::Throwable::new(::items::ThrowableKind::Dart)),
+        );
+
+        let mut system = MenuSystem;
+        let mut step = |world: &mut World, resources: &mut Resources, action: PlayerAction| {
+            resources.input_buffer.completed_actions = vec![action];
+            system.run(world, resources);
+        };
+
+        step(&mut world, &mut resources, PlayerAction::BeginTargeting(TargetingPurpose::Throw(0)));
+        assert!(matches!(
+            resources.game_state.game_state,
+            GameStatus::Targeting { cursor_x: 3, cursor_y: 6, .. }
         ));
-        
-        // Create floor tiles
-        for x in 4..=6 {
-            for y in 4..=7 {
-                ecs_world.world.spawn((
-                    Position::new(x, y, 0),
-                    Tile {
-                        terrain_type: TerrainType::Floor,
-                        is_passable: true,
-                        blocks_sight: false,
-                        has_items: false,
-                        has_monster: false,
-                    },
-                ));
-            }
+
+        step(&mut world, &mut resources, PlayerAction::CycleTarget);
+        step(&mut world, &mut resources, PlayerAction::ConfirmTarget);
+
+        assert!(matches!(resources.game_state.game_state, GameStatus::Running));
+        assert!(matches!(
+            resources.input_buffer.pending_actions.as_slice(),
+            [PlayerAction::ThrowItemAt(0, Position { x: 5, y: 3, .. })]
+        ));
+    }
+
+    #[test]
+    fn test_quickslots_follow_item_kinds_and_skip_used_up_stacks() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        give_item(
+            &mut world,
+            player,
+            ::items::ItemKind::Potion(::items::Potion::new_alchemy(::items::potion::PotionKind::Healing)),
+        );
+        give_item(
+            &mut world,
+            player,
+            ::items::ItemKind::Scroll(::items::Scroll::new(::items::scroll::ScrollKind::Lullaby)),
+        );
+
+        let mut system = MenuSystem;
+        let mut step = |world: &mut World, resources: &mut Resources, action: PlayerAction| {
+            resources.input_buffer.completed_actions = vec![action];
+            resources.input_buffer.pending_actions.clear();
+            system.run(world, resources);
+        };
+
+        step(&mut world, &mut resources, PlayerAction::AssignQuickslot(1));
+        step(&mut world, &mut resources, PlayerAction::AssignQuickslot(0));
+        {
+            let quickslot = world.get::<&Quickslot>(player).unwrap();
+            assert!(quickslot.entry(0).unwrap().key.starts_with("scroll:"));
+            assert!(quickslot.entry(1).unwrap().key.starts_with("potion:"));
+        }
+
+        // 药水用完后卷轴移到了背包第一格，快捷栏仍然找得到它
+        world.get::<&mut Inventory>(player).unwrap().items.remove(0);
+        step(&mut world, &mut resources, PlayerAction::UseQuickslot(0));
+        assert!(matches!(resources.input_buffer.pending_actions.as_slice(), [PlayerAction::UseItem(0)]));
+
+        // 用完的物品和空格都不会产生动作
+        step(&mut world, &mut resources, PlayerAction::UseQuickslot(1));
+        assert!(resources.input_buffer.pending_actions.is_empty());
+        assert!(resources.game_state.message_log.last().unwrap().contains("用完"));
+        step(&mut world, &mut resources, PlayerAction::UseQuickslot(5));
+        assert!(resources.input_buffer.pending_actions.is_empty());
+
+        // 再次放入同种物品则从快捷栏移出
+        step(&mut world, &mut resources, PlayerAction::AssignQuickslot(0));
+        assert!(world.get::<&Quickslot>(player).unwrap().entry(0).is_none());
+    }
+
+    #[test]
+    fn test_inventory_tabs_group_by_category_and_navigate() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        give_item(
+            &mut world,
+            player,
+            ::items::ItemKind::Scroll(::items::Scroll::new(::items::scroll::ScrollKind::Lullaby)),
+        );
+        give_item(
+            &mut world,
+            player,
+            ::items::ItemKind::Weapon(::items::Weapon::new(1, ::items::weapon::WeaponKind::Dagger)),
+        );
+        give_item(
+            &mut world,
+            player,
+            ::items::ItemKind::Scroll(::items::Scroll::new(::items::scroll::ScrollKind::Upgrade)),
+        );
+
+        // 分类按枚举顺序排列，存储顺序不变
+        let tabs = world.get::<&Inventory>(player).unwrap().category_tabs();
+        assert_eq!(tabs.len(), 2);
+        assert_eq!(tabs[0], (::items::ItemCategory::Weapon, vec![1]));
+        assert_eq!(tabs[1].0, ::items::ItemCategory::Scroll);
+        assert_eq!(tabs[1].1.len(), 2);
+
+        let mut system = MenuSystem;
+        let mut step = |world: &mut World, resources: &mut Resources, action: PlayerAction| {
+            resources.input_buffer.completed_actions = vec![action];
+            resources.input_buffer.pending_actions.clear();
+            system.run(world, resources);
+        };
+        let selected = |resources: &Resources| match resources.game_state.game_state {
+            GameStatus::Inventory { selected_item } => selected_item,
+            _ => panic!("背包应保持打开"),
+        };
+
+        step(&mut world, &mut resources, PlayerAction::OpenInventory);
+        assert_eq!(selected(&resources), 1);
+        step(&mut world, &mut resources, PlayerAction::MenuNavigate(NavigateDirection::Right));
+        assert_eq!(selected(&resources), tabs[1].1[0]);
+        step(&mut world, &mut resources, PlayerAction::MenuNavigate(NavigateDirection::Down));
+        assert_eq!(selected(&resources), tabs[1].1[1]);
+        // 在页内不会越过最后一项
+        step(&mut world, &mut resources, PlayerAction::MenuNavigate(NavigateDirection::Down));
+        assert_eq!(selected(&resources), tabs[1].1[1]);
+        // 切换分类循环回到第一页
+        step(&mut world, &mut resources, PlayerAction::MenuNavigate(NavigateDirection::Right));
+        assert_eq!(selected(&resources), 1);
+    }
+
+    #[test]
+    fn test_warrior_charge_skill_dashes_and_goes_on_cooldown() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        ecs_world.world.get::<&mut Stats>(player).unwrap().class = Some(Class::Warrior);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 3);
+        let target = Position::new(5, 3, 0);
+
+        assert!(CombatSystem::cast_targeted_skill(&mut ecs_world, player, 0, &target).is_ok());
+
+        let pos = (*ecs_world.world.get::<&Position>(player).unwrap()).clone();
+        assert_eq!((pos.x, pos.y), (5, 4));
+        assert_eq!(ecs_world.world.get::<&Stats>(enemy).unwrap().hp, 12);
+        assert!(ecs_world
+            .world
+            .get::<&StatusEffects>(enemy)
+            .unwrap()
+            .has_effect(EffectType::Paralysis));
+
+        let again = CombatSystem::cast_targeted_skill(&mut ecs_world, player, 0, &target);
+        assert_eq!(again.unwrap_err().1, "技能冷却中");
+    }
+
+    #[test]
+    fn test_zapping_wand_spends_charge_and_damages_target() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 2);
+        let wand = ::items::Wand::new(::items::wand::WandKind::MagicMissile, 1);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Wand(wand));
+
+        assert!(InventorySystem::handle_zap_wand(
+            &mut ecs_world,
+            player,
+            0,
+            &Position::new(5, 2, 0)
+        ));
+
+        assert_eq!(ecs_world.world.get::<&Stats>(enemy).unwrap().hp, 28);
+        let wand = crate::targeting::wand_in_slot(&ecs_world.world, player, 0).unwrap();
+        assert_eq!(wand.charges, wand.max_charges - 1);
+    }
+
+    fn zap(ecs_world: &mut ECSWorld, player: Entity, kind: ::items::wand::WandKind, target: Position) {
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Wand(::items::Wand::new(kind, 0)));
+        let slot = ecs_world.world.get::<&Inventory>(player).unwrap().items.len() - 1;
+        assert!(InventorySystem::handle_zap_wand(ecs_world, player, slot, &target));
+    }
+
+    #[test]
+    fn test_fireblast_cone_burns_enemies_in_front_only() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let ahead = create_enemy(&mut ecs_world.world, 5, 3);
+        let flank = create_enemy(&mut ecs_world.world, 6, 2);
+        let behind = create_enemy(&mut ecs_world.world, 5, 7);
+
+        zap(&mut ecs_world, player, ::items::wand::WandKind::Fireblast, Position::new(5, 1, 0));
+
+        for enemy in [ahead, flank] {
+            assert!(ecs_world
+                .world
+                .get::<&StatusEffects>(enemy)
+                .unwrap()
+                .has_effect(EffectType::Burning));
+        }
+        assert_eq!(ecs_world.world.get::<&Stats>(behind).unwrap().hp, 30);
+    }
+
+    #[test]
+    fn test_lightning_chains_to_adjacent_enemies() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 2, 5);
+        let first = create_enemy(&mut ecs_world.world, 5, 5);
+        let adjacent = create_enemy(&mut ecs_world.world, 5, 6);
+
+        zap(&mut ecs_world, player, ::items::wand::WandKind::Lightning, Position::new(5, 5, 0));
+        assert_eq!(ecs_world.world.get::<&Stats>(first).unwrap().hp, 22);
+        assert_eq!(ecs_world.world.get::<&Stats>(adjacent).unwrap().hp, 22);
+    }
+
+    #[test]
+    fn test_disintegration_pierces_every_enemy_in_line() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 2, 5);
+        let first = create_enemy(&mut ecs_world.world, 5, 5);
+        let behind = create_enemy(&mut ecs_world.world, 7, 5);
+
+        zap(&mut ecs_world, player, ::items::wand::WandKind::Disintegration, Position::new(8, 5, 0));
+        assert_eq!(ecs_world.world.get::<&Stats>(first).unwrap().hp, 20);
+        assert_eq!(ecs_world.world.get::<&Stats>(behind).unwrap().hp, 20);
+    }
+
+    #[test]
+    fn test_corruption_turns_wounded_enemy_into_ally() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 3);
+        ecs_world.world.get::<&mut Stats>(enemy).unwrap().hp = 1;
+
+        zap(&mut ecs_world, player, ::items::wand::WandKind::Corruption, Position::new(5, 3, 0));
+
+        assert!(matches!(
+            ecs_world.world.get::<&Actor>(enemy).unwrap().faction,
+            Faction::Player
+        ));
+    }
+
+    #[test]
+    fn test_wands_recharge_over_turns() {
+        let (mut world, mut resources) = create_test_world();
+        let player = create_player(&mut world, 5, 5);
+        let mut wand = ::items::Wand::new(::items::wand::WandKind::Frost, 3);
+        wand.charges = 0;
+        give_item(&mut world, player, ::items::ItemKind::Wand(wand));
+
+        let mut system = WandRechargeSystem;
+        for _ in 0..200 {
+            system.run(&mut world, &mut resources);
+        }
+
+        let inventory = world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items[0].item.as_ref().unwrap().charges(), Some(6));
+    }
+
+    #[test]
+    fn test_equipping_weapon_and_ring_updates_derived_stats() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let weapon = ::items::Weapon::new(2, ::items::weapon::WeaponKind::Sword);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(weapon));
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Ring(::items::Ring::new(::items::ring::RingKind::Accuracy, 2)),
+        );
+
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+
+        let bonus = ecs_world.world.get::<&Equipment>(player).unwrap().bonuses();
+        assert!(bonus.attack > 0 && bonus.accuracy > 0);
+        let stats = ecs_world.world.get::<&Stats>(player).unwrap();
+        assert_eq!(stats.attack as i32, 10 + bonus.attack);
+        assert_eq!(stats.accuracy as i32, 80 + bonus.accuracy);
+        assert!(ecs_world.world.get::<&Inventory>(player).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn test_unequip_returns_item_and_restores_base_stats() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let weapon = ::items::Weapon::new(3, ::items::weapon::WeaponKind::Sword);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(weapon));
+
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+        assert!(InventorySystem::handle_unequip_item(&mut ecs_world, player, 0));
+        assert!(!InventorySystem::handle_unequip_item(&mut ecs_world, player, 0));
+
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().attack, 10);
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 1);
+        assert!(matches!(
+            inventory.items[0].item.as_ref().unwrap().kind,
+            ::items::ItemKind::Weapon(_)
+        ));
+    }
+
+    #[test]
+    fn test_equipping_second_weapon_swaps_previous_into_inventory() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let first = ::items::Weapon::new(1, ::items::weapon::WeaponKind::Dagger);
+        let second = ::items::Weapon::new(3, ::items::weapon::WeaponKind::Sword);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(first));
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(second));
+        let first_name = ecs_world.world.get::<&Inventory>(player).unwrap().items[0]
+            .item
+            .as_ref()
+            .unwrap()
+            .name
+            .clone();
+
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 1);
+        assert_eq!(inventory.items[0].item.as_ref().unwrap().name, first_name);
+        let equipment = ecs_world.world.get::<&Equipment>(player).unwrap();
+        assert!(matches!(
+            equipment.weapon.as_ref().map(|w| w.kind),
+            Some(::items::weapon::WeaponKind::Sword)
+        ));
+    }
+
+    #[test]
+    fn test_weapon_catalog_signature_properties() {
+        use ::items::weapon::{Tier, WeaponKind};
+
+        let bonus_with = |tier, kind| {
+            Equipment { weapon: Some(::items::Weapon::new(tier, kind)), ..Default::default() }
+                .bonuses()
+        };
+        let sword = bonus_with(5, WeaponKind::Sword);
+        let greataxe = bonus_with(5, WeaponKind::Greataxe);
+        assert_eq!(sword.attack_cost, 0.0);
+        assert_eq!(greataxe.attack_cost, 0.5);
+        assert!(greataxe.attack > sword.attack);
+        assert_eq!(bonus_with(2, WeaponKind::Quarterstaff).defense, 0);
+        assert_eq!(::items::Weapon::new(2, WeaponKind::Spear).range(), 2);
+        assert!(::items::Weapon::new(2, WeaponKind::Dagger).surprise_multiplier() > 1.0);
+
+        // 浅层不会出现五阶武器，生成的武器都来自所在品阶的目录
+        for _ in 0..200 {
+            let weapon = ::items::Weapon::random_new(1);
+            assert_ne!(weapon.tier, Tier::Five);
+            assert!(weapon.tier.catalog().iter().any(|spec| spec.name == weapon.name));
         }
-        
-        // Perform multiple attacks to test for critical hits
-        for _ in 0..10 {
-            let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
-            let enemy_pos = ecs_world.world.get::<&Position>(enemy).map(|p| (*p).clone()).unwrap();
-            
-            // Reset enemy HP for each test
-            if let Ok(mut stats) = ecs_world.world.get::<&mut Stats>(enemy) {
-                stats.hp = 50;
+    }
+
+    #[test]
+    fn test_quarterstaff_blocks_each_melee_hit() {
+        use ::items::weapon::WeaponKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        ecs_world.world.insert_one(player, Shielding { amount: 1 }).unwrap();
+        let staff = Equipment {
+            weapon: Some(::items::Weapon::new(2, WeaponKind::Quarterstaff)),
+            ..Default::default()
+        };
+
+        // 长棍先格挡 2 点，护盾再吸收 1 点
+        assert_eq!(CombatSystem::mitigate_melee_hit(&mut ecs_world, player, &staff, 5), 2);
+        assert_eq!(CombatSystem::mitigate_melee_hit(&mut ecs_world, player, &staff, 2), 0);
+        assert_eq!(CombatSystem::mitigate_melee_hit(&mut ecs_world, player, &Equipment::default(), 2), 2);
+    }
+
+    #[test]
+    fn test_melee_overkill_goes_past_shield() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 4);
+        ecs_world.world.get::<&mut Stats>(player).unwrap().hp = 1;
+        ecs_world.world.get::<&mut Stats>(enemy).unwrap().attack = 200;
+        ecs_world.world.insert_one(player, Shielding { amount: 3 }).unwrap();
+
+        // 护盾只挡下自身的量，超出部分照常扣除生命值
+        let intent = CombatIntent::new(enemy, player, Position::new(5, 4, 0), Position::new(5, 5, 0), false);
+        for _ in 0..100 {
+            if ecs_world.world.get::<&Shielding>(player).is_err() {
+                break;
             }
-            
-            ecs_world.resources.combat_intents.push(
-                CombatIntent::new(player, enemy, player_pos, enemy_pos, true)
-            );
-            
-            CombatSystem::run_with_events(&mut ecs_world);
-            
-            // Check if any CombatHit events were published with is_critical = true
-            // This would require inspecting the event bus
+            CombatSystem::resolve_combat_intent(&mut ecs_world, &intent);
         }
+        assert!(ecs_world.world.get::<&Shielding>(player).is_err());
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().hp, 0);
     }
-    
+
+    /// 玩家与远处的敌人，玩家背包里只有给定职业的专属物品
+    fn signature_setup(class: hero::class::Class) -> (ECSWorld, Entity, Entity) {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 2);
+        give_item(&mut ecs_world.world, player, class.signature_item().kind);
+        (ecs_world, player, enemy)
+    }
+
     #[test]
-    fn test_aftermath_death_handling() {
-        let mut ecs_world = ECSWorld::new();
-        
-        let enemy = ecs_world.world.spawn((
-            Actor {
-                name: "Goblin".to_string(),
-                faction: Faction::Enemy,
-            },
-            Stats {
-                hp: 0, // Already dead
-                max_hp: 20,
-                attack: 5,
-                defense: 2,
-                accuracy: 8,
-                evasion: 3,
-                level: 1,
-                experience: 5,
-                class: None,
-            },
-            Position::new(5, 6, 0),
-        ));
-        
-        // Queue death event
-        ecs_world.resources.aftermath_queue.push(AftermathEvent::Death {
-            entity: enemy,
-            entity_id: enemy.id(),
-            entity_name: "Goblin".to_string(),
-            killer: None,
-        });
-        
-        // Run aftermath system
-        AftermathSystem::run_with_events(&mut ecs_world);
-        
-        // Verify entity was despawned
-        assert!(ecs_world.world.get::<&Actor>(enemy).is_err(), "Dead enemy should be despawned");
+    fn test_broken_seal_shield_absorbs_damage_first() {
+        let (mut ecs_world, player, enemy) = signature_setup(hero::class::Class::Warrior);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        let shield = ecs_world.world.get::<&Shielding>(player).unwrap().amount;
+        assert_eq!(shield, hero::class::warrior::seal_shield(1));
+        CombatSystem::deal_direct_damage(&mut ecs_world, enemy, player, shield + 2);
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().hp, 98);
+        assert!(ecs_world.world.get::<&Shielding>(player).is_err());
+
+        // 充能用尽后无法再次使用
+        assert!(!InventorySystem::handle_use_item(&mut ecs_world, player, 0));
     }
-    
+
     #[test]
-    fn test_aftermath_experience_gain() {
-        let mut ecs_world = ECSWorld::new();
-        
-        let player = ecs_world.world.spawn((
-            Player,
-            Actor {
-                name: "Player".to_string(),
-                faction: Faction::Player,
-            },
-            PlayerProgress {
-                turns: 0,
-                strength: 10,
-                class: Class::Warrior,
-                skill_state: SkillState::default(),
-            },
+    fn test_cloak_of_shadows_turns_wearer_invisible() {
+        let (mut ecs_world, player, _) = signature_setup(hero::class::Class::Rogue);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert!(ecs_world
+            .world
+            .get::<&StatusEffects>(player)
+            .unwrap()
+            .has_effect(EffectType::Invisibility));
+    }
+
+    #[test]
+    fn test_mages_staff_absorbs_wand_and_zaps_with_it() {
+        let (mut ecs_world, player, enemy) = signature_setup(hero::class::Class::Mage);
+        let wand = ::items::Wand::new(::items::wand::WandKind::MagicMissile, 1);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Wand(wand));
+
+        // 没有吸收法杖时先选择要吸收的法杖，被吸收的法杖离开背包并充满充能
+        assert!(!InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert!(matches!(ecs_world.resources.game_state.game_state, GameStatus::ItemPicker { source_slot: 0, .. }));
+        assert!(InventorySystem::handle_use_item_on(&mut ecs_world, player, 0, 1));
+        assert_eq!(ecs_world.world.get::<&Inventory>(player).unwrap().items.len(), 1);
+        let absorbed = crate::targeting::wand_in_slot(&ecs_world.world, player, 0).unwrap();
+        assert_eq!(absorbed.charges, absorbed.max_charges);
+
+        assert!(InventorySystem::handle_zap_wand(&mut ecs_world, player, 0, &Position::new(5, 2, 0)));
+        assert_eq!(ecs_world.world.get::<&Stats>(enemy).unwrap().hp, 28);
+        let staff = crate::targeting::wand_in_slot(&ecs_world.world, player, 0).unwrap();
+        assert_eq!(staff.charges, absorbed.max_charges - 1);
+    }
+
+    #[test]
+    fn test_spirit_bow_is_kept_after_shooting() {
+        let (mut ecs_world, player, _) = signature_setup(hero::class::Class::Huntress);
+
+        assert!(InventorySystem::throw_item_at(&mut ecs_world, player, 0, &Position::new(5, 2, 0)));
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        assert!(matches!(
+            &inventory.items[0].item.as_ref().unwrap().kind,
+            ::items::ItemKind::Signature(bow) if bow.kind == ::items::SignatureKind::SpiritBow
         ));
-        
-        // Queue experience gain
-        ecs_world.resources.aftermath_queue.push(AftermathEvent::ExperienceGain {
-            entity: player,
-            amount: 50,
-        });
-        
-        // Run aftermath system
-        AftermathSystem::run_with_events(&mut ecs_world);
-        
-        // Verify log message was published (experience system is TODO)
-        // For now just check system doesn't panic
     }
-    
+
     #[test]
-    fn test_combat_intent_skips_dead_entities() {
-        let mut ecs_world = ECSWorld::new();
-        
-        let player = ecs_world.world.spawn((
-            Player,
-            Actor {
-                name: "Player".to_string(),
-                faction: Faction::Player,
-            },
-            Stats {
-                hp: 100,
-                max_hp: 100,
-                attack: 10,
-                defense: 5,
-                accuracy: 10,
-                evasion: 5,
-                level: 1,
-                experience: 0,
-                class: None,
-            },
-            Position::new(5, 5, 0),
+    fn test_strength_potion_lifts_weapon_penalty() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        ecs_world.world.insert_one(player, PlayerProgress::default()).unwrap();
+        // 长剑需要 13 力量，默认力量为 10
+        let weapon = ::items::Weapon::new(2, ::items::weapon::WeaponKind::Sword);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(weapon));
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+        let weak_accuracy = ecs_world.world.get::<&Stats>(player).unwrap().accuracy;
+
+        for _ in 0..3 {
+            give_item(
+                &mut ecs_world.world,
+                player,
+                ::items::ItemKind::Potion(::items::Potion::new_alchemy(
+                    ::items::potion::PotionKind::Strength,
+                )),
+            );
+            assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        }
+
+        assert_eq!(ecs_world.world.get::<&PlayerProgress>(player).unwrap().strength, 13);
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().accuracy, weak_accuracy + 30);
+    }
+
+    #[test]
+    fn test_cursed_ring_sticks_until_remove_curse() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Ring(::items::Ring::new_cursed(::items::ring::RingKind::Accuracy, 1)),
+        );
+
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+        // 诅咒戒指按 -2 级计算，降低命中
+        assert!(ecs_world.world.get::<&Stats>(player).unwrap().accuracy < 80);
+        assert!(!InventorySystem::handle_unequip_item(&mut ecs_world, player, 2));
+        assert!(ecs_world
+            .resources
+            .game_state
+            .message_log
+            .iter()
+            .any(|msg| msg.contains("被诅咒了，无法卸下")));
+
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Scroll(::items::Scroll::new(::items::scroll::ScrollKind::RemoveCurse)),
+        );
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert!(InventorySystem::handle_unequip_item(&mut ecs_world, player, 2));
+        assert_eq!(ecs_world.world.get::<&Stats>(player).unwrap().accuracy, 80);
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        assert!(!inventory.items[0].item.as_ref().unwrap().is_cursed());
+    }
+
+    #[test]
+    fn test_cursed_weapon_blocks_replacement() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let mut cursed = ::items::Weapon::new(1, ::items::weapon::WeaponKind::Sword);
+        cursed.curse();
+        assert!(cursed.enchanted.is_some());
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(cursed));
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Weapon(::items::Weapon::new(2, ::items::weapon::WeaponKind::Sword)),
+        );
+
+        assert!(InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+        assert!(!InventorySystem::handle_equip_item(&mut ecs_world, player, 0));
+
+        let equipment = ecs_world.world.get::<&Equipment>(player).unwrap();
+        assert!(equipment.is_cursed(EquipSlot::Weapon));
+        assert!(equipment.slot_name(EquipSlot::Weapon).unwrap().ends_with("(诅咒)"));
+        assert_eq!(ecs_world.world.get::<&Inventory>(player).unwrap().items.len(), 1);
+    }
+
+    fn give_scroll(world: &mut World, entity: Entity, kind: ::items::scroll::ScrollKind) {
+        give_item(world, entity, ::items::ItemKind::Scroll(::items::Scroll::new(kind)));
+    }
+
+    #[test]
+    fn test_upgrade_scroll_opens_picker_then_upgrades_target() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Weapon(::items::Weapon::new(2, ::items::weapon::WeaponKind::Sword)),
+        );
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Upgrade);
+
+        // 没有目标时进入物品选择，不消耗卷轴
+        assert!(!InventorySystem::handle_use_item(&mut ecs_world, player, 1));
+        assert!(matches!(
+            ecs_world.resources.game_state.game_state,
+            GameStatus::ItemPicker { source_slot: 1, .. }
         ));
-        
-        let dead_enemy = ecs_world.world.spawn((
-            Actor {
-                name: "DeadGoblin".to_string(),
-                faction: Faction::Enemy,
-            },
-            Stats {
-                hp: 0, // Already dead
-                max_hp: 20,
-                attack: 5,
-                defense: 2,
-                accuracy: 8,
-                evasion: 3,
-                level: 1,
-                experience: 5,
-                class: None,
-            },
-            Position::new(5, 6, 0),
+        assert!(!InventorySystem::handle_use_item_on(&mut ecs_world, player, 1, 1));
+
+        assert!(InventorySystem::handle_use_item_on(&mut ecs_world, player, 1, 0));
+        let weapon = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        assert!(matches!(weapon.kind, ::items::ItemKind::Weapon(w) if w.upgrade_level == 1));
+        assert_eq!(ecs_world.world.get::<&Inventory>(player).unwrap().items.len(), 1);
+    }
+
+    #[test]
+    fn test_recharging_scroll_refills_wands() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let mut wand = ::items::Wand::new(::items::wand::WandKind::Frost, 3);
+        wand.charges = 0;
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Wand(wand));
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Recharging);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 1));
+        let wand = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        assert!(matches!(wand.kind, ::items::ItemKind::Wand(w) if w.charges == w.max_charges));
+    }
+
+    #[test]
+    fn test_transmutation_scroll_changes_ring_kind_but_keeps_level() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Ring(::items::Ring::new(::items::ring::RingKind::Accuracy, 1)),
+        );
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Transmutation);
+
+        assert!(InventorySystem::handle_use_item_on(&mut ecs_world, player, 1, 0));
+        let ring = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        assert!(matches!(
+            ring.kind,
+            ::items::ItemKind::Ring(r) if r.kind != ::items::ring::RingKind::Accuracy && r.level == 1
         ));
-        
-        // Queue combat intent with dead enemy
-        let player_pos = ecs_world.world.get::<&Position>(player).map(|p| (*p).clone()).unwrap();
-        let enemy_pos = ecs_world.world.get::<&Position>(dead_enemy).map(|p| (*p).clone()).unwrap();
-        
-        ecs_world.resources.combat_intents.push(
-            CombatIntent::new(player, dead_enemy, player_pos, enemy_pos, true)
+        assert_eq!(ecs_world.world.get::<&Inventory>(player).unwrap().items.len(), 1);
+    }
+
+    #[test]
+    fn test_transmutation_scroll_rebuilds_weapon_from_same_tier() {
+        use ::items::weapon::{Tier, WeaponEnhance, WeaponKind};
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let mut dagger = ::items::Weapon::new(2, WeaponKind::Dagger);
+        dagger.upgrade();
+        dagger.upgrade();
+        dagger.enchanted = Some(WeaponEnhance::Burning);
+        dagger.curse();
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Weapon(dagger));
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Transmutation);
+
+        assert!(InventorySystem::handle_use_item_on(&mut ecs_world, player, 1, 0));
+        let item = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        let ::items::ItemKind::Weapon(weapon) = &item.kind else {
+            panic!("变形后应仍是武器");
+        };
+        let spec = Tier::Two.catalog().iter().find(|spec| spec.kind == weapon.kind).unwrap();
+        assert_ne!(weapon.kind, WeaponKind::Dagger);
+        assert_eq!(weapon.tier, Tier::Two);
+        assert_eq!(weapon.name, spec.name);
+        assert_eq!(weapon.damage, (spec.damage.0 + 2, spec.damage.1 + 4));
+        assert_eq!(weapon.upgrade_level, 2);
+        assert!(matches!(weapon.enchanted, Some(WeaponEnhance::Burning)));
+        assert!(weapon.cursed);
+    }
+
+    #[test]
+    fn test_transmutation_scroll_changes_wand_kind_but_keeps_level() {
+        use ::items::wand::WandKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Wand(::items::Wand::new(WandKind::Frost, 2)));
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Transmutation);
+
+        assert!(InventorySystem::handle_use_item_on(&mut ecs_world, player, 1, 0));
+        let wand = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        assert!(matches!(wand.kind, ::items::ItemKind::Wand(w) if w.kind != WandKind::Frost && w.level == 2));
+    }
+
+    #[test]
+    fn test_transmutation_is_reproducible_from_seed() {
+        use rand::SeedableRng;
+
+        let dagger = ::items::Item::new(::items::ItemKind::Weapon(::items::Weapon::new(
+            2,
+            ::items::weapon::WeaponKind::Dagger,
+        )));
+        let transmute = |seed| dagger.transmute(&mut rand::rngs::StdRng::seed_from_u64(seed)).unwrap().name;
+        for seed in 0..10 {
+            assert_eq!(transmute(seed), transmute(seed));
+        }
+    }
+
+    #[test]
+    fn test_lullaby_scroll_puts_enemies_to_sleep() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let enemy = create_enemy(&mut ecs_world.world, 6, 6);
+        ecs_world.world.insert_one(enemy, StatusEffects::new()).unwrap();
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Lullaby);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert!(ecs_world
+            .world
+            .get::<&StatusEffects>(enemy)
+            .unwrap()
+            .has_effect(EffectType::Paralysis));
+    }
+
+    #[test]
+    fn test_teleport_scroll_moves_reader_to_passable_tile() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Teleportation);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        let pos = ecs_world.world.get::<&Position>(player).unwrap().clone();
+        assert_ne!((pos.x, pos.y), (5, 5));
+        assert!(MovementSystem::is_tile_passable(&ecs_world.world, &pos));
+        assert!(ecs_world.world.get::<&Inventory>(player).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn test_magic_mapping_reveals_secret_doors() {
+        use dungeon::level::tiles::DoorState;
+
+        let mut ecs_world = create_test_ecs_world();
+        crate::ecs::set_dungeon_instance(&mut ecs_world.world, dungeon::Dungeon::generate(1, 3).unwrap());
+        crate::ecs::with_dungeon_mut(&mut ecs_world.world, |dungeon| {
+            let tile = dungeon.current_level_mut().get_tile_mut(0, 3).unwrap();
+            tile.info.terrain_type = dungeon::TerrainType::Door(DoorState::Hidden);
+        });
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::MagicMapping);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        ecs_world.process_events();
+        let dungeon = crate::ecs::get_dungeon_clone(&ecs_world.world).unwrap();
+        assert_eq!(
+            dungeon.current_level().get_tile(0, 3).unwrap().info.terrain_type,
+            dungeon::TerrainType::Door(DoorState::Closed)
         );
-        
-        // Run combat system
-        CombatSystem::run_with_events(&mut ecs_world);
-        
-        // Verify player HP didn't change (no combat occurred)
-        let player_stats = ecs_world.world.get::<&Stats>(player).unwrap();
-        assert_eq!(player_stats.hp, 100, "Player shouldn't take damage from dead enemy");
+        assert!(ecs_world
+            .world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .filter(|(_, (pos, _))| (pos.x, pos.y) == (0, 3))
+            .all(|(_, (_, tile))| matches!(tile.terrain_type, TerrainType::Door)));
+        assert!(ecs_world.resources.game_state.message_log.iter().any(|m| m == "发现了暗门！"));
+    }
+
+    fn give_potion(world: &mut World, entity: Entity, kind: ::items::potion::PotionKind) {
+        give_item(world, entity, ::items::ItemKind::Potion(::items::Potion::new_alchemy(kind)));
+    }
+
+    #[test]
+    fn test_drinking_potions_applies_their_effects() {
+        use ::items::potion::PotionKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        ecs_world.world.insert_one(player, StatusEffects::new()).unwrap();
+        give_potion(&mut ecs_world.world, player, PotionKind::Haste);
+        give_potion(&mut ecs_world.world, player, PotionKind::Experience);
+        give_potion(&mut ecs_world.world, player, PotionKind::ToxicGas);
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        let stats = (*ecs_world.world.get::<&Stats>(player).unwrap()).clone();
+        assert_eq!(stats.level, 2);
+        assert_eq!(stats.hp, stats.max_hp);
+        assert!(stats.max_hp > 100);
+
+        // 有害药水在饮用者脚下爆发
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        let effects = ecs_world.world.get::<&StatusEffects>(player).unwrap();
+        assert!(effects.has_effect(EffectType::Haste));
+        assert!(effects.has_effect(EffectType::Poison));
+        drop(effects);
+        assert!(ecs_world.world.query::<&Blob>().iter().count() > 0);
+        assert!(ecs_world.world.get::<&Inventory>(player).unwrap().items.is_empty());
+    }
+
+    #[test]
+    fn test_gas_cloud_spreads_then_dissipates() {
+        let mut ecs_world = create_test_ecs_world();
+        let enemy = create_enemy(&mut ecs_world.world, 5, 4);
+        BlobSystem::spawn_blob(&mut ecs_world.world, &Position::new(5, 5, 0), BlobKind::ParalyticGas, 8);
+        // 墙上不会生成气体
+        BlobSystem::spawn_blob(&mut ecs_world.world, &Position::new(0, 0, 0), BlobKind::ToxicGas, 8);
+        assert_eq!(ecs_world.world.query::<&Blob>().iter().count(), 1);
+
+        BlobSystem::run_with_events(&mut ecs_world);
+        assert_eq!(ecs_world.world.query::<&Blob>().iter().count(), 5);
+
+        BlobSystem::run_with_events(&mut ecs_world);
+        let effects = ecs_world.world.get::<&StatusEffects>(enemy).unwrap();
+        assert!(effects.has_effect(EffectType::Paralysis));
+        drop(effects);
+
+        for _ in 0..20 {
+            BlobSystem::run_with_events(&mut ecs_world);
+        }
+        assert_eq!(ecs_world.world.query::<&Blob>().iter().count(), 0);
+    }
+
+    #[test]
+    fn test_thrown_frost_potion_freezes_water_and_enemies() {
+        let mut ecs_world = create_test_ecs_world();
+        for (_, (pos, tile)) in ecs_world.world.query_mut::<(&Position, &mut Tile)>() {
+            if (pos.x, pos.y) == (4, 2) {
+                tile.terrain_type = TerrainType::Water;
+            }
+        }
+        let player = create_player(&mut ecs_world.world, 5, 6);
+        let enemy = create_enemy(&mut ecs_world.world, 5, 3);
+        give_potion(&mut ecs_world.world, player, ::items::potion::PotionKind::Frost);
+
+        assert!(InventorySystem::handle_throw_item(&mut ecs_world, player, 0, Direction::North));
+
+        assert!(ecs_world
+            .world
+            .get::<&StatusEffects>(enemy)
+            .unwrap()
+            .has_effect(EffectType::Frost));
+        assert!(ecs_world
+            .world
+            .query::<(&Position, &Tile)>()
+            .iter()
+            .any(|(_, (pos, tile))| (pos.x, pos.y) == (4, 2) && matches!(tile.terrain_type, TerrainType::Ice)));
     }
 
     #[test]
-    fn test_trap_detection() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-
-        // Place a trap at (5, 6)
-        world.spawn((
-            Position::new(5, 6, 0),
-            Tile {
-                terrain_type: TerrainType::Trap,
-                is_passable: true,
-                blocks_sight: false,
-                has_items: false,
-                has_monster: false,
-            },
-        ));
+    fn test_reading_a_scroll_identifies_its_kind_for_the_run() {
+        use ::items::scroll::ScrollKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        ecs_world.resources.item_knowledge = ::items::ItemKnowledge::from_seed(9);
+        let rune = ecs_world.resources.item_knowledge.scroll_rune(ScrollKind::Teleportation);
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_scroll(&mut ecs_world.world, player, ScrollKind::Teleportation);
+        give_scroll(&mut ecs_world.world, player, ScrollKind::Teleportation);
+        InventorySystem::sync_item_knowledge(&mut ecs_world);
+
+        let name_of = |ecs_world: &ECSWorld, slot: usize| {
+            let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+            inventory.items[slot].item.as_ref().unwrap().name.clone()
+        };
+        assert_eq!(name_of(&ecs_world, 0), format!("「{}」卷轴", rune.name()));
+
+        assert!(InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert_eq!(name_of(&ecs_world, 0), "传送卷轴");
+        assert!(ecs_world
+            .resources
+            .item_knowledge
+            .catalog()
+            .iter()
+            .any(|entry| entry.name.as_deref() == Some("传送卷轴")));
+    }
 
-        let initial_hp = world.get::<&Stats>(player).unwrap().hp;
+    #[test]
+    fn test_pick_up_from_pile_opens_chooser() {
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let sword = || ::items::ItemKind::Weapon(::items::Weapon::new(1, ::items::weapon::WeaponKind::Sword));
+        for name in ["Sword A", "Sword B", "Sword C"] {
+            InventorySystem::spawn_ground_item(&mut ecs_world, &Position::new(5, 6, 0), named_item(name, sword()));
+        }
 
-        // Move south onto the trap
-        resources.input_buffer.pending_actions.push(PlayerAction::Move(Direction::South));
-        let mut system = MovementSystem;
-        system.run(&mut world, &mut resources);
+        // 走上物品堆时在日志中列出整堆物品
+        ecs_world.resources.input_buffer.pending_actions.push(PlayerAction::Move(Direction::South));
+        MovementSystem::run_with_events(&mut ecs_world);
+        let log = ecs_world.resources.game_state.message_log.join("\n");
+        assert!(log.contains("Sword A、Sword B、Sword C"), "{}", log);
 
-        // Check that damage was applied
-        let current_hp = world.get::<&Stats>(player).unwrap().hp;
-        assert!(current_hp < initial_hp);
+        // 多件物品时 g 打开拾取选择，不消耗回合
+        ecs_world.resources.input_buffer.pending_actions.push(PlayerAction::PickUp);
+        InventorySystem::run_with_events(&mut ecs_world);
+        assert!(matches!(
+            ecs_world.resources.game_state.game_state,
+            GameStatus::PickupChooser { selected_item: 0 }
+        ));
+        assert!(!ecs_world
+            .resources
+            .input_buffer
+            .completed_actions
+            .iter()
+            .any(|action| matches!(action, PlayerAction::PickUp)));
 
-        // Check that a trap message was logged
-        let has_trap_message = resources.game_state.message_log.iter()
-            .any(|msg| msg.contains("陷阱"));
-        assert!(has_trap_message);
+        // 选中第二件并确认，只拾起这一件
+        let mut menu = MenuSystem;
+        let mut step = |ecs_world: &mut ECSWorld, action: PlayerAction| {
+            ecs_world.resources.input_buffer.completed_actions = vec![action];
+            menu.run(&mut ecs_world.world, &mut ecs_world.resources);
+        };
+        step(&mut ecs_world, PlayerAction::MenuNavigate(NavigateDirection::Down));
+        step(&mut ecs_world, PlayerAction::MenuSelect);
+        assert!(matches!(ecs_world.resources.game_state.game_state, GameStatus::Running));
+        InventorySystem::run_with_events(&mut ecs_world);
+
+        let inventory = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        assert_eq!(inventory.name, "Sword B");
+        let remaining: Vec<String> = InventorySystem::ground_items_at(&ecs_world.world, &Position::new(5, 6, 0))
+            .into_iter()
+            .map(|entity| ecs_world.world.get::<&ECSItem>(entity).unwrap().name.clone())
+            .collect();
+        assert_eq!(remaining, vec!["Sword A".to_string(), "Sword C".to_string()]);
     }
 
     #[test]
-    fn test_item_detection() {
-        let (mut world, mut resources) = create_test_world();
-        let _player = create_player(&mut world, 5, 5);
-
-        // Place an item at (5, 6)
-        world.spawn((
-            Position::new(5, 6, 0),
-            ECSItem {
-                name: "Health Potion".to_string(),
-                item_type: ItemType::Consumable {
-                    effect: ConsumableEffect::Healing { amount: 20 },
-                },
-                value: 10,
-                identified: true,
-                quantity: 1,
-                level: 0,
-                cursed: false,
-                charges: None,
-                detailed_data: None,
-            },
-            Tile {
-                terrain_type: TerrainType::Floor,
-                is_passable: true,
-                blocks_sight: false,
-                has_items: true,
-                has_monster: false,
-            },
-        ));
+    fn test_containers_hold_their_category_outside_main_slots() {
+        use ::items::potion::PotionKind;
 
-        // Move south onto the item
-        resources.input_buffer.pending_actions.push(PlayerAction::Move(Direction::South));
-        let mut system = MovementSystem;
-        system.run(&mut world, &mut resources);
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let potion = |kind| ECSItem::new(::items::Item::new(::items::ItemKind::Potion(::items::Potion::new_alchemy(kind))));
+        let misc = |kind| ECSItem::new(::items::Item::new(::items::ItemKind::Misc(::items::MiscItem::new(kind))));
+        {
+            let mut inventory = ecs_world.world.get::<&mut Inventory>(player).unwrap();
+            inventory.max_slots = 2;
+            assert!(inventory.add_item(potion(PotionKind::Healing)).is_none());
+            assert!(inventory.add_item(misc(::items::MiscKind::PotionBandolier)).is_none());
+            assert_eq!(inventory.main_slots_used(), 1);
+
+            // 背包已满，药水仍然可以放进药剂带
+            assert!(inventory.add_item(misc(::items::MiscKind::Torch)).is_none());
+            assert!(inventory.add_item(misc(::items::MiscKind::Key)).is_some());
+            assert!(inventory.add_item(potion(PotionKind::Frost)).is_none());
+            assert_eq!(inventory.main_slots_used(), 2);
+
+            // 药水嵌套显示在药剂带之下，不再单独成为一个分类
+            let tabs = inventory.category_tabs();
+            assert_eq!(tabs.len(), 1);
+            assert_eq!(tabs[0].0, ::items::ItemCategory::Misc);
+            assert_eq!(tabs[0].1, vec![2, 1, 0, 3]);
+        }
 
-        // Check that an item message was logged
-        let has_item_message = resources.game_state.message_log.iter()
-            .any(|msg| msg.contains("Health Potion"));
-        assert!(has_item_message);
+        // 药剂带中的药水不会被冻裂
+        CombatSystem::apply_status_effect(
+            &mut ecs_world,
+            player,
+            ::combat::effect::Effect::new(EffectType::Frost, 3),
+        );
+        assert_eq!(ecs_world.world.get::<&Inventory>(player).unwrap().items.len(), 4);
+
+        // 丢掉药剂带后，冰冻会冻裂一瓶暴露在外的药水
+        let pos = Position::new(5, 5, 0);
+        assert!(InventorySystem::handle_drop_item(&mut ecs_world, player, 1));
+        ecs_world.world.get::<&mut StatusEffects>(player).unwrap().effects.clear();
+        CombatSystem::apply_status_effect(
+            &mut ecs_world,
+            player,
+            ::combat::effect::Effect::new(EffectType::Frost, 3),
+        );
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        assert_eq!(inventory.items.len(), 2);
+        assert!(ecs_world.resources.game_state.message_log.iter().any(|msg| msg.contains("冻裂了")));
+        assert_eq!(InventorySystem::ground_items_at(&ecs_world.world, &pos).len(), 1);
     }
-    
-    // ============ Inventory Tests ============
-    //
-    // Note: These tests use the legacy InventorySystem::run() method.
-    // To use the new event-integrated version, call InventorySystem::run_with_events()
-    // which properly marks actions as completed and publishes events.
-    
+
     #[test]
-    fn test_use_healing_potion() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-        
-        // Damage the player first
-        if let Ok(mut stats) = world.get::<&mut Stats>(player) {
-            stats.hp = 50; // Down from 100
-        }
-        
-        // Add a healing potion to inventory
-        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
-            inventory.items.push(ItemSlot {
-                item: Some(ECSItem {
-                    name: "Healing Potion".to_string(),
-                    item_type: ItemType::Consumable {
-                        effect: ConsumableEffect::Healing { amount: 30 },
-                    },
-                    value: 10,
-                    identified: true,
-                    quantity: 1,
-                    level: 0,
-                    cursed: false,
-                    charges: None,
-                    detailed_data: None,
-                }),
-                quantity: 1,
-            });
+    fn test_item_stacks_merge_split_and_rekey_on_identify() {
+        use ::items::scroll::ScrollKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        ecs_world.resources.item_knowledge = ::items::ItemKnowledge::from_seed(9);
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let darts = |quantity: u32| {
+            let mut item = ::items::Item::new(::items::ItemKind::Throwable(::items::Throwable::new(
+                ::items::ThrowableKind::Dart,
+            )));
+            item.set_quantity(quantity);
+            ECSItem::new(item)
+        };
+        let quantities = |ecs_world: &ECSWorld| {
+            let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+            inventory
+                .items
+                .iter()
+                .map(|slot| {
+                    assert_eq!(slot.item.as_ref().unwrap().quantity, slot.quantity);
+                    slot.quantity
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // 飞镖一堆最多 24 支，超出的部分另起一格
+        {
+            let mut inventory = ecs_world.world.get::<&mut Inventory>(player).unwrap();
+            assert!(inventory.add_item(darts(20)).is_none());
+            assert!(inventory.add_item(darts(10)).is_none());
         }
-        
-        // Use the potion (slot 0)
-        resources.input_buffer.pending_actions.push(PlayerAction::UseItem(0));
-        
-        let mut system = InventorySystem;
-        system.run(&mut world, &mut resources);
-        
-        // Check that HP was restored
-        let stats = world.get::<&Stats>(player).unwrap();
-        assert_eq!(stats.hp, 80); // 50 + 30
-        
-        // Check that the potion was removed from inventory
-        let inventory = world.get::<&Inventory>(player).unwrap();
-        assert_eq!(inventory.items.len(), 0);
-        
-        // Check that action was not marked as completed (old system doesn't do this)
-        // This would be tested with run_with_events
+        assert_eq!(quantities(&ecs_world), vec![24, 6]);
+
+        // 拆分出的一半紧跟在原格子之后
+        ecs_world.world.get::<&mut Inventory>(player).unwrap().split_stack(0);
+        assert_eq!(quantities(&ecs_world), vec![12, 12, 6]);
+        assert!(!ecs_world.world.get::<&mut Inventory>(player).unwrap().split_stack(5));
+
+        // 拾起的飞镖并入第一堆未满的飞镖，地上的物品随之消失
+        InventorySystem::spawn_ground_item(&mut ecs_world, &Position::new(5, 5, 0), darts(5));
+        InventorySystem::spawn_ground_item(&mut ecs_world, &Position::new(5, 5, 0), darts(2));
+        assert_eq!(ecs_world.world.query::<&ECSItem>().iter().count(), 1);
+        ecs_world.resources.input_buffer.pending_actions.push(PlayerAction::PickUp);
+        InventorySystem::run_with_events(&mut ecs_world);
+        assert_eq!(quantities(&ecs_world), vec![19, 12, 6]);
+        assert_eq!(ecs_world.world.query::<&ECSItem>().iter().count(), 0);
+
+        // 读过卷轴后认出了种类，分开存放的同种卷轴合并为一堆（拆开的飞镖也会重新合并）
+        give_scroll(&mut ecs_world.world, player, ScrollKind::Teleportation);
+        give_scroll(&mut ecs_world.world, player, ScrollKind::Teleportation);
+        give_scroll(&mut ecs_world.world, player, ScrollKind::Teleportation);
+        InventorySystem::sync_item_knowledge(&mut ecs_world);
+        ecs_world.resources.input_buffer.pending_actions.push(PlayerAction::UseItem(3));
+        InventorySystem::run_with_events(&mut ecs_world);
+        assert_eq!(quantities(&ecs_world), vec![24, 13, 2]);
+        let scroll = InventorySystem::inventory_item(&ecs_world, player, 2).unwrap();
+        assert_eq!(scroll.name, "传送卷轴");
     }
-    
+
     #[test]
-    fn test_drop_item() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-        
-        // Add an item to inventory
-        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
-            inventory.items.push(ItemSlot {
-                item: Some(ECSItem {
-                    name: "Sword".to_string(),
-                    item_type: ItemType::Weapon {
-                        damage: 10,
-                    },
-                    value: 20,
-                    identified: true,
-                    quantity: 1,
-                    level: 1,
-                    cursed: false,
-                    charges: None,
-                    detailed_data: None,
-                }),
-                quantity: 1,
-            });
+    fn test_nothing_spawns_on_the_alchemy_pot() {
+        let mut pots = 0;
+        for seed in 0..20 {
+            let dungeon = dungeon::Dungeon::generate(4, seed).unwrap();
+            for level in &dungeon.levels {
+                let Some(pot) = level
+                    .tiles
+                    .iter()
+                    .find(|tile| tile.info.terrain_type == dungeon::TerrainType::AlchemyPot)
+                    .map(|tile| (tile.x, tile.y))
+                else {
+                    continue;
+                };
+                pots += 1;
+                assert!(level.enemies.iter().all(|enemy| (enemy.x, enemy.y) != pot));
+                assert!(level.items.iter().all(|item| (item.x, item.y) != pot));
+            }
         }
-        
-        // Drop the item (slot 0)
-        resources.input_buffer.pending_actions.push(PlayerAction::DropItem(0));
-        
-        let mut system = InventorySystem;
-        system.run(&mut world, &mut resources);
-        
-        // Note: The old InventorySystem auto-picks up items at the player's position,
-        // so after dropping, the item is immediately picked back up.
-        // This test verifies the drop+pickup cycle works.
-        
-        // Check that the item is back in inventory (auto-pickup)
-        let inventory = world.get::<&Inventory>(player).unwrap();
-        assert_eq!(inventory.items.len(), 1);
-        
-        // Verify the item is still the sword
-        assert_eq!(inventory.items[0].item.as_ref().unwrap().name, "Sword");
+        assert!(pots > 0);
     }
-    
+
     #[test]
-    fn test_auto_pickup() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-        
-        // Place an item at the player's position
-        world.spawn((
-            Position::new(5, 5, 0),
-            ECSItem {
-                name: "Gold Coin".to_string(),
-                item_type: ItemType::Quest,
-                value: 1,
-                identified: true,
-                quantity: 1,
-                level: 0,
-                cursed: false,
-                charges: None,
-                detailed_data: None,
-            },
+    fn test_alchemy_pot_brews_recipes_and_remembers_them() {
+        use ::items::herb::HerbKind;
+        use ::items::seed::SeedKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        ecs_world.world.spawn((
+            Position::new(6, 5, 0),
             Tile {
-                terrain_type: TerrainType::Floor,
-                is_passable: true,
+                terrain_type: TerrainType::AlchemyPot,
+                is_passable: false,
                 blocks_sight: false,
-                has_items: true,
+                has_items: false,
                 has_monster: false,
             },
         ));
-        
-        // Run inventory system (it should auto-pickup)
-        let mut system = InventorySystem;
-        system.run(&mut world, &mut resources);
-        
-        // Check that the item was picked up
-        let inventory = world.get::<&Inventory>(player).unwrap();
-        assert_eq!(inventory.items.len(), 1);
-        assert_eq!(inventory.items[0].item.as_ref().unwrap().name, "Gold Coin");
-        
-        // Check that the item entity was despawned
-        let item_count = world.query::<(&Position, &ECSItem)>()
-            .iter()
-            .filter(|(_, (pos, _))| pos.x == 5 && pos.y == 5)
-            .count();
-        assert_eq!(item_count, 0);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Herb(::items::Herb::new(HerbKind::Sungrass)));
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Seed(::items::Seed::new(SeedKind::Earthroot)));
+        give_scroll(&mut ecs_world.world, player, ::items::scroll::ScrollKind::Lullaby);
+
+        // 走向炼金釜打开炼金界面，不移动也不消耗回合
+        ecs_world.resources.input_buffer.pending_actions.push(PlayerAction::Move(Direction::East));
+        MovementSystem::run_with_events(&mut ecs_world);
+        assert!(matches!(ecs_world.resources.game_state.game_state, GameStatus::Alchemy { .. }));
+        assert_eq!(ecs_world.world.get::<&Position>(player).unwrap().x, 5);
+        assert_eq!(InventorySystem::alchemy_slots(&ecs_world.world, player), vec![0, 1, 2]);
+
+        // 不成配方的材料不会被消耗
+        assert!(!InventorySystem::handle_brew(&mut ecs_world, player, &[1]));
+        assert!(!InventorySystem::handle_brew(&mut ecs_world, player, &[1, 1]));
+        assert_eq!(ecs_world.world.get::<&Inventory>(player).unwrap().items.len(), 3);
+
+        assert!(InventorySystem::handle_brew(&mut ecs_world, player, &[1, 0]));
+        let potion = InventorySystem::inventory_item(&ecs_world, player, 1).unwrap();
+        assert!(matches!(potion.kind, ::items::ItemKind::Potion(ref p) if p.identified));
+        assert_eq!(potion.name, "治疗药水");
+        assert_eq!(ecs_world.resources.item_knowledge.catalog().iter().filter(|e| e.name.is_some()).count(), 1);
+        let recipe = ::items::alchemy::find_recipe(&[
+            ::items::alchemy::Ingredient::Herb(HerbKind::Sungrass),
+            ::items::alchemy::Ingredient::Seed(SeedKind::Earthroot),
+        ])
+        .unwrap();
+        assert!(ecs_world.resources.item_knowledge.knows_recipe(&recipe));
+
+        // 卷轴化为两颗对应的魔法石
+        assert!(InventorySystem::handle_brew(&mut ecs_world, player, &[0]));
+        let inventory = ecs_world.world.get::<&Inventory>(player).unwrap();
+        let stones = inventory.items[1].item.as_ref().unwrap();
+        assert_eq!(stones.name, "催眠之石");
+        assert_eq!(stones.quantity, ::items::alchemy::STONES_PER_SCROLL);
     }
-    
+
     #[test]
-    fn test_inventory_full() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-        
-        // Fill up the inventory
-        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
-            inventory.max_slots = 2; // Small inventory for testing
-            for i in 0..2 {
-                inventory.items.push(ItemSlot {
-                    item: Some(ECSItem {
-                        name: format!("Item {}", i),
-                        item_type: ItemType::Quest,
-                        value: 1,
-                        identified: true,
-                        quantity: 1,
-                        level: 0,
-                        cursed: false,
-                        charges: None,
-                        detailed_data: None,
-                    }),
-                    quantity: 1,
-                });
-            }
+    fn test_planted_seeds_trigger_on_whoever_steps_on_them() {
+        use ::items::seed::SeedKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Seed(::items::Seed::new(SeedKind::Earthroot)));
+        give_item(&mut ecs_world.world, player, ::items::ItemKind::Seed(::items::Seed::new(SeedKind::Sorrowmoss)));
+
+        // 种在脚下，同一格不能种两株
+        assert!(InventorySystem::handle_plant_seed(&mut ecs_world, player, 0));
+        assert!(!InventorySystem::handle_plant_seed(&mut ecs_world, player, 0));
+        assert_eq!(ecs_world.world.query::<&Plant>().iter().count(), 1);
+
+        // 离开再踩回来触发地根草
+        for direction in [Direction::East, Direction::West] {
+            ecs_world.resources.input_buffer.pending_actions.push(PlayerAction::Move(direction));
+            MovementSystem::run_with_events(&mut ecs_world);
         }
-        
-        // Place an item at the player's position
-        world.spawn((
-            Position::new(5, 5, 0),
-            ECSItem {
-                name: "Extra Item".to_string(),
-                item_type: ItemType::Quest,
-                value: 1,
-                identified: true,
-                quantity: 1,
-                level: 0,
-                cursed: false,
-                charges: None,
-                detailed_data: None,
-            },
-            Tile {
-                terrain_type: TerrainType::Floor,
-                is_passable: true,
-                blocks_sight: false,
-                has_items: true,
-                has_monster: false,
-            },
-        ));
-        
-        // Run inventory system
-        let mut system = InventorySystem;
-        system.run(&mut world, &mut resources);
-        
-        // Check that inventory is still full
-        let inventory = world.get::<&Inventory>(player).unwrap();
-        assert_eq!(inventory.items.len(), 2);
-        
-        // Check that the item is still on the ground
-        let item_count = world.query::<(&Position, &ECSItem)>()
-            .iter()
-            .filter(|(_, (pos, item))| pos.x == 5 && pos.y == 5 && item.name == "Extra Item")
-            .count();
-        assert_eq!(item_count, 1);
-        
-        // Check that a "full inventory" message was logged
-        let has_full_message = resources.game_state.message_log.iter()
-            .any(|msg| msg.contains("full"));
-        assert!(has_full_message);
+        assert_eq!(ecs_world.world.get::<&Position>(player).unwrap().x, 5);
+        assert!(ecs_world.world.get::<&StatusEffects>(player).unwrap().has_effect(EffectType::Barkskin));
+        assert_eq!(ecs_world.world.query::<&Plant>().iter().count(), 0);
+
+        // 敌人踩上哀伤苔同样会中毒
+        ecs_world.world.get::<&mut Position>(player).unwrap().x = 3;
+        assert!(InventorySystem::handle_plant_seed(&mut ecs_world, player, 0));
+        let enemy = create_enemy(&mut ecs_world.world, 3, 6);
+        let trap = Position::new(3, 5, 0);
+        *ecs_world.world.get::<&mut Position>(enemy).unwrap() = trap.clone();
+        DungeonSystem::process_environmental_effects(&mut ecs_world, enemy, &trap);
+        assert!(ecs_world.world.get::<&StatusEffects>(enemy).unwrap().has_effect(EffectType::Poison));
+        assert!(ecs_world.world.query::<&Blob>().iter().count() > 0);
+        assert!(ecs_world.world.get::<&Inventory>(player).unwrap().items.is_empty());
     }
-    
+
     #[test]
-    fn test_use_teleport_scroll() {
-        let (mut world, mut resources) = create_test_world();
-        let player = create_player(&mut world, 5, 5);
-        
-        // Add a teleport scroll to inventory
-        if let Ok(mut inventory) = world.get::<&mut Inventory>(player) {
-            inventory.items.push(ItemSlot {
-                item: Some(ECSItem {
-                    name: "Scroll of Teleportation".to_string(),
-                    item_type: ItemType::Consumable {
-                        effect: ConsumableEffect::Teleport,
-                    },
-                    value: 15,
-                    identified: true,
-                    quantity: 1,
-                    level: 0,
-                    cursed: false,
-                    charges: None,
-                    detailed_data: None,
-                }),
-                quantity: 1,
-            });
-        }
-        
-        // Use the scroll (slot 0)
-        resources.input_buffer.pending_actions.push(PlayerAction::UseItem(0));
-        
-        let mut system = InventorySystem;
-        system.run(&mut world, &mut resources);
-        
-        // Check that player position changed
-        let pos = world.get::<&Position>(player).unwrap();
-        assert!(pos.x != 5 || pos.y != 5); // Position should have changed
-        
-        // Check that the scroll was removed from inventory
-        let inventory = world.get::<&Inventory>(player).unwrap();
-        assert_eq!(inventory.items.len(), 0);
+    fn test_stones_apply_to_items_or_land_on_tiles() {
+        use ::items::stone::StoneKind;
+
+        let mut ecs_world = create_test_ecs_world();
+        let player = create_player(&mut ecs_world.world, 5, 5);
+        let stone = |kind| ::items::ItemKind::Stone(::items::Stone::new(kind));
+        give_item(&mut ecs_world.world, player, stone(StoneKind::Upgrade));
+        give_item(
+            &mut ecs_world.world,
+            player,
+            ::items::ItemKind::Weapon(::items::Weapon::new(1, ::items::weapon::WeaponKind::Sword)),
+        );
+
+        // 强化之石先选择目标物品，选中后强化并消耗
+        assert!(!InventorySystem::handle_use_item(&mut ecs_world, player, 0));
+        assert!(matches!(ecs_world.resources.game_state.game_state, GameStatus::ItemPicker { source_slot: 0, .. }));
+        assert!(InventorySystem::handle_use_item_on(&mut ecs_world, player, 0, 1));
+        let weapon = InventorySystem::inventory_item(&ecs_world, player, 0).unwrap();
+        assert!(matches!(weapon.kind, ::items::ItemKind::Weapon(ref w) if w.upgrade_level == 1));
+
+        // 催眠之石进入瞄准模式，投出后让落点周围的生物沉睡
+        give_item(&mut ecs_world.world, player, stone(StoneKind::Lullaby));
+        ecs_world.resources.game_state.game_state = GameStatus::Running;
+        assert!(!InventorySystem::handle_use_item(&mut ecs_world, player, 1));
+        assert!(matches!(
+            ecs_world.resources.game_state.game_state,
+            GameStatus::Targeting { purpose: TargetingPurpose::Throw(1), .. }
+        ));
+        let enemy = create_enemy(&mut ecs_world.world, 8, 8);
+        assert!(InventorySystem::throw_item_at(&mut ecs_world, player, 1, &Position::new(8, 7, 0)));
+        assert!(ecs_world.world.get::<&StatusEffects>(enemy).unwrap().has_effect(EffectType::Paralysis));
+        assert!(!ecs_world.world.get::<&StatusEffects>(player).is_ok_and(|e| e.has_effect(EffectType::Paralysis)));
+        assert_eq!(ecs_world.world.query::<&ECSItem>().iter().count(), 0);
+
+        // 传送之石把投掷者送到落点
+        give_item(&mut ecs_world.world, player, stone(StoneKind::Teleportation));
+        assert!(InventorySystem::throw_item_at(&mut ecs_world, player, 1, &Position::new(2, 5, 0)));
+        let pos = ecs_world.world.get::<&Position>(player).unwrap();
+        assert_eq!((pos.x, pos.y), (2, 5));
     }
 }
@@ -0,0 +1,290 @@
+//! 瞄准与弹道计算
+//!
+//! 瞄准模式、投掷、法杖和远程攻击共用的弹道逻辑：
+//! - 弹道沿 Bresenham 直线飞行，遇墙停在墙前，遇到生物停在其所在格
+//! - 根据瞄准用途计算射程与作用范围，供渲染器预览
+
+use crate::ecs::{
+    Actor, Equipment, Faction, Inventory, Player, Position, Stats, TargetingPurpose, Viewshed,
+};
+use crate::systems::MovementSystem;
+use hecs::{Entity, World};
+
+/// 非投掷武器的默认投掷距离
+pub const DEFAULT_THROW_RANGE: i32 = 4;
+
+/// 法杖弹道的最大距离
+pub const BOLT_RANGE: i32 = 8;
+
+/// 火焰冲击锥形范围的距离
+pub const CONE_RANGE: i32 = 4;
+
+/// 催眠、狂暴等魔法石落地后的作用半径
+pub const STONE_RADIUS: i32 = 2;
+
+/// 锥形范围的半角（余弦值，约 30 度）
+const CONE_HALF_ANGLE_COS: f32 = 0.86;
+
+/// 一次弹道的计算结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProjectilePath {
+    /// 弹道经过的格子（不含起点，含落点）
+    pub tiles: Vec<Position>,
+    /// 最终落点
+    pub landing: Position,
+    /// 命中的第一个生物
+    pub hit: Option<Entity>,
+}
+
+/// 瞄准预览：弹道与作用范围
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TargetPreview {
+    pub path: Vec<Position>,
+    pub area: Vec<Position>,
+}
+
+/// 计算从 `from` 射向 `to` 的弹道，最多飞行 `range` 格
+pub fn trace_projectile(world: &World, from: &Position, to: &Position, range: i32) -> ProjectilePath {
+    let mut tiles = Vec::new();
+    let mut landing = from.clone();
+
+    for pos in from.line_to(to).into_iter().take(range.max(0) as usize) {
+        if !MovementSystem::is_tile_passable(world, &pos) {
+            break;
+        }
+        tiles.push(pos.clone());
+        if let Some(entity) = MovementSystem::find_entity_at_position(world, &pos) {
+            return ProjectilePath {
+                tiles,
+                landing: pos,
+                hit: Some(entity),
+            };
+        }
+        landing = pos;
+    }
+
+    ProjectilePath {
+        tiles,
+        landing,
+        hit: None,
+    }
+}
+
+/// 计算穿透光束经过的格子：只会被墙壁阻挡，穿过途中的所有生物
+pub fn trace_beam(world: &World, from: &Position, to: &Position, range: i32) -> Vec<Position> {
+    from.line_to(to)
+        .into_iter()
+        .take(range.max(0) as usize)
+        .take_while(|pos| MovementSystem::is_tile_passable(world, pos))
+        .collect()
+}
+
+/// 计算从 `from` 朝 `to` 展开的锥形范围（需要与起点之间没有墙壁阻隔）
+pub fn cone_tiles(world: &World, from: &Position, to: &Position, range: i32) -> Vec<Position> {
+    let (aim_x, aim_y) = ((to.x - from.x) as f32, (to.y - from.y) as f32);
+    let aim_len = (aim_x * aim_x + aim_y * aim_y).sqrt();
+    if aim_len == 0.0 {
+        return Vec::new();
+    }
+
+    let mut tiles = Vec::new();
+    for dy in -range..=range {
+        for dx in -range..=range {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let len = ((dx * dx + dy * dy) as f32).sqrt();
+            if len > range as f32 + 0.5 {
+                continue;
+            }
+            let cos = (dx as f32 * aim_x + dy as f32 * aim_y) / (len * aim_len);
+            if cos < CONE_HALF_ANGLE_COS {
+                continue;
+            }
+
+            let tile = Position::new(from.x + dx, from.y + dy, from.z);
+            let reached = trace_beam(world, from, &tile, range)
+                .last()
+                .is_some_and(|last| *last == tile);
+            if reached {
+                tiles.push(tile);
+            }
+        }
+    }
+    tiles
+}
+
+/// 读取背包格子中的法杖（法师之杖读取其吸收的法杖）
+pub fn wand_in_slot(world: &World, entity: Entity, slot: usize) -> Option<items::Wand> {
+    let inventory = world.get::<&Inventory>(entity).ok()?;
+    let item = inventory.items.get(slot)?.item.as_ref()?.item.clone();
+    match item.kind {
+        items::ItemKind::Wand(wand) => Some(wand),
+        items::ItemKind::Signature(signature) => signature.absorbed,
+        _ => None,
+    }
+}
+
+/// 瞄准用途对应的射程
+pub fn purpose_range(world: &World, entity: Entity, purpose: TargetingPurpose) -> i32 {
+    match purpose {
+        TargetingPurpose::Throw(slot) => world
+            .get::<&Inventory>(entity)
+            .ok()
+            .and_then(|inventory| {
+                inventory
+                    .items
+                    .get(slot)
+                    .and_then(|slot| slot.item.as_ref())
+                    .map(|item| item.item.clone())
+            })
+            .map(|item| match item.kind {
+                items::ItemKind::Throwable(throwable) => throwable.range as i32,
+                items::ItemKind::Signature(signature) if signature.kind == items::SignatureKind::SpiritBow => {
+                    hero::class::huntress::SPIRIT_BOW_RANGE as i32
+                }
+                _ => DEFAULT_THROW_RANGE,
+            })
+            .unwrap_or(DEFAULT_THROW_RANGE),
+        TargetingPurpose::Zap(_) => BOLT_RANGE,
+        TargetingPurpose::Skill(index) => class_skill(world, entity, index)
+            .map(|skill| skill.range as i32)
+            .unwrap_or(0),
+        TargetingPurpose::RangedAttack => world
+            .get::<&Equipment>(entity)
+            .ok()
+            .and_then(|equipment| equipment.weapon.as_ref().map(|w| w.range() as i32))
+            .unwrap_or(1),
+    }
+}
+
+/// 获取实体职业的第 `index` 个主动技能
+pub fn class_skill(world: &World, entity: Entity, index: usize) -> Option<hero::abilities::ActiveSkill> {
+    let class = world.get::<&Stats>(entity).ok()?.class.clone()?;
+    hero::abilities::ClassAbilitySet::for_class(class)
+        .active_skills
+        .into_iter()
+        .nth(index)
+}
+
+/// 玩家视野内的敌人，按距离由近到远排序
+pub fn visible_enemies(world: &World, player: Entity) -> Vec<(Entity, Position)> {
+    let Ok(player_pos) = world.get::<&Position>(player).map(|p| (*p).clone()) else {
+        return Vec::new();
+    };
+    let visible = world
+        .get::<&Viewshed>(player)
+        .map(|v| v.visible_tiles.clone())
+        .unwrap_or_default();
+
+    let mut enemies: Vec<(Entity, Position)> = world
+        .query::<(&Position, &Actor)>()
+        .without::<&Player>()
+        .iter()
+        .filter(|(_, (pos, actor))| {
+            matches!(actor.faction, Faction::Enemy)
+                && pos.z == player_pos.z
+                && visible.iter().any(|v| v.x == pos.x && v.y == pos.y)
+        })
+        .map(|(entity, (pos, _))| (entity, pos.clone()))
+        .collect();
+
+    enemies.sort_by(|(_, a), (_, b)| {
+        a.distance_to(&player_pos)
+            .total_cmp(&b.distance_to(&player_pos))
+            .then(a.y.cmp(&b.y))
+            .then(a.x.cmp(&b.x))
+    });
+    enemies
+}
+
+/// 进入瞄准模式时的初始光标：最近的可见敌人，没有则为玩家自身
+pub fn initial_cursor(world: &World, player: Entity) -> Option<Position> {
+    visible_enemies(world, player)
+        .into_iter()
+        .next()
+        .map(|(_, pos)| pos)
+        .or_else(|| world.get::<&Position>(player).ok().map(|p| (*p).clone()))
+}
+
+/// Tab 循环：返回当前光标之后的下一个可见敌人位置
+pub fn next_target(world: &World, player: Entity, cursor: &Position) -> Option<Position> {
+    let enemies = visible_enemies(world, player);
+    let current = enemies
+        .iter()
+        .position(|(_, pos)| pos.x == cursor.x && pos.y == cursor.y);
+    let next = match current {
+        Some(index) => (index + 1) % enemies.len(),
+        None => 0,
+    };
+    enemies.get(next).map(|(_, pos)| pos.clone())
+}
+
+/// 计算瞄准预览
+pub fn preview(world: &World, player: Entity, purpose: TargetingPurpose, cursor: &Position) -> TargetPreview {
+    let Ok(origin) = world.get::<&Position>(player).map(|p| (*p).clone()) else {
+        return TargetPreview::default();
+    };
+    let range = purpose_range(world, player, purpose);
+    let wand = match purpose {
+        TargetingPurpose::Zap(slot) => wand_in_slot(world, player, slot).map(|w| w.kind),
+        _ => None,
+    };
+
+    match wand {
+        Some(items::wand::WandKind::Disintegration) => TargetPreview {
+            path: trace_beam(world, &origin, cursor, range),
+            area: Vec::new(),
+        },
+        Some(items::wand::WandKind::Fireblast) => TargetPreview {
+            path: Vec::new(),
+            area: cone_tiles(world, &origin, cursor, CONE_RANGE),
+        },
+        _ => {
+            let path = trace_projectile(world, &origin, cursor, range);
+            let area = area_of_effect(world, player, purpose, &path.landing);
+            TargetPreview {
+                path: path.tiles,
+                area,
+            }
+        }
+    }
+}
+
+/// 瞄准用途在落点处的作用范围
+///
+/// 投掷的药水与再生法杖波及落点周围一格，催眠与狂暴之石波及 `STONE_RADIUS` 格，
+/// 其余只作用于落点本身。
+pub fn area_of_effect(world: &World, entity: Entity, purpose: TargetingPurpose, landing: &Position) -> Vec<Position> {
+    let radius = match purpose {
+        TargetingPurpose::Throw(slot) => world
+            .get::<&Inventory>(entity)
+            .ok()
+            .and_then(|inventory| {
+                inventory
+                    .items
+                    .get(slot)
+                    .and_then(|slot| slot.item.as_ref())
+                    .map(|item| item.item.clone())
+            })
+            .map_or(0, |item| match item.kind {
+                items::ItemKind::Potion(_) => 1,
+                items::ItemKind::Stone(stone) => match stone.kind {
+                    items::stone::StoneKind::Lullaby | items::stone::StoneKind::Rage => STONE_RADIUS,
+                    _ => 0,
+                },
+                _ => 0,
+            }),
+        TargetingPurpose::Zap(slot) => wand_in_slot(world, entity, slot)
+            .map_or(0, |wand| i32::from(wand.kind == items::wand::WandKind::Regrowth)),
+        _ => 0,
+    };
+
+    let mut area = Vec::with_capacity(((2 * radius + 1) * (2 * radius + 1)) as usize);
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            area.push(Position::new(landing.x + dx, landing.y + dy, landing.z));
+        }
+    }
+    area
+}
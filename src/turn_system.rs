@@ -64,10 +64,16 @@ pub mod energy_costs {
             | PlayerAction::Attack(_) => FULL_ACTION,
             
             // Inventory actions
-            PlayerAction::UseItem(_) => USE_ITEM,
+            PlayerAction::UseItem(_)
+            | PlayerAction::UseItemOn(_, _)
+            | PlayerAction::Brew(_)
+            | PlayerAction::PlantSeed(_) => USE_ITEM,
+            PlayerAction::PickUp | PlayerAction::PickUpItem(_) => PICKUP_ITEM,
             PlayerAction::DropItem(_) => DROP_ITEM,
             PlayerAction::EquipItem(_) | PlayerAction::UnequipItem(_) => EQUIP_ITEM,
-            PlayerAction::ThrowItem(_, _) => THROW_ITEM,
+            PlayerAction::ThrowItem(_, _) | PlayerAction::ThrowItemAt(_, _) => THROW_ITEM,
+            PlayerAction::ZapWand(_, _) => USE_ITEM,
+            PlayerAction::UseSkill(_, _) => FULL_ACTION,
             
             // Environment interactions have explicit costs
             PlayerAction::Descend | PlayerAction::Ascend => STAIR_USE,
@@ -80,10 +86,18 @@ pub mod energy_costs {
             | PlayerAction::OpenOptions
             | PlayerAction::OpenHelp
             | PlayerAction::OpenCharacterInfo
+            | PlayerAction::OpenCatalog
             | PlayerAction::CloseMenu
             | PlayerAction::MenuNavigate(_)
             | PlayerAction::MenuSelect
-            | PlayerAction::MenuBack => FREE,
+            | PlayerAction::MenuBack
+            | PlayerAction::BeginTargeting(_)
+            | PlayerAction::MoveCursor(_)
+            | PlayerAction::CycleTarget
+            | PlayerAction::ConfirmTarget
+            | PlayerAction::UseQuickslot(_)
+            | PlayerAction::AssignQuickslot(_)
+            | PlayerAction::SplitStack(_) => FREE,
         }
     }
     
@@ -300,10 +314,19 @@ impl TurnSystem {
         world: &mut World,
         action: &PlayerAction,
     ) -> Result<(), anyhow::Error> {
-        let energy_cost = energy_costs::player_action_cost(action);
+        let mut energy_cost = energy_costs::player_action_cost(action);
 
         if energy_cost > 0 {
             if let Some(player_entity) = find_player(world) {
+                // 急速之戒减少移动耗能（最多减半，诅咒时反而增加），力量不足则增加移动与攻击耗能
+                let bonus = Equipment::bonuses_of(world, player_entity);
+                let modifier = match action {
+                    PlayerAction::Move(_) => bonus.move_cost - bonus.haste.clamp(-0.5, 0.5),
+                    PlayerAction::Attack(_) => bonus.attack_cost,
+                    _ => 0.0,
+                };
+                energy_cost = (energy_cost as f32 * (1.0 + modifier)) as u32;
+
                 if let Ok(mut energy) = world.get::<&mut Energy>(player_entity) {
                     let before = energy.current;
                     energy.current = energy.current.saturating_sub(energy_cost);
@@ -4,7 +4,7 @@
 use terminal_pixel_dungeon::ecs::{
     Actor, ECSWorld, Energy, Faction, Position, Stats, StatusEffects, Player,
 };
-use terminal_pixel_dungeon::systems::{EffectPhase, EffectSystem, SystemResult};
+use terminal_pixel_dungeon::systems::{AftermathSystem, EffectPhase, EffectSystem, SystemResult};
 use combat::effect::{Effect, EffectType};
 
 /// Helper function to create a test entity with status effects
@@ -198,8 +198,10 @@ fn test_effect_removal_on_death() {
     ecs_world.resources.clock.turn_count = 1;
     let result = EffectSystem::run_with_events(&mut ecs_world, EffectPhase::EndOfTurn);
     assert!(matches!(result, SystemResult::Continue));
+    assert!(ecs_world.world.get::<&StatusEffects>(entity).unwrap().effects.is_empty());
     
-    // Entity should be despawned
+    // Entity should be despawned once the aftermath is resolved
+    AftermathSystem::run_with_events(&mut ecs_world);
     assert!(ecs_world.world.get::<&Stats>(entity).is_err(), "Entity should be despawned on death");
 }
 